@@ -0,0 +1,62 @@
+use crate::paths::{SettingsFile, UserPaths};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Accessibility constants
+const REDUCE_MOTION_TOGGLE_KEY: KeyCode = KeyCode::F6;
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AccessibilitySettings>()
+            .add_systems(Startup, load_accessibility_settings)
+            .add_systems(Update, toggle_reduce_motion);
+    }
+}
+
+// Resource every presentation system checks before adding motion purely for effect
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    // Disables camera bob, shake, FOV kicks and decorative wobble, and slows UI animation
+    pub reduce_motion: bool,
+}
+
+impl SettingsFile for AccessibilitySettings {
+    const FILE: &'static str = "accessibility.ron";
+}
+
+impl AccessibilitySettings {
+    // Scale for purely decorative motion, zero when motion is reduced
+    pub fn motion_scale(&self) -> f32 {
+        if self.reduce_motion { 0.0 } else { 1.0 }
+    }
+
+    // Multiplier on how long UI transitions take, gentler when motion is reduced
+    pub fn ui_duration_scale(&self) -> f32 {
+        if self.reduce_motion { 2.0 } else { 1.0 }
+    }
+}
+
+fn load_accessibility_settings(paths: Res<UserPaths>, mut settings: ResMut<AccessibilitySettings>) {
+    if let Some(loaded) = paths.load_settings() {
+        *settings = loaded;
+    }
+}
+
+fn toggle_reduce_motion(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    paths: Res<UserPaths>,
+    mut settings: ResMut<AccessibilitySettings>,
+) {
+    if !keyboard.just_pressed(REDUCE_MOTION_TOGGLE_KEY) {
+        return;
+    }
+    settings.reduce_motion = !settings.reduce_motion;
+    println!(
+        "Reduce motion: {}",
+        if settings.reduce_motion { "on" } else { "off" }
+    );
+    paths.save_settings(&*settings);
+}
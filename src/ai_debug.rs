@@ -0,0 +1,196 @@
+use crate::{
+    Npc, PlayerCamera,
+    factions::{Faction, FactionStandings, Relationship},
+    perception::{EYE_HEIGHT, Perception, SIGHT_COS, SIGHT_RANGE},
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::f32::consts::FRAC_PI_2;
+
+// AI debug constants
+const AI_DEBUG_TOGGLE_KEY: KeyCode = KeyCode::F3;
+const AI_DEBUG_FOCUS_KEY: KeyCode = KeyCode::F4;
+const CROSSHAIR_FOCUS_DOT: f32 = 0.95; // How closely the camera must point at an NPC
+const PATH_COLOR: Color = Color::srgb(0.2, 0.6, 1.0);
+const TARGET_COLOR: Color = Color::srgb(1.0, 0.3, 0.3);
+const STEERING_COLOR: Color = Color::srgb(1.0, 0.8, 0.1);
+const SIGHT_COLOR: Color = Color::srgba(0.9, 0.9, 0.9, 0.6);
+const SIGHT_SPOTTED_COLOR: Color = Color::srgb(1.0, 0.2, 0.6);
+const SIGHT_ARC_SEGMENTS: usize = 12;
+const WANDER_AREA_COLOR: Color = Color::srgba(0.3, 1.0, 0.3, 0.5);
+const HOSTILE_COLOR: Color = Color::srgb(1.0, 0.1, 0.1);
+const UNFRIENDLY_COLOR: Color = Color::srgb(1.0, 0.5, 0.2);
+const FRIENDLY_COLOR: Color = Color::srgb(0.4, 0.9, 1.0);
+const ALLIED_COLOR: Color = Color::srgb(0.2, 0.4, 1.0);
+
+pub struct AiDebugPlugin;
+
+impl Plugin for AiDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AiDebugSettings>()
+            .add_systems(
+                FixedUpdate,
+                track_resolved_velocity.after(PhysicsSet::Writeback),
+            )
+            .add_systems(Update, (toggle_ai_debug, draw_ai_debug).chain());
+    }
+}
+
+// Resource controlling the AI debug overlay
+#[derive(Resource, Default)]
+pub struct AiDebugSettings {
+    pub enabled: bool,
+    // Only draw the NPC the camera is pointing at
+    pub focus_crosshair: bool,
+}
+
+// Component with how fast an NPC actually moved over the last fixed step, once wandering,
+// avoidance, yielding and everything else moving it have had their say
+#[derive(Component)]
+struct ResolvedVelocity {
+    last_position: Vec3,
+    velocity: Vec3,
+}
+
+fn track_resolved_velocity(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut npcs: Query<(Entity, &Transform, Option<&mut ResolvedVelocity>), With<Npc>>,
+) {
+    let delta_time = time.delta_secs();
+    if delta_time <= 0.0 {
+        return;
+    }
+    for (entity, transform, resolved) in npcs.iter_mut() {
+        let position = transform.translation;
+        match resolved {
+            Some(mut resolved) => {
+                resolved.velocity = (position - resolved.last_position) / delta_time;
+                resolved.last_position = position;
+            }
+            None => {
+                commands.entity(entity).insert(ResolvedVelocity {
+                    last_position: position,
+                    velocity: Vec3::ZERO,
+                });
+            }
+        }
+    }
+}
+
+fn toggle_ai_debug(keyboard: Res<ButtonInput<KeyCode>>, mut settings: ResMut<AiDebugSettings>) {
+    if keyboard.just_pressed(AI_DEBUG_TOGGLE_KEY) {
+        settings.enabled = !settings.enabled;
+    }
+    if keyboard.just_pressed(AI_DEBUG_FOCUS_KEY) {
+        settings.focus_crosshair = !settings.focus_crosshair;
+    }
+}
+
+fn draw_ai_debug(
+    settings: Res<AiDebugSettings>,
+    camera_query: Query<&GlobalTransform, With<PlayerCamera>>,
+    npc_query: Query<(Entity, &Transform, &Npc, &Faction)>,
+    senses: Query<(Option<&Perception>, Option<&ResolvedVelocity>)>,
+    standings: Res<FactionStandings>,
+    mut gizmos: Gizmos,
+) {
+    if !settings.enabled {
+        return;
+    }
+
+    let focused = if settings.focus_crosshair {
+        let Ok(camera) = camera_query.get_single() else {
+            return;
+        };
+        crosshair_npc(camera, &npc_query)
+    } else {
+        None
+    };
+
+    for (entity, transform, npc, faction) in npc_query.iter() {
+        if settings.focus_crosshair && focused != Some(entity) {
+            continue;
+        }
+
+        let position = transform.translation;
+
+        // Current path to the wander target and the target itself
+        gizmos.line(position, npc.target_position, PATH_COLOR);
+        gizmos.sphere(
+            Isometry3d::from_translation(npc.target_position),
+            0.2,
+            TARGET_COLOR,
+        );
+
+        let (perception, resolved) = senses.get(entity).unwrap_or_default();
+
+        // How the NPC is actually moving, after avoidance and yielding
+        if let Some(resolved) = resolved
+            && resolved.velocity.length() > 0.1
+        {
+            gizmos.arrow(position, position + resolved.velocity, STEERING_COLOR);
+        }
+
+        // Sight cone, flattened onto the ground plane at eye height
+        let eye = position + Vec3::Y * EYE_HEIGHT;
+        let forward = transform.rotation * Vec3::Z;
+        let half_angle = SIGHT_COS.acos();
+        let edge = |angle: f32| eye + Quat::from_rotation_y(angle) * forward * SIGHT_RANGE;
+        let color = if perception.is_some_and(|perception| perception.sees_player) {
+            SIGHT_SPOTTED_COLOR
+        } else {
+            SIGHT_COLOR
+        };
+        gizmos.line(eye, edge(-half_angle), color);
+        gizmos.line(eye, edge(half_angle), color);
+        gizmos.linestrip(
+            (0..=SIGHT_ARC_SEGMENTS).map(|segment| {
+                let share = segment as f32 / SIGHT_ARC_SEGMENTS as f32;
+                edge(-half_angle + 2.0 * half_angle * share)
+            }),
+            color,
+        );
+
+        // Area the NPC picks wander targets from
+        gizmos.circle(
+            Isometry3d::new(npc.home_position, Quat::from_rotation_x(FRAC_PI_2)),
+            npc.wander_radius,
+            WANDER_AREA_COLOR,
+        );
+
+        // How the focused NPC regards everyone else, skipping the indifferent
+        if focused == Some(entity) {
+            for (other, other_transform, _, other_faction) in npc_query.iter() {
+                let color = match standings.relationship(*faction, *other_faction) {
+                    _ if other == entity => continue,
+                    Relationship::Neutral => continue,
+                    Relationship::Hostile => HOSTILE_COLOR,
+                    Relationship::Unfriendly => UNFRIENDLY_COLOR,
+                    Relationship::Friendly => FRIENDLY_COLOR,
+                    Relationship::Allied => ALLIED_COLOR,
+                };
+                gizmos.line(position, other_transform.translation, color);
+            }
+        }
+    }
+}
+
+// Find the NPC closest to the center of the screen
+fn crosshair_npc(
+    camera: &GlobalTransform,
+    npc_query: &Query<(Entity, &Transform, &Npc, &Faction)>,
+) -> Option<Entity> {
+    let origin = camera.translation();
+    let forward = camera.forward();
+
+    npc_query
+        .iter()
+        .map(|(entity, transform, _, _)| {
+            let dot = forward.dot((transform.translation - origin).normalize_or_zero());
+            (entity, dot)
+        })
+        .filter(|(_, dot)| *dot > CROSSHAIR_FOCUS_DOT)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
@@ -0,0 +1,85 @@
+use crate::{GameStateSet, Npc, npc_rng::NpcRng, update_npcs};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use rand::Rng;
+
+// AI LOD constants
+const FULL_DISTANCE: f32 = 30.0; // NPCs closer than this think every physics step
+const REDUCED_DISTANCE: f32 = 80.0; // Beyond this they sleep until the player comes back
+const REDUCED_INTERVAL: f32 = 0.25; // Seconds between updates for NPCs in between
+
+pub struct AiLodPlugin;
+
+impl Plugin for AiLodPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            update_ai_lod
+                .before(update_npcs)
+                .before(PhysicsSet::SyncBackend)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AiLodLevel {
+    #[default]
+    Full,
+    Reduced,
+    Asleep,
+}
+
+// Component deciding how often an NPC's AI gets to run
+#[derive(Component, Default)]
+pub struct AiLod {
+    pub level: AiLodLevel,
+    // Time gathered up since the NPC's last reduced update
+    elapsed: f32,
+}
+
+impl AiLod {
+    // The time step this NPC should simulate now, or `None` if it's not its turn
+    pub fn step(&mut self, delta_time: f32) -> Option<f32> {
+        match self.level {
+            AiLodLevel::Full => Some(delta_time),
+            AiLodLevel::Asleep => None,
+            AiLodLevel::Reduced => {
+                self.elapsed += delta_time;
+                if self.elapsed < REDUCED_INTERVAL {
+                    return None;
+                }
+                Some(std::mem::take(&mut self.elapsed))
+            }
+        }
+    }
+}
+
+pub fn update_ai_lod(
+    mut rng: ResMut<NpcRng>,
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<Npc>)>,
+    mut npcs: Query<(&Transform, &mut AiLod), With<Npc>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let rng = &mut rng.0;
+    for (transform, mut lod) in npcs.iter_mut() {
+        let distance = transform.translation.distance(player.translation);
+        let level = if distance < FULL_DISTANCE {
+            AiLodLevel::Full
+        } else if distance < REDUCED_DISTANCE {
+            AiLodLevel::Reduced
+        } else {
+            AiLodLevel::Asleep
+        };
+        if lod.level == level {
+            continue;
+        }
+        // Start somewhere random in the interval so far-off NPCs don't all update on the same step
+        if level == AiLodLevel::Reduced {
+            lod.elapsed = rng.random_range(0.0..REDUCED_INTERVAL);
+        }
+        lod.level = level;
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{
+    DialogueDatabase, GameState, GameStateSet, Npc, clock::GameClock, dialogue_context,
+    dialogue_variables::DialogueVariables, npc_rng::NpcRng,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::control::KinematicCharacterController;
+use rand::Rng;
+
+// Ambient dialogue constants
+const AMBIENT_RANGE: f32 = 12.0;
+const AMBIENT_MIN_INTERVAL: f32 = 6.0;
+const AMBIENT_MAX_INTERVAL: f32 = 14.0;
+const SUBTITLE_BASE_DURATION: f32 = 2.5;
+const SUBTITLE_DURATION_PER_CHAR: f32 = 0.05;
+const MAX_SUBTITLES: usize = 3;
+const SUBTITLE_FONT_SIZE: f32 = 18.0;
+const SUBTITLE_TEXT_COLOR: Color = Color::srgb(0.95, 0.95, 0.95);
+const SUBTITLE_BACKGROUND_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+pub struct AmbientDialoguePlugin;
+
+impl Plugin for AmbientDialoguePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AmbientLine>()
+            .init_resource::<AmbientChatter>()
+            .add_systems(Startup, setup_subtitle_area)
+            .add_systems(
+                Update,
+                pick_ambient_lines
+                    .in_set(GameStateSet::Playing)
+                    .before(show_subtitles),
+            )
+            .add_systems(Update, (show_subtitles, expire_subtitles).chain());
+    }
+}
+
+// Event for an NPC saying something as a subtitle, without starting a conversation
+#[derive(Event)]
+pub struct AmbientLine {
+    pub npc_entity: Entity,
+    pub text: String,
+}
+
+// Resource counting down to the next bit of ambient chatter
+#[derive(Resource)]
+struct AmbientChatter {
+    timer: Timer,
+}
+
+impl Default for AmbientChatter {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(AMBIENT_MIN_INTERVAL, TimerMode::Once),
+        }
+    }
+}
+
+// Marker for the column subtitles stack up in at the bottom of the screen
+#[derive(Component)]
+struct SubtitleArea;
+
+// A subtitle line and how long it has left on screen
+#[derive(Component)]
+struct Subtitle(Timer);
+
+fn setup_subtitle_area(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(5.0),
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(4.0),
+            ..default()
+        },
+        SubtitleArea,
+    ));
+}
+
+// Every so often, have a random NPC near the player say one of their ambient lines
+#[allow(clippy::too_many_arguments)]
+fn pick_ambient_lines(
+    time: Res<Time>,
+    mut chatter: ResMut<AmbientChatter>,
+    player_query: Query<&Transform, With<KinematicCharacterController>>,
+    npc_query: Query<(Entity, &Transform, &Npc, &Visibility)>,
+    dialogue_db: Res<DialogueDatabase>,
+    variables: Res<DialogueVariables>,
+    clock: Res<GameClock>,
+    mut rng: ResMut<NpcRng>,
+    mut lines: EventWriter<AmbientLine>,
+) {
+    if !chatter.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let rng = &mut rng.0;
+    chatter.timer = Timer::from_seconds(
+        rng.random_range(AMBIENT_MIN_INTERVAL..AMBIENT_MAX_INTERVAL),
+        TimerMode::Once,
+    );
+
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let candidates: Vec<_> = npc_query
+        .iter()
+        .filter(|(_, transform, _, visibility)| {
+            **visibility != Visibility::Hidden
+                && transform.translation.distance(player_transform.translation) <= AMBIENT_RANGE
+        })
+        .filter_map(|(entity, _, npc, _)| {
+            let tree = dialogue_db.dialogues.get(&npc.dialogue_id)?;
+            (!tree.ambient.is_empty()).then_some((entity, npc, &tree.ambient))
+        })
+        .collect();
+    if candidates.is_empty() {
+        return;
+    }
+
+    let (npc_entity, npc, ambient) = candidates[rng.random_range(0..candidates.len())];
+    let line = &ambient[rng.random_range(0..ambient.len())];
+    lines.send(AmbientLine {
+        npc_entity,
+        text: dialogue_context(npc, &clock, &variables).substitute(line),
+    });
+}
+
+fn show_subtitles(
+    mut commands: Commands,
+    mut lines: EventReader<AmbientLine>,
+    npc_query: Query<&Npc>,
+    area_query: Query<Entity, With<SubtitleArea>>,
+    subtitles: Query<(Entity, &Subtitle)>,
+) {
+    let Ok(area) = area_query.get_single() else {
+        return;
+    };
+    let mut showing: Vec<_> = subtitles
+        .iter()
+        .map(|(entity, subtitle)| (entity, subtitle.0.elapsed()))
+        .collect();
+
+    for line in lines.read() {
+        let Ok(npc) = npc_query.get(line.npc_entity) else {
+            continue;
+        };
+
+        // Make room by dropping whichever line has been up the longest
+        if showing.len() >= MAX_SUBTITLES {
+            showing.sort_by_key(|(_, elapsed)| *elapsed);
+            if let Some((oldest, _)) = showing.pop() {
+                commands.entity(oldest).despawn_recursive();
+            }
+        }
+
+        let duration =
+            SUBTITLE_BASE_DURATION + line.text.chars().count() as f32 * SUBTITLE_DURATION_PER_CHAR;
+        let subtitle = commands
+            .spawn((
+                Text::new(format!("{}: {}", npc.name, line.text)),
+                TextFont {
+                    font_size: SUBTITLE_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(SUBTITLE_TEXT_COLOR),
+                BackgroundColor(SUBTITLE_BACKGROUND_COLOR),
+                Node {
+                    padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                    ..default()
+                },
+                Subtitle(Timer::from_seconds(duration, TimerMode::Once)),
+                // Starting a proper conversation clears the chatter
+                StateScoped(GameState::Playing),
+            ))
+            .id();
+        commands.entity(area).add_child(subtitle);
+        showing.push((subtitle, default()));
+    }
+}
+
+fn expire_subtitles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut subtitles: Query<(Entity, &mut Subtitle)>,
+) {
+    for (entity, mut subtitle) in subtitles.iter_mut() {
+        if subtitle.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
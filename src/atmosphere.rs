@@ -0,0 +1,244 @@
+use crate::{
+    FloatingCube, PlayerCamera, accessibility::AccessibilitySettings, clock::GameClock,
+    graphics_settings::GraphicsSettings,
+};
+use bevy::{pbr::NotShadowCaster, prelude::*};
+use rand::Rng;
+use std::f32::consts::TAU;
+
+// Atmosphere constants
+const DUST_COUNT: usize = 240; // At full density
+const FIREFLY_COUNT: usize = 60;
+const SPARKLES_PER_CUBE: usize = 6;
+// Motes fill a box around the camera, wrapping around as it moves so there are always some nearby
+const DUST_FIELD: Vec3 = Vec3::new(24.0, 10.0, 24.0);
+const FIREFLY_FIELD: Vec3 = Vec3::new(30.0, 2.0, 30.0);
+const FIREFLY_FLOOR: f32 = 0.3; // Fireflies keep to the grass, this far up and higher
+const DUST_SIZE: f32 = 0.025;
+const FIREFLY_SIZE: f32 = 0.05;
+const SPARKLE_SIZE: f32 = 0.04;
+const DUST_DRIFT: f32 = 0.15;
+const FIREFLY_DRIFT: f32 = 0.4;
+const FIREFLY_PULSE_SPEED: f32 = 2.0;
+const NIGHT_START: f32 = 19.0; // Hours between which fireflies are out
+const NIGHT_END: f32 = 6.0;
+const SPARKLE_ORBIT: f32 = 0.9; // Just outside the cube's corners
+const SPARKLE_SPEED: f32 = 0.8;
+const SPARKLE_TWINKLE_SPEED: f32 = 5.0;
+
+pub struct AtmospherePlugin;
+
+impl Plugin for AtmospherePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_motes)
+            .add_systems(Update, (add_cube_sparkles, drift_motes, twinkle_sparkles));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum MoteKind {
+    Dust,
+    Firefly,
+}
+
+// Component for a speck of dust or a firefly drifting around the camera
+#[derive(Component)]
+struct Mote {
+    kind: MoteKind,
+    // Where it would be at time zero, before wrapping into the field
+    origin: Vec3,
+    drift: Vec3,
+    phase: f32,
+    // Shown only while the particle density is above this, so lower settings thin them out evenly
+    rank: f32,
+}
+
+// Component for a glint circling a floating cube
+#[derive(Component)]
+struct Sparkle {
+    tilt: Quat,
+    phase: f32,
+    rank: f32,
+}
+
+// Resource with the mesh and material cube sparkles share
+#[derive(Resource)]
+struct SparkleAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+fn glow_material(
+    materials: &mut Assets<StandardMaterial>,
+    color: Color,
+) -> Handle<StandardMaterial> {
+    materials.add(StandardMaterial {
+        base_color: color,
+        emissive: LinearRgba::from(color) * 2.0,
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    })
+}
+
+// Every mote shares one mesh and material per kind, so they're drawn in a single instanced batch
+fn spawn_motes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut rng = rand::rng();
+    let mesh = meshes.add(Sphere::new(1.0).mesh().uv(8, 6));
+    let dust = glow_material(&mut materials, Color::srgba(1.0, 0.95, 0.8, 0.35));
+    let firefly = glow_material(&mut materials, Color::srgb(0.8, 1.0, 0.3));
+    let kinds = [
+        (MoteKind::Dust, DUST_COUNT, DUST_SIZE, DUST_DRIFT, dust),
+        (
+            MoteKind::Firefly,
+            FIREFLY_COUNT,
+            FIREFLY_SIZE,
+            FIREFLY_DRIFT,
+            firefly,
+        ),
+    ];
+    for (kind, count, size, drift, material) in kinds {
+        for _ in 0..count {
+            let origin = Vec3::new(
+                rng.random_range(0.0..1000.0),
+                rng.random_range(0.0..1000.0),
+                rng.random_range(0.0..1000.0),
+            );
+            let drift = Vec3::new(
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-0.3..0.3),
+                rng.random_range(-1.0..1.0),
+            ) * drift;
+            commands.spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_scale(Vec3::splat(size)),
+                Visibility::Hidden,
+                NotShadowCaster,
+                Mote {
+                    kind,
+                    origin,
+                    drift,
+                    phase: rng.random_range(0.0..TAU),
+                    rank: rng.random_range(0.0..1.0),
+                },
+            ));
+        }
+    }
+
+    commands.insert_resource(SparkleAssets {
+        mesh,
+        material: glow_material(&mut materials, Color::srgb(0.9, 0.95, 1.0)),
+    });
+}
+
+fn add_cube_sparkles(
+    mut commands: Commands,
+    assets: Res<SparkleAssets>,
+    cubes: Query<Entity, Added<FloatingCube>>,
+) {
+    let mut rng = rand::rng();
+    for cube in cubes.iter() {
+        commands.entity(cube).with_children(|parent| {
+            for _ in 0..SPARKLES_PER_CUBE {
+                parent.spawn((
+                    Mesh3d(assets.mesh.clone()),
+                    MeshMaterial3d(assets.material.clone()),
+                    Transform::from_scale(Vec3::splat(SPARKLE_SIZE)),
+                    Visibility::Hidden,
+                    NotShadowCaster,
+                    Sparkle {
+                        tilt: Quat::from_euler(
+                            EulerRot::XYZ,
+                            rng.random_range(-1.0..1.0),
+                            0.0,
+                            rng.random_range(-1.0..1.0),
+                        ),
+                        phase: rng.random_range(0.0..TAU),
+                        rank: rng.random_range(0.0..1.0),
+                    },
+                ));
+            }
+        });
+    }
+}
+
+// Drift each mote along its own heading, wrapped into the field around the camera
+fn drift_motes(
+    time: Res<Time>,
+    clock: Res<GameClock>,
+    graphics: Res<GraphicsSettings>,
+    accessibility: Res<AccessibilitySettings>,
+    camera: Query<&GlobalTransform, With<PlayerCamera>>,
+    mut motes: Query<(&mut Transform, &mut Visibility, &Mote)>,
+) {
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+    let t = time.elapsed_secs() * accessibility.motion_scale();
+    let density = graphics.particle_density();
+    let night = clock.hour >= NIGHT_START || clock.hour < NIGHT_END;
+    let center = camera.translation();
+
+    for (mut transform, mut visibility, mote) in motes.iter_mut() {
+        let shown = mote.rank < density && (mote.kind == MoteKind::Dust || night);
+        visibility.set_if_neq(if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        if !shown {
+            continue;
+        }
+
+        let (field, corner, size) = match mote.kind {
+            MoteKind::Dust => (DUST_FIELD, center - DUST_FIELD / 2.0, DUST_SIZE),
+            MoteKind::Firefly => (
+                FIREFLY_FIELD,
+                (center - FIREFLY_FIELD / 2.0).with_y(FIREFLY_FLOOR),
+                FIREFLY_SIZE,
+            ),
+        };
+        // A slow wander on top of the steady drift
+        let wander = Vec3::new(
+            (t * 0.7 + mote.phase).sin(),
+            (t * 0.5 + mote.phase * 2.0).sin(),
+            (t * 0.6 + mote.phase * 3.0).cos(),
+        ) * 0.3;
+        let position = mote.origin + mote.drift * t + wander;
+        transform.translation = corner + (position - corner).rem_euclid(field);
+        if mote.kind == MoteKind::Firefly {
+            let glow = 0.5 + 0.5 * (t * FIREFLY_PULSE_SPEED + mote.phase).sin();
+            transform.scale = Vec3::splat(size * glow);
+        }
+    }
+}
+
+// Circle each sparkle around its cube on a tilted orbit, winking in and out
+fn twinkle_sparkles(
+    time: Res<Time>,
+    graphics: Res<GraphicsSettings>,
+    accessibility: Res<AccessibilitySettings>,
+    mut sparkles: Query<(&mut Transform, &mut Visibility, &Sparkle)>,
+) {
+    let t = time.elapsed_secs() * accessibility.motion_scale();
+    let density = graphics.particle_density();
+    for (mut transform, mut visibility, sparkle) in sparkles.iter_mut() {
+        if sparkle.rank >= density {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+        let angle = t * SPARKLE_SPEED + sparkle.phase;
+        transform.translation =
+            sparkle.tilt * Vec3::new(angle.cos(), 0.0, angle.sin()) * SPARKLE_ORBIT;
+        let twinkle = (t * SPARKLE_TWINKLE_SPEED + sparkle.phase * 3.0)
+            .sin()
+            .max(0.0);
+        transform.scale = Vec3::splat(SPARKLE_SIZE * twinkle);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    GameStateSet, accessibility::AccessibilitySettings, footsteps::SurfaceMaterial, player_movement,
+};
+use bevy::{audio::Volume, prelude::*};
+use bevy_rapier3d::prelude::*;
+
+// Bounce pad constants
+const PAD_RADIUS: f32 = 1.2;
+const PAD_HALF_HEIGHT: f32 = 0.1;
+const PAD_REACH: f32 = 2.0; // How far above the pad something's middle can be and still be on it
+const SQUASH_DEPTH: f32 = 0.5; // Share of its height the pad gives under a bounce
+const SQUASH_RECOVERY: f32 = 4.0; // How quickly it springs back, in whole squashes per second
+const BOUNCE_CLIP: &str = "audio/impacts/bounce.ogg";
+const BOUNCE_VOLUME: f32 = 0.6;
+// One at the foot of the ladder tower that throws the player up level with its roof, and a
+// gentler one out in the plaza
+const PADS: [(Vec3, f32); 2] = [
+    (Vec3::new(18.0, 0.0, 6.5), 14.0),
+    (Vec3::new(-4.0, 0.0, -8.0), 9.0),
+];
+
+pub struct BouncePadsPlugin;
+
+impl Plugin for BouncePadsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlayerLaunched>()
+            .add_event::<PadBounced>()
+            .add_systems(Startup, (spawn_bounce_pads, load_bounce_clip))
+            .add_systems(
+                FixedUpdate,
+                launch_from_pads
+                    .before(player_movement)
+                    .in_set(GameStateSet::Playing),
+            )
+            .add_systems(
+                Update,
+                (squash_pads, play_bounces).in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Component for a pad that launches whatever lands on it straight up
+#[derive(Component)]
+pub struct BouncePad {
+    // Upward speed it gives the player or any dynamic body that lands on it
+    pub launch_speed: f32,
+    // How squashed it is, from 1 just after a bounce back to 0 at rest
+    squash: f32,
+}
+
+impl BouncePad {
+    pub fn new(launch_speed: f32) -> Self {
+        Self {
+            launch_speed,
+            squash: 0.0,
+        }
+    }
+}
+
+// Event sent to `player_movement` when a pad throws the player up
+#[derive(Event)]
+pub struct PlayerLaunched {
+    pub speed: f32,
+}
+
+// Event sent whenever a pad bounces something, for its squash and sound
+#[derive(Event)]
+struct PadBounced(Entity);
+
+// Resource with the sound a pad makes
+#[derive(Resource)]
+struct BounceClip(Handle<AudioSource>);
+
+// Marker for the springy top of a pad, which squashes without moving its collider
+#[derive(Component)]
+struct PadSpring;
+
+fn spawn_bounce_pads(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Cylinder::new(PAD_RADIUS, PAD_HALF_HEIGHT * 2.0));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.95, 0.4, 0.6),
+        perceptual_roughness: 0.5,
+        ..default()
+    });
+    for (position, launch_speed) in PADS {
+        commands
+            .spawn((
+                Transform::from_translation(position + Vec3::Y * PAD_HALF_HEIGHT),
+                Visibility::default(),
+                Collider::cylinder(PAD_HALF_HEIGHT, PAD_RADIUS),
+                SurfaceMaterial::Stone,
+                BouncePad::new(launch_speed),
+            ))
+            .with_child((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::default(),
+                PadSpring,
+            ));
+    }
+}
+
+fn load_bounce_clip(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(BounceClip(asset_server.load(BOUNCE_CLIP)));
+}
+
+// Throw the player up off any pad they're standing on, along with any dynamic body settling on one
+fn launch_from_pads(
+    pads: Query<(Entity, &Transform, &BouncePad)>,
+    player: Query<(&Transform, &KinematicCharacterControllerOutput)>,
+    mut bodies: Query<(&GlobalTransform, &RigidBody, &mut Velocity)>,
+    mut launched: EventWriter<PlayerLaunched>,
+    mut bounced: EventWriter<PadBounced>,
+    mut player_on_pad: Local<bool>,
+) {
+    let on_pad = |pad: &Transform, position: Vec3| {
+        let offset = position - pad.translation;
+        offset.with_y(0.0).length() < PAD_RADIUS && (0.0..PAD_REACH).contains(&offset.y)
+    };
+    let player = player
+        .get_single()
+        .ok()
+        .filter(|(_, output)| output.grounded)
+        .map(|(player, _)| player.translation);
+    // Only touching down counts, not the steps spent leaving the pad after a bounce
+    let landed = !*player_on_pad;
+    *player_on_pad = false;
+    for (entity, transform, pad) in pads.iter() {
+        if let Some(player) = player
+            && on_pad(transform, player)
+        {
+            *player_on_pad = true;
+            if !landed {
+                continue;
+            }
+            launched.send(PlayerLaunched {
+                speed: pad.launch_speed,
+            });
+            bounced.send(PadBounced(entity));
+        }
+        for (body, rigid_body, mut velocity) in bodies.iter_mut() {
+            // Bodies already on their way up have been bounced
+            if *rigid_body != RigidBody::Dynamic
+                || velocity.linvel.y > 0.0
+                || !on_pad(transform, body.translation())
+            {
+                continue;
+            }
+            velocity.linvel.y = pad.launch_speed;
+            bounced.send(PadBounced(entity));
+        }
+    }
+}
+
+// Flatten each pad the moment it bounces something, then let it spring back up
+fn squash_pads(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    mut bounced: EventReader<PadBounced>,
+    mut pads: Query<&mut BouncePad>,
+    mut springs: Query<(&mut Transform, &Parent), With<PadSpring>>,
+) {
+    for PadBounced(pad) in bounced.read() {
+        if let Ok(mut pad) = pads.get_mut(*pad) {
+            // Left flat with motion reduced, the sound alone marks the bounce
+            pad.squash = accessibility.motion_scale();
+        }
+    }
+    for (mut transform, parent) in springs.iter_mut() {
+        let Ok(mut pad) = pads.get_mut(parent.get()) else {
+            continue;
+        };
+        if pad.squash <= 0.0 && transform.scale == Vec3::ONE {
+            continue;
+        }
+        pad.squash = (pad.squash - SQUASH_RECOVERY * time.delta_secs()).max(0.0);
+        let height = 1.0 - SQUASH_DEPTH * pad.squash;
+        // Bulging out a little as it's pressed down
+        let width = 1.0 + SQUASH_DEPTH * 0.5 * pad.squash;
+        transform.scale = Vec3::new(width, height, width);
+        // Pressed down from the top, keeping its base on the ground
+        transform.translation.y = -PAD_HALF_HEIGHT * (1.0 - height);
+    }
+}
+
+fn play_bounces(
+    mut commands: Commands,
+    mut bounced: EventReader<PadBounced>,
+    clip: Res<BounceClip>,
+) {
+    // Several bodies bouncing together still make one sound
+    if bounced.read().count() == 0 {
+        return;
+    }
+    commands.spawn((
+        AudioPlayer(clip.0.clone()),
+        PlaybackSettings::DESPAWN.with_volume(Volume::new(BOUNCE_VOLUME)),
+    ));
+}
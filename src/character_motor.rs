@@ -0,0 +1,433 @@
+use crate::{GameStateSet, player_movement, ron_asset::RonAssetLoader};
+use bevy::prelude::*;
+use bevy_rapier3d::{
+    control::{KinematicCharacterController, KinematicCharacterControllerOutput},
+    prelude::*,
+};
+use serde::Deserialize;
+
+// Character motor constants
+const MOTOR_CONFIG_PATH: &str = "player.motor.ron";
+// How many times a single move may be deflected off surfaces before giving up
+const MAX_SLIDE_ITERATIONS: usize = 4;
+// Moves shorter than this are treated as no movement at all
+const MIN_MOVE_DISTANCE: f32 = 1e-4;
+// Player mass used for pushing when the controller doesn't set one
+const DEFAULT_CHARACTER_MASS: f32 = 1.0;
+// How far below the character to look for what it's standing on
+const GROUND_PROBE_DISTANCE: f32 = 0.1;
+
+pub struct CharacterMotorPlugin;
+
+impl Plugin for CharacterMotorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<MotorConfig>()
+            .register_asset_loader(RonAssetLoader::<MotorConfig>::new(&["motor.ron"]))
+            .init_resource::<ActiveMotor>()
+            .init_resource::<PushCurve>()
+            .add_systems(Startup, load_motor_config)
+            .add_systems(Update, (apply_motor_config, track_pushable_mass))
+            .add_systems(
+                FixedUpdate,
+                push_dynamic_bodies
+                    .after(player_movement)
+                    .before(PhysicsSet::SyncBackend)
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Which motor drives the player, loaded from data so it can be swapped without code changes
+#[derive(Asset, TypePath, Deserialize)]
+pub struct MotorConfig {
+    pub backend: MotorBackend,
+    #[serde(default)]
+    pub push_strength: PushCurve,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub enum MotorBackend {
+    #[default]
+    Rapier,
+    CollideAndSlide,
+}
+
+impl MotorBackend {
+    fn create(self) -> Box<dyn CharacterMotor> {
+        match self {
+            MotorBackend::Rapier => Box::new(RapierMotor),
+            MotorBackend::CollideAndSlide => Box::new(CollideAndSlideMotor::default()),
+        }
+    }
+}
+
+// Resource with how hard the player shoves a dynamic body, by the player's mass over the body's,
+// as (mass ratio, strength) points sorted by ratio and blended linearly between them
+#[derive(Resource, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct PushCurve(Vec<(f32, f32)>);
+
+impl Default for PushCurve {
+    // Light crates go flying, anything as heavy as the player barely budges
+    fn default() -> Self {
+        Self(vec![(1.0, 0.0), (2.0, 0.5), (5.0, 1.0)])
+    }
+}
+
+impl PushCurve {
+    pub fn strength(&self, mass_ratio: f32) -> f32 {
+        let points = &self.0;
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            return 0.0;
+        };
+        if mass_ratio <= first.0 {
+            return first.1;
+        }
+        points
+            .windows(2)
+            .find(|pair| mass_ratio <= pair[1].0)
+            .map_or(last.1, |pair| {
+                let ((from_ratio, from), (to_ratio, to)) = (pair[0], pair[1]);
+                let blend = (mass_ratio - from_ratio) / (to_ratio - from_ratio).max(f32::EPSILON);
+                from.lerp(to, blend)
+            })
+    }
+}
+
+// Everything a motor may need to move the player for one step
+pub struct CharacterBody<'a> {
+    pub entity: Entity,
+    pub transform: &'a mut Transform,
+    pub controller: &'a mut KinematicCharacterController,
+    pub output: Option<&'a KinematicCharacterControllerOutput>,
+    pub collider: &'a Collider,
+    pub physics: &'a RapierContext<'a>,
+}
+
+impl CharacterBody<'_> {
+    // Collider under the character's feet, found by sweeping its own shape a little downward
+    pub fn ground(&self) -> Option<Entity> {
+        self.ground_hit().map(|(entity, _)| entity)
+    }
+
+    // Which way the floor under the character's feet faces
+    pub fn ground_normal(&self) -> Option<Vec3> {
+        self.ground_hit()
+            .and_then(|(_, hit)| hit.details)
+            .map(|details| details.normal1)
+    }
+
+    fn ground_hit(&self) -> Option<(Entity, ShapeCastHit)> {
+        let filter = QueryFilter::default()
+            .exclude_collider(self.entity)
+            .exclude_sensors();
+        let options = ShapeCastOptions {
+            max_time_of_impact: GROUND_PROBE_DISTANCE,
+            target_distance: 0.0,
+            stop_at_penetration: true,
+            compute_impact_geometry_on_penetration: false,
+        };
+        self.physics.cast_shape(
+            self.transform.translation,
+            self.transform.rotation,
+            Vec3::NEG_Y,
+            self.collider,
+            options,
+            filter,
+        )
+    }
+}
+
+// Something the character ran into during a move
+#[derive(Clone, Copy)]
+pub struct MotorContact {
+    pub entity: Entity,
+    // The surface's normal, pointing back out toward the character
+    pub normal: Vec3,
+}
+
+// Where a move was meant to go and what got in its way
+#[derive(Clone, Default)]
+pub struct MotorMove {
+    pub desired_translation: Vec3,
+    pub contacts: Vec<MotorContact>,
+}
+
+// Moves a character through the world; `player_movement` decides where it wants to go
+pub trait CharacterMotor: Send + Sync + 'static {
+    // Whether the character was standing on something after its last move
+    fn is_grounded(&self, body: &CharacterBody) -> bool;
+
+    // Move the character by `translation` in world space, colliding as the motor sees fit
+    fn move_by(&mut self, body: &mut CharacterBody, translation: Vec3);
+
+    // The last move that's been resolved, for shoving whatever it bumped into
+    fn last_move(&self, output: Option<&KinematicCharacterControllerOutput>) -> MotorMove;
+}
+
+// Resource holding the motor currently driving the player
+#[derive(Resource)]
+pub struct ActiveMotor {
+    pub backend: MotorBackend,
+    pub motor: Box<dyn CharacterMotor>,
+}
+
+impl Default for ActiveMotor {
+    fn default() -> Self {
+        let backend = MotorBackend::default();
+        Self {
+            backend,
+            motor: backend.create(),
+        }
+    }
+}
+
+// Hands movement to Rapier's kinematic character controller
+struct RapierMotor;
+
+impl CharacterMotor for RapierMotor {
+    fn is_grounded(&self, body: &CharacterBody) -> bool {
+        body.output.map(|o| o.grounded).unwrap_or(false)
+    }
+
+    fn move_by(&mut self, body: &mut CharacterBody, translation: Vec3) {
+        body.controller.translation = Some(translation);
+    }
+
+    // Rapier reports its collisions once physics has run, so this is the previous step's move
+    fn last_move(&self, output: Option<&KinematicCharacterControllerOutput>) -> MotorMove {
+        let Some(output) = output else {
+            return MotorMove::default();
+        };
+        MotorMove {
+            desired_translation: output.desired_translation,
+            contacts: output
+                .collisions
+                .iter()
+                .filter_map(|collision| {
+                    Some(MotorContact {
+                        entity: collision.entity,
+                        normal: collision.hit.details?.normal1,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+// Shape-casts the player's collider and slides along whatever it hits
+#[derive(Default)]
+struct CollideAndSlideMotor {
+    grounded: bool,
+    last_move: MotorMove,
+}
+
+impl CharacterMotor for CollideAndSlideMotor {
+    fn is_grounded(&self, _body: &CharacterBody) -> bool {
+        self.grounded
+    }
+
+    fn last_move(&self, _output: Option<&KinematicCharacterControllerOutput>) -> MotorMove {
+        self.last_move.clone()
+    }
+
+    fn move_by(&mut self, body: &mut CharacterBody, translation: Vec3) {
+        // Reuse the controller's tuning so both motors agree on what counts as floor
+        let skin = match body.controller.offset {
+            CharacterLength::Absolute(length) => length,
+            CharacterLength::Relative(_) => MIN_MOVE_DISTANCE,
+        };
+        let min_floor_normal_y = body.controller.max_slope_climb_angle.cos();
+        let filter = QueryFilter::default()
+            .exclude_collider(body.entity)
+            .exclude_sensors();
+
+        let mut position = body.transform.translation;
+        let mut remaining = translation;
+        let was_grounded = self.grounded;
+        self.grounded = false;
+        self.last_move = MotorMove {
+            desired_translation: translation,
+            contacts: Vec::new(),
+        };
+
+        for _ in 0..MAX_SLIDE_ITERATIONS {
+            let distance = remaining.length();
+            if distance < MIN_MOVE_DISTANCE {
+                break;
+            }
+            let direction = remaining / distance;
+            let options = ShapeCastOptions {
+                max_time_of_impact: distance,
+                target_distance: skin,
+                stop_at_penetration: false,
+                compute_impact_geometry_on_penetration: true,
+            };
+            let Some((entity, hit)) = body.physics.cast_shape(
+                position,
+                body.transform.rotation,
+                direction,
+                body.collider,
+                options,
+                filter,
+            ) else {
+                position += remaining;
+                break;
+            };
+
+            // With a unit velocity the time of impact is the distance travelled
+            position += direction * hit.time_of_impact;
+            let Some(details) = hit.details else {
+                break;
+            };
+            let normal = details.normal1;
+            if normal.y >= min_floor_normal_y {
+                self.grounded = true;
+            }
+            self.last_move
+                .contacts
+                .push(MotorContact { entity, normal });
+
+            // Drop the part of the move that pushes into the surface and keep sliding
+            remaining -= direction * hit.time_of_impact;
+            remaining -= normal * remaining.dot(normal).min(0.0);
+        }
+
+        // Stay on the floor when it drops away underneath, like walking down stairs,
+        // unless the move was meant to leave it
+        let snap_distance = match body.controller.snap_to_ground {
+            Some(CharacterLength::Absolute(length)) => length,
+            _ => 0.0,
+        };
+        if was_grounded && !self.grounded && translation.y <= 0.0 && snap_distance > 0.0 {
+            let options = ShapeCastOptions {
+                max_time_of_impact: snap_distance,
+                target_distance: skin,
+                stop_at_penetration: false,
+                compute_impact_geometry_on_penetration: true,
+            };
+            let floor = body
+                .physics
+                .cast_shape(
+                    position,
+                    body.transform.rotation,
+                    Vec3::NEG_Y,
+                    body.collider,
+                    options,
+                    filter,
+                )
+                .filter(|(_, hit)| {
+                    hit.details
+                        .is_some_and(|details| details.normal1.y >= min_floor_normal_y)
+                });
+            if let Some((_, hit)) = floor {
+                position.y -= hit.time_of_impact;
+                self.grounded = true;
+            }
+        }
+
+        body.transform.translation = position;
+    }
+}
+
+#[derive(Resource)]
+struct MotorConfigHandle(Handle<MotorConfig>);
+
+fn load_motor_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(MotorConfigHandle(asset_server.load(MOTOR_CONFIG_PATH)));
+}
+
+// Swap motors whenever the config loads or is edited
+fn apply_motor_config(
+    mut events: EventReader<AssetEvent<MotorConfig>>,
+    configs: Res<Assets<MotorConfig>>,
+    handle: Option<Res<MotorConfigHandle>>,
+    mut active_motor: ResMut<ActiveMotor>,
+    mut push_curve: ResMut<PushCurve>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(config) = configs.get(&handle.0) else {
+            continue;
+        };
+        *push_curve = config.push_strength.clone();
+        if config.backend != active_motor.backend {
+            println!("Character motor: {:?}", config.backend);
+            *active_motor = ActiveMotor {
+                backend: config.backend,
+                motor: config.backend.create(),
+            };
+        }
+    }
+}
+
+// Dynamic bodies only report their mass when asked to
+#[allow(clippy::type_complexity)]
+fn track_pushable_mass(
+    mut commands: Commands,
+    bodies: Query<(Entity, &RigidBody), (Added<RigidBody>, Without<ReadMassProperties>)>,
+) {
+    for (entity, body) in bodies.iter() {
+        if *body == RigidBody::Dynamic {
+            commands
+                .entity(entity)
+                .insert(ReadMassProperties::default());
+        }
+    }
+}
+
+// Shove whatever dynamic bodies the player walked into, scaled by the push curve
+fn push_dynamic_bodies(
+    mut commands: Commands,
+    time: Res<Time>,
+    push_curve: Res<PushCurve>,
+    active_motor: Res<ActiveMotor>,
+    player: Query<(
+        &KinematicCharacterController,
+        Option<&KinematicCharacterControllerOutput>,
+    )>,
+    bodies: Query<(&RigidBody, &ReadMassProperties)>,
+) {
+    let Ok((controller, output)) = player.get_single() else {
+        return;
+    };
+    let delta_time = time.delta_secs();
+    if delta_time <= 0.0 {
+        return;
+    }
+    let character_mass = controller.custom_mass.unwrap_or(DEFAULT_CHARACTER_MASS);
+    let last_move = active_motor.motor.last_move(output);
+    let desired_velocity = last_move.desired_translation / delta_time;
+
+    let mut pushed = Vec::new();
+    for contact in last_move.contacts {
+        // A move can hit the same body more than once while sliding along it
+        if pushed.contains(&contact.entity) {
+            continue;
+        }
+        let Ok((RigidBody::Dynamic, mass)) = bodies.get(contact.entity) else {
+            continue;
+        };
+        // Away from the character, and only sideways, standing on a crate shouldn't drive it into
+        // the floor
+        let direction = -contact.normal.with_y(0.0).normalize_or_zero();
+        let speed = desired_velocity.dot(direction).max(0.0);
+        if mass.mass <= 0.0 || speed <= 0.0 {
+            continue;
+        }
+        let strength = push_curve.strength(character_mass / mass.mass);
+        if strength <= 0.0 {
+            continue;
+        }
+        pushed.push(contact.entity);
+        commands.entity(contact.entity).insert(ExternalImpulse {
+            impulse: direction * speed * mass.mass * strength,
+            ..default()
+        });
+    }
+}
@@ -0,0 +1,47 @@
+use crate::character_motor::PushCurve;
+
+const TOLERANCE: f32 = 1e-5;
+
+fn assert_strength(curve: &PushCurve, mass_ratio: f32, expected: f32) {
+    let strength = curve.strength(mass_ratio);
+    assert!(
+        (strength - expected).abs() < TOLERANCE,
+        "strength at mass ratio {mass_ratio} was {strength}, expected {expected}"
+    );
+}
+
+#[test]
+fn default_push_curve_blends_between_points() {
+    let curve = PushCurve::default();
+    // Exactly on each point
+    assert_strength(&curve, 1.0, 0.0);
+    assert_strength(&curve, 2.0, 0.5);
+    assert_strength(&curve, 5.0, 1.0);
+    // Halfway along each segment
+    assert_strength(&curve, 1.5, 0.25);
+    assert_strength(&curve, 3.5, 0.75);
+}
+
+#[test]
+fn push_curve_holds_its_ends() {
+    let curve = PushCurve::default();
+    // Bodies heavier than the player don't budge, and the lightest go no faster than the last point
+    assert_strength(&curve, 0.1, 0.0);
+    assert_strength(&curve, 50.0, 1.0);
+}
+
+#[test]
+fn push_curve_from_config() {
+    let curve: PushCurve = ron::from_str("[(0.5, 0.2), (1.0, 0.2), (4.0, 0.8)]").unwrap();
+    assert_strength(&curve, 0.0, 0.2);
+    assert_strength(&curve, 0.75, 0.2);
+    assert_strength(&curve, 2.5, 0.5);
+    assert_strength(&curve, 10.0, 0.8);
+
+    let single: PushCurve = ron::from_str("[(2.0, 0.6)]").unwrap();
+    assert_strength(&single, 1.0, 0.6);
+    assert_strength(&single, 3.0, 0.6);
+
+    let empty: PushCurve = ron::from_str("[]").unwrap();
+    assert_strength(&empty, 2.0, 0.0);
+}
@@ -0,0 +1,54 @@
+use crate::GameStateSet;
+use bevy::prelude::*;
+
+// Clock constants
+const GAME_HOURS_PER_SECOND: f32 = 1.0 / 60.0; // One in-game hour per real minute
+const START_HOUR: f32 = 8.0;
+pub const DAYS_PER_WEEK: u32 = 7;
+
+pub struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameClock>()
+            .add_systems(First, advance_clock.in_set(GameStateSet::Playing));
+    }
+}
+
+// Resource tracking the in-game calendar
+#[derive(Resource)]
+pub struct GameClock {
+    pub day: u32,
+    pub hour: f32,
+    pub hours_per_second: f32,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self {
+            day: 0,
+            hour: START_HOUR,
+            hours_per_second: GAME_HOURS_PER_SECOND,
+        }
+    }
+}
+
+impl GameClock {
+    // Rough part of the day, for dialogue and other flavor text
+    pub fn time_of_day(&self) -> &'static str {
+        match self.hour {
+            hour if (5.0..12.0).contains(&hour) => "morning",
+            hour if (12.0..17.0).contains(&hour) => "afternoon",
+            hour if (17.0..21.0).contains(&hour) => "evening",
+            _ => "night",
+        }
+    }
+}
+
+fn advance_clock(time: Res<Time>, mut clock: ResMut<GameClock>) {
+    clock.hour += time.delta_secs() * clock.hours_per_second;
+    while clock.hour >= 24.0 {
+        clock.hour -= 24.0;
+        clock.day += 1;
+    }
+}
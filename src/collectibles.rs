@@ -0,0 +1,295 @@
+use crate::{
+    GameState, GameStateSet,
+    accessibility::AccessibilitySettings,
+    level::{LevelConfig, LevelConfigHandle},
+    particles::ParticleBurst,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::control::KinematicCharacterController;
+use serde::Deserialize;
+use std::{collections::HashMap, f32::consts::FRAC_PI_2};
+
+// Collectible constants
+const PICKUP_RADIUS: f32 = 1.0; // From the middle of the player's capsule
+const BOB_HEIGHT: f32 = 0.15;
+const BOB_SPEED: f32 = 2.0;
+const SPIN_SPEED: f32 = 1.5; // Radians per second
+const COUNTER_MARGIN: f32 = 16.0;
+const COUNTER_FONT_SIZE: f32 = 16.0;
+const COUNTER_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const COUNTER_BACKGROUND_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.7);
+
+pub struct CollectiblesPlugin;
+
+impl Plugin for CollectiblesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Collection>()
+            .add_event::<CollectiblePickedUp>()
+            .add_systems(Startup, setup_collectible_assets)
+            .add_systems(OnEnter(GameState::Playing), setup_collection_counter)
+            .add_systems(Update, (place_collectibles, animate_collectibles))
+            .add_systems(
+                Update,
+                (
+                    pick_up_collectibles,
+                    burst_picked_up,
+                    update_collection_counter,
+                )
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// What sort of thing a collectible is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum CollectibleKind {
+    Coin,
+    Datapad,
+}
+
+impl CollectibleKind {
+    const ALL: [CollectibleKind; 2] = [CollectibleKind::Coin, CollectibleKind::Datapad];
+
+    fn label(self) -> &'static str {
+        match self {
+            CollectibleKind::Coin => "Coins",
+            CollectibleKind::Datapad => "Datapads",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            CollectibleKind::Coin => Color::srgb(1.0, 0.8, 0.2),
+            CollectibleKind::Datapad => Color::srgb(0.3, 0.9, 1.0),
+        }
+    }
+}
+
+// Where a collectible sits in the level, from the level asset
+#[derive(Deserialize)]
+pub struct CollectiblePlacement {
+    pub kind: CollectibleKind,
+    pub position: Vec3,
+}
+
+// Component for an item waiting to be picked up
+#[derive(Component)]
+struct Collectible {
+    kind: CollectibleKind,
+    // Where it hovers, before bobbing
+    home: Vec3,
+}
+
+// Event sent when the player picks something up
+#[derive(Event)]
+pub struct CollectiblePickedUp {
+    pub kind: CollectibleKind,
+    pub position: Vec3,
+}
+
+// Resource counting how many of each kind the player has found, out of how many the level holds
+#[derive(Resource, Default)]
+pub struct Collection {
+    found: HashMap<CollectibleKind, u32>,
+    total: HashMap<CollectibleKind, u32>,
+}
+
+impl Collection {
+    pub fn found(&self, kind: CollectibleKind) -> u32 {
+        self.found.get(&kind).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self, kind: CollectibleKind) -> u32 {
+        self.total.get(&kind).copied().unwrap_or(0)
+    }
+}
+
+// Resource with the mesh and material each kind is drawn with
+#[derive(Resource)]
+struct CollectibleAssets(HashMap<CollectibleKind, (Handle<Mesh>, Handle<StandardMaterial>)>);
+
+// Marker for the HUD counter
+#[derive(Component)]
+struct CollectionCounter;
+
+fn setup_collectible_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let assets = CollectibleKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let mesh = match kind {
+                // Standing on its edge so it spins like a coin
+                CollectibleKind::Coin => Cylinder::new(0.3, 0.06)
+                    .mesh()
+                    .build()
+                    .rotated_by(Quat::from_rotation_x(FRAC_PI_2)),
+                CollectibleKind::Datapad => Cuboid::new(0.4, 0.55, 0.05).into(),
+            };
+            let material = StandardMaterial {
+                base_color: kind.color(),
+                emissive: LinearRgba::from(kind.color()) * 0.5,
+                metallic: 0.8,
+                perceptual_roughness: 0.3,
+                ..default()
+            };
+            (kind, (meshes.add(mesh), materials.add(material)))
+        })
+        .collect();
+    commands.insert_resource(CollectibleAssets(assets));
+}
+
+// Put the level's collectibles out whenever it's loaded, or edited while running
+fn place_collectibles(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelConfig>>,
+    configs: Res<Assets<LevelConfig>>,
+    handle: Option<Res<LevelConfigHandle>>,
+    assets: Res<CollectibleAssets>,
+    mut collection: ResMut<Collection>,
+    placed: Query<Entity, With<Collectible>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(config) = configs.get(&handle.0) else {
+            continue;
+        };
+        for entity in placed.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        *collection = Collection::default();
+        for placement in &config.collectibles {
+            let Some((mesh, material)) = assets.0.get(&placement.kind) else {
+                continue;
+            };
+            *collection.total.entry(placement.kind).or_default() += 1;
+            commands.spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(placement.position),
+                Collectible {
+                    kind: placement.kind,
+                    home: placement.position,
+                },
+            ));
+        }
+    }
+}
+
+// Bob up and down and turn slowly, each a little out of step with the rest
+fn animate_collectibles(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    mut collectibles: Query<(&mut Transform, &Collectible)>,
+) {
+    let t = time.elapsed_secs();
+    let scale = accessibility.motion_scale();
+    for (mut transform, collectible) in collectibles.iter_mut() {
+        let phase = collectible.home.x + collectible.home.z;
+        transform.translation.y =
+            collectible.home.y + BOB_HEIGHT * scale * (BOB_SPEED * t + phase).sin();
+        transform.rotation = Quat::from_rotation_y(SPIN_SPEED * scale * t + phase);
+    }
+}
+
+fn pick_up_collectibles(
+    mut commands: Commands,
+    player: Query<&Transform, With<KinematicCharacterController>>,
+    collectibles: Query<(Entity, &Transform, &Collectible)>,
+    mut collection: ResMut<Collection>,
+    mut picked_up: EventWriter<CollectiblePickedUp>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    for (entity, transform, collectible) in collectibles.iter() {
+        if transform.translation.distance(player.translation) > PICKUP_RADIUS {
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+        *collection.found.entry(collectible.kind).or_default() += 1;
+        picked_up.send(CollectiblePickedUp {
+            kind: collectible.kind,
+            position: transform.translation,
+        });
+    }
+}
+
+// A glint in the item's own color where it was picked up
+fn burst_picked_up(
+    mut picked_up: EventReader<CollectiblePickedUp>,
+    mut bursts: EventWriter<ParticleBurst>,
+) {
+    for item in picked_up.read() {
+        bursts.send(ParticleBurst {
+            position: item.position,
+            count: 10,
+            color: item.kind.color(),
+            speed: 2.5,
+            lifetime: 0.5,
+            size: 0.06,
+        });
+    }
+}
+
+fn setup_collection_counter(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: COUNTER_FONT_SIZE,
+            ..default()
+        },
+        TextColor(COUNTER_TEXT_COLOR),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(COUNTER_MARGIN),
+            top: Val::Px(COUNTER_MARGIN),
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(COUNTER_BACKGROUND_COLOR),
+        BorderRadius::all(Val::Px(4.0)),
+        Visibility::Hidden,
+        StateScoped(GameState::Playing),
+        CollectionCounter,
+    ));
+}
+
+// Show a line for each kind the level has any of, hiding the counter when it has none
+fn update_collection_counter(
+    collection: Res<Collection>,
+    mut counter: Query<(&mut Text, &mut Visibility), With<CollectionCounter>>,
+) {
+    let Ok((mut text, mut visibility)) = counter.get_single_mut() else {
+        return;
+    };
+    let lines: Vec<String> = CollectibleKind::ALL
+        .into_iter()
+        .filter(|kind| collection.total(*kind) > 0)
+        .map(|kind| {
+            format!(
+                "{} {}/{}",
+                kind.label(),
+                collection.found(kind),
+                collection.total(kind)
+            )
+        })
+        .collect();
+    *visibility = if lines.is_empty() {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    let label = lines.join("\n");
+    if text.0 != label {
+        text.0 = label;
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    ActiveDialogue, GameState, GameStateSet, Npc,
+    dialogue_variables::{DialogueValue, DialogueVariables},
+    quests::{DialogueAction, DialogueActionTriggered},
+    setup_dialogue_ui, update_npcs,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use std::collections::VecDeque;
+
+// Companion constants
+const FOLLOW_DISTANCE: f32 = 2.5; // How far behind the player a follower stays
+const BREADCRUMB_SPACING: f32 = 0.75; // Distance the player walks between trail points
+const BREADCRUMB_REACHED: f32 = 0.3;
+const FOLLOW_SPEED: f32 = 4.5;
+const CATCH_UP_SPEED: f32 = 8.0; // Used once a follower is twice its distance behind
+const TELEPORT_DISTANCE: f32 = 30.0; // Left further behind than this, a follower just reappears
+const FOLLOW_TURN_SPEED: f32 = 8.0;
+
+pub struct CompanionsPlugin;
+
+impl Plugin for CompanionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::InDialogue),
+            expose_following.before(setup_dialogue_ui),
+        )
+        .add_systems(Update, apply_follow_actions)
+        .add_systems(
+            FixedUpdate,
+            follow_player
+                .before(update_npcs)
+                .before(PhysicsSet::SyncBackend)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// Component on an NPC walking the player's route a few steps behind them
+#[derive(Component, Default)]
+pub struct Following {
+    // Where the player has been, oldest first
+    trail: VecDeque<Vec3>,
+}
+
+// Dialogue only offers to dismiss the NPC you're talking to if it's actually following
+fn expose_following(
+    active_dialogue: Query<&ActiveDialogue>,
+    npcs: Query<Has<Following>, With<Npc>>,
+    mut variables: ResMut<DialogueVariables>,
+) {
+    let Ok(active_dialogue) = active_dialogue.get_single() else {
+        return;
+    };
+    let following = npcs.get(active_dialogue.npc_entity).unwrap_or_default();
+    variables.set("npc_following", DialogueValue::Bool(following));
+}
+
+fn apply_follow_actions(
+    mut commands: Commands,
+    mut events: EventReader<DialogueActionTriggered>,
+    mut npcs: Query<&mut Npc>,
+) {
+    for event in events.read() {
+        match event.action {
+            DialogueAction::StartFollowing => {
+                commands
+                    .entity(event.npc_entity)
+                    .insert(Following::default());
+            }
+            DialogueAction::StopFollowing => {
+                commands.entity(event.npc_entity).remove::<Following>();
+                // Head back home and pick up wandering from there
+                if let Ok(mut npc) = npcs.get_mut(event.npc_entity) {
+                    npc.target_position = npc.home_position;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Walk along the player's trail rather than straight at them, so followers go around what the player went around
+pub fn follow_player(
+    time: Res<Time>,
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<Npc>)>,
+    mut followers: Query<(&mut Transform, &mut Following), With<Npc>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let delta_time = time.delta_secs();
+    for (mut transform, mut following) in followers.iter_mut() {
+        // Followers only steer across the ground, and `ground_npcs` takes care of height
+        let position = transform.translation.with_y(0.0);
+        let player_position = player.translation.with_y(0.0);
+        let distance = position.distance(player_position);
+
+        if distance > TELEPORT_DISTANCE {
+            // Reappear level with the player, then drop onto whatever they're standing on
+            let behind = player.back().as_vec3().with_y(0.0).normalize_or_zero();
+            transform.translation = player.translation + behind * FOLLOW_DISTANCE;
+            following.trail.clear();
+            continue;
+        }
+
+        if following
+            .trail
+            .back()
+            .is_none_or(|last| last.distance(player_position) >= BREADCRUMB_SPACING)
+        {
+            following.trail.push_back(player_position);
+        }
+        while following
+            .trail
+            .front()
+            .is_some_and(|crumb| crumb.distance(position) <= BREADCRUMB_REACHED)
+        {
+            following.trail.pop_front();
+        }
+
+        if distance <= FOLLOW_DISTANCE {
+            continue;
+        }
+        let Some(&next) = following.trail.front() else {
+            continue;
+        };
+        let direction = (next - position).normalize_or_zero();
+        let speed = if distance > FOLLOW_DISTANCE * 2.0 {
+            CATCH_UP_SPEED
+        } else {
+            FOLLOW_SPEED
+        };
+        transform.translation += direction * speed * delta_time;
+        if direction != Vec3::ZERO {
+            let facing = Quat::from_rotation_y(f32::atan2(direction.x, direction.z));
+            let blend = (FOLLOW_TURN_SPEED * delta_time).min(1.0);
+            transform.rotation = transform.rotation.slerp(facing, blend);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    GRAVITY, GameStateSet, MovementInput, PLAYER_BORDER_RADIUS, PLAYER_EYE_HEIGHT,
+    PLAYER_HALF_HEIGHT, PLAYER_RADIUS, PlayerCamera,
+    input_map::{ActionState, InputAction},
+    movement_tuning::MovementTuning,
+    player_movement,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+// Crouch constants
+const CROUCH_HALF_HEIGHT: f32 = 0.45;
+const CROUCH_SPEED_SCALE: f32 = 0.4;
+const CROUCH_CAMERA_SPEED: f32 = 10.0; // How quickly the camera follows the new height
+const SLIDE_DECELERATION: f32 = 8.0;
+const SLIDE_MIN_SPEED: f32 = 3.0; // Slides slower than this settle into a crouch
+
+pub struct CrouchPlugin;
+
+impl Plugin for CrouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            update_crouch
+                .before(player_movement)
+                .in_set(GameStateSet::Playing),
+        )
+        .add_systems(Update, crouch_camera.in_set(GameStateSet::Playing));
+    }
+}
+
+// Component on the player tracking whether they're ducking
+#[derive(Component, Default)]
+pub struct Crouch {
+    crouched: bool,
+    // Momentum carried through a crouch-slide, in world space
+    slide: Option<Vec3>,
+}
+
+impl Crouch {
+    pub fn slide_velocity(&self) -> Option<Vec3> {
+        self.slide
+    }
+
+    pub fn stop_slide(&mut self) {
+        self.slide = None;
+    }
+
+    // Slow the slide down, trading any height it loses down the slope under it for speed, and
+    // end it once it's a crawl
+    pub fn update_slide(&mut self, delta_time: f32, ground_normal: Option<Vec3>) {
+        let Some(velocity) = self.slide else {
+            return;
+        };
+        // Taken from the floor itself, so the capsule shrinking or being moved by a teleporter or
+        // lift doesn't count as sliding downhill
+        let drop = ground_normal
+            .filter(|normal| normal.y > 0.0)
+            .map_or(0.0, |normal| {
+                normal.with_y(0.0).dot(velocity) * delta_time / normal.y
+            });
+        let speed = velocity.length();
+        let speed = (speed * speed - 2.0 * GRAVITY * drop).max(0.0).sqrt()
+            - SLIDE_DECELERATION * delta_time;
+        self.slide = (speed > SLIDE_MIN_SPEED).then(|| velocity.normalize_or_zero() * speed);
+    }
+
+    pub fn is_crouched(&self) -> bool {
+        self.crouched
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        if self.crouched {
+            CROUCH_SPEED_SCALE
+        } else {
+            1.0
+        }
+    }
+
+    // How far the capsule's center drops when crouching, with the feet staying put
+    fn drop() -> f32 {
+        PLAYER_HALF_HEIGHT - CROUCH_HALF_HEIGHT
+    }
+
+    // Distance from the capsule's center down to the feet
+    pub fn feet_offset(&self) -> f32 {
+        self.half_height() + PLAYER_BORDER_RADIUS
+    }
+
+    fn half_height(&self) -> f32 {
+        if self.crouched {
+            CROUCH_HALF_HEIGHT
+        } else {
+            PLAYER_HALF_HEIGHT
+        }
+    }
+
+    fn collider(&self) -> Collider {
+        Collider::round_cylinder(self.half_height(), PLAYER_RADIUS, PLAYER_BORDER_RADIUS)
+    }
+
+    // Camera height above the capsule's center, so the eyes sit the same distance below the top
+    fn eye_height(&self) -> f32 {
+        if self.crouched {
+            PLAYER_EYE_HEIGHT - Crouch::drop()
+        } else {
+            PLAYER_EYE_HEIGHT
+        }
+    }
+}
+
+fn update_crouch(
+    actions: Res<ActionState>,
+    input: Res<MovementInput>,
+    tuning: Res<MovementTuning>,
+    rapier_context: ReadRapierContext,
+    mut player: Query<(Entity, &mut Transform, &mut Collider, &mut Crouch)>,
+) {
+    let Ok((entity, mut transform, mut collider, mut crouch)) = player.get_single_mut() else {
+        return;
+    };
+    let wants_crouch = actions.pressed(InputAction::Crouch);
+    if wants_crouch == crouch.crouched {
+        return;
+    }
+
+    if !wants_crouch {
+        // Only stand if the full-height capsule has room above the crouched one
+        let physics = rapier_context.single();
+        let filter = QueryFilter::default()
+            .exclude_collider(entity)
+            .exclude_sensors();
+        let options = ShapeCastOptions {
+            max_time_of_impact: Crouch::drop() * 2.0,
+            target_distance: 0.0,
+            stop_at_penetration: false,
+            compute_impact_geometry_on_penetration: false,
+        };
+        let blocked = physics
+            .cast_shape(
+                transform.translation,
+                transform.rotation,
+                Vec3::Y,
+                &collider,
+                options,
+                filter,
+            )
+            .is_some();
+        if blocked {
+            return;
+        }
+    }
+
+    crouch.crouched = wants_crouch;
+    *collider = crouch.collider();
+    // Ducking mid-sprint turns the run into a slide, standing up ends it
+    let horizontal = input.with_y(0.0);
+    crouch.slide =
+        (wants_crouch && actions.pressed(InputAction::Sprint) && horizontal != Vec3::ZERO)
+            .then(|| transform.rotation * horizontal * tuning.move_speed);
+    // Keep the feet where they were as the capsule changes size
+    if crouch.crouched {
+        transform.translation.y -= Crouch::drop();
+    } else {
+        transform.translation.y += Crouch::drop();
+    }
+}
+
+fn crouch_camera(
+    time: Res<Time>,
+    player: Query<(&Crouch, &Children)>,
+    mut cameras: Query<&mut Transform, With<PlayerCamera>>,
+) {
+    let Ok((crouch, children)) = player.get_single() else {
+        return;
+    };
+    let target = crouch.eye_height();
+    let blend = (CROUCH_CAMERA_SPEED * time.delta_secs()).min(1.0);
+    let mut cameras = cameras.iter_many_mut(children);
+    while let Some(mut transform) = cameras.fetch_next() {
+        transform.translation.y = transform.translation.y.lerp(target, blend);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    GameStateSet,
+    accessibility::AccessibilitySettings,
+    interpolation::TransformInterpolation,
+    particles::ParticleBurst,
+    platforms::{PlatformMotion, Riding},
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use rand::Rng;
+
+// Crumbling platform constants
+const CRUMBLE_HALF_EXTENTS: Vec3 = Vec3::new(1.0, 0.15, 1.0);
+const CRUMBLE_DELAY: f32 = 0.8; // Seconds of shaking before it gives way
+const SHAKE_AMOUNT: f32 = 0.06;
+const FALL_GRAVITY: f32 = 15.0;
+const FALL_TIME: f32 = 1.5; // Seconds it's seen falling before it's gone
+const RESPAWN_DELAY: f32 = 4.0;
+const DUST_COLOR: Color = Color::srgb(0.6, 0.55, 0.45);
+// Stepping stones off the top of the north-east stairs, each a little lower than the last
+const CRUMBLE_PATH: [Vec3; 5] = [
+    Vec3::new(37.0, 11.85, 37.0),
+    Vec3::new(33.5, 11.6, 37.0),
+    Vec3::new(30.0, 11.35, 37.0),
+    Vec3::new(26.5, 11.1, 37.0),
+    Vec3::new(23.0, 10.85, 37.0),
+];
+
+pub struct CrumblingPlatformsPlugin;
+
+impl Plugin for CrumblingPlatformsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_crumbling_path).add_systems(
+            Update,
+            (start_crumbling, crumble_platforms, shake_platforms)
+                .chain()
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CrumblePhase {
+    Solid,
+    // Seconds since the player first stood on it
+    Shaking(f32),
+    // Seconds since it gave way
+    Falling(f32),
+    // Seconds since it vanished, until it's back where it started
+    Gone(f32),
+}
+
+// Component for a platform that shakes and collapses a moment after the player stands on it,
+// coming back after a while
+#[derive(Component)]
+#[require(
+    RigidBody(|| RigidBody::KinematicVelocityBased),
+    PlatformMotion,
+    TransformInterpolation
+)]
+pub struct CrumblingPlatform {
+    home: Vec3,
+    phase: CrumblePhase,
+}
+
+impl CrumblingPlatform {
+    pub fn new(home: Vec3) -> Self {
+        Self {
+            home,
+            phase: CrumblePhase::Solid,
+        }
+    }
+}
+
+// Marker for the mesh of a crumbling platform, which shakes without moving its collider
+#[derive(Component)]
+struct CrumbleMesh;
+
+fn spawn_crumbling_path(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Cuboid::from_size(CRUMBLE_HALF_EXTENTS * 2.0));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.6, 0.5, 0.4),
+        perceptual_roughness: 0.95,
+        ..default()
+    });
+    for position in CRUMBLE_PATH {
+        commands
+            .spawn((
+                Transform::from_translation(position),
+                Visibility::default(),
+                Collider::cuboid(
+                    CRUMBLE_HALF_EXTENTS.x,
+                    CRUMBLE_HALF_EXTENTS.y,
+                    CRUMBLE_HALF_EXTENTS.z,
+                ),
+                CrumblingPlatform::new(position),
+            ))
+            .with_child((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::default(),
+                CrumbleMesh,
+            ));
+    }
+}
+
+fn start_crumbling(
+    player: Query<&Riding, With<KinematicCharacterController>>,
+    mut platforms: Query<&mut CrumblingPlatform>,
+) {
+    if let Ok(riding) = player.get_single()
+        && let Ok(mut platform) = platforms.get_mut(riding.0)
+        && platform.phase == CrumblePhase::Solid
+    {
+        platform.phase = CrumblePhase::Shaking(0.0);
+    }
+}
+
+// Give way once the shaking's done, drop out of sight, then come back where it started
+fn crumble_platforms(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut platforms: Query<(
+        Entity,
+        &mut Transform,
+        &mut Velocity,
+        &mut Visibility,
+        &mut CrumblingPlatform,
+    )>,
+    mut bursts: EventWriter<ParticleBurst>,
+) {
+    let delta_time = time.delta_secs();
+    for (entity, mut transform, mut velocity, mut visibility, mut platform) in platforms.iter_mut()
+    {
+        platform.phase = match platform.phase {
+            CrumblePhase::Solid => CrumblePhase::Solid,
+            CrumblePhase::Shaking(elapsed) if elapsed + delta_time < CRUMBLE_DELAY => {
+                CrumblePhase::Shaking(elapsed + delta_time)
+            }
+            CrumblePhase::Shaking(_) => {
+                // Nothing stands on it from here, so whoever was on it drops too
+                commands.entity(entity).insert(ColliderDisabled);
+                bursts.send(ParticleBurst {
+                    position: transform.translation,
+                    count: 16,
+                    color: DUST_COLOR,
+                    speed: 2.0,
+                    lifetime: 0.7,
+                    size: 0.1,
+                });
+                CrumblePhase::Falling(0.0)
+            }
+            CrumblePhase::Falling(elapsed) if elapsed + delta_time < FALL_TIME => {
+                velocity.linvel.y -= FALL_GRAVITY * delta_time;
+                CrumblePhase::Falling(elapsed + delta_time)
+            }
+            CrumblePhase::Falling(_) => {
+                velocity.linvel = Vec3::ZERO;
+                *visibility = Visibility::Hidden;
+                CrumblePhase::Gone(0.0)
+            }
+            CrumblePhase::Gone(elapsed) if elapsed + delta_time < RESPAWN_DELAY => {
+                CrumblePhase::Gone(elapsed + delta_time)
+            }
+            // Put back outside the fixed steps, so it appears there rather than sliding up
+            CrumblePhase::Gone(_) => {
+                transform.translation = platform.home;
+                *visibility = Visibility::Inherited;
+                commands.entity(entity).remove::<ColliderDisabled>();
+                CrumblePhase::Solid
+            }
+        };
+    }
+}
+
+// Rattle the mesh harder the closer it is to giving way
+fn shake_platforms(
+    accessibility: Res<AccessibilitySettings>,
+    platforms: Query<&CrumblingPlatform>,
+    mut meshes: Query<(&mut Transform, &Parent), With<CrumbleMesh>>,
+) {
+    let mut rng = rand::rng();
+    for (mut transform, parent) in meshes.iter_mut() {
+        let Ok(platform) = platforms.get(parent.get()) else {
+            continue;
+        };
+        let CrumblePhase::Shaking(elapsed) = platform.phase else {
+            transform.translation = Vec3::ZERO;
+            continue;
+        };
+        let amount = SHAKE_AMOUNT * (elapsed / CRUMBLE_DELAY) * accessibility.motion_scale();
+        transform.translation = Vec3::new(
+            rng.random_range(-amount..=amount),
+            0.0,
+            rng.random_range(-amount..=amount),
+        );
+    }
+}
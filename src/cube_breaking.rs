@@ -0,0 +1,227 @@
+use crate::{
+    FloatingCube, GameStateSet, PlayerCamera,
+    dialogue_variables::{DialogueValue, DialogueVariables},
+    input_map::{ActionState, InputAction, controls_menu_closed},
+    particles::ParticleBurst,
+    platforms::Riding,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use rand::Rng;
+
+// Cube breaking constants
+const CUBE_HITS: u32 = 3; // Stomps or punches a cube takes before it shatters
+const PUNCH_REACH: f32 = 2.5;
+const PUNCH_COOLDOWN: f32 = 0.4;
+const DEBRIS_SIZE: f32 = 0.4; // One piece per octant of the cube, with a little gap between them
+const DEBRIS_SPEED: f32 = 3.0;
+const DEBRIS_LIFETIME: f32 = 6.0;
+const HIT_COLOR: Color = Color::srgb(0.9, 0.9, 1.0);
+
+pub struct CubeBreakingPlugin;
+
+impl Plugin for CubeBreakingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CubeHit>()
+            .add_event::<CubeBroken>()
+            .add_systems(
+                Update,
+                (
+                    (stomp_cubes, punch_cubes.run_if(controls_menu_closed)),
+                    damage_cubes,
+                    (burst_broken_cubes, record_broken_cubes, despawn_debris),
+                )
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Component counting down the hits a cube can take
+#[derive(Component)]
+pub struct Breakable {
+    pub hits_left: u32,
+}
+
+impl Default for Breakable {
+    fn default() -> Self {
+        Self {
+            hits_left: CUBE_HITS,
+        }
+    }
+}
+
+// Event sent when the player lands on or punches a cube
+#[derive(Event)]
+struct CubeHit(Entity);
+
+// Event sent when a cube shatters
+#[derive(Event)]
+pub struct CubeBroken {
+    pub position: Vec3,
+}
+
+// Component on a fragment of a broken cube, cleared away after a while
+#[derive(Component)]
+struct Debris(Timer);
+
+// Each landing on a cube is a stomp, since riding it only starts once the player touches down
+fn stomp_cubes(
+    player: Query<&Riding, (With<KinematicCharacterController>, Changed<Riding>)>,
+    cubes: Query<(), With<FloatingCube>>,
+    mut hits: EventWriter<CubeHit>,
+) {
+    if let Ok(riding) = player.get_single()
+        && cubes.contains(riding.0)
+    {
+        hits.send(CubeHit(riding.0));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn punch_cubes(
+    time: Res<Time>,
+    actions: Res<ActionState>,
+    rapier_context: ReadRapierContext,
+    player: Query<Entity, With<KinematicCharacterController>>,
+    camera: Query<&GlobalTransform, With<PlayerCamera>>,
+    cubes: Query<(), With<FloatingCube>>,
+    mut hits: EventWriter<CubeHit>,
+    mut cooldown: Local<f32>,
+) {
+    *cooldown -= time.delta_secs();
+    if !actions.just_pressed(InputAction::Attack) || *cooldown > 0.0 {
+        return;
+    }
+    *cooldown = PUNCH_COOLDOWN;
+    let (Ok(player), Ok(camera)) = (player.get_single(), camera.get_single()) else {
+        return;
+    };
+
+    let physics = rapier_context.single();
+    let filter = QueryFilter::default()
+        .exclude_collider(player)
+        .exclude_sensors();
+    let hit = physics.cast_ray(
+        camera.translation(),
+        *camera.forward(),
+        PUNCH_REACH,
+        true,
+        filter,
+    );
+    if let Some((cube, _)) = hit.filter(|(entity, _)| cubes.contains(*entity)) {
+        hits.send(CubeHit(cube));
+    }
+}
+
+// Chip away at each cube that's hit, and shatter it into debris on the last hit
+fn damage_cubes(
+    mut commands: Commands,
+    mut hits: EventReader<CubeHit>,
+    mut cubes: Query<(
+        &Transform,
+        &Velocity,
+        &MeshMaterial3d<StandardMaterial>,
+        &mut Breakable,
+    )>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut bursts: EventWriter<ParticleBurst>,
+    mut broken: EventWriter<CubeBroken>,
+) {
+    let mut rng = rand::rng();
+    let mut debris_mesh = None;
+    for CubeHit(cube) in hits.read() {
+        let Ok((transform, velocity, material, mut breakable)) = cubes.get_mut(*cube) else {
+            continue;
+        };
+        // A cube can be hit twice in a frame, but only shatters once
+        if breakable.hits_left == 0 {
+            continue;
+        }
+        breakable.hits_left -= 1;
+        bursts.send(ParticleBurst {
+            position: transform.translation,
+            count: 6,
+            color: HIT_COLOR,
+            speed: 2.0,
+            lifetime: 0.4,
+            size: 0.06,
+        });
+        if breakable.hits_left > 0 {
+            continue;
+        }
+
+        let mesh = debris_mesh
+            .get_or_insert_with(|| meshes.add(Cuboid::from_length(DEBRIS_SIZE)))
+            .clone();
+        for x in [-1.0, 1.0] {
+            for y in [-1.0, 1.0] {
+                for z in [-1.0, 1.0] {
+                    let direction = Vec3::new(x, y, z);
+                    let jitter = Vec3::new(
+                        rng.random_range(-0.5..0.5),
+                        rng.random_range(0.0..1.0),
+                        rng.random_range(-0.5..0.5),
+                    );
+                    commands.spawn((
+                        Mesh3d(mesh.clone()),
+                        material.clone(),
+                        Transform::from_translation(transform.transform_point(direction * 0.25))
+                            .with_rotation(transform.rotation),
+                        RigidBody::Dynamic,
+                        Collider::cuboid(DEBRIS_SIZE / 2.0, DEBRIS_SIZE / 2.0, DEBRIS_SIZE / 2.0),
+                        Velocity::linear(
+                            velocity.linvel
+                                + (transform.rotation * direction + jitter) * DEBRIS_SPEED,
+                        ),
+                        Debris(Timer::from_seconds(DEBRIS_LIFETIME, TimerMode::Once)),
+                    ));
+                }
+            }
+        }
+        commands.entity(*cube).despawn_recursive();
+        broken.send(CubeBroken {
+            position: transform.translation,
+        });
+    }
+}
+
+// A last, bigger puff of dust where the cube was
+fn burst_broken_cubes(mut broken: EventReader<CubeBroken>, mut bursts: EventWriter<ParticleBurst>) {
+    for cube in broken.read() {
+        bursts.send(ParticleBurst {
+            position: cube.position,
+            count: 20,
+            color: HIT_COLOR,
+            speed: 4.0,
+            lifetime: 0.8,
+            size: 0.1,
+        });
+    }
+}
+
+// Let dialogue know how many cubes the player has broken
+fn record_broken_cubes(
+    mut broken: EventReader<CubeBroken>,
+    mut variables: ResMut<DialogueVariables>,
+    mut count: Local<u32>,
+) {
+    let newly_broken = broken.read().count() as u32;
+    if newly_broken == 0 {
+        return;
+    }
+    *count += newly_broken;
+    variables.set("cubes_broken", DialogueValue::Number(*count as f32));
+}
+
+fn despawn_debris(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut debris: Query<(Entity, &mut Debris)>,
+) {
+    for (entity, mut debris) in debris.iter_mut() {
+        if debris.0.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    DialogueDatabase, DialogueTree,
+    dialogue_callbacks::{DialogueCallback, DialogueCallbacks},
+    ron_asset::RonAssetLoader,
+    twee::TweeLoader,
+    yarn::YarnLoader,
+};
+use bevy::{
+    asset::{AssetPath, LoadedFolder, io::file::FileAssetReader},
+    prelude::*,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, path::PathBuf};
+
+// Folder under `assets/` that dialogue files are loaded from
+const DIALOGUE_FOLDER: &str = "dialogue";
+// Extension of the native dialogue format the editor saves
+pub const NATIVE_DIALOGUE_EXTENSION: &str = "dialogue.ron";
+
+pub struct DialogueAssetsPlugin;
+
+impl Plugin for DialogueAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<DialogueAsset>()
+            .init_asset_loader::<YarnLoader>()
+            .init_asset_loader::<TweeLoader>()
+            .register_asset_loader(RonAssetLoader::<DialogueAsset>::new(&[
+                NATIVE_DIALOGUE_EXTENSION,
+            ]))
+            .init_resource::<DialogueSources>()
+            .add_systems(Startup, load_dialogue_folder)
+            .add_systems(Update, register_dialogue_assets);
+    }
+}
+
+// Lets plugins contribute dialogue trees and node callbacks while the app is being built, e.g.
+// `app.register_dialogue("engineer", tree)`; files in `assets/dialogue` still override the trees
+pub trait RegisterDialogueExt {
+    fn register_dialogue(
+        &mut self,
+        dialogue_id: impl Into<String>,
+        tree: DialogueTree,
+    ) -> &mut Self;
+
+    // Run game logic whenever a node is entered, keyed "dialogue_id:node_id"
+    fn on_dialogue_node(
+        &mut self,
+        key: impl Into<String>,
+        callback: impl DialogueCallback,
+    ) -> &mut Self;
+}
+
+impl RegisterDialogueExt for App {
+    fn register_dialogue(
+        &mut self,
+        dialogue_id: impl Into<String>,
+        tree: DialogueTree,
+    ) -> &mut Self {
+        let dialogue_id = dialogue_id.into();
+        let mut dialogue_db = self.world_mut().get_resource_or_init::<DialogueDatabase>();
+        if dialogue_db.insert_tree(dialogue_id.clone(), tree).is_some() {
+            println!("Replaced dialogue: {dialogue_id}");
+        }
+        self
+    }
+
+    fn on_dialogue_node(
+        &mut self,
+        key: impl Into<String>,
+        callback: impl DialogueCallback,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<DialogueCallbacks>()
+            .on_node(key, callback);
+        self
+    }
+}
+
+// A dialogue tree imported from a file, registered under the file's name
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+pub struct DialogueAsset {
+    pub tree: DialogueTree,
+}
+
+// Resource remembering which file each loaded dialogue tree came from
+#[derive(Resource, Default)]
+pub struct DialogueSources {
+    sources: HashMap<String, AssetPath<'static>>,
+}
+
+impl DialogueSources {
+    // Where the native version of a dialogue tree lives on disk
+    pub fn native_path(&self, dialogue_id: &str) -> PathBuf {
+        FileAssetReader::get_base_path()
+            .join("assets")
+            .join(DIALOGUE_FOLDER)
+            .join(format!("{dialogue_id}.{NATIVE_DIALOGUE_EXTENSION}"))
+    }
+
+    pub fn source(&self, dialogue_id: &str) -> Option<&AssetPath<'static>> {
+        self.sources.get(dialogue_id)
+    }
+}
+
+// Keeps the dialogue folder and every file in it loaded
+#[derive(Resource)]
+struct DialogueFolder(#[allow(dead_code)] Handle<LoadedFolder>);
+
+fn load_dialogue_folder(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(DialogueFolder(asset_server.load_folder(DIALOGUE_FOLDER)));
+}
+
+fn is_native(path: &AssetPath) -> bool {
+    path.get_full_extension().as_deref() == Some(NATIVE_DIALOGUE_EXTENSION)
+}
+
+// Add imported trees to the database as they load (or reload)
+fn register_dialogue_assets(
+    mut events: EventReader<AssetEvent<DialogueAsset>>,
+    assets: Res<Assets<DialogueAsset>>,
+    asset_server: Res<AssetServer>,
+    mut dialogue_db: ResMut<DialogueDatabase>,
+    mut sources: ResMut<DialogueSources>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let (Some(asset), Some(path)) = (assets.get(*id), asset_server.get_path(*id)) else {
+            continue;
+        };
+        // Everything before the first dot, so `guard.dialogue.ron` registers as `guard`
+        let Some(dialogue_id) = path
+            .path()
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.split('.').next())
+            .map(str::to_string)
+        else {
+            continue;
+        };
+
+        // Trees saved from the editor win over the files they were imported from
+        if let Some(existing) = sources.source(&dialogue_id)
+            && is_native(existing)
+            && !is_native(&path)
+        {
+            continue;
+        }
+
+        println!("Loaded dialogue: {dialogue_id}");
+        dialogue_db.insert_tree(dialogue_id.clone(), asset.tree.clone());
+        sources.sources.insert(dialogue_id, path.into_owned());
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+// Logic that runs with full world access when a dialogue node is entered
+pub trait DialogueCallback: Send + Sync + 'static {
+    fn run(&self, world: &mut World);
+}
+
+impl<F> DialogueCallback for F
+where
+    F: Fn(&mut World) + Send + Sync + 'static,
+{
+    fn run(&self, world: &mut World) {
+        self(world)
+    }
+}
+
+// Resource mapping "dialogue_id:node_id" keys to callbacks
+#[derive(Resource, Default)]
+pub struct DialogueCallbacks {
+    callbacks: HashMap<String, Vec<Box<dyn DialogueCallback>>>,
+}
+
+impl DialogueCallbacks {
+    // Register a callback for a node, e.g. `on_node("guard:trouble", |world| ...)`
+    pub fn on_node(
+        &mut self,
+        key: impl Into<String>,
+        callback: impl DialogueCallback,
+    ) -> &mut Self {
+        self.callbacks
+            .entry(key.into())
+            .or_default()
+            .push(Box::new(callback));
+        self
+    }
+
+    fn run(&self, key: &str, world: &mut World) {
+        let Some(callbacks) = self.callbacks.get(key) else {
+            return;
+        };
+        for callback in callbacks {
+            callback.run(world);
+        }
+    }
+}
+
+// Queue every callback registered for a node to run once commands are applied
+pub fn queue_node_callbacks(commands: &mut Commands, dialogue_id: &str, node_id: &str) {
+    let key = format!("{dialogue_id}:{node_id}");
+    commands.queue(move |world: &mut World| {
+        if !world.contains_resource::<DialogueCallbacks>() {
+            return;
+        }
+        world.resource_scope(|world, callbacks: Mut<DialogueCallbacks>| {
+            callbacks.run(&key, world);
+        });
+    });
+}
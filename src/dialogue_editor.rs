@@ -0,0 +1,281 @@
+use crate::{
+    ActiveDialogue, DialogueDatabase, DialogueMode, DialogueNode, DialogueOption, GameState, Npc,
+    dialogue_assets::DialogueSources,
+};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use ron::ser::PrettyConfig;
+
+// Dialogue editor constants
+const EDITOR_TOGGLE_KEY: KeyCode = KeyCode::F2;
+const MISSING_TARGET_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 80, 80);
+const NEW_OPTION_TEXT: &str = "...";
+const NEW_EXIT_TEXT: &str = "Goodbye.";
+
+pub struct DialogueEditorPlugin;
+
+impl Plugin for DialogueEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DialogueEditorState>()
+            .add_systems(
+                Update,
+                toggle_dialogue_editor.run_if(in_state(GameState::InDialogue)),
+            )
+            .add_systems(OnEnter(DialogueMode::Editing), open_dialogue_editor)
+            .add_systems(
+                Update,
+                dialogue_editor_ui.run_if(in_state(DialogueMode::Editing)),
+            );
+    }
+}
+
+// Resource tracking what the editor is looking at
+#[derive(Resource, Default)]
+struct DialogueEditorState {
+    dialogue_id: String,
+    selected_node: String,
+    new_node_id: String,
+    new_tag: String,
+    status: String,
+}
+
+fn toggle_dialogue_editor(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mode: Res<State<DialogueMode>>,
+    mut next_mode: ResMut<NextState<DialogueMode>>,
+) {
+    if !keyboard.just_pressed(EDITOR_TOGGLE_KEY) {
+        return;
+    }
+    match mode.get() {
+        DialogueMode::Talking => next_mode.set(DialogueMode::Editing),
+        DialogueMode::Editing => next_mode.set(DialogueMode::Talking),
+    }
+}
+
+// Open the editor on the tree and node of the current conversation
+fn open_dialogue_editor(
+    active_dialogue_query: Query<&ActiveDialogue>,
+    npc_query: Query<&Npc>,
+    mut editor: ResMut<DialogueEditorState>,
+) {
+    let Ok(active_dialogue) = active_dialogue_query.get_single() else {
+        return;
+    };
+    let Ok(npc) = npc_query.get(active_dialogue.npc_entity) else {
+        return;
+    };
+
+    editor.dialogue_id = npc.dialogue_id.clone();
+    editor.selected_node = active_dialogue.current_node.clone();
+    editor.status.clear();
+}
+
+fn dialogue_editor_ui(
+    mut contexts: EguiContexts,
+    mut editor: ResMut<DialogueEditorState>,
+    mut dialogue_db: ResMut<DialogueDatabase>,
+    sources: Res<DialogueSources>,
+    mut next_mode: ResMut<NextState<DialogueMode>>,
+) {
+    let editor = &mut *editor;
+    let Some(tree) = dialogue_db.dialogues.get_mut(&editor.dialogue_id) else {
+        return;
+    };
+
+    // Root first, then everything else alphabetically
+    let mut node_ids: Vec<String> = tree.nodes.keys().cloned().collect();
+    node_ids.sort_by_key(|id| (*id != tree.root_node, id.clone()));
+
+    egui::Window::new(format!("Dialogue Editor: {}", editor.dialogue_id))
+        .default_width(720.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    let path = sources.native_path(&editor.dialogue_id);
+                    editor.status = match save_tree(&path, tree) {
+                        Ok(()) => format!("Saved to {}", path.display()),
+                        Err(error) => format!("Save failed: {error}"),
+                    };
+                }
+                if ui.button("Close").clicked() {
+                    next_mode.set(DialogueMode::Talking);
+                }
+                if let Some(source) = sources.source(&editor.dialogue_id) {
+                    ui.label(format!("Loaded from {source}"));
+                } else {
+                    ui.label("Built-in dialogue");
+                }
+            });
+            if !editor.status.is_empty() {
+                ui.label(&editor.status);
+            }
+            ui.separator();
+
+            ui.columns(2, |columns| {
+                // Graph overview: every node and where its options lead
+                egui::ScrollArea::vertical()
+                    .id_salt("nodes")
+                    .show(&mut columns[0], |ui| {
+                        for id in &node_ids {
+                            let label = if *id == tree.root_node {
+                                format!("{id} (root)")
+                            } else {
+                                id.clone()
+                            };
+                            if ui
+                                .selectable_label(*id == editor.selected_node, label)
+                                .clicked()
+                            {
+                                editor.selected_node = id.clone();
+                            }
+                            for option in &tree.nodes[id].options {
+                                match option {
+                                    DialogueOption::Reply { target_node, .. }
+                                        if tree.nodes.contains_key(target_node) =>
+                                    {
+                                        ui.label(format!("    -> {target_node}"));
+                                    }
+                                    DialogueOption::Reply { target_node, .. } => {
+                                        ui.colored_label(
+                                            MISSING_TARGET_COLOR,
+                                            format!("    -> {target_node} (missing)"),
+                                        );
+                                    }
+                                    DialogueOption::Exit { .. } => {
+                                        ui.label("    -> [exit]");
+                                    }
+                                }
+                            }
+                            if let Some(next) = &tree.nodes[id].next {
+                                if tree.nodes.contains_key(next) {
+                                    ui.label(format!("    => {next}"));
+                                } else {
+                                    ui.colored_label(
+                                        MISSING_TARGET_COLOR,
+                                        format!("    => {next} (missing)"),
+                                    );
+                                }
+                            }
+                        }
+
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut editor.new_node_id);
+                            let new_id = editor.new_node_id.trim().to_string();
+                            if ui.button("Add node").clicked()
+                                && !new_id.is_empty()
+                                && !tree.nodes.contains_key(&new_id)
+                            {
+                                tree.nodes.insert(
+                                    new_id.clone(),
+                                    DialogueNode {
+                                        text: String::new(),
+                                        options: vec![DialogueOption::exit(NEW_EXIT_TEXT)],
+                                        next: None,
+                                        tags: Vec::new(),
+                                    },
+                                );
+                                editor.selected_node = new_id;
+                                editor.new_node_id.clear();
+                            }
+                        });
+                    });
+
+                // Selected node details
+                let Some(node) = tree.nodes.get_mut(&editor.selected_node) else {
+                    return;
+                };
+                let ui = &mut columns[1];
+                ui.heading(&editor.selected_node);
+                ui.label("Text");
+                ui.text_edit_multiline(&mut node.text);
+                ui.horizontal(|ui| {
+                    ui.label("Auto-advance to");
+                    egui::ComboBox::from_id_salt("next")
+                        .selected_text(node.next.as_deref().unwrap_or("(none)"))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut node.next, None, "(none)");
+                            for id in &node_ids {
+                                ui.selectable_value(&mut node.next, Some(id.clone()), id);
+                            }
+                        });
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Tags");
+                    let mut remove = None;
+                    for (index, tag) in node.tags.iter().enumerate() {
+                        if ui.button(format!("@{tag} x")).clicked() {
+                            remove = Some(index);
+                        }
+                    }
+                    if let Some(index) = remove {
+                        node.tags.remove(index);
+                    }
+                    ui.text_edit_singleline(&mut editor.new_tag);
+                    let new_tag = editor.new_tag.trim().trim_start_matches('@').to_string();
+                    if ui.button("Add tag").clicked()
+                        && !new_tag.is_empty()
+                        && !node.tags.contains(&new_tag)
+                    {
+                        node.tags.push(new_tag);
+                        editor.new_tag.clear();
+                    }
+                });
+
+                ui.separator();
+                ui.label("Options");
+                let mut remove = None;
+                for (index, option) in node.options.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        match option {
+                            DialogueOption::Reply {
+                                text, target_node, ..
+                            } => {
+                                ui.text_edit_singleline(text);
+                                egui::ComboBox::from_id_salt(("target", index))
+                                    .selected_text(target_node.as_str())
+                                    .show_ui(ui, |ui| {
+                                        for id in &node_ids {
+                                            ui.selectable_value(target_node, id.clone(), id);
+                                        }
+                                    });
+                            }
+                            DialogueOption::Exit { text, .. } => {
+                                ui.text_edit_singleline(text);
+                                ui.label("ends conversation");
+                            }
+                        }
+                        if ui.button("Remove").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = remove {
+                    node.options.remove(index);
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("Add reply").clicked() {
+                        node.options.push(DialogueOption::reply(
+                            NEW_OPTION_TEXT,
+                            editor.selected_node.clone(),
+                        ));
+                    }
+                    if ui.button("Add exit").clicked() {
+                        node.options.push(DialogueOption::exit(NEW_EXIT_TEXT));
+                    }
+                });
+            });
+        });
+}
+
+fn save_tree(path: &std::path::Path, tree: &crate::DialogueTree) -> Result<(), String> {
+    let contents =
+        ron::ser::to_string_pretty(tree, PrettyConfig::default()).map_err(|e| e.to_string())?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, contents).map_err(|e| e.to_string())
+}
@@ -0,0 +1,162 @@
+use crate::{
+    DialogueNode, DialogueOption, DialogueTree,
+    twee::parse_twee,
+    yarn::{YarnError, parse_yarn},
+};
+
+// (text, target) of every option on a node, with `None` for exits
+fn options(node: &DialogueNode) -> Vec<(&str, Option<&str>)> {
+    node.options
+        .iter()
+        .map(|option| match option {
+            DialogueOption::Reply {
+                text, target_node, ..
+            } => (text.as_str(), Some(target_node.as_str())),
+            DialogueOption::Exit { text, .. } => (text.as_str(), None),
+        })
+        .collect()
+}
+
+fn node<'a>(tree: &'a DialogueTree, id: &str) -> &'a DialogueNode {
+    tree.nodes
+        .get(id)
+        .unwrap_or_else(|| panic!("no node `{id}`"))
+}
+
+#[test]
+fn twee_links_become_replies() {
+    let tree = parse_twee(
+        ":: Start\n\
+         Hello.\n\
+         [[Ask about the cubes->Cubes]]\n\
+         [[Cubes<-Ask again]]\n\
+         [[Leave|End]]\n\
+         [[End]]\n\
+         :: Cubes\n\
+         They float.\n\
+         :: End\n\
+         Bye.\n",
+    )
+    .unwrap();
+    assert_eq!(tree.root_node, "Start");
+    assert_eq!(node(&tree, "Start").text, "Hello.");
+    assert_eq!(
+        options(node(&tree, "Start")),
+        vec![
+            ("Ask about the cubes", Some("Cubes")),
+            ("Ask again", Some("Cubes")),
+            ("Leave", Some("End")),
+            ("End", Some("End")),
+        ]
+    );
+    // Passages without links end the conversation
+    assert_eq!(options(node(&tree, "Cubes")), vec![("Goodbye.", None)]);
+}
+
+#[test]
+fn twee_link_text_can_contain_arrows() {
+    let tree = parse_twee(":: Start\n[[Go -> up->Roof]]\n:: Roof\nWindy.\n").unwrap();
+    assert_eq!(
+        options(node(&tree, "Start")),
+        vec![("Go -> up", Some("Roof"))]
+    );
+}
+
+#[test]
+fn twee_starts_where_story_data_says() {
+    let tree = parse_twee(
+        ":: StoryTitle\nTest\n\
+         :: StoryData\n{\"start\": \"Second\"}\n\
+         :: First [intro]\nOne.\n\
+         :: Second [wave shrug]\nTwo.\n",
+    )
+    .unwrap();
+    assert_eq!(tree.root_node, "Second");
+    assert!(!tree.nodes.contains_key("StoryTitle"));
+    assert!(!tree.nodes.contains_key("StoryData"));
+    assert_eq!(node(&tree, "Second").tags, vec!["wave", "shrug"]);
+}
+
+#[test]
+fn twee_drops_macros() {
+    let tree = parse_twee(
+        ":: Start\n<<set $met to true>>\nHi <<if $met>>again<</if>>.\n[[Bye->End]]\n:: End\nBye.\n",
+    )
+    .unwrap();
+    assert_eq!(node(&tree, "Start").text, "Hi again.");
+}
+
+#[test]
+fn twee_without_passages_is_an_error() {
+    assert!(parse_twee("no passages here").is_err());
+}
+
+#[test]
+fn yarn_options_jumps_and_conditions() {
+    let tree = parse_yarn(
+        "title: Start\n\
+         ---\n\
+         Villager: Hello! #wave\n\
+         -> Who are you?\n    \
+             Villager: Nobody much.\n\
+         -> Where's the guard? <<if $guard_warned>>\n    \
+             <<jump Guard>>\n\
+         -> Bye.\n\
+         ===\n\
+         title: Guard\n\
+         ---\n\
+         Villager: Over there.\n\
+         <<jump Start>>\n\
+         ===\n",
+    )
+    .unwrap();
+    assert_eq!(tree.root_node, "Start");
+
+    let start = node(&tree, "Start");
+    assert_eq!(start.text, "Hello!");
+    assert_eq!(start.tags, vec!["wave"]);
+    assert_eq!(
+        options(start),
+        vec![
+            ("Who are you?", Some("Start.0")),
+            ("Where's the guard?", Some("Guard")),
+            ("Bye.", None),
+        ]
+    );
+    let DialogueOption::Reply { condition, .. } = &start.options[1] else {
+        panic!("expected a reply");
+    };
+    assert_eq!(
+        condition
+            .as_ref()
+            .map(|condition| condition.variable.as_str()),
+        Some("guard_warned")
+    );
+
+    // Lines under an option become a node of their own
+    let answer = node(&tree, "Start.0");
+    assert_eq!(answer.text, "Nobody much.");
+    assert_eq!(options(answer), vec![("Goodbye.", None)]);
+
+    // A node that only jumps on moves straight there
+    let guard = node(&tree, "Guard");
+    assert_eq!(guard.next.as_deref(), Some("Start"));
+    assert!(guard.options.is_empty());
+}
+
+#[test]
+fn yarn_errors() {
+    assert!(matches!(
+        parse_yarn("---\nHello.\n===\n"),
+        Err(YarnError::MissingTitle { .. })
+    ));
+    assert!(matches!(
+        parse_yarn("title: Start\n---\nHello.\n"),
+        Err(YarnError::UnterminatedNode { .. })
+    ));
+    assert!(matches!(
+        parse_yarn("title: Start\n---\n-> Hi <<if $a $b>>\n===\n"),
+        Err(YarnError::InvalidCondition { .. })
+    ));
+    assert!(matches!(parse_yarn(""), Err(YarnError::NoNodes)));
+}
@@ -0,0 +1,101 @@
+use crate::{DialogueNode, GameState, Npc};
+use bevy::prelude::*;
+
+// Dialogue tag constants
+const GESTURE_DURATION: f32 = 2.5;
+const GESTURE_COLOR: Color = Color::srgb(0.75, 0.75, 0.85);
+const GESTURE_FONT_SIZE: f32 = 16.0;
+
+pub struct DialogueTagsPlugin;
+
+impl Plugin for DialogueTagsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DialogueTagTriggered>()
+            .add_systems(Update, (show_tag_gestures, fade_tag_gestures).chain());
+    }
+}
+
+// Event sent for each tag on a dialogue node as it's shown, for animation and audio to react to
+#[derive(Event)]
+pub struct DialogueTagTriggered {
+    pub npc_entity: Entity,
+    pub tag: String,
+}
+
+// Publish the tags of a node the NPC just said
+pub fn queue_node_tags(commands: &mut Commands, npc_entity: Entity, node: &DialogueNode) {
+    for tag in &node.tags {
+        commands.send_event(DialogueTagTriggered {
+            npc_entity,
+            tag: tag.clone(),
+        });
+    }
+}
+
+// Stage direction shown under the dialogue panel until there's animation to play instead
+#[derive(Component)]
+struct TagGesture(Timer);
+
+// How a tag reads as a stage direction, if it's one we know
+fn gesture_verb(tag: &str) -> Option<&'static str> {
+    match tag {
+        "angry" => Some("scowls"),
+        "wave" => Some("waves"),
+        "shrug" => Some("shrugs"),
+        "nod" => Some("nods"),
+        "laugh" => Some("laughs"),
+        "sigh" => Some("sighs"),
+        "suspicious" => Some("eyes you suspiciously"),
+        _ => None,
+    }
+}
+
+fn show_tag_gestures(
+    mut commands: Commands,
+    mut events: EventReader<DialogueTagTriggered>,
+    npc_query: Query<&Npc>,
+    gestures: Query<Entity, With<TagGesture>>,
+) {
+    let directions: Vec<String> = events
+        .read()
+        .filter_map(|event| {
+            let npc = npc_query.get(event.npc_entity).ok()?;
+            Some(format!("*{} {}*", npc.name, gesture_verb(&event.tag)?))
+        })
+        .collect();
+    if directions.is_empty() {
+        return;
+    }
+
+    for entity in gestures.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.spawn((
+        Text::new(directions.join(" ")),
+        TextFont {
+            font_size: GESTURE_FONT_SIZE,
+            ..default()
+        },
+        TextColor(GESTURE_COLOR),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(25.0),
+            bottom: Val::Percent(15.0),
+            ..default()
+        },
+        TagGesture(Timer::from_seconds(GESTURE_DURATION, TimerMode::Once)),
+        StateScoped(GameState::InDialogue),
+    ));
+}
+
+fn fade_tag_gestures(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut gestures: Query<(Entity, &mut TagGesture)>,
+) {
+    for (entity, mut gesture) in gestures.iter_mut() {
+        if gesture.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
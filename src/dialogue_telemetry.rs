@@ -0,0 +1,165 @@
+use crate::{
+    ActiveDialogue, GameStateSet, Npc,
+    paths::{UserDir, UserPaths},
+};
+use bevy::{prelude::*, utils::SystemTime};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{LineWriter, Write},
+};
+
+pub struct DialogueTelemetryPlugin;
+
+impl Plugin for DialogueTelemetryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DialogueOptionChosen>()
+            .add_systems(Startup, open_telemetry_log)
+            .add_systems(
+                Update,
+                // After the dialogue systems so a choice is logged before the node it leads to
+                (log_chosen_options, log_visited_nodes)
+                    .chain()
+                    .after(GameStateSet::InDialogue),
+            );
+    }
+}
+
+// Event sent when the player picks a dialogue option
+#[derive(Event)]
+pub struct DialogueOptionChosen {
+    pub npc_entity: Entity,
+    pub node: String,
+    // Position in the list as shown, counting resume and continue options
+    pub option_index: usize,
+    pub text: String,
+    pub target_node: String,
+}
+
+// Resource writing this session's dialogue log, one JSON object per line
+#[derive(Resource)]
+struct DialogueTelemetry {
+    log: LineWriter<File>,
+}
+
+// A line in the log
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum TelemetryRecord<'a> {
+    NodeVisited {
+        npc: &'a str,
+        npc_name: &'a str,
+        node: &'a str,
+    },
+    OptionChosen {
+        npc: &'a str,
+        npc_name: &'a str,
+        node: &'a str,
+        option_index: usize,
+        text: &'a str,
+        target_node: &'a str,
+    },
+}
+
+#[derive(Serialize)]
+struct TimestampedRecord<'a> {
+    // Milliseconds since the Unix epoch
+    timestamp: u128,
+    // Seconds since the game started
+    session_time: f32,
+    #[serde(flatten)]
+    record: TelemetryRecord<'a>,
+}
+
+// Bevy's clock, as the standard one panics in the browser
+fn unix_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis())
+}
+
+impl DialogueTelemetry {
+    fn write(&mut self, time: &Time, record: TelemetryRecord) {
+        let record = TimestampedRecord {
+            timestamp: unix_millis(),
+            session_time: time.elapsed_secs(),
+            record,
+        };
+        let written = serde_json::to_string(&record)
+            .map_err(|error| error.to_string())
+            .and_then(|line| writeln!(self.log, "{line}").map_err(|error| error.to_string()));
+        if let Err(error) = written {
+            println!("Could not write dialogue telemetry: {error}");
+        }
+    }
+}
+
+// Each session gets its own file so logs can be compared run by run
+fn open_telemetry_log(mut commands: Commands, paths: Res<UserPaths>) {
+    // Web builds have no filesystem to keep it in
+    if cfg!(target_arch = "wasm32") {
+        return;
+    }
+    let path = paths
+        .dir(UserDir::Logs)
+        .join(format!("dialogue-{}.jsonl", unix_millis()));
+    match File::create(&path) {
+        Ok(file) => commands.insert_resource(DialogueTelemetry {
+            log: LineWriter::new(file),
+        }),
+        Err(error) => println!("Dialogue telemetry disabled, {}: {error}", path.display()),
+    }
+}
+
+// The active dialogue is reinserted on every node change, so this sees each visit
+fn log_visited_nodes(
+    time: Res<Time>,
+    telemetry: Option<ResMut<DialogueTelemetry>>,
+    active_dialogue_query: Query<&ActiveDialogue, Changed<ActiveDialogue>>,
+    npc_query: Query<&Npc>,
+) {
+    let Some(mut telemetry) = telemetry else {
+        return;
+    };
+    for active_dialogue in active_dialogue_query.iter() {
+        let Ok(npc) = npc_query.get(active_dialogue.npc_entity) else {
+            continue;
+        };
+        telemetry.write(
+            &time,
+            TelemetryRecord::NodeVisited {
+                npc: &npc.dialogue_id,
+                npc_name: &npc.name,
+                node: &active_dialogue.current_node,
+            },
+        );
+    }
+}
+
+fn log_chosen_options(
+    time: Res<Time>,
+    telemetry: Option<ResMut<DialogueTelemetry>>,
+    mut events: EventReader<DialogueOptionChosen>,
+    npc_query: Query<&Npc>,
+) {
+    let Some(mut telemetry) = telemetry else {
+        events.clear();
+        return;
+    };
+    for event in events.read() {
+        let Ok(npc) = npc_query.get(event.npc_entity) else {
+            continue;
+        };
+        telemetry.write(
+            &time,
+            TelemetryRecord::OptionChosen {
+                npc: &npc.dialogue_id,
+                npc_name: &npc.name,
+                node: &event.node,
+                option_index: event.option_index,
+                text: &event.text,
+                target_node: &event.target_node,
+            },
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{
+    DialogueDatabase, DialogueOption, DialogueTree,
+    dialogue_assets::{DialogueAssetsPlugin, RegisterDialogueExt},
+    merchant_stalls::merchant_dialogue,
+};
+use bevy::{asset::LoadedFolder, prelude::*};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::collections::{HashMap, HashSet};
+
+// How many frames to wait for the dialogue folder before giving up
+const MAX_LOAD_FRAMES: usize = 10_000;
+const RANDOM_WALKS_PER_TREE: usize = 500;
+// A walk this long without reaching an exit is treated as stuck in a loop
+const MAX_WALK_STEPS: usize = 10_000;
+
+// Where a node can go next; `None` is leaving the conversation
+fn transitions(tree: &DialogueTree, node_id: &str) -> Vec<Option<String>> {
+    let node = &tree.nodes[node_id];
+    if let Some(next) = &node.next {
+        return vec![Some(next.clone())];
+    }
+    node.options
+        .iter()
+        .map(|option| match option {
+            DialogueOption::Reply { target_node, .. } => Some(target_node.clone()),
+            DialogueOption::Exit { .. } => None,
+        })
+        .collect()
+}
+
+// Every tree the game knows about: the built-in ones, those plugins register, and everything in
+// `assets/dialogue`
+fn load_all_dialogue() -> HashMap<String, DialogueTree> {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), DialogueAssetsPlugin))
+        .init_resource::<DialogueDatabase>()
+        .register_dialogue("merchant", merchant_dialogue());
+
+    let folder: Handle<LoadedFolder> = app
+        .world()
+        .resource::<AssetServer>()
+        .load_folder("dialogue");
+    let mut frames = 0;
+    while !app
+        .world()
+        .resource::<AssetServer>()
+        .is_loaded_with_dependencies(&folder)
+    {
+        assert!(
+            frames < MAX_LOAD_FRAMES,
+            "dialogue folder never finished loading"
+        );
+        app.update();
+        frames += 1;
+    }
+    // Let the asset events reach the database
+    app.update();
+    app.update();
+
+    app.world_mut()
+        .remove_resource::<DialogueDatabase>()
+        .expect("dialogue database missing")
+        .dialogues
+}
+
+#[test]
+fn every_dialogue_target_exists() {
+    for (dialogue_id, tree) in load_all_dialogue() {
+        assert!(
+            tree.nodes.contains_key(&tree.root_node),
+            "{dialogue_id}: root node `{}` does not exist",
+            tree.root_node
+        );
+        for node_id in tree.nodes.keys() {
+            for target in transitions(&tree, node_id).into_iter().flatten() {
+                assert!(
+                    tree.nodes.contains_key(&target),
+                    "{dialogue_id}:{node_id} leads to missing node `{target}`"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn every_reachable_node_can_reach_an_exit() {
+    for (dialogue_id, tree) in load_all_dialogue() {
+        // Work backwards from the nodes with an exit to everything that can get to one
+        let mut can_exit: HashSet<String> = tree
+            .nodes
+            .keys()
+            .filter(|node_id| transitions(&tree, node_id).contains(&None))
+            .cloned()
+            .collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for node_id in tree.nodes.keys() {
+                if can_exit.contains(node_id) {
+                    continue;
+                }
+                let leads_out = transitions(&tree, node_id)
+                    .into_iter()
+                    .flatten()
+                    .any(|target| can_exit.contains(&target));
+                if leads_out {
+                    can_exit.insert(node_id.clone());
+                    changed = true;
+                }
+            }
+        }
+
+        // Then check every node the player can actually get to from the root
+        let mut visited = HashSet::new();
+        let mut stack = vec![tree.root_node.clone()];
+        while let Some(node_id) = stack.pop() {
+            if !tree.nodes.contains_key(&node_id) || !visited.insert(node_id.clone()) {
+                continue;
+            }
+            assert!(
+                can_exit.contains(&node_id),
+                "{dialogue_id}:{node_id} can never reach an exit"
+            );
+            stack.extend(transitions(&tree, &node_id).into_iter().flatten());
+        }
+    }
+}
+
+#[test]
+fn every_node_has_an_unconditional_way_on() {
+    for (dialogue_id, tree) in load_all_dialogue() {
+        for (node_id, node) in &tree.nodes {
+            let unconditional = node.next.is_some()
+                || node.options.iter().any(|option| match option {
+                    DialogueOption::Reply { condition, .. }
+                    | DialogueOption::Exit { condition, .. } => condition.is_none(),
+                });
+            assert!(
+                unconditional,
+                "{dialogue_id}:{node_id} may show no options at all"
+            );
+        }
+    }
+}
+
+#[test]
+fn random_walks_terminate() {
+    let mut rng = StdRng::seed_from_u64(0x9a9e_5c11);
+    for (dialogue_id, tree) in load_all_dialogue() {
+        for _ in 0..RANDOM_WALKS_PER_TREE {
+            let mut node_id = tree.root_node.clone();
+            let mut steps = 0;
+            loop {
+                assert!(
+                    steps < MAX_WALK_STEPS,
+                    "{dialogue_id}: walk from the root never ended, last at {node_id}"
+                );
+                let choices = transitions(&tree, &node_id);
+                assert!(!choices.is_empty(), "{dialogue_id}:{node_id} is a dead end");
+                match &choices[rng.random_range(0..choices.len())] {
+                    Some(target) if tree.nodes.contains_key(target) => node_id = target.clone(),
+                    Some(target) => panic!("{dialogue_id}:{node_id} leads to missing `{target}`"),
+                    None => break,
+                }
+                steps += 1;
+            }
+        }
+    }
+}
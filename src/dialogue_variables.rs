@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt};
+
+// A value stored in a dialogue variable
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DialogueValue {
+    Bool(bool),
+    Number(f32),
+    Text(String),
+}
+
+impl DialogueValue {
+    // Parse a literal as written in dialogue content
+    fn parse(literal: &str) -> Self {
+        let literal = literal.trim();
+        match literal {
+            "true" => return DialogueValue::Bool(true),
+            "false" => return DialogueValue::Bool(false),
+            _ => {}
+        }
+        if let Ok(number) = literal.parse::<f32>() {
+            return DialogueValue::Number(number);
+        }
+        DialogueValue::Text(literal.trim_matches('"').to_string())
+    }
+
+    // The value an unset variable of the same type has
+    fn default_like(&self) -> Self {
+        match self {
+            DialogueValue::Bool(_) => DialogueValue::Bool(false),
+            DialogueValue::Number(_) => DialogueValue::Number(0.0),
+            DialogueValue::Text(_) => DialogueValue::Text(String::new()),
+        }
+    }
+}
+
+impl fmt::Display for DialogueValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DialogueValue::Bool(value) => write!(f, "{value}"),
+            // Whole numbers read better without a trailing `.0`
+            DialogueValue::Number(value) if value.fract() == 0.0 => write!(f, "{value:.0}"),
+            DialogueValue::Number(value) => write!(f, "{value}"),
+            DialogueValue::Text(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+// Resource holding the variables dialogue conditions are checked against
+#[derive(Resource, Default)]
+pub struct DialogueVariables {
+    values: HashMap<String, DialogueValue>,
+}
+
+impl DialogueVariables {
+    pub fn get(&self, name: &str) -> Option<&DialogueValue> {
+        self.values.get(name)
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: DialogueValue) {
+        self.values.insert(name.into(), value);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+// A single variable comparison gating a dialogue option, e.g. `$gold >= 10`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DialogueCondition {
+    pub variable: String,
+    pub comparison: Comparison,
+    pub value: DialogueValue,
+}
+
+impl DialogueCondition {
+    // Parse `$flag`, `not $flag` or `$variable <op> literal`
+    pub fn parse(expression: &str) -> Option<Self> {
+        let expression = expression.trim();
+
+        let negated = expression
+            .strip_prefix("not ")
+            .or_else(|| expression.strip_prefix('!'));
+        if let Some(variable) = negated {
+            return Some(Self {
+                variable: parse_variable(variable)?,
+                comparison: Comparison::Equal,
+                value: DialogueValue::Bool(false),
+            });
+        }
+
+        let operators = [
+            ("==", Comparison::Equal),
+            ("!=", Comparison::NotEqual),
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+            (" is ", Comparison::Equal),
+            (" eq ", Comparison::Equal),
+            (" neq ", Comparison::NotEqual),
+            (" lte ", Comparison::LessOrEqual),
+            (" gte ", Comparison::GreaterOrEqual),
+            (" lt ", Comparison::Less),
+            (" gt ", Comparison::Greater),
+        ];
+        for (operator, comparison) in operators {
+            if let Some((variable, value)) = expression.split_once(operator) {
+                return Some(Self {
+                    variable: parse_variable(variable)?,
+                    comparison,
+                    value: DialogueValue::parse(value),
+                });
+            }
+        }
+
+        Some(Self {
+            variable: parse_variable(expression)?,
+            comparison: Comparison::Equal,
+            value: DialogueValue::Bool(true),
+        })
+    }
+
+    // Player-facing summary, e.g. "Requires 10 gold" for `$gold >= 10`
+    pub fn describe(&self) -> String {
+        let variable = self.variable.replace('_', " ");
+        match (&self.value, self.comparison) {
+            (DialogueValue::Bool(true), Comparison::Equal) => format!("Requires {variable}"),
+            (DialogueValue::Bool(false), Comparison::Equal) => format!("Requires not {variable}"),
+            (value, Comparison::GreaterOrEqual) => format!("Requires {value} {variable}"),
+            (value, comparison) => {
+                let operator = match comparison {
+                    Comparison::Equal => "=",
+                    Comparison::NotEqual => "not",
+                    Comparison::Less => "under",
+                    Comparison::LessOrEqual => "at most",
+                    Comparison::Greater => "over",
+                    Comparison::GreaterOrEqual => "at least",
+                };
+                format!("Requires {variable} {operator} {value}")
+            }
+        }
+    }
+
+    pub fn evaluate(&self, variables: &DialogueVariables) -> bool {
+        let default = self.value.default_like();
+        let current = variables.get(&self.variable).unwrap_or(&default);
+
+        match (current, &self.value) {
+            (DialogueValue::Number(a), DialogueValue::Number(b)) => match self.comparison {
+                Comparison::Equal => a == b,
+                Comparison::NotEqual => a != b,
+                Comparison::Less => a < b,
+                Comparison::LessOrEqual => a <= b,
+                Comparison::Greater => a > b,
+                Comparison::GreaterOrEqual => a >= b,
+            },
+            (a, b) => match self.comparison {
+                Comparison::Equal => a == b,
+                Comparison::NotEqual => a != b,
+                _ => false,
+            },
+        }
+    }
+}
+
+fn parse_variable(text: &str) -> Option<String> {
+    let name = text.trim().strip_prefix('$')?;
+    (!name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_'))
+        .then(|| name.to_string())
+}
+
+// Values for the `{placeholder}`s in dialogue text, resolved when a node is shown
+#[derive(Default)]
+pub struct DialogueContext {
+    values: HashMap<String, String>,
+}
+
+impl DialogueContext {
+    pub fn with(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.values.insert(key.into(), value.to_string());
+        self
+    }
+
+    // Every dialogue variable is available as a placeholder too, and wins over built-ins
+    pub fn with_variables(mut self, variables: &DialogueVariables) -> Self {
+        for (name, value) in &variables.values {
+            self.values.insert(name.clone(), value.to_string());
+        }
+        self
+    }
+
+    // Replace `{key}` with its value, leaving unknown placeholders untouched
+    pub fn substitute(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            match after
+                .find('}')
+                .and_then(|end| Some((end, self.values.get(&after[..end])?)))
+            {
+                Some((end, value)) => {
+                    result.push_str(value);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    result.push('{');
+                    rest = after;
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{GameStateSet, clock::GameClock};
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Economy constants
+const SUPPLY_RECOVERY_RATE: f32 = 0.25; // Fraction of the supply gap closed each day
+const SUPPLY_NOISE: f32 = 0.1; // Random daily supply fluctuation
+const MIN_PRICE_FACTOR: f32 = 0.25;
+const MAX_PRICE_FACTOR: f32 = 4.0;
+const STARTING_CREDITS: f32 = 50.0;
+const STARTING_PAPERCLIPS: u32 = 200;
+
+pub struct EconomyPlugin;
+
+impl Plugin for EconomyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Economy>()
+            .init_resource::<Purse>()
+            .add_systems(Update, tick_economy.in_set(GameStateSet::Playing));
+    }
+}
+
+// Goods that merchants trade in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TradeGood {
+    Paperclips,
+    Wire,
+    CubeShards,
+    DataChips,
+}
+
+impl TradeGood {
+    pub const ALL: [TradeGood; 4] = [
+        TradeGood::Paperclips,
+        TradeGood::Wire,
+        TradeGood::CubeShards,
+        TradeGood::DataChips,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TradeGood::Paperclips => "paperclips",
+            TradeGood::Wire => "wire",
+            TradeGood::CubeShards => "cube shards",
+            TradeGood::DataChips => "data chips",
+        }
+    }
+}
+
+// Market state for a single good
+pub struct MarketEntry {
+    pub base_price: f32,
+    pub baseline_supply: f32,
+    pub supply: f32,
+}
+
+impl MarketEntry {
+    fn new(base_price: f32, baseline_supply: f32) -> Self {
+        Self {
+            base_price,
+            baseline_supply,
+            supply: baseline_supply,
+        }
+    }
+
+    // Price rises when supply is scarce and falls when the market is flooded
+    pub fn price(&self) -> f32 {
+        let factor =
+            (self.baseline_supply / self.supply.max(1.0)).clamp(MIN_PRICE_FACTOR, MAX_PRICE_FACTOR);
+        self.base_price * factor
+    }
+}
+
+// Resource holding the shared market all merchants trade against
+#[derive(Resource)]
+pub struct Economy {
+    pub market: HashMap<TradeGood, MarketEntry>,
+    // Last game day the market was updated for
+    pub day: u32,
+}
+
+impl Default for Economy {
+    fn default() -> Self {
+        let market = [
+            (TradeGood::Paperclips, MarketEntry::new(1.0, 500.0)),
+            (TradeGood::Wire, MarketEntry::new(5.0, 100.0)),
+            (TradeGood::CubeShards, MarketEntry::new(40.0, 20.0)),
+            (TradeGood::DataChips, MarketEntry::new(25.0, 30.0)),
+        ]
+        .into_iter()
+        .collect();
+
+        Self { market, day: 0 }
+    }
+}
+
+impl Economy {
+    // Market price of a good before any merchant modifier
+    pub fn price(&self, good: TradeGood) -> f32 {
+        self.market
+            .get(&good)
+            .map(MarketEntry::price)
+            .unwrap_or(0.0)
+    }
+
+    // Price a specific merchant asks for a good
+    pub fn merchant_price(&self, good: TradeGood, merchant: &Merchant) -> f32 {
+        self.price(good) * merchant.price_modifier
+    }
+
+    // The player sold goods to a merchant, flooding the market
+    pub fn record_sale(&mut self, good: TradeGood, quantity: u32) {
+        if let Some(entry) = self.market.get_mut(&good) {
+            entry.supply += quantity as f32;
+        }
+    }
+
+    // The player bought goods from a merchant, making them scarcer
+    pub fn record_purchase(&mut self, good: TradeGood, quantity: u32) {
+        if let Some(entry) = self.market.get_mut(&good) {
+            entry.supply = (entry.supply - quantity as f32).max(0.0);
+        }
+    }
+
+    // A line of market gossip based on whichever good is furthest from normal
+    pub fn flavor_line(&self, merchant: &Merchant) -> String {
+        let Some((good, entry)) = self.market.iter().max_by(|(_, a), (_, b)| {
+            let a = (a.supply / a.baseline_supply).ln().abs();
+            let b = (b.supply / b.baseline_supply).ln().abs();
+            a.total_cmp(&b)
+        }) else {
+            return String::new();
+        };
+
+        let ratio = entry.supply / entry.baseline_supply;
+        if ratio > 1.25 {
+            format!(
+                "Everyone's selling {} these days. I can barely give them away at {:.1} a piece.",
+                good.name(),
+                self.merchant_price(*good, merchant)
+            )
+        } else if ratio < 0.8 {
+            format!(
+                "You can't find {} anywhere right now. They're going for {:.1} each!",
+                good.name(),
+                self.merchant_price(*good, merchant)
+            )
+        } else {
+            "Prices have been steady lately. Can't complain.".to_string()
+        }
+    }
+}
+
+// Resource with the player's money and the goods they're carrying to trade
+#[derive(Resource)]
+pub struct Purse {
+    pub credits: f32,
+    pub goods: HashMap<TradeGood, u32>,
+}
+
+impl Default for Purse {
+    fn default() -> Self {
+        Self {
+            credits: STARTING_CREDITS,
+            goods: [(TradeGood::Paperclips, STARTING_PAPERCLIPS)]
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+impl Purse {
+    pub fn held(&self, good: TradeGood) -> u32 {
+        self.goods.get(&good).copied().unwrap_or(0)
+    }
+}
+
+// Component for NPCs that trade, with their personal markup
+#[derive(Component)]
+pub struct Merchant {
+    pub price_modifier: f32,
+}
+
+// Advance the economy once per game day, letting supply drift back to normal
+fn tick_economy(clock: Res<GameClock>, mut economy: ResMut<Economy>) {
+    let mut rng = rand::rng();
+    while economy.day < clock.day {
+        economy.day += 1;
+        for entry in economy.market.values_mut() {
+            entry.supply += (entry.baseline_supply - entry.supply) * SUPPLY_RECOVERY_RATE;
+            entry.supply *= 1.0 + rng.random_range(-SUPPLY_NOISE..SUPPLY_NOISE);
+        }
+    }
+}
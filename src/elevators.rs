@@ -0,0 +1,236 @@
+use crate::{
+    GameStateSet,
+    footsteps::SurfaceMaterial,
+    hold_interaction::{HoldInteractable, HoldInteractionCompleted},
+    interpolation::TransformInterpolation,
+    platforms::{PlatformMotion, step_toward, track_platform_motion},
+    triggers::TriggerActivated,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+// Elevator constants
+const CAR_HALF_EXTENTS: Vec3 = Vec3::new(1.4, 0.15, 1.4);
+// Beside the east face of the ladder tower, from the ground to its roof
+const ELEVATOR_STOPS: [Vec3; 2] = [Vec3::new(22.5, -0.15, 12.0), Vec3::new(22.5, 7.85, 12.0)];
+// Where each stop's call button stands, and which stop it calls to
+const CALL_BUTTONS: [(Vec3, usize); 2] = [
+    (Vec3::new(24.3, 0.0, 10.2), 0),
+    (Vec3::new(20.4, 8.0, 10.2), 1),
+];
+const ELEVATOR_SPEED: f32 = 2.0;
+const ELEVATOR_DWELL: f32 = 1.5; // Seconds the doors would stay open at each stop
+const BUTTON_PRESS_DURATION: f32 = 0.2; // Just long enough that a glance with the key held doesn't count
+const BUTTON_POST_HEIGHT: f32 = 1.1;
+
+pub struct ElevatorsPlugin;
+
+impl Plugin for ElevatorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_elevator)
+            .add_systems(
+                Update,
+                (press_call_buttons, trigger_elevators).in_set(GameStateSet::Playing),
+            )
+            .add_systems(
+                FixedUpdate,
+                move_elevators
+                    .before(track_platform_motion)
+                    .before(PhysicsSet::SyncBackend)
+                    .in_set(GameStateSet::WorldMotion),
+            );
+    }
+}
+
+// Component for a platform that rides between stops only when called to them
+#[derive(Component)]
+#[require(
+    RigidBody(|| RigidBody::KinematicVelocityBased),
+    PlatformMotion,
+    TransformInterpolation
+)]
+pub struct Elevator {
+    pub stops: Vec<Vec3>,
+    pub speed: f32,
+    // Stops called for, in the order they were called
+    requests: Vec<usize>,
+    // The stop it's standing at, if it isn't between two
+    at: Option<usize>,
+    dwell: f32,
+}
+
+impl Elevator {
+    pub fn new(stops: Vec<Vec3>, speed: f32) -> Self {
+        Self {
+            stops,
+            speed,
+            requests: Vec::new(),
+            at: Some(0),
+            dwell: 0.0,
+        }
+    }
+
+    // The stop after the one it's standing at, or None while it's between stops
+    fn next_stop(&self) -> Option<usize> {
+        self.at.map(|at| (at + 1) % self.stops.len())
+    }
+
+    fn call(&mut self, stop: usize) {
+        // Calling it to where it already waits does nothing
+        let waiting_here = self.at == Some(stop) && self.requests.is_empty();
+        if stop < self.stops.len() && !waiting_here && !self.requests.contains(&stop) {
+            self.requests.push(stop);
+        }
+    }
+}
+
+// Component for a button that calls an elevator to a stop.
+// Without a stop it sends the elevator on to the next one, for the button inside the car.
+#[derive(Component)]
+#[require(HoldInteractable(|| HoldInteractable { duration: BUTTON_PRESS_DURATION }))]
+pub struct CallButton {
+    pub elevator: Entity,
+    pub stop: Option<usize>,
+}
+
+fn spawn_elevator(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let car_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.4, 0.42, 0.45),
+        metallic: 0.6,
+        perceptual_roughness: 0.4,
+        ..default()
+    });
+    let post_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.2, 0.2, 0.22),
+        perceptual_roughness: 0.6,
+        ..default()
+    });
+    let button_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.9, 0.6, 0.1),
+        emissive: LinearRgba::rgb(0.6, 0.3, 0.0),
+        ..default()
+    });
+    let post_mesh = meshes.add(Cuboid::new(0.12, BUTTON_POST_HEIGHT, 0.12));
+    let button_mesh = meshes.add(Cuboid::from_length(0.16));
+
+    let elevator = commands
+        .spawn((
+            Mesh3d(meshes.add(Cuboid::from_size(CAR_HALF_EXTENTS * 2.0))),
+            MeshMaterial3d(car_material),
+            Transform::from_translation(ELEVATOR_STOPS[0]),
+            Collider::cuboid(CAR_HALF_EXTENTS.x, CAR_HALF_EXTENTS.y, CAR_HALF_EXTENTS.z),
+            SurfaceMaterial::Stone,
+            Elevator::new(ELEVATOR_STOPS.to_vec(), ELEVATOR_SPEED),
+        ))
+        .id();
+
+    // A post in the corner of the car to send it on, riding along as its child
+    let car_post = Vec3::new(
+        CAR_HALF_EXTENTS.x - 0.2,
+        CAR_HALF_EXTENTS.y + BUTTON_POST_HEIGHT / 2.0,
+        CAR_HALF_EXTENTS.z - 0.2,
+    );
+    commands.entity(elevator).with_children(|parent| {
+        parent.spawn((
+            Mesh3d(post_mesh.clone()),
+            MeshMaterial3d(post_material.clone()),
+            Transform::from_translation(car_post),
+        ));
+        parent.spawn((
+            Mesh3d(button_mesh.clone()),
+            MeshMaterial3d(button_material.clone()),
+            Transform::from_translation(car_post + Vec3::Y * BUTTON_POST_HEIGHT / 2.0),
+            CallButton {
+                elevator,
+                stop: None,
+            },
+        ));
+    });
+
+    for (position, stop) in CALL_BUTTONS {
+        commands.spawn((
+            Mesh3d(post_mesh.clone()),
+            MeshMaterial3d(post_material.clone()),
+            Transform::from_translation(position + Vec3::Y * BUTTON_POST_HEIGHT / 2.0),
+            Collider::cuboid(0.06, BUTTON_POST_HEIGHT / 2.0, 0.06),
+        ));
+        commands.spawn((
+            Mesh3d(button_mesh.clone()),
+            MeshMaterial3d(button_material.clone()),
+            Transform::from_translation(position + Vec3::Y * BUTTON_POST_HEIGHT),
+            CallButton {
+                elevator,
+                stop: Some(stop),
+            },
+        ));
+    }
+}
+
+fn press_call_buttons(
+    mut events: EventReader<HoldInteractionCompleted>,
+    buttons: Query<&CallButton>,
+    mut elevators: Query<&mut Elevator>,
+) {
+    for event in events.read() {
+        let Ok(button) = buttons.get(event.entity) else {
+            continue;
+        };
+        let Ok(mut elevator) = elevators.get_mut(button.elevator) else {
+            continue;
+        };
+        // The button in the car only works while it's stopped
+        if let Some(stop) = button.stop.or_else(|| elevator.next_stop()) {
+            elevator.call(stop);
+        }
+    }
+}
+
+// Triggers wired to an elevator send it on to its next stop, like the button in the car
+fn trigger_elevators(
+    mut activated: EventReader<TriggerActivated>,
+    mut elevators: Query<&mut Elevator>,
+) {
+    for event in activated.read() {
+        let Ok(mut elevator) = elevators.get_mut(event.target) else {
+            continue;
+        };
+        if let Some(stop) = elevator.next_stop() {
+            elevator.call(stop);
+        }
+    }
+}
+
+// Travel to each called stop in turn, pausing at each before heading for the next
+fn move_elevators(
+    time: Res<Time>,
+    mut elevators: Query<(&Transform, &mut Velocity, &mut Elevator)>,
+) {
+    let delta_time = time.delta_secs();
+    for (transform, mut velocity, mut elevator) in elevators.iter_mut() {
+        velocity.linvel = Vec3::ZERO;
+        if elevator.dwell > 0.0 {
+            elevator.dwell -= delta_time;
+            continue;
+        }
+        let Some(&stop) = elevator.requests.first() else {
+            continue;
+        };
+        elevator.at = None;
+        let (linvel, arrived) = step_toward(
+            transform.translation,
+            elevator.stops[stop],
+            elevator.speed,
+            delta_time,
+        );
+        velocity.linvel = linvel;
+        if arrived {
+            elevator.requests.remove(0);
+            elevator.at = Some(stop);
+            elevator.dwell = ELEVATOR_DWELL;
+        }
+    }
+}
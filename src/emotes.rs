@@ -0,0 +1,328 @@
+use crate::{
+    Npc, PlayerCamera,
+    clock::GameClock,
+    dialogue_tags::DialogueTagTriggered,
+    perception::{PerceivedPlayer, PerceivedThreat, Sense},
+    quests::{DialogueAction, DialogueActionTriggered},
+    world_events::WorldEventStarted,
+};
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    pbr::NotShadowCaster,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use bevy_rapier3d::control::KinematicCharacterController;
+use std::collections::HashMap;
+
+// Emote constants
+const EMOTE_HEIGHT: f32 = 1.6; // Above the NPC's center, over their head
+const EMOTE_SIZE: f32 = 0.5;
+const EMOTE_DURATION: f32 = 2.0;
+const EMOTE_POP_TIME: f32 = 0.15; // Seconds to grow to full size
+const EMOTE_RANGE: f32 = 30.0; // World-wide moments only get a reaction from NPCs this close
+const BUBBLE_COLOR: [u8; 4] = [250, 250, 245, 255];
+const BUBBLE_OUTLINE_COLOR: [u8; 4] = [40, 40, 50, 255];
+
+pub struct EmotesPlugin;
+
+impl Plugin for EmotesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShowEmote>()
+            .add_systems(Startup, setup_emote_assets)
+            .add_systems(
+                Update,
+                (
+                    (emote_on_perception, emote_on_dialogue, emote_on_schedule),
+                    show_emotes,
+                    update_emotes,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EmoteKind {
+    Alert,
+    Question,
+    Heart,
+    Sleep,
+}
+
+impl EmoteKind {
+    const ALL: [EmoteKind; 4] = [
+        EmoteKind::Alert,
+        EmoteKind::Question,
+        EmoteKind::Heart,
+        EmoteKind::Sleep,
+    ];
+
+    // 8x8 pixel art for the symbol, `#` being filled
+    fn glyph(self) -> [&'static str; 8] {
+        match self {
+            EmoteKind::Alert => [
+                "...##...", "...##...", "...##...", "...##...", "...##...", "........", "...##...",
+                "...##...",
+            ],
+            EmoteKind::Question => [
+                "..####..", ".##..##.", ".....##.", "....##..", "...##...", "........", "...##...",
+                "...##...",
+            ],
+            EmoteKind::Heart => [
+                "........", ".##..##.", "########", "########", ".######.", "..####..", "...##...",
+                "........",
+            ],
+            EmoteKind::Sleep => [
+                "....####", "......#.", ".....#..", "....####", "###.....", "..#.....", ".#......",
+                "###.....",
+            ],
+        }
+    }
+
+    fn color(self) -> [u8; 4] {
+        match self {
+            EmoteKind::Alert => [220, 40, 40, 255],
+            EmoteKind::Question => [230, 170, 20, 255],
+            EmoteKind::Heart => [230, 70, 130, 255],
+            EmoteKind::Sleep => [70, 110, 220, 255],
+        }
+    }
+
+    // The symbol drawn onto a round speech bubble, as a 12x12 texture
+    fn image(self) -> Image {
+        const SIZE: usize = 12;
+        let center = (SIZE as f32 - 1.0) / 2.0;
+        let mut data = Vec::with_capacity(SIZE * SIZE * 4);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let distance = Vec2::new(x as f32 - center, y as f32 - center).length();
+                let glyph_pixel = (2..10).contains(&x)
+                    && (2..10).contains(&y)
+                    && self.glyph()[y - 2].as_bytes()[x - 2] == b'#';
+                let pixel = if glyph_pixel {
+                    self.color()
+                } else if distance <= center - 0.5 {
+                    BUBBLE_COLOR
+                } else if distance <= center + 0.5 {
+                    BUBBLE_OUTLINE_COLOR
+                } else {
+                    [0; 4]
+                };
+                data.extend_from_slice(&pixel);
+            }
+        }
+        let mut image = Image::new(
+            Extent3d {
+                width: SIZE as u32,
+                height: SIZE as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        // Keep the pixel art crisp
+        image.sampler = ImageSampler::nearest();
+        image
+    }
+}
+
+// Event asking for an emote to pop up over an NPC, replacing any it's already showing
+#[derive(Event, Clone, Copy)]
+pub struct ShowEmote {
+    pub npc_entity: Entity,
+    pub kind: EmoteKind,
+}
+
+// Resource with the shared quad and a material per kind of emote
+#[derive(Resource)]
+struct EmoteAssets {
+    mesh: Handle<Mesh>,
+    materials: HashMap<EmoteKind, Handle<StandardMaterial>>,
+}
+
+// Component for an emote bubble floating over an NPC's head
+#[derive(Component)]
+struct Emote {
+    npc_entity: Entity,
+    timer: Timer,
+}
+
+fn setup_emote_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let materials = EmoteKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let material = materials.add(StandardMaterial {
+                base_color_texture: Some(images.add(kind.image())),
+                alpha_mode: AlphaMode::Mask(0.5),
+                unlit: true,
+                ..default()
+            });
+            (kind, material)
+        })
+        .collect();
+    commands.insert_resource(EmoteAssets {
+        mesh: meshes.add(Rectangle::from_length(EMOTE_SIZE)),
+        materials,
+    });
+}
+
+// Spotting the player or trouble is alarming, and hearing something is puzzling
+fn emote_on_perception(
+    mut perceived: EventReader<PerceivedPlayer>,
+    mut threats: EventReader<PerceivedThreat>,
+    mut emotes: EventWriter<ShowEmote>,
+) {
+    for threat in threats.read() {
+        emotes.send(ShowEmote {
+            npc_entity: threat.npc,
+            kind: EmoteKind::Alert,
+        });
+    }
+    for event in perceived.read() {
+        let kind = match event.sense {
+            Sense::Sight => EmoteKind::Alert,
+            Sense::Hearing => EmoteKind::Question,
+        };
+        emotes.send(ShowEmote {
+            npc_entity: event.npc,
+            kind,
+        });
+    }
+}
+
+// Tags like `@surprised` or `@love` on a node, and being asked to come along
+fn emote_on_dialogue(
+    mut tags: EventReader<DialogueTagTriggered>,
+    mut actions: EventReader<DialogueActionTriggered>,
+    mut emotes: EventWriter<ShowEmote>,
+) {
+    for event in tags.read() {
+        let kind = match event.tag.as_str() {
+            "surprised" | "angry" => EmoteKind::Alert,
+            "confused" | "suspicious" => EmoteKind::Question,
+            "love" => EmoteKind::Heart,
+            "tired" => EmoteKind::Sleep,
+            _ => continue,
+        };
+        emotes.send(ShowEmote {
+            npc_entity: event.npc_entity,
+            kind,
+        });
+    }
+    for event in actions.read() {
+        if matches!(event.action, DialogueAction::StartFollowing) {
+            emotes.send(ShowEmote {
+                npc_entity: event.npc_entity,
+                kind: EmoteKind::Heart,
+            });
+        }
+    }
+}
+
+// NPCs near the player react when a world event kicks off, and yawn when night falls
+fn emote_on_schedule(
+    clock: Res<GameClock>,
+    mut last_time_of_day: Local<Option<&'static str>>,
+    mut started: EventReader<WorldEventStarted>,
+    player: Query<&Transform, With<KinematicCharacterController>>,
+    npcs: Query<(Entity, &Transform, &Visibility), With<Npc>>,
+    mut emotes: EventWriter<ShowEmote>,
+) {
+    let time_of_day = clock.time_of_day();
+    let nightfall =
+        last_time_of_day.is_some_and(|last| last != time_of_day) && time_of_day == "night";
+    *last_time_of_day = Some(time_of_day);
+    let kind = if started.read().count() > 0 {
+        EmoteKind::Alert
+    } else if nightfall {
+        EmoteKind::Sleep
+    } else {
+        return;
+    };
+
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    for (entity, transform, visibility) in npcs.iter() {
+        if *visibility != Visibility::Hidden
+            && transform.translation.distance(player.translation) <= EMOTE_RANGE
+        {
+            emotes.send(ShowEmote {
+                npc_entity: entity,
+                kind,
+            });
+        }
+    }
+}
+
+fn show_emotes(
+    mut commands: Commands,
+    mut events: EventReader<ShowEmote>,
+    assets: Option<Res<EmoteAssets>>,
+    existing: Query<(Entity, &Emote)>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+    // Only the latest emote per NPC is worth showing
+    let mut latest = HashMap::new();
+    for event in events.read() {
+        latest.insert(event.npc_entity, event.kind);
+    }
+    for (npc_entity, kind) in latest {
+        for (entity, emote) in existing.iter() {
+            if emote.npc_entity == npc_entity {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        commands.spawn((
+            Mesh3d(assets.mesh.clone()),
+            MeshMaterial3d(assets.materials[&kind].clone()),
+            Transform::from_scale(Vec3::ZERO),
+            NotShadowCaster,
+            Emote {
+                npc_entity,
+                timer: Timer::from_seconds(EMOTE_DURATION, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+// Keep each bubble over its NPC's head and facing the camera, popping in and out
+fn update_emotes(
+    mut commands: Commands,
+    time: Res<Time>,
+    camera: Query<&GlobalTransform, With<PlayerCamera>>,
+    npcs: Query<(&GlobalTransform, &Visibility), With<Npc>>,
+    mut emotes: Query<(Entity, &mut Emote, &mut Transform)>,
+) {
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+    for (entity, mut emote, mut transform) in emotes.iter_mut() {
+        emote.timer.tick(time.delta());
+        let npc = npcs
+            .get(emote.npc_entity)
+            .ok()
+            .filter(|(_, visibility)| **visibility != Visibility::Hidden);
+        let Some((npc_transform, _)) = npc.filter(|_| !emote.timer.finished()) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        let elapsed = emote.timer.elapsed_secs();
+        let remaining = emote.timer.remaining_secs();
+        let scale = (elapsed.min(remaining) / EMOTE_POP_TIME).min(1.0);
+        transform.translation = npc_transform.translation() + Vec3::Y * EMOTE_HEIGHT;
+        transform.rotation = camera.compute_transform().rotation;
+        transform.scale = Vec3::splat(scale);
+    }
+}
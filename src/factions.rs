@@ -0,0 +1,184 @@
+use crate::dialogue_variables::{DialogueValue, DialogueVariables};
+use bevy::prelude::*;
+
+// Faction constants
+const HOSTILE_BELOW: f32 = -50.0;
+const UNFRIENDLY_BELOW: f32 = -10.0;
+const FRIENDLY_ABOVE: f32 = 10.0;
+const ALLIED_ABOVE: f32 = 50.0;
+const MAX_STANDING: f32 = 100.0;
+
+pub struct FactionsPlugin;
+
+impl Plugin for FactionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FactionStandings>()
+            .add_systems(Update, sync_standing_variables);
+    }
+}
+
+// Component with the group an NPC belongs to
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Faction {
+    #[default]
+    Civilians,
+    Guards,
+    Merchants,
+    Institute,
+    Scrappers,
+}
+
+impl Faction {
+    pub const ALL: [Faction; 5] = [
+        Faction::Civilians,
+        Faction::Guards,
+        Faction::Merchants,
+        Faction::Institute,
+        Faction::Scrappers,
+    ];
+
+    // The faction each kind of NPC joins, by dialogue id
+    pub fn for_dialogue(dialogue_id: &str) -> Self {
+        match dialogue_id {
+            "guard" => Faction::Guards,
+            "merchant" => Faction::Merchants,
+            "scientist" | "mysterious" => Faction::Institute,
+            "scrapper" => Faction::Scrappers,
+            _ => Faction::Civilians,
+        }
+    }
+
+    // Name used for this faction's dialogue variables, e.g. `$guards_standing`
+    pub fn key(self) -> &'static str {
+        match self {
+            Faction::Civilians => "civilians",
+            Faction::Guards => "guards",
+            Faction::Merchants => "merchants",
+            Faction::Institute => "institute",
+            Faction::Scrappers => "scrappers",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Relationship {
+    Hostile,
+    Unfriendly,
+    Neutral,
+    Friendly,
+    Allied,
+}
+
+impl Relationship {
+    pub fn from_standing(standing: f32) -> Self {
+        if standing < HOSTILE_BELOW {
+            Relationship::Hostile
+        } else if standing < UNFRIENDLY_BELOW {
+            Relationship::Unfriendly
+        } else if standing > ALLIED_ABOVE {
+            Relationship::Allied
+        } else if standing > FRIENDLY_ABOVE {
+            Relationship::Friendly
+        } else {
+            Relationship::Neutral
+        }
+    }
+}
+
+// Resource with how each faction feels about the others and about the player, from -100 to 100
+#[derive(Resource)]
+pub struct FactionStandings {
+    // `between[a][b]` is how faction `a` regards faction `b`, which needn't be mutual
+    between: [[f32; Faction::ALL.len()]; Faction::ALL.len()],
+    player: [f32; Faction::ALL.len()],
+}
+
+impl Default for FactionStandings {
+    fn default() -> Self {
+        use Faction::*;
+        let mut standings = Self {
+            between: [[0.0; Faction::ALL.len()]; Faction::ALL.len()],
+            player: [0.0; Faction::ALL.len()],
+        };
+        for faction in Faction::ALL {
+            standings.set(faction, faction, ALLIED_ABOVE + 25.0);
+        }
+        standings.set(Civilians, Guards, 20.0);
+        standings.set(Guards, Civilians, 30.0);
+        standings.set(Civilians, Merchants, 25.0);
+        standings.set(Merchants, Civilians, 20.0);
+        standings.set(Merchants, Guards, 40.0);
+        standings.set(Guards, Merchants, 15.0);
+        // Nobody quite trusts the Institute, and the guards keep a close eye on it
+        standings.set(Civilians, Institute, -15.0);
+        standings.set(Guards, Institute, -30.0);
+        standings.set(Merchants, Institute, 5.0);
+        standings.set(Institute, Civilians, -5.0);
+        standings.set(Institute, Guards, -20.0);
+        standings.set(Institute, Merchants, 10.0);
+        // Scrappers strip the town for parts, and everyone knows it
+        for faction in [Civilians, Guards, Merchants, Institute] {
+            standings.set(faction, Scrappers, -70.0);
+            standings.set(Scrappers, faction, -80.0);
+        }
+        standings.player[Scrappers.index()] = -90.0;
+        standings
+    }
+}
+
+impl FactionStandings {
+    // How `from` regards `toward`
+    pub fn standing(&self, from: Faction, toward: Faction) -> f32 {
+        self.between[from.index()][toward.index()]
+    }
+
+    pub fn relationship(&self, from: Faction, toward: Faction) -> Relationship {
+        Relationship::from_standing(self.standing(from, toward))
+    }
+
+    pub fn set(&mut self, from: Faction, toward: Faction, standing: f32) {
+        self.between[from.index()][toward.index()] = standing.clamp(-MAX_STANDING, MAX_STANDING);
+    }
+
+    // How `faction` regards the player
+    pub fn player_standing(&self, faction: Faction) -> f32 {
+        self.player[faction.index()]
+    }
+
+    pub fn player_relationship(&self, faction: Faction) -> Relationship {
+        Relationship::from_standing(self.player_standing(faction))
+    }
+
+    // Helping or wronging one faction spills over onto the factions that care about it
+    pub fn adjust_player_standing(&mut self, faction: Faction, delta: f32) {
+        for other in Faction::ALL {
+            let share = if other == faction {
+                1.0
+            } else {
+                self.standing(other, faction) / MAX_STANDING
+            };
+            let standing = &mut self.player[other.index()];
+            *standing = (*standing + delta * share).clamp(-MAX_STANDING, MAX_STANDING);
+        }
+    }
+}
+
+// Mirror the player's standings into dialogue variables so conditions like `$guards_standing >= 10` work
+fn sync_standing_variables(
+    standings: Res<FactionStandings>,
+    mut variables: ResMut<DialogueVariables>,
+) {
+    if !standings.is_changed() {
+        return;
+    }
+    for faction in Faction::ALL {
+        variables.set(
+            format!("{}_standing", faction.key()),
+            DialogueValue::Number(standings.player_standing(faction)),
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{
+    GameState, PlayerCamera,
+    accessibility::AccessibilitySettings,
+    input_map::{ActionState, InputAction},
+    prop_grab::Carrying,
+    setup_player,
+};
+use bevy::{pbr::NotShadowCaster, prelude::*};
+use bevy_rapier3d::control::KinematicCharacterController;
+use std::f32::consts::FRAC_PI_2;
+
+// First-person arms constants
+const ARM_COLOR: Color = Color::srgb(0.25, 0.3, 0.4); // Sleeves matching the player's body
+const HAND_COLOR: Color = Color::srgb(0.85, 0.7, 0.6);
+const SHOULDER_OFFSET: Vec3 = Vec3::new(0.2, -0.22, 0.0); // Right shoulder, below and beside the eyes
+const UPPER_ARM_LENGTH: f32 = 0.3;
+const FOREARM_LENGTH: f32 = 0.28;
+const ARM_RADIUS: f32 = 0.045;
+const HAND_RADIUS: f32 = 0.055;
+const ARM_BLEND_SPEED: f32 = 12.0; // How quickly joints ease into each new pose
+const IDLE_SWAY: f32 = 0.03; // Radians the arms drift while breathing
+const IDLE_SWAY_SPEED: f32 = 1.5;
+const WAVE_DURATION: f32 = 1.5;
+const WAVE_SPEED: f32 = 12.0;
+const THROW_DURATION: f32 = 0.35;
+
+pub struct FirstPersonArmsPlugin;
+
+impl Plugin for FirstPersonArmsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ArmsAnimator>()
+            .add_systems(Startup, spawn_arms.after(setup_player))
+            .add_systems(OnEnter(GameState::InDialogue), wave_on_greeting)
+            .add_systems(Update, (follow_carrying, animate_arms).chain());
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum ArmAnimation {
+    #[default]
+    Idle,
+    // Right hand raised to say hello
+    Wave,
+    // Both hands out in front around a carried prop
+    Hold,
+    // Quick shove forward as a prop is let go
+    Throw,
+}
+
+impl ArmAnimation {
+    // Seconds before a one-shot animation returns to idle, or `None` if it loops
+    fn duration(self) -> Option<f32> {
+        match self {
+            ArmAnimation::Wave => Some(WAVE_DURATION),
+            ArmAnimation::Throw => Some(THROW_DURATION),
+            ArmAnimation::Idle | ArmAnimation::Hold => None,
+        }
+    }
+
+    // Shoulder and elbow rotations for one arm, `side` being 1 for the right and -1 for the left,
+    // with the breathing sway scaled by `sway_scale`
+    fn pose(self, side: f32, elapsed: f32, sway_scale: f32) -> (Quat, Quat) {
+        let sway = (elapsed * IDLE_SWAY_SPEED).sin() * IDLE_SWAY * sway_scale;
+        let idle = (Quat::from_rotation_x(sway), Quat::from_rotation_x(0.25));
+        match self {
+            ArmAnimation::Idle => idle,
+            ArmAnimation::Wave if side > 0.0 => {
+                let wave = (elapsed * WAVE_SPEED).sin() * 0.5;
+                (
+                    Quat::from_rotation_y(-0.3) * Quat::from_rotation_x(1.1),
+                    Quat::from_rotation_x(0.6) * Quat::from_rotation_z(wave),
+                )
+            }
+            ArmAnimation::Wave => idle,
+            ArmAnimation::Hold => (
+                Quat::from_rotation_y(side * 0.25) * Quat::from_rotation_x(0.2),
+                Quat::from_rotation_x(0.1),
+            ),
+            ArmAnimation::Throw => (Quat::from_rotation_x(0.45), Quat::IDENTITY),
+        }
+    }
+}
+
+// Resource with what the arms are doing and for how long
+#[derive(Resource, Default)]
+struct ArmsAnimator {
+    animation: ArmAnimation,
+    elapsed: f32,
+}
+
+impl ArmsAnimator {
+    fn play(&mut self, animation: ArmAnimation) {
+        self.animation = animation;
+        self.elapsed = 0.0;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum JointKind {
+    Shoulder,
+    Elbow,
+}
+
+// Pivot in the arms rig, posed by the current animation
+#[derive(Component)]
+struct ArmJoint {
+    kind: JointKind,
+    side: f32,
+}
+
+// Each arm hangs off the camera as shoulder, upper arm, elbow, forearm and hand
+fn spawn_arms(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    cameras: Query<Entity, With<PlayerCamera>>,
+) {
+    let Ok(camera) = cameras.get_single() else {
+        return;
+    };
+    let sleeve = materials.add(StandardMaterial {
+        base_color: ARM_COLOR,
+        perceptual_roughness: 0.8,
+        ..default()
+    });
+    let skin = materials.add(StandardMaterial {
+        base_color: HAND_COLOR,
+        perceptual_roughness: 0.6,
+        ..default()
+    });
+    // Capsules stand along y, so lay them along the arm pointing down -z
+    let along_arm = Quat::from_rotation_x(FRAC_PI_2);
+    let upper_arm = meshes.add(Capsule3d::new(ARM_RADIUS, UPPER_ARM_LENGTH));
+    let forearm = meshes.add(Capsule3d::new(ARM_RADIUS, FOREARM_LENGTH));
+    let hand = meshes.add(Sphere::new(HAND_RADIUS));
+
+    commands.entity(camera).with_children(|camera| {
+        for side in [-1.0, 1.0] {
+            camera
+                .spawn((
+                    ArmJoint {
+                        kind: JointKind::Shoulder,
+                        side,
+                    },
+                    Transform::from_translation(SHOULDER_OFFSET * Vec3::new(side, 1.0, 1.0)),
+                    Visibility::default(),
+                ))
+                .with_children(|shoulder| {
+                    shoulder.spawn((
+                        Mesh3d(upper_arm.clone()),
+                        MeshMaterial3d(sleeve.clone()),
+                        Transform::from_xyz(0.0, 0.0, -UPPER_ARM_LENGTH / 2.0)
+                            .with_rotation(along_arm),
+                        // The player's body already casts the shadow
+                        NotShadowCaster,
+                    ));
+                    shoulder
+                        .spawn((
+                            ArmJoint {
+                                kind: JointKind::Elbow,
+                                side,
+                            },
+                            Transform::from_xyz(0.0, 0.0, -UPPER_ARM_LENGTH),
+                            Visibility::default(),
+                        ))
+                        .with_children(|elbow| {
+                            elbow.spawn((
+                                Mesh3d(forearm.clone()),
+                                MeshMaterial3d(sleeve.clone()),
+                                Transform::from_xyz(0.0, 0.0, -FOREARM_LENGTH / 2.0)
+                                    .with_rotation(along_arm),
+                                NotShadowCaster,
+                            ));
+                            elbow.spawn((
+                                Mesh3d(hand.clone()),
+                                MeshMaterial3d(skin.clone()),
+                                Transform::from_xyz(0.0, 0.0, -FOREARM_LENGTH),
+                                NotShadowCaster,
+                            ));
+                        });
+                });
+        }
+    });
+}
+
+// Starting a conversation is a greeting
+fn wave_on_greeting(mut animator: ResMut<ArmsAnimator>) {
+    animator.play(ArmAnimation::Wave);
+}
+
+// Hold out both hands while carrying a prop, and shove them forward when it's thrown
+fn follow_carrying(
+    actions: Res<ActionState>,
+    player: Query<Has<Carrying>, With<KinematicCharacterController>>,
+    mut animator: ResMut<ArmsAnimator>,
+) {
+    let Ok(carrying) = player.get_single() else {
+        return;
+    };
+    let holding = animator.animation == ArmAnimation::Hold;
+    if carrying && !holding {
+        animator.play(ArmAnimation::Hold);
+    } else if !carrying && holding {
+        if actions.pressed(InputAction::Throw) {
+            animator.play(ArmAnimation::Throw);
+        } else {
+            animator.play(ArmAnimation::Idle);
+        }
+    }
+}
+
+fn animate_arms(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    mut animator: ResMut<ArmsAnimator>,
+    mut joints: Query<(&ArmJoint, &mut Transform)>,
+) {
+    let delta_time = time.delta_secs();
+    animator.elapsed += delta_time;
+    if animator
+        .animation
+        .duration()
+        .is_some_and(|duration| animator.elapsed >= duration)
+    {
+        animator.play(ArmAnimation::Idle);
+    }
+
+    let blend = (ARM_BLEND_SPEED * delta_time).min(1.0);
+    for (joint, mut transform) in joints.iter_mut() {
+        let (shoulder, elbow) =
+            animator
+                .animation
+                .pose(joint.side, animator.elapsed, accessibility.motion_scale());
+        let target = match joint.kind {
+            JointKind::Shoulder => shoulder,
+            JointKind::Elbow => elbow,
+        };
+        transform.rotation = transform.rotation.slerp(target, blend);
+    }
+}
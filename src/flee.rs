@@ -0,0 +1,105 @@
+use crate::{
+    GameStateSet, Npc, companions::Following, factions::Faction, npc_avoidance::yield_to_player,
+    perception::PerceivedThreat, update_npcs,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+// Flee constants
+const FLEE_SPEED: f32 = 5.0;
+const FLEE_DISTANCE: f32 = 12.0; // How far from the threat a fleeing NPC wants to end up
+const FLEE_REACHED: f32 = 0.5;
+const FLEE_TURN_SPEED: f32 = 10.0;
+const COWER_DURATION: f32 = 8.0;
+
+pub struct FleePlugin;
+
+impl Plugin for FleePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, start_fleeing).add_systems(
+            FixedUpdate,
+            flee_threats
+                .after(yield_to_player)
+                .before(update_npcs)
+                .before(PhysicsSet::SyncBackend)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// Component on a civilian running from trouble, then cowering once they're clear of it
+#[derive(Component)]
+pub struct Fleeing {
+    threat: Vec3,
+    refuge: Vec3,
+    // Seconds left hunkered down, zero while still running
+    cowering: f32,
+}
+
+// Home if getting there leads away from the threat and ends up far enough from it, otherwise straight away
+fn refuge(position: Vec3, home: Vec3, threat: Vec3) -> Vec3 {
+    let away = (position - threat).with_y(0.0);
+    let home_is_safe = home.with_y(0.0).distance(threat.with_y(0.0)) >= FLEE_DISTANCE
+        && (home - position).with_y(0.0).dot(away) >= 0.0;
+    if home_is_safe {
+        home
+    } else {
+        position + away.normalize_or(Vec3::X) * FLEE_DISTANCE
+    }
+}
+
+// Civilians who notice a fight or an alarm bolt, even if they were already cowering
+fn start_fleeing(
+    mut commands: Commands,
+    mut threats: EventReader<PerceivedThreat>,
+    civilians: Query<(&Transform, &Npc, &Faction), Without<Following>>,
+) {
+    for threat in threats.read() {
+        let Ok((transform, npc, faction)) = civilians.get(threat.npc) else {
+            continue;
+        };
+        if *faction != Faction::Civilians {
+            continue;
+        }
+        commands.entity(threat.npc).insert(Fleeing {
+            threat: threat.position,
+            refuge: refuge(transform.translation, npc.home_position, threat.position),
+            cowering: 0.0,
+        });
+    }
+}
+
+fn flee_threats(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut npcs: Query<(Entity, &mut Transform, &mut Npc, &mut Fleeing)>,
+) {
+    let delta_time = time.delta_secs();
+    for (entity, mut transform, mut npc, mut fleeing) in npcs.iter_mut() {
+        // Keep wandering from picking up where it left off once fleeing's over
+        npc.target_position = transform.translation;
+
+        if fleeing.cowering > 0.0 {
+            fleeing.cowering -= delta_time;
+            if fleeing.cowering <= 0.0 {
+                commands.entity(entity).remove::<Fleeing>();
+            }
+            continue;
+        }
+
+        let offset = (fleeing.refuge - transform.translation).with_y(0.0);
+        if offset.length() <= FLEE_REACHED {
+            fleeing.cowering = COWER_DURATION;
+            // Hunker down with their back to the trouble
+            let away = (transform.translation - fleeing.threat).with_y(0.0);
+            if away != Vec3::ZERO {
+                transform.rotation = Quat::from_rotation_y(f32::atan2(away.x, away.z));
+            }
+            continue;
+        }
+        transform.translation += offset.clamp_length_max(FLEE_SPEED * delta_time);
+        let facing = Quat::from_rotation_y(f32::atan2(offset.x, offset.z));
+        let blend = (FLEE_TURN_SPEED * delta_time).min(1.0);
+        transform.rotation = transform.rotation.slerp(facing, blend);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::GameStateSet;
+use bevy::{audio::Volume, prelude::*};
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// Footstep constants
+pub const STRIDE_LENGTH: f32 = 1.6; // Distance walked between steps, so faster movement steps more often
+const FOOTSTEP_VOLUME: f32 = 0.5;
+const FOOTSTEP_PITCH_VARIATION: f32 = 0.1;
+const GROUND_PROBE_DISTANCE: f32 = 0.3; // How far below the feet to look for the surface
+
+pub struct FootstepsPlugin;
+
+impl Plugin for FootstepsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Footstep>()
+            .add_systems(Startup, load_footstep_clips)
+            .add_systems(Update, play_footsteps.in_set(GameStateSet::Playing));
+    }
+}
+
+// What a piece of world geometry is made of, for anything that sounds different per surface
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+pub enum SurfaceMaterial {
+    #[default]
+    Grass,
+    Stone,
+    Wood,
+}
+
+impl SurfaceMaterial {
+    const ALL: [SurfaceMaterial; 3] = [
+        SurfaceMaterial::Grass,
+        SurfaceMaterial::Stone,
+        SurfaceMaterial::Wood,
+    ];
+
+    fn footstep_clips(self) -> &'static [&'static str] {
+        match self {
+            SurfaceMaterial::Grass => &[
+                "audio/footsteps/grass_1.ogg",
+                "audio/footsteps/grass_2.ogg",
+                "audio/footsteps/grass_3.ogg",
+            ],
+            SurfaceMaterial::Stone => &[
+                "audio/footsteps/stone_1.ogg",
+                "audio/footsteps/stone_2.ogg",
+                "audio/footsteps/stone_3.ogg",
+            ],
+            SurfaceMaterial::Wood => &["audio/footsteps/wood_1.ogg", "audio/footsteps/wood_2.ogg"],
+        }
+    }
+}
+
+// Event sent by `player_movement` each time the player's foot comes down
+#[derive(Event)]
+pub struct Footstep {
+    pub entity: Entity,
+    // Where the feet are, for finding the surface underneath
+    pub position: Vec3,
+}
+
+// Resource with the loaded clips for each surface
+#[derive(Resource, Default)]
+struct FootstepClips(HashMap<SurfaceMaterial, Vec<Handle<AudioSource>>>);
+
+fn load_footstep_clips(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let clips = SurfaceMaterial::ALL
+        .into_iter()
+        .map(|surface| {
+            let handles = surface
+                .footstep_clips()
+                .iter()
+                .map(|path| asset_server.load(*path))
+                .collect();
+            (surface, handles)
+        })
+        .collect();
+    commands.insert_resource(FootstepClips(clips));
+}
+
+fn play_footsteps(
+    mut commands: Commands,
+    mut footsteps: EventReader<Footstep>,
+    clips: Res<FootstepClips>,
+    rapier_context: ReadRapierContext,
+    surfaces: Query<&SurfaceMaterial>,
+) {
+    let physics = rapier_context.single();
+    let mut rng = rand::rng();
+    for footstep in footsteps.read() {
+        let filter = QueryFilter::default()
+            .exclude_collider(footstep.entity)
+            .exclude_sensors();
+        // Untagged geometry sounds like the default surface
+        let surface = physics
+            .cast_ray(
+                footstep.position,
+                Vec3::NEG_Y,
+                GROUND_PROBE_DISTANCE,
+                true,
+                filter,
+            )
+            .and_then(|(entity, _)| surfaces.get(entity).ok().copied())
+            .unwrap_or_default();
+        let Some(clips) = clips.0.get(&surface).filter(|clips| !clips.is_empty()) else {
+            continue;
+        };
+        let clip = clips[rng.random_range(0..clips.len())].clone();
+        // Slight pitch changes keep repeated clips from sounding mechanical
+        let speed = 1.0 + rng.random_range(-FOOTSTEP_PITCH_VARIATION..=FOOTSTEP_PITCH_VARIATION);
+        commands.spawn((
+            AudioPlayer(clip),
+            PlaybackSettings::DESPAWN
+                .with_volume(Volume::new(FOOTSTEP_VOLUME))
+                .with_speed(speed),
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::paths::{SettingsFile, UserPaths};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GamepadConfig>()
+            .add_systems(Startup, load_gamepad_config);
+    }
+}
+
+// Resource with the player's stick tuning, separate from mouse sensitivity
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadConfig {
+    pub move_deadzone: f32,
+    pub look_deadzone: f32,
+    // Degrees per second at full right stick deflection
+    pub look_sensitivity: f32,
+    pub invert_look_y: bool,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            move_deadzone: 0.15,
+            look_deadzone: 0.1,
+            look_sensitivity: 180.0,
+            invert_look_y: false,
+        }
+    }
+}
+
+impl SettingsFile for GamepadConfig {
+    const FILE: &'static str = "gamepad.ron";
+}
+
+// Ignore small deflections and rescale the rest so movement still starts at zero
+pub fn apply_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= deadzone || deadzone >= 1.0 {
+        return Vec2::ZERO;
+    }
+    let scaled = ((length - deadzone) / (1.0 - deadzone)).min(1.0);
+    stick / length * scaled
+}
+
+fn load_gamepad_config(paths: Res<UserPaths>, mut config: ResMut<GamepadConfig>) {
+    if let Some(loaded) = paths.load_settings() {
+        *config = loaded;
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    ActiveDialogue, GameState, GameStateSet, Npc,
+    ai_lod::{AiLod, AiLodLevel},
+    dialogue_tags::DialogueTagTriggered,
+    dialogue_variables::{DialogueValue, DialogueVariables},
+    npc_memory::NpcMemory,
+    npc_rng::NpcRng,
+    setup_dialogue_ui,
+};
+use bevy::prelude::*;
+use rand::{Rng, seq::IndexedRandom};
+use std::collections::HashSet;
+
+// Gossip constants
+const GOSSIP_RANGE: f32 = 3.0; // NPCs closer than this can swap rumors
+const GOSSIP_INTERVAL: f32 = 2.0; // Seconds between chances to pass something on
+const GOSSIP_CHANCE: f64 = 0.25; // So news spreads over a few encounters rather than instantly
+const RUMOR_TAG: &str = "rumor_";
+
+pub struct GossipPlugin;
+
+impl Plugin for GossipPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Gossip {
+            timer: Timer::from_seconds(GOSSIP_INTERVAL, TimerMode::Repeating),
+            rumors: HashSet::new(),
+        })
+        .add_systems(
+            OnEnter(GameState::InDialogue),
+            expose_rumors.before(setup_dialogue_ui),
+        )
+        .add_systems(Update, learn_rumors)
+        .add_systems(Update, spread_gossip.in_set(GameStateSet::Playing));
+    }
+}
+
+#[derive(Resource)]
+struct Gossip {
+    timer: Timer,
+    // Every rumor started so far, so dialogue can tell which ones an NPC hasn't heard
+    rumors: HashSet<String>,
+}
+
+// Dialogue starts a rumor with a `@rumor_<name>` tag, e.g. `@rumor_rude_to_guard`
+fn learn_rumors(
+    mut events: EventReader<DialogueTagTriggered>,
+    mut gossip: ResMut<Gossip>,
+    mut memories: Query<&mut NpcMemory>,
+) {
+    for event in events.read() {
+        let Some(rumor) = event.tag.strip_prefix(RUMOR_TAG) else {
+            continue;
+        };
+        gossip.rumors.insert(rumor.to_string());
+        if let Ok(mut memory) = memories.get_mut(event.npc_entity) {
+            memory.hear(rumor);
+        }
+    }
+}
+
+// NPCs passing each other now and then tell the other one something they hadn't heard
+fn spread_gossip(
+    time: Res<Time>,
+    mut gossip: ResMut<Gossip>,
+    mut rng: ResMut<NpcRng>,
+    mut npcs: Query<(Entity, &Transform, &Npc, &mut NpcMemory, &AiLod)>,
+) {
+    if !gossip.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let rng = &mut rng.0;
+    let mut told = Vec::new();
+    for [
+        (a_entity, a, a_npc, a_memory, a_lod),
+        (b_entity, b, b_npc, b_memory, b_lod),
+    ] in npcs.iter_combinations()
+    {
+        if a_lod.level == AiLodLevel::Asleep || b_lod.level == AiLodLevel::Asleep {
+            continue;
+        }
+        if a.translation.distance(b.translation) > GOSSIP_RANGE {
+            continue;
+        }
+        for (teller, (listener_entity, listener), teller_memory, listener_memory) in [
+            (a_npc, (b_entity, b_npc), &a_memory, &b_memory),
+            (b_npc, (a_entity, a_npc), &b_memory, &a_memory),
+        ] {
+            let news: Vec<&String> = teller_memory
+                .rumors
+                .iter()
+                .filter(|rumor| !listener_memory.rumors.contains(rumor))
+                .collect();
+            if let Some(rumor) = news.choose(rng)
+                && rng.random_bool(GOSSIP_CHANCE)
+            {
+                told.push((listener_entity, (*rumor).clone()));
+                println!("{} told {} about {rumor}", teller.name, listener.name);
+            }
+        }
+    }
+
+    // Heard after everyone's had their say, so a rumor only travels one step per round
+    for (listener, rumor) in told {
+        if let Ok((_, _, _, mut memory, _)) = npcs.get_mut(listener) {
+            memory.hear(&rumor);
+        }
+    }
+}
+
+// Dialogue checks whether the NPC has heard a rumor with `$heard_<name>`
+fn expose_rumors(
+    gossip: Res<Gossip>,
+    active_dialogue: Query<&ActiveDialogue>,
+    memories: Query<&NpcMemory>,
+    mut variables: ResMut<DialogueVariables>,
+) {
+    let Ok(active_dialogue) = active_dialogue.get_single() else {
+        return;
+    };
+    let heard = memories.get(active_dialogue.npc_entity).ok();
+    // Clear what the last NPC knew before setting what this one does
+    for rumor in &gossip.rumors {
+        let known = heard.is_some_and(|memory| memory.rumors.contains(rumor));
+        variables.set(format!("heard_{rumor}"), DialogueValue::Bool(known));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::paths::{SettingsFile, UserPaths};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Graphics settings constants
+const PARTICLE_QUALITY_KEY: KeyCode = KeyCode::F8;
+
+pub struct GraphicsSettingsPlugin;
+
+impl Plugin for GraphicsSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GraphicsSettings>()
+            .add_systems(Startup, load_graphics_settings)
+            .add_systems(Update, cycle_particle_quality);
+    }
+}
+
+// How much decorative particle work to do
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParticleQuality {
+    Off,
+    Low,
+    Medium,
+    #[default]
+    High,
+}
+
+impl ParticleQuality {
+    fn next(self) -> Self {
+        match self {
+            ParticleQuality::Off => ParticleQuality::Low,
+            ParticleQuality::Low => ParticleQuality::Medium,
+            ParticleQuality::Medium => ParticleQuality::High,
+            ParticleQuality::High => ParticleQuality::Off,
+        }
+    }
+}
+
+// Resource with the player's graphics options, saved to `graphics.ron`
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    pub particle_quality: ParticleQuality,
+}
+
+impl SettingsFile for GraphicsSettings {
+    const FILE: &'static str = "graphics.ron";
+}
+
+impl GraphicsSettings {
+    // Share of the ambient particles to show, from 0 to 1
+    pub fn particle_density(&self) -> f32 {
+        match self.particle_quality {
+            ParticleQuality::Off => 0.0,
+            ParticleQuality::Low => 0.25,
+            ParticleQuality::Medium => 0.6,
+            ParticleQuality::High => 1.0,
+        }
+    }
+}
+
+fn load_graphics_settings(paths: Res<UserPaths>, mut settings: ResMut<GraphicsSettings>) {
+    if let Some(loaded) = paths.load_settings() {
+        *settings = loaded;
+    }
+}
+
+fn cycle_particle_quality(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    paths: Res<UserPaths>,
+    mut settings: ResMut<GraphicsSettings>,
+) {
+    if !keyboard.just_pressed(PARTICLE_QUALITY_KEY) {
+        return;
+    }
+    settings.particle_quality = settings.particle_quality.next();
+    println!("Particle quality: {:?}", settings.particle_quality);
+    paths.save_settings(&*settings);
+}
@@ -0,0 +1,198 @@
+use crate::{
+    Landed, Npc,
+    dialogue_telemetry::DialogueOptionChosen,
+    health::PlayerDamaged,
+    input_map::{controls_menu_closed, slider_settled},
+    paths::{SettingsFile, UserPaths},
+};
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+use bevy_egui::{EguiContexts, egui};
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Haptics constants
+const HEAVY_HIT_DAMAGE: f32 = 30.0; // Hits this hard or harder rumble at full strength
+const BUMP_COOLDOWN: f32 = 0.5; // Leaning on an NPC shouldn't buzz constantly
+
+pub struct HapticsPlugin;
+
+impl Plugin for HapticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HapticsSettings>()
+            .add_event::<Rumble>()
+            .add_systems(Startup, load_haptics_settings)
+            .add_systems(
+                Update,
+                (
+                    rumble_on_landing,
+                    rumble_on_damage,
+                    rumble_on_npc_bump,
+                    rumble_on_dialogue_choice,
+                    play_rumbles,
+                )
+                    .chain(),
+            )
+            // Shown next to the controls window, which frees the cursor
+            .add_systems(
+                Update,
+                haptics_settings_ui.run_if(not(controls_menu_closed)),
+            );
+    }
+}
+
+// Resource with how strongly the controller shakes, saved to `haptics.ron`
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HapticsSettings {
+    pub enabled: bool,
+    // Multiplier on every rumble
+    pub intensity: f32,
+}
+
+impl Default for HapticsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            intensity: 1.0,
+        }
+    }
+}
+
+impl SettingsFile for HapticsSettings {
+    const FILE: &'static str = "haptics.ron";
+}
+
+// Event asking every connected gamepad to rumble, before the player's intensity setting
+#[derive(Event, Clone, Copy)]
+pub struct Rumble {
+    // Low-frequency motor, for heavy thuds
+    pub strong: f32,
+    // High-frequency motor, for light taps
+    pub weak: f32,
+    pub duration: f32,
+}
+
+impl Rumble {
+    const BUMP: Rumble = Rumble {
+        strong: 0.0,
+        weak: 0.4,
+        duration: 0.1,
+    };
+    const CONFIRM: Rumble = Rumble {
+        strong: 0.0,
+        weak: 0.25,
+        duration: 0.06,
+    };
+
+    // Harder landings thud harder and longer
+    fn landing(strength: f32) -> Rumble {
+        Rumble {
+            strong: strength,
+            weak: strength * 0.5,
+            duration: 0.1 + 0.2 * strength,
+        }
+    }
+
+    // Getting hit is a sharp jolt, stronger the more it hurt
+    fn hit(damage: f32) -> Rumble {
+        let strength = (damage / HEAVY_HIT_DAMAGE).clamp(0.3, 1.0);
+        Rumble {
+            strong: strength,
+            weak: strength,
+            duration: 0.15,
+        }
+    }
+}
+
+fn load_haptics_settings(paths: Res<UserPaths>, mut settings: ResMut<HapticsSettings>) {
+    if let Some(loaded) = paths.load_settings() {
+        *settings = loaded;
+    }
+}
+
+fn rumble_on_landing(mut landings: EventReader<Landed>, mut rumbles: EventWriter<Rumble>) {
+    for landing in landings.read() {
+        rumbles.send(Rumble::landing(landing.strength()));
+    }
+}
+
+fn rumble_on_damage(mut hits: EventReader<PlayerDamaged>, mut rumbles: EventWriter<Rumble>) {
+    for hit in hits.read() {
+        rumbles.send(Rumble::hit(hit.amount));
+    }
+}
+
+fn rumble_on_npc_bump(
+    time: Res<Time>,
+    player: Query<&KinematicCharacterControllerOutput>,
+    npcs: Query<(), With<Npc>>,
+    mut rumbles: EventWriter<Rumble>,
+    mut cooldown: Local<f32>,
+) {
+    *cooldown -= time.delta_secs();
+    let Ok(output) = player.get_single() else {
+        return;
+    };
+    let bumped = output
+        .collisions
+        .iter()
+        .any(|collision| npcs.contains(collision.entity));
+    if bumped && *cooldown <= 0.0 {
+        rumbles.send(Rumble::BUMP);
+        *cooldown = BUMP_COOLDOWN;
+    }
+}
+
+fn rumble_on_dialogue_choice(
+    mut choices: EventReader<DialogueOptionChosen>,
+    mut rumbles: EventWriter<Rumble>,
+) {
+    for _ in choices.read() {
+        rumbles.send(Rumble::CONFIRM);
+    }
+}
+
+fn play_rumbles(
+    settings: Res<HapticsSettings>,
+    mut rumbles: EventReader<Rumble>,
+    gamepads: Query<Entity, With<Gamepad>>,
+    mut requests: EventWriter<GamepadRumbleRequest>,
+) {
+    if !settings.enabled {
+        rumbles.clear();
+        return;
+    }
+    for rumble in rumbles.read() {
+        let intensity = GamepadRumbleIntensity {
+            strong_motor: (rumble.strong * settings.intensity).clamp(0.0, 1.0),
+            weak_motor: (rumble.weak * settings.intensity).clamp(0.0, 1.0),
+        };
+        for gamepad in gamepads.iter() {
+            requests.send(GamepadRumbleRequest::Add {
+                duration: Duration::from_secs_f32(rumble.duration),
+                intensity,
+                gamepad,
+            });
+        }
+    }
+}
+
+fn haptics_settings_ui(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<HapticsSettings>,
+    paths: Res<UserPaths>,
+) {
+    egui::Window::new("Haptics").show(contexts.ctx_mut(), |ui| {
+        let mut changed = ui.checkbox(&mut settings.enabled, "Rumble").changed();
+        changed |= slider_settled(
+            &ui.add(egui::Slider::new(&mut settings.intensity, 0.0..=1.0).text("Intensity")),
+        );
+        if changed {
+            paths.save_settings(&*settings);
+        }
+    });
+}
@@ -0,0 +1,161 @@
+use crate::{ActiveDialogue, Npc, PlayerCamera};
+use bevy::{app::Animation, prelude::*};
+
+// Head look constants
+const LOOK_RANGE: f32 = 6.0; // Players further away than this don't get a glance
+const LOOK_SPEED: f32 = 6.0;
+const MAX_YAW: f32 = 70.0; // Degrees either side of straight ahead
+const GIVE_UP_YAW: f32 = 110.0; // Further round than this and they stop trying and face forward
+const MAX_PITCH_UP: f32 = 40.0;
+const MAX_PITCH_DOWN: f32 = 30.0;
+
+pub struct HeadLookPlugin;
+
+impl Plugin for HeadLookPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (find_head_bones, untwist_heads, aim_heads).chain())
+            .add_systems(
+                PostUpdate,
+                turn_heads
+                    .after(Animation)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+// Component for NPCs whose model has a head bone that can turn toward the player
+#[derive(Component)]
+pub struct HeadLook {
+    bone_name: String,
+    bone: Option<Entity>,
+    // Current head angles relative to the body, in radians
+    yaw: f32,
+    pitch: f32,
+    // The bone's rotation before it was turned, put back before animation poses it again
+    unturned: Option<Quat>,
+}
+
+impl HeadLook {
+    pub fn new(bone_name: String) -> Self {
+        Self {
+            bone_name,
+            bone: None,
+            yaw: 0.0,
+            pitch: 0.0,
+            unturned: None,
+        }
+    }
+}
+
+// Find the head bone by name once the model's scene has spawned
+fn find_head_bones(
+    mut commands: Commands,
+    mut npcs: Query<(Entity, &Npc, &mut HeadLook)>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+) {
+    for (entity, npc, mut look) in npcs.iter_mut() {
+        if look.bone.is_some() {
+            continue;
+        }
+        let mut named = children
+            .iter_descendants(entity)
+            .filter_map(|descendant| Some((descendant, names.get(descendant).ok()?)))
+            .peekable();
+        // Nothing named yet means the scene is still on its way
+        if named.peek().is_none() {
+            continue;
+        }
+        match named.find(|(_, name)| name.as_str() == look.bone_name) {
+            Some((bone, _)) => look.bone = Some(bone),
+            None => {
+                println!(
+                    "Error: No head bone named {} on {}'s model",
+                    look.bone_name, npc.name
+                );
+                commands.entity(entity).remove::<HeadLook>();
+            }
+        }
+    }
+}
+
+// Undo last frame's turn, so models that aren't animated don't keep spinning their head
+fn untwist_heads(mut npcs: Query<&mut HeadLook>, mut bones: Query<&mut Transform>) {
+    for mut look in npcs.iter_mut() {
+        let (Some(bone), Some(unturned)) = (look.bone, look.unturned.take()) else {
+            continue;
+        };
+        if let Ok(mut transform) = bones.get_mut(bone) {
+            transform.rotation = unturned;
+        }
+    }
+}
+
+// Ease each head toward the player's eyes if they're close or being talked to, otherwise back to the front
+fn aim_heads(
+    time: Res<Time>,
+    camera: Query<&GlobalTransform, With<PlayerCamera>>,
+    active_dialogue: Query<&ActiveDialogue>,
+    mut npcs: Query<(Entity, &GlobalTransform, &mut HeadLook)>,
+    bones: Query<&GlobalTransform>,
+) {
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+    let eyes = camera.translation();
+    let talking_to = active_dialogue
+        .get_single()
+        .ok()
+        .map(|active_dialogue| active_dialogue.npc_entity);
+    let blend = (LOOK_SPEED * time.delta_secs()).min(1.0);
+
+    for (entity, npc_transform, mut look) in npcs.iter_mut() {
+        let Some(head) = look.bone.and_then(|bone| bones.get(bone).ok()) else {
+            continue;
+        };
+        let to_eyes = eyes - head.translation();
+        let (mut yaw, mut pitch) = (0.0, 0.0);
+        if talking_to == Some(entity) || to_eyes.length() <= LOOK_RANGE {
+            // Bodies face +Z, so measure the angles from there
+            let local = npc_transform.rotation().inverse() * to_eyes;
+            let target_yaw = f32::atan2(local.x, local.z);
+            if target_yaw.abs() <= GIVE_UP_YAW.to_radians() {
+                yaw = target_yaw.clamp(-MAX_YAW.to_radians(), MAX_YAW.to_radians());
+                pitch = f32::atan2(local.y, local.xz().length())
+                    .clamp(-MAX_PITCH_DOWN.to_radians(), MAX_PITCH_UP.to_radians());
+            }
+        }
+        look.yaw += (yaw - look.yaw) * blend;
+        look.pitch += (pitch - look.pitch) * blend;
+    }
+}
+
+// Turn the head bone on top of whatever pose the animation left it in
+fn turn_heads(
+    mut npcs: Query<(&GlobalTransform, &mut HeadLook)>,
+    mut bones: Query<(&mut Transform, &Parent)>,
+    parents: Query<&GlobalTransform>,
+) {
+    for (npc_transform, mut look) in npcs.iter_mut() {
+        let Some(bone) = look.bone else {
+            continue;
+        };
+        let Ok((mut transform, parent)) = bones.get_mut(bone) else {
+            continue;
+        };
+        let Ok(parent_transform) = parents.get(parent.get()) else {
+            continue;
+        };
+        look.unturned = Some(transform.rotation);
+
+        // The turn happens around the body's axes, so bring it into the bone's parent space
+        let body = npc_transform.rotation();
+        let parent_rotation = parent_transform.rotation();
+        let turn = body
+            * Quat::from_rotation_y(look.yaw)
+            * Quat::from_rotation_x(-look.pitch)
+            * body.inverse();
+        transform.rotation =
+            parent_rotation.inverse() * turn * parent_rotation * transform.rotation;
+    }
+}
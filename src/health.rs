@@ -0,0 +1,180 @@
+use crate::{
+    GameState, GameStateSet,
+    respawn::{PlayerRespawned, RespawnPlayer},
+};
+use bevy::prelude::*;
+use bevy_rapier3d::control::KinematicCharacterController;
+
+// Health constants
+const PLAYER_MAX_HEALTH: f32 = 100.0;
+const REGEN_DELAY: f32 = 5.0; // Seconds without being hurt before health starts coming back
+const REGEN_RATE: f32 = 5.0; // Per second
+const HEALTH_MARGIN: f32 = 16.0;
+const HEALTH_FONT_SIZE: f32 = 16.0;
+const HEALTH_TEXT_COLOR: Color = Color::srgb(0.95, 0.4, 0.4);
+const HEALTH_BACKGROUND_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.7);
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamagePlayer>()
+            .add_event::<PlayerDamaged>()
+            .add_systems(OnEnter(GameState::Playing), setup_health_display)
+            .add_systems(
+                Update,
+                (
+                    damage_player,
+                    regenerate_health,
+                    respawn_on_death,
+                    heal_on_respawn,
+                    update_health_display,
+                )
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Component with how much punishment something can take before it's done for
+#[derive(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+    // Seconds since the last hit, for regeneration
+    since_damaged: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            since_damaged: 0.0,
+        }
+    }
+
+    pub fn player() -> Self {
+        Self::new(PLAYER_MAX_HEALTH)
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+// Event asking for the player to be hurt
+#[derive(Event, Clone, Copy)]
+pub struct DamagePlayer {
+    pub amount: f32,
+}
+
+// Event sent once the player has actually lost health, for feedback
+#[derive(Event, Clone, Copy)]
+pub struct PlayerDamaged {
+    pub amount: f32,
+}
+
+// Marker for the HUD readout shown while the player is hurt
+#[derive(Component)]
+struct HealthDisplay;
+
+fn damage_player(
+    mut requests: EventReader<DamagePlayer>,
+    mut damaged: EventWriter<PlayerDamaged>,
+    mut player: Query<&mut Health, With<KinematicCharacterController>>,
+) {
+    let Ok(mut health) = player.get_single_mut() else {
+        requests.clear();
+        return;
+    };
+    for request in requests.read() {
+        if health.is_dead() || request.amount <= 0.0 {
+            continue;
+        }
+        let amount = request.amount.min(health.current);
+        health.current -= amount;
+        health.since_damaged = 0.0;
+        damaged.send(PlayerDamaged { amount });
+    }
+}
+
+fn regenerate_health(
+    time: Res<Time>,
+    mut player: Query<&mut Health, With<KinematicCharacterController>>,
+) {
+    let Ok(mut health) = player.get_single_mut() else {
+        return;
+    };
+    health.since_damaged += time.delta_secs();
+    if health.is_dead() || health.since_damaged < REGEN_DELAY {
+        return;
+    }
+    health.current = (health.current + REGEN_RATE * time.delta_secs()).min(health.max);
+}
+
+fn respawn_on_death(
+    player: Query<&Health, With<KinematicCharacterController>>,
+    mut respawns: EventWriter<RespawnPlayer>,
+) {
+    if player.get_single().is_ok_and(Health::is_dead) {
+        respawns.send(RespawnPlayer);
+    }
+}
+
+// Come back at full health, whether from dying or falling off the world
+fn heal_on_respawn(
+    mut respawned: EventReader<PlayerRespawned>,
+    mut player: Query<&mut Health, With<KinematicCharacterController>>,
+) {
+    if respawned.read().count() == 0 {
+        return;
+    }
+    if let Ok(mut health) = player.get_single_mut() {
+        health.current = health.max;
+    }
+}
+
+fn setup_health_display(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: HEALTH_FONT_SIZE,
+            ..default()
+        },
+        TextColor(HEALTH_TEXT_COLOR),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(HEALTH_MARGIN),
+            bottom: Val::Px(HEALTH_MARGIN),
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(HEALTH_BACKGROUND_COLOR),
+        BorderRadius::all(Val::Px(4.0)),
+        Visibility::Hidden,
+        StateScoped(GameState::Playing),
+        HealthDisplay,
+    ));
+}
+
+fn update_health_display(
+    player: Query<&Health, With<KinematicCharacterController>>,
+    mut display: Query<(&mut Text, &mut Visibility), With<HealthDisplay>>,
+) {
+    let (Ok(health), Ok((mut text, mut visibility))) =
+        (player.get_single(), display.get_single_mut())
+    else {
+        return;
+    };
+    // Only worth showing once something has taken a bite out of it
+    if health.current >= health.max {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Inherited;
+    let label = format!("Health {:.0}/{:.0}", health.current.ceil(), health.max);
+    if text.0 != label {
+        text.0 = label;
+    }
+}
@@ -0,0 +1,357 @@
+use crate::{
+    GameState, GameStateSet, INTERACTION_DISTANCE, Npc, PlayerCamera,
+    dialogue_variables::{DialogueValue, DialogueVariables},
+    input_map::{ActionState, InputAction},
+    level::{LevelConfig, LevelConfigHandle},
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+use std::f32::consts::TAU;
+
+// Hold interaction constants
+const HOLD_CANCEL_DISTANCE: f32 = 0.25; // Moving further than this cancels the hold
+const PROGRESS_SEGMENTS: usize = 24;
+const PROGRESS_RADIUS: f32 = 28.0;
+const PROGRESS_SEGMENT_SIZE: f32 = 6.0;
+const PROGRESS_FILLED_COLOR: Color = Color::srgb(0.8, 0.8, 0.3);
+const PROGRESS_EMPTY_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.6);
+const LEVER_PULL_DURATION: f32 = 1.5;
+const LEVER_TILT: f32 = 0.6;
+const LEVER_HEIGHT: f32 = 1.2;
+const LEVER_WIDTH: f32 = 0.2;
+const LEVER_PULLED_VARIABLE: &str = "lever_pulled";
+
+pub struct HoldInteractionPlugin;
+
+impl Plugin for HoldInteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HoldProgress>()
+            .add_event::<HoldInteractionCompleted>()
+            .add_systems(Startup, (setup_hold_progress_ui, setup_lever_assets))
+            .add_systems(Update, place_levers)
+            .add_systems(
+                Update,
+                (
+                    update_hold_interaction,
+                    update_hold_progress_ui,
+                    pull_levers,
+                )
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            )
+            .add_systems(OnExit(GameState::Playing), cancel_hold_interaction);
+    }
+}
+
+// Component for things that must be held for a while to interact with
+#[derive(Component)]
+pub struct HoldInteractable {
+    pub duration: f32,
+}
+
+// Event sent when a hold interaction runs to completion
+#[derive(Event)]
+pub struct HoldInteractionCompleted {
+    pub entity: Entity,
+}
+
+// Resource tracking the hold currently in progress
+#[derive(Resource, Default)]
+struct HoldProgress {
+    active: Option<ActiveHold>,
+    // Set after a hold completes or is cancelled until the key is released
+    needs_release: bool,
+}
+
+struct ActiveHold {
+    entity: Entity,
+    elapsed: f32,
+    duration: f32,
+    start_position: Vec3,
+}
+
+// Component for the radial progress indicator root
+#[derive(Component)]
+struct HoldProgressRing;
+
+// Component for a single segment of the radial progress indicator
+#[derive(Component)]
+struct HoldProgressSegment(usize);
+
+// A lever placed in the level, from the level asset
+#[derive(Deserialize)]
+pub struct LeverPlacement {
+    // Where its base sits
+    pub position: Vec3,
+    // Degrees around the vertical axis, turning the way it tilts away from +Z
+    #[serde(default)]
+    pub yaw: f32,
+}
+
+// Component for a lever, which flips `lever_pulled` each time it's pulled
+#[derive(Component)]
+struct Lever {
+    pulled: bool,
+    yaw: f32,
+}
+
+impl Lever {
+    fn rotation(&self) -> Quat {
+        let tilt = if self.pulled { LEVER_TILT } else { -LEVER_TILT };
+        Quat::from_rotation_y(self.yaw) * Quat::from_rotation_x(tilt)
+    }
+}
+
+// Resource with the mesh and material every lever shares
+#[derive(Resource)]
+struct LeverAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_hold_interaction(
+    time: Res<Time>,
+    actions: Res<ActionState>,
+    player_query: Query<(Entity, &Transform), With<KinematicCharacterController>>,
+    camera_query: Query<&GlobalTransform, With<PlayerCamera>>,
+    npc_query: Query<&Visibility, With<Npc>>,
+    interactables: Query<&HoldInteractable>,
+    rapier_context: ReadRapierContext,
+    mut progress: ResMut<HoldProgress>,
+    mut completed: EventWriter<HoldInteractionCompleted>,
+) {
+    if !actions.pressed(InputAction::Interact) {
+        progress.active = None;
+        progress.needs_release = false;
+        return;
+    }
+    if progress.needs_release {
+        return;
+    }
+
+    let Ok((player_entity, player)) = player_query.get_single() else {
+        return;
+    };
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+
+    // Start a new hold on whatever we are looking at
+    if progress.active.is_none() {
+        let physics = rapier_context.single();
+        let Some(entity) = targeted_interactable(&physics, camera, player_entity, &npc_query)
+        else {
+            return;
+        };
+        let Ok(interactable) = interactables.get(entity) else {
+            return;
+        };
+        progress.active = Some(ActiveHold {
+            entity,
+            elapsed: 0.0,
+            duration: interactable.duration,
+            start_position: player.translation,
+        });
+    }
+
+    let Some(active) = progress.active.as_mut() else {
+        return;
+    };
+
+    // Moving away from where the hold started cancels it
+    if player.translation.distance(active.start_position) > HOLD_CANCEL_DISTANCE {
+        progress.active = None;
+        progress.needs_release = true;
+        return;
+    }
+
+    active.elapsed += time.delta_secs();
+    if active.elapsed >= active.duration {
+        completed.send(HoldInteractionCompleted {
+            entity: active.entity,
+        });
+        progress.active = None;
+        progress.needs_release = true;
+    }
+}
+
+// Whatever is under the crosshair in reach, with the same ray as talking and reading, so walls
+// and cubes stand in the way of a lever just as they do a conversation
+fn targeted_interactable(
+    physics: &RapierContext,
+    camera: &GlobalTransform,
+    player_entity: Entity,
+    npc_query: &Query<&Visibility, With<Npc>>,
+) -> Option<Entity> {
+    let is_present = |entity| {
+        npc_query
+            .get(entity)
+            .map_or(true, |visibility| *visibility != Visibility::Hidden)
+    };
+    let filter = QueryFilter::default()
+        .exclude_collider(player_entity)
+        .exclude_sensors()
+        .predicate(&is_present);
+    physics
+        .cast_ray(
+            camera.translation(),
+            *camera.forward(),
+            INTERACTION_DISTANCE,
+            true,
+            filter,
+        )
+        .map(|(entity, _)| entity)
+}
+
+fn cancel_hold_interaction(
+    mut progress: ResMut<HoldProgress>,
+    mut ring_query: Query<&mut Visibility, With<HoldProgressRing>>,
+) {
+    progress.active = None;
+    progress.needs_release = true;
+    for mut visibility in ring_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn setup_hold_progress_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(50.0),
+                top: Val::Percent(50.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            HoldProgressRing,
+        ))
+        .with_children(|parent| {
+            for i in 0..PROGRESS_SEGMENTS {
+                // Start at the top and go clockwise
+                let angle = i as f32 / PROGRESS_SEGMENTS as f32 * TAU;
+                let x = angle.sin() * PROGRESS_RADIUS - PROGRESS_SEGMENT_SIZE / 2.0;
+                let y = -angle.cos() * PROGRESS_RADIUS - PROGRESS_SEGMENT_SIZE / 2.0;
+
+                parent.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(x),
+                        top: Val::Px(y),
+                        width: Val::Px(PROGRESS_SEGMENT_SIZE),
+                        height: Val::Px(PROGRESS_SEGMENT_SIZE),
+                        ..default()
+                    },
+                    BorderRadius::MAX,
+                    BackgroundColor(PROGRESS_EMPTY_COLOR),
+                    HoldProgressSegment(i),
+                ));
+            }
+        });
+}
+
+fn update_hold_progress_ui(
+    progress: Res<HoldProgress>,
+    mut ring_query: Query<&mut Visibility, With<HoldProgressRing>>,
+    mut segment_query: Query<(&HoldProgressSegment, &mut BackgroundColor)>,
+) {
+    let Ok(mut visibility) = ring_query.get_single_mut() else {
+        return;
+    };
+
+    let Some(active) = &progress.active else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+
+    let filled = (active.elapsed / active.duration * PROGRESS_SEGMENTS as f32) as usize;
+    for (segment, mut color) in segment_query.iter_mut() {
+        *color = if segment.0 < filled {
+            BackgroundColor(PROGRESS_FILLED_COLOR)
+        } else {
+            BackgroundColor(PROGRESS_EMPTY_COLOR)
+        };
+    }
+}
+
+fn setup_lever_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(LeverAssets {
+        mesh: meshes.add(Cuboid::new(LEVER_WIDTH, LEVER_HEIGHT, LEVER_WIDTH)),
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.5, 0.3, 0.2),
+            perceptual_roughness: 0.7,
+            ..default()
+        }),
+    });
+}
+
+// Put the level's levers out whenever it's loaded, or edited while running, set the way
+// `lever_pulled` says they were left
+fn place_levers(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelConfig>>,
+    configs: Res<Assets<LevelConfig>>,
+    handle: Option<Res<LevelConfigHandle>>,
+    assets: Res<LeverAssets>,
+    variables: Res<DialogueVariables>,
+    placed: Query<Entity, With<Lever>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(config) = configs.get(&handle.0) else {
+            continue;
+        };
+        for entity in placed.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        let pulled = matches!(
+            variables.get(LEVER_PULLED_VARIABLE),
+            Some(DialogueValue::Bool(true))
+        );
+        for placement in &config.levers {
+            let lever = Lever {
+                pulled,
+                yaw: placement.yaw.to_radians(),
+            };
+            commands.spawn((
+                Mesh3d(assets.mesh.clone()),
+                MeshMaterial3d(assets.material.clone()),
+                Transform::from_translation(placement.position + Vec3::Y * LEVER_HEIGHT / 2.0)
+                    .with_rotation(lever.rotation()),
+                Collider::cuboid(LEVER_WIDTH / 2.0, LEVER_HEIGHT / 2.0, LEVER_WIDTH / 2.0),
+                HoldInteractable {
+                    duration: LEVER_PULL_DURATION,
+                },
+                lever,
+            ));
+        }
+    }
+}
+
+// Flip the lever over, letting dialogue know which way it's set through `lever_pulled`
+fn pull_levers(
+    mut events: EventReader<HoldInteractionCompleted>,
+    mut levers: Query<(&mut Lever, &mut Transform)>,
+    mut variables: ResMut<DialogueVariables>,
+) {
+    for event in events.read() {
+        let Ok((mut lever, mut transform)) = levers.get_mut(event.entity) else {
+            continue;
+        };
+        lever.pulled = !lever.pulled;
+        transform.rotation = lever.rotation();
+        variables.set(LEVER_PULLED_VARIABLE, DialogueValue::Bool(lever.pulled));
+    }
+}
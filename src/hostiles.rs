@@ -0,0 +1,206 @@
+use crate::{
+    GameStateSet, Npc,
+    ai_lod::{AiLod, AiLodLevel},
+    health::{DamagePlayer, Health},
+    npc_avoidance::yield_to_player,
+    perception::{Commotion, Perception},
+    respawn::PlayerRespawned,
+    update_npcs,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use serde::Deserialize;
+
+// Hostile constants
+const HOME_REACHED: f32 = 1.0;
+const LAST_SEEN_REACHED: f32 = 0.5;
+const HOSTILE_TURN_SPEED: f32 = 10.0;
+const ATTACK_COMMOTION_RADIUS: f32 = 15.0; // Bystanders this close notice a fight
+
+pub struct HostilesPlugin;
+
+impl Plugin for HostilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, leash_on_respawn).add_systems(
+            FixedUpdate,
+            (notice_player, chase_player)
+                .chain()
+                .after(yield_to_player)
+                .before(update_npcs)
+                .before(PhysicsSet::SyncBackend)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// How an enemy archetype fights, set in `npcs.spawn.ron`
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct HostileConfig {
+    // The player gets noticed this close even from behind
+    pub aggro_radius: f32,
+    // Dragged further than this from home, the enemy gives up and goes back
+    pub leash_radius: f32,
+    pub chase_speed: f32,
+    pub attack_range: f32,
+    pub attack_damage: f32,
+    // Seconds between swings
+    pub attack_cooldown: f32,
+}
+
+// Component for NPCs that attack the player on sight
+#[derive(Component)]
+pub struct Hostile {
+    pub config: HostileConfig,
+    // Seconds until the next swing is ready
+    cooldown: f32,
+}
+
+impl Hostile {
+    pub fn new(config: HostileConfig) -> Self {
+        Self {
+            config,
+            cooldown: 0.0,
+        }
+    }
+}
+
+// Component on a hostile NPC that has stopped wandering to fight, or to head back after one
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggro {
+    Chasing,
+    Leashing,
+}
+
+// Pick a fight with a living player that's in view or too close
+#[allow(clippy::type_complexity)]
+fn notice_player(
+    mut commands: Commands,
+    player: Query<(&Transform, &Health), (With<KinematicCharacterController>, Without<Npc>)>,
+    hostiles: Query<(Entity, &Transform, &Hostile, &Perception, &AiLod), Without<Aggro>>,
+) {
+    let Ok((player, health)) = player.get_single() else {
+        return;
+    };
+    if health.is_dead() {
+        return;
+    }
+    for (entity, transform, hostile, perception, lod) in hostiles.iter() {
+        if lod.level == AiLodLevel::Asleep {
+            continue;
+        }
+        if perception.sees_player
+            || transform.translation.distance(player.translation) <= hostile.config.aggro_radius
+        {
+            commands.entity(entity).insert(Aggro::Chasing);
+        }
+    }
+}
+
+// Run at the player, or where they were last seen, and swing once in reach
+fn chase_player(
+    mut commands: Commands,
+    time: Res<Time>,
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<Npc>)>,
+    mut hostiles: Query<(
+        Entity,
+        &mut Transform,
+        &mut Npc,
+        &mut Hostile,
+        &mut Aggro,
+        &mut Perception,
+    )>,
+    mut damage: EventWriter<DamagePlayer>,
+    mut commotions: EventWriter<Commotion>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let delta_time = time.delta_secs();
+
+    for (entity, mut transform, mut npc, mut hostile, mut aggro, mut perception) in
+        hostiles.iter_mut()
+    {
+        hostile.cooldown = (hostile.cooldown - delta_time).max(0.0);
+        let position = transform.translation;
+        let config = hostile.config;
+
+        if *aggro == Aggro::Chasing
+            && position.with_y(0.0).distance(npc.home_position.with_y(0.0)) > config.leash_radius
+        {
+            *aggro = Aggro::Leashing;
+        }
+
+        // Up close the player can't slip away just by standing behind them
+        let senses_player =
+            perception.sees_player || position.distance(player.translation) <= config.aggro_radius;
+        if *aggro == Aggro::Chasing && senses_player {
+            perception.last_known_position = Some(player.translation);
+        }
+
+        let (target, stop_distance) = match *aggro {
+            Aggro::Chasing if senses_player => (player.translation, config.attack_range),
+            Aggro::Chasing => match perception.last_known_position {
+                Some(last_known) => (last_known, LAST_SEEN_REACHED),
+                None => {
+                    *aggro = Aggro::Leashing;
+                    (npc.home_position, HOME_REACHED)
+                }
+            },
+            Aggro::Leashing => (npc.home_position, HOME_REACHED),
+        };
+
+        let offset = (target - position).with_y(0.0);
+        let distance = offset.length();
+        if distance > stop_distance * 0.8 {
+            transform.translation += offset.clamp_length_max(config.chase_speed * delta_time);
+        }
+        if offset != Vec3::ZERO {
+            let facing = Quat::from_rotation_y(f32::atan2(offset.x, offset.z));
+            let blend = (HOSTILE_TURN_SPEED * delta_time).min(1.0);
+            transform.rotation = transform.rotation.slerp(facing, blend);
+        }
+        npc.target_position = transform.translation;
+
+        match *aggro {
+            Aggro::Chasing if senses_player => {
+                let reach = (player.translation - position).with_y(0.0).length();
+                if reach <= config.attack_range && hostile.cooldown <= 0.0 {
+                    damage.send(DamagePlayer {
+                        amount: config.attack_damage,
+                    });
+                    hostile.cooldown = config.attack_cooldown;
+                    commotions.send(Commotion {
+                        position: player.translation,
+                        radius: ATTACK_COMMOTION_RADIUS,
+                    });
+                }
+            }
+            Aggro::Chasing => {
+                // Nobody where they were last seen, so give up
+                if distance <= stop_distance {
+                    perception.last_known_position = None;
+                    *aggro = Aggro::Leashing;
+                }
+            }
+            Aggro::Leashing => {
+                if distance <= stop_distance {
+                    commands.entity(entity).remove::<Aggro>();
+                }
+            }
+        }
+    }
+}
+
+// A respawned player is somewhere else entirely, so everyone chasing them heads home
+fn leash_on_respawn(
+    mut respawned: EventReader<PlayerRespawned>,
+    mut hostiles: Query<(&mut Aggro, &mut Perception)>,
+) {
+    if respawned.read().count() == 0 {
+        return;
+    }
+    for (mut aggro, mut perception) in hostiles.iter_mut() {
+        *aggro = Aggro::Leashing;
+        perception.last_known_position = None;
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{GameStateSet, Landed};
+use bevy::{audio::Volume, prelude::*};
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+// Impact audio constants
+const LANDING_CLIP: &str = "audio/impacts/land.ogg";
+const BUMP_CLIP: &str = "audio/impacts/bump.ogg";
+const LANDING_MIN_VOLUME: f32 = 0.3;
+const BUMP_VOLUME: f32 = 0.25;
+const BUMP_COOLDOWN: f32 = 0.4; // Scraping along a wall shouldn't become a drum roll
+const WALL_NORMAL_MAX_Y: f32 = 0.3; // Surfaces steeper than this count as walls rather than floor
+const PITCH_VARIATION: f32 = 0.1;
+
+pub struct ImpactAudioPlugin;
+
+impl Plugin for ImpactAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_impact_clips).add_systems(
+            Update,
+            (play_landing_impacts, play_wall_bumps).in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// Resource with the loaded impact clips
+#[derive(Resource)]
+struct ImpactClips {
+    landing: Handle<AudioSource>,
+    bump: Handle<AudioSource>,
+}
+
+fn load_impact_clips(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ImpactClips {
+        landing: asset_server.load(LANDING_CLIP),
+        bump: asset_server.load(BUMP_CLIP),
+    });
+}
+
+fn play_clip(commands: &mut Commands, clip: &Handle<AudioSource>, volume: f32, speed: f32) {
+    let speed = speed + rand::rng().random_range(-PITCH_VARIATION..=PITCH_VARIATION);
+    commands.spawn((
+        AudioPlayer(clip.clone()),
+        PlaybackSettings::DESPAWN
+            .with_volume(Volume::new(volume))
+            .with_speed(speed),
+    ));
+}
+
+// Harder landings are louder and deeper
+fn play_landing_impacts(
+    mut commands: Commands,
+    mut landings: EventReader<Landed>,
+    clips: Res<ImpactClips>,
+) {
+    for landing in landings.read() {
+        let strength = landing.strength();
+        let volume = LANDING_MIN_VOLUME.lerp(1.0, strength);
+        play_clip(&mut commands, &clips.landing, volume, 1.1 - 0.3 * strength);
+    }
+}
+
+// A soft bump when the player first runs into a wall, not every step spent sliding along it
+fn play_wall_bumps(
+    mut commands: Commands,
+    time: Res<Time>,
+    clips: Res<ImpactClips>,
+    player: Query<&KinematicCharacterControllerOutput>,
+    mut touching_wall: Local<bool>,
+    mut cooldown: Local<f32>,
+) {
+    *cooldown -= time.delta_secs();
+    let Ok(output) = player.get_single() else {
+        return;
+    };
+    let touching = output.collisions.iter().any(|collision| {
+        collision
+            .hit
+            .details
+            .is_some_and(|details| details.normal1.y.abs() < WALL_NORMAL_MAX_Y)
+    });
+    if touching && !*touching_wall && *cooldown <= 0.0 {
+        play_clip(&mut commands, &clips.bump, BUMP_VOLUME, 1.0);
+        *cooldown = BUMP_COOLDOWN;
+    }
+    *touching_wall = touching;
+}
@@ -0,0 +1,428 @@
+use crate::{
+    GameStateSet,
+    look_settings::LookSettings,
+    paths::{SettingsFile, UserPaths},
+};
+use bevy::{input::InputSystem, prelude::*, window::CursorGrabMode};
+use bevy_egui::{EguiContexts, egui};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// Input map constants
+const CONTROLS_TOGGLE_KEY: KeyCode = KeyCode::F7;
+
+pub struct InputMapPlugin;
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputMap>()
+            .init_resource::<ActionState>()
+            .init_resource::<ControlsMenu>()
+            .add_systems(Startup, load_bindings)
+            .add_systems(PreUpdate, update_action_state.after(InputSystem))
+            .add_systems(
+                Update,
+                (toggle_controls_menu, capture_rebind, controls_menu_ui)
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Things the player can do, independent of what they're bound to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputAction {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Sprint,
+    Crouch,
+    Interact,
+    Grab,
+    Throw,
+    Zoom,
+    AutoWalk,
+    LeanLeft,
+    LeanRight,
+    Attack,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 15] = [
+        InputAction::MoveForward,
+        InputAction::MoveBack,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Jump,
+        InputAction::Sprint,
+        InputAction::Crouch,
+        InputAction::Interact,
+        InputAction::Grab,
+        InputAction::Throw,
+        InputAction::Zoom,
+        InputAction::AutoWalk,
+        InputAction::LeanLeft,
+        InputAction::LeanRight,
+        InputAction::Attack,
+    ];
+}
+
+// A single button that can trigger an action
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl InputBinding {
+    // Name shown to the player, like "E" or "Mouse Right"
+    pub fn label(&self) -> String {
+        match self {
+            InputBinding::Key(key) => {
+                let name = format!("{key:?}");
+                name.strip_prefix("Key").unwrap_or(&name).to_string()
+            }
+            InputBinding::Mouse(button) => format!("Mouse {button:?}"),
+            InputBinding::Gamepad(button) => format!("Gamepad {button:?}"),
+        }
+    }
+
+    fn same_device(self, other: InputBinding) -> bool {
+        std::mem::discriminant(&self) == std::mem::discriminant(&other)
+    }
+}
+
+// Resource mapping each action to the buttons that trigger it, saved to `bindings.ron`
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputMap {
+    bindings: HashMap<InputAction, Vec<InputBinding>>,
+    // Sprint flips on and off with each press instead of being held
+    pub toggle_sprint: bool,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        use InputBinding::{Gamepad, Key, Mouse};
+        let bindings = [
+            (InputAction::MoveForward, vec![Key(KeyCode::KeyW)]),
+            (InputAction::MoveBack, vec![Key(KeyCode::KeyS)]),
+            (InputAction::MoveLeft, vec![Key(KeyCode::KeyA)]),
+            (InputAction::MoveRight, vec![Key(KeyCode::KeyD)]),
+            (
+                InputAction::Jump,
+                vec![Key(KeyCode::Space), Gamepad(GamepadButton::South)],
+            ),
+            (
+                InputAction::Sprint,
+                vec![Key(KeyCode::ShiftLeft), Gamepad(GamepadButton::LeftThumb)],
+            ),
+            (
+                InputAction::Crouch,
+                vec![Key(KeyCode::ControlLeft), Gamepad(GamepadButton::East)],
+            ),
+            (
+                InputAction::Interact,
+                vec![Key(KeyCode::KeyE), Gamepad(GamepadButton::West)],
+            ),
+            (
+                InputAction::Grab,
+                vec![Key(KeyCode::KeyF), Gamepad(GamepadButton::RightTrigger)],
+            ),
+            (
+                InputAction::Throw,
+                vec![
+                    Mouse(MouseButton::Left),
+                    Gamepad(GamepadButton::RightTrigger2),
+                ],
+            ),
+            (
+                InputAction::Zoom,
+                vec![
+                    Mouse(MouseButton::Right),
+                    Gamepad(GamepadButton::LeftTrigger2),
+                ],
+            ),
+            (
+                InputAction::AutoWalk,
+                vec![Key(KeyCode::NumLock), Gamepad(GamepadButton::DPadUp)],
+            ),
+            // E is taken by Interact, so leaning uses Z and C instead of the usual Q and E
+            (
+                InputAction::LeanLeft,
+                vec![Key(KeyCode::KeyZ), Gamepad(GamepadButton::DPadLeft)],
+            ),
+            (
+                InputAction::LeanRight,
+                vec![Key(KeyCode::KeyC), Gamepad(GamepadButton::DPadRight)],
+            ),
+            // The left mouse button throws, so punches get a key of their own
+            (
+                InputAction::Attack,
+                vec![Key(KeyCode::KeyQ), Gamepad(GamepadButton::North)],
+            ),
+        ];
+        Self {
+            bindings: bindings.into_iter().collect(),
+            toggle_sprint: false,
+        }
+    }
+}
+
+impl InputMap {
+    pub fn bindings(&self, action: InputAction) -> &[InputBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    // Add another button for an action, keeping the existing ones
+    pub fn bind(&mut self, action: InputAction, binding: InputBinding) -> &mut Self {
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    // Replace an action's button on the same device, so rebinding a key keeps the gamepad binding
+    pub fn rebind(&mut self, action: InputAction, binding: InputBinding) -> &mut Self {
+        // A button only does one thing at a time
+        for bindings in self.bindings.values_mut() {
+            bindings.retain(|existing| *existing != binding);
+        }
+        self.bindings
+            .entry(action)
+            .or_default()
+            .retain(|existing| !existing.same_device(binding));
+        self.bind(action, binding)
+    }
+}
+
+impl SettingsFile for InputMap {
+    const FILE: &'static str = "bindings.ron";
+}
+
+// Resource with which actions are held this frame, for gameplay to read instead of raw input
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pressed: HashSet<InputAction>,
+    just_pressed: HashSet<InputAction>,
+    // Whether sprint is switched on, when it's a toggle
+    sprint_toggled: bool,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    // Hold an action this frame from something other than a binding, like an on-screen button
+    #[cfg(feature = "touch")]
+    pub fn press(&mut self, action: InputAction, just_pressed: bool) {
+        self.pressed.insert(action);
+        if just_pressed {
+            self.just_pressed.insert(action);
+        }
+    }
+}
+
+// Resource for the controls window and the action waiting for a new button, if any
+#[derive(Resource, Default)]
+pub struct ControlsMenu {
+    open: bool,
+    capturing: Option<InputAction>,
+}
+
+// Run condition keeping gameplay input off while the controls window is up
+pub fn controls_menu_closed(menu: Res<ControlsMenu>) -> bool {
+    !menu.open
+}
+
+fn load_bindings(paths: Res<UserPaths>, mut input_map: ResMut<InputMap>) {
+    let Some(loaded) = paths.load_settings::<InputMap>() else {
+        return;
+    };
+    // Actions added since the file was saved keep their default buttons
+    for (action, bindings) in loaded.bindings {
+        input_map.bindings.insert(action, bindings);
+    }
+    input_map.toggle_sprint = loaded.toggle_sprint;
+}
+
+pub fn update_action_state(
+    input_map: Res<InputMap>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut state: ResMut<ActionState>,
+) {
+    state.pressed.clear();
+    state.just_pressed.clear();
+    for action in InputAction::ALL {
+        for binding in input_map.bindings(action) {
+            let (pressed, just_pressed) = match *binding {
+                InputBinding::Key(key) => (keyboard.pressed(key), keyboard.just_pressed(key)),
+                InputBinding::Mouse(button) => (mouse.pressed(button), mouse.just_pressed(button)),
+                InputBinding::Gamepad(button) => (
+                    gamepads.iter().any(|gamepad| gamepad.pressed(button)),
+                    gamepads.iter().any(|gamepad| gamepad.just_pressed(button)),
+                ),
+            };
+            if pressed {
+                state.pressed.insert(action);
+            }
+            if just_pressed {
+                state.just_pressed.insert(action);
+            }
+        }
+    }
+
+    // Gameplay sees a toggled sprint as held, so it doesn't need to know about the setting
+    if !input_map.toggle_sprint {
+        state.sprint_toggled = false;
+        return;
+    }
+    if state.just_pressed(InputAction::Sprint) {
+        state.sprint_toggled = !state.sprint_toggled;
+    }
+    if state.sprint_toggled {
+        state.pressed.insert(InputAction::Sprint);
+    } else {
+        state.pressed.remove(&InputAction::Sprint);
+    }
+}
+
+fn toggle_controls_menu(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut menu: ResMut<ControlsMenu>,
+    mut windows: Query<&mut Window>,
+) {
+    if !keyboard.just_pressed(CONTROLS_TOGGLE_KEY) {
+        return;
+    }
+    menu.open = !menu.open;
+    menu.capturing = None;
+
+    // The cursor is needed to click around the window
+    let mut window = windows.single_mut();
+    window.cursor_options.visible = menu.open;
+    window.cursor_options.grab_mode = if menu.open {
+        CursorGrabMode::None
+    } else {
+        CursorGrabMode::Locked
+    };
+}
+
+// Bind the next button pressed to the action being rebound
+fn capture_rebind(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    paths: Res<UserPaths>,
+    mut menu: ResMut<ControlsMenu>,
+    mut input_map: ResMut<InputMap>,
+) {
+    let Some(action) = menu.capturing else {
+        return;
+    };
+    // Escape cancels, and the window's own toggle can't be taken
+    if keyboard.just_pressed(KeyCode::Escape) {
+        menu.capturing = None;
+        return;
+    }
+
+    let binding = keyboard
+        .get_just_pressed()
+        .find(|key| **key != CONTROLS_TOGGLE_KEY)
+        .map(|key| InputBinding::Key(*key))
+        // Left click is how the window itself is used
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .find(|button| **button != MouseButton::Left)
+                .map(|button| InputBinding::Mouse(*button))
+        })
+        .or_else(|| {
+            gamepads.iter().find_map(|gamepad| {
+                gamepad
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| InputBinding::Gamepad(*button))
+            })
+        });
+    if let Some(binding) = binding {
+        input_map.rebind(action, binding);
+        paths.save_settings(&*input_map);
+        menu.capturing = None;
+    }
+}
+
+fn controls_menu_ui(
+    mut contexts: EguiContexts,
+    mut menu: ResMut<ControlsMenu>,
+    mut input_map: ResMut<InputMap>,
+    mut look_settings: ResMut<LookSettings>,
+    paths: Res<UserPaths>,
+) {
+    if !menu.open {
+        return;
+    }
+    egui::Window::new("Controls").show(contexts.ctx_mut(), |ui| {
+        egui::Grid::new("bindings").show(ui, |ui| {
+            for action in InputAction::ALL {
+                ui.label(format!("{action:?}"));
+                let bindings: Vec<String> = input_map
+                    .bindings(action)
+                    .iter()
+                    .map(InputBinding::label)
+                    .collect();
+                ui.label(bindings.join(", "));
+                if menu.capturing == Some(action) {
+                    ui.label("Press a button... (Esc to cancel)");
+                } else if ui.button("Rebind").clicked() {
+                    menu.capturing = Some(action);
+                }
+                ui.end_row();
+            }
+        });
+        if ui
+            .checkbox(&mut input_map.toggle_sprint, "Toggle sprint")
+            .changed()
+        {
+            paths.save_settings(&*input_map);
+        }
+
+        ui.separator();
+        let mut changed = false;
+        egui::Grid::new("mouse").show(ui, |ui| {
+            ui.label("Mouse sensitivity");
+            changed |= slider_settled(&ui.add(egui::Slider::new(
+                &mut look_settings.sensitivity,
+                0.05..=1.0,
+            )));
+            ui.end_row();
+            ui.label("Invert Y");
+            changed |= ui.checkbox(&mut look_settings.invert_y, "").changed();
+            ui.end_row();
+            ui.label("Smoothing");
+            changed |= slider_settled(
+                &ui.add(egui::Slider::new(&mut look_settings.smoothing, 0.0..=0.2).suffix(" s")),
+            );
+            ui.end_row();
+        });
+        if changed {
+            paths.save_settings(&*look_settings);
+        }
+    });
+}
+
+// Whether a slider has a new value worth saving, once a drag is let go rather than every frame of it
+pub fn slider_settled(response: &egui::Response) -> bool {
+    response.drag_stopped() || (response.changed() && !response.dragged())
+}
@@ -0,0 +1,122 @@
+use crate::{
+    GameState, GameStateSet, InteractionTarget, Npc, PlayerCamera,
+    input_map::{InputAction, InputMap},
+    readables::Readable,
+    update_interaction_target,
+};
+use bevy::prelude::*;
+
+// Interaction prompt constants
+const PROMPT_HEIGHT: f32 = 1.5; // Above the NPC's center, clear of their head
+const READABLE_PROMPT_HEIGHT: f32 = 0.4;
+const PROMPT_WIDTH: f32 = 400.0; // Wide enough to center any name under the anchor point
+const PROMPT_FONT_SIZE: f32 = 18.0;
+const PROMPT_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const PROMPT_BACKGROUND_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.7);
+
+pub struct InteractionPromptPlugin;
+
+impl Plugin for InteractionPromptPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), setup_interaction_prompt)
+            .add_systems(
+                Update,
+                update_interaction_prompt
+                    .after(update_interaction_target)
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Marker for the prompt's screen-space anchor
+#[derive(Component)]
+struct InteractionPrompt;
+
+// Marker for the prompt's text
+#[derive(Component)]
+struct InteractionPromptText;
+
+// Gone while talking, and rebuilt hidden whenever play resumes
+fn setup_interaction_prompt(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(PROMPT_WIDTH),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            Visibility::Hidden,
+            StateScoped(GameState::Playing),
+            InteractionPrompt,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font_size: PROMPT_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(PROMPT_TEXT_COLOR),
+                Node {
+                    padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                    ..default()
+                },
+                BackgroundColor(PROMPT_BACKGROUND_COLOR),
+                BorderRadius::all(Val::Px(4.0)),
+                InteractionPromptText,
+            ));
+        });
+}
+
+// Pin the prompt over the targeted NPC's head, or just above a sign or note, following it across
+// the screen
+fn update_interaction_prompt(
+    target: Res<InteractionTarget>,
+    input_map: Res<InputMap>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    npc_query: Query<(&Npc, &GlobalTransform)>,
+    readables: Query<(&Readable, &GlobalTransform)>,
+    mut prompt_query: Query<(&mut Node, &mut Visibility), With<InteractionPrompt>>,
+    mut text_query: Query<&mut Text, With<InteractionPromptText>>,
+) {
+    let Ok((mut node, mut visibility)) = prompt_query.get_single_mut() else {
+        return;
+    };
+    let on_screen = target.0.and_then(|entity| {
+        let (action, anchor) = if let Ok((npc, npc_transform)) = npc_query.get(entity) {
+            (
+                format!("talk to {}", npc.name),
+                npc_transform.translation() + Vec3::Y * PROMPT_HEIGHT,
+            )
+        } else {
+            let (readable, transform) = readables.get(entity).ok()?;
+            (
+                format!("read the {}", readable.kind.label()),
+                transform.translation() + Vec3::Y * READABLE_PROMPT_HEIGHT,
+            )
+        };
+        let (camera, camera_transform) = camera_query.get_single().ok()?;
+        let position = camera.world_to_viewport(camera_transform, anchor).ok()?;
+        Some((action, position))
+    });
+    let Some((action, position)) = on_screen else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    *visibility = Visibility::Inherited;
+    node.left = Val::Px(position.x - PROMPT_WIDTH / 2.0);
+    node.top = Val::Px(position.y);
+    if let Ok(mut text) = text_query.get_single_mut() {
+        let key = input_map
+            .bindings(InputAction::Interact)
+            .first()
+            .map_or_else(|| "Interact".to_string(), |binding| binding.label());
+        let prompt = format!("Press {key} to {action}");
+        // Only touch the text when it changes so it isn't laid out again every frame
+        if text.0 != prompt {
+            text.0 = prompt;
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::{app::RunFixedMainLoopSystem, prelude::*};
+
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            RunFixedMainLoop,
+            restore_fixed_transforms.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+        )
+        .add_systems(FixedLast, record_fixed_transforms)
+        .add_systems(
+            RunFixedMainLoop,
+            interpolate_transforms.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+        );
+    }
+}
+
+// Smooths an entity moved in `FixedUpdate` by rendering it between its last two fixed steps
+#[derive(Component, Clone, Copy)]
+pub struct TransformInterpolation {
+    // Off for entities whose rotation is driven every frame, like the player's mouse look
+    interpolate_rotation: bool,
+    state: Option<InterpolationState>,
+}
+
+#[derive(Clone, Copy)]
+struct InterpolationState {
+    previous: Transform,
+    current: Transform,
+    // What we last wrote for rendering, to spot entities moved outside the fixed steps
+    rendered: Transform,
+}
+
+impl Default for TransformInterpolation {
+    fn default() -> Self {
+        Self {
+            interpolate_rotation: true,
+            state: None,
+        }
+    }
+}
+
+impl TransformInterpolation {
+    pub fn translation_only() -> Self {
+        Self {
+            interpolate_rotation: false,
+            state: None,
+        }
+    }
+
+    fn snap(&mut self, transform: Transform) {
+        self.state = Some(InterpolationState {
+            previous: transform,
+            current: transform,
+            rendered: transform,
+        });
+    }
+}
+
+// Put entities back where the simulation left them before the fixed steps run
+fn restore_fixed_transforms(mut query: Query<(&mut Transform, &mut TransformInterpolation)>) {
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        let rotate = interpolation.interpolate_rotation;
+        let Some(state) = interpolation.state else {
+            interpolation.snap(*transform);
+            continue;
+        };
+
+        // Something outside the fixed steps moved it, so treat that as a teleport
+        let moved = transform.translation != state.rendered.translation
+            || (rotate && transform.rotation != state.rendered.rotation);
+        if moved {
+            interpolation.snap(*transform);
+            continue;
+        }
+
+        transform.translation = state.current.translation;
+        if rotate {
+            transform.rotation = state.current.rotation;
+        }
+    }
+}
+
+fn record_fixed_transforms(mut query: Query<(&Transform, &mut TransformInterpolation)>) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        if let Some(state) = interpolation.state.as_mut() {
+            state.previous = state.current;
+            state.current = *transform;
+        }
+    }
+}
+
+// Blend between the last two fixed steps by how far we are into the next one
+fn interpolate_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &mut TransformInterpolation)>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        let rotate = interpolation.interpolate_rotation;
+        let Some(state) = interpolation.state.as_mut() else {
+            continue;
+        };
+
+        transform.translation = state
+            .previous
+            .translation
+            .lerp(state.current.translation, alpha);
+        if rotate {
+            transform.rotation = state.previous.rotation.slerp(state.current.rotation, alpha);
+        }
+        state.rendered = *transform;
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{GameStateSet, footsteps::SurfaceMaterial, player_movement};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+
+// Ladder constants
+pub const CLIMB_SPEED: f32 = 3.0;
+pub const MANTLE_PUSH: f32 = 4.0; // Speed the player is pushed onto the platform at the top
+const TOWER_POSITION: Vec3 = Vec3::new(18.0, 0.0, 12.0);
+const TOWER_HALF_EXTENTS: Vec3 = Vec3::new(3.0, 4.0, 3.0);
+const LADDER_HALF_WIDTH: f32 = 0.5;
+const LADDER_DEPTH: f32 = 0.8; // How far in front of the wall the ladder can be grabbed
+const LADDER_OVERHANG: f32 = 0.5; // How far the climbable volume reaches above the top
+const RUNG_SPACING: f32 = 0.4;
+
+pub struct LadderPlugin;
+
+impl Plugin for LadderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_ladder_tower).add_systems(
+            FixedUpdate,
+            detect_ladders
+                .before(player_movement)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// Sensor volume in front of a climbable wall
+#[derive(Component)]
+pub struct Ladder {
+    // Height of the platform the ladder leads up to
+    pub top: f32,
+    // Horizontal direction from the ladder into the wall
+    pub into_wall: Vec3,
+}
+
+// Component on the player while they're holding onto a ladder
+#[derive(Component)]
+pub struct Climbing {
+    pub top: f32,
+    pub into_wall: Vec3,
+}
+
+// The player has no rigid body, so sensor pairs aren't reported and the volume is queried directly
+fn detect_ladders(
+    mut commands: Commands,
+    rapier_context: ReadRapierContext,
+    player: Query<
+        (Entity, &Transform, &Collider, Option<&Climbing>),
+        With<KinematicCharacterController>,
+    >,
+    ladders: Query<&Ladder>,
+) {
+    let Ok((player_entity, transform, collider, climbing)) = player.get_single() else {
+        return;
+    };
+    let physics = rapier_context.single();
+    let mut touching = None;
+    physics.intersections_with_shape(
+        transform.translation,
+        transform.rotation,
+        collider,
+        QueryFilter::default().exclude_collider(player_entity),
+        |entity| {
+            touching = ladders.get(entity).ok();
+            touching.is_none()
+        },
+    );
+
+    match (touching, climbing) {
+        (Some(ladder), _) => {
+            commands.entity(player_entity).insert(Climbing {
+                top: ladder.top,
+                into_wall: ladder.into_wall,
+            });
+        }
+        (None, Some(_)) => {
+            commands.entity(player_entity).remove::<Climbing>();
+        }
+        (None, None) => {}
+    }
+}
+
+fn spawn_ladder_tower(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let tower_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.55, 0.5, 0.45),
+        perceptual_roughness: 0.8,
+        ..default()
+    });
+    let ladder_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.45, 0.3, 0.15),
+        perceptual_roughness: 0.7,
+        ..default()
+    });
+
+    let tower_center = TOWER_POSITION + Vec3::Y * TOWER_HALF_EXTENTS.y;
+    let top = TOWER_HALF_EXTENTS.y * 2.0;
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_size(TOWER_HALF_EXTENTS * 2.0))),
+        MeshMaterial3d(tower_material),
+        Transform::from_translation(tower_center),
+        Collider::cuboid(
+            TOWER_HALF_EXTENTS.x,
+            TOWER_HALF_EXTENTS.y,
+            TOWER_HALF_EXTENTS.z,
+        ),
+        SurfaceMaterial::Stone,
+    ));
+
+    // Ladder up the west face, looking back toward the middle of the map
+    let into_wall = Vec3::X;
+    let face = TOWER_POSITION.with_y(0.0) - into_wall * TOWER_HALF_EXTENTS.x;
+    let rail_mesh = meshes.add(Cuboid::new(0.08, top, 0.08));
+    let rung_mesh = meshes.add(Cuboid::new(0.06, 0.06, LADDER_HALF_WIDTH * 2.0));
+    for side in [-1.0, 1.0] {
+        commands.spawn((
+            Mesh3d(rail_mesh.clone()),
+            MeshMaterial3d(ladder_material.clone()),
+            Transform::from_translation(
+                face - into_wall * 0.1 + Vec3::new(0.0, top / 2.0, side * LADDER_HALF_WIDTH),
+            ),
+        ));
+    }
+    let rungs = (top / RUNG_SPACING) as usize;
+    for i in 1..rungs {
+        commands.spawn((
+            Mesh3d(rung_mesh.clone()),
+            MeshMaterial3d(ladder_material.clone()),
+            Transform::from_translation(
+                face - into_wall * 0.1 + Vec3::Y * (i as f32 * RUNG_SPACING),
+            ),
+        ));
+    }
+
+    let volume_height = top + LADDER_OVERHANG;
+    commands.spawn((
+        Transform::from_translation(
+            face - into_wall * (LADDER_DEPTH / 2.0) + Vec3::Y * (volume_height / 2.0),
+        ),
+        Collider::cuboid(LADDER_DEPTH / 2.0, volume_height / 2.0, LADDER_HALF_WIDTH),
+        Sensor,
+        Ladder { top, into_wall },
+    ));
+}
@@ -0,0 +1,99 @@
+use crate::{
+    GameState, GameStateSet, PlayerCamera,
+    input_map::{ActionState, InputAction, controls_menu_closed},
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+
+// Lean constants
+const LEAN_DISTANCE: f32 = 0.5; // How far the camera slides out to the side at full lean
+const LEAN_ROLL: f32 = 0.25; // Radians the view tilts at full lean
+const LEAN_SPEED: f32 = 8.0; // How quickly the camera follows the keys
+const LEAN_CAMERA_RADIUS: f32 = 0.15; // Room kept between the camera and whatever it leans toward
+
+pub struct LeanPlugin;
+
+impl Plugin for LeanPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lean>()
+            .add_systems(
+                Update,
+                update_lean
+                    .run_if(controls_menu_closed)
+                    .in_set(GameStateSet::Playing),
+            )
+            .add_systems(OnExit(GameState::Playing), reset_lean);
+    }
+}
+
+// Resource with how far the camera is leaning out to the side
+#[derive(Resource, Default)]
+pub struct Lean {
+    // Sideways camera offset in meters, negative to the left
+    offset: f32,
+}
+
+impl Lean {
+    // Camera roll to go with the offset, tipping the view toward the side being leaned to
+    pub fn roll(&self) -> f32 {
+        -self.offset / LEAN_DISTANCE * LEAN_ROLL
+    }
+}
+
+fn update_lean(
+    time: Res<Time>,
+    actions: Res<ActionState>,
+    rapier_context: ReadRapierContext,
+    player: Query<(Entity, &Transform), With<KinematicCharacterController>>,
+    mut cameras: Query<&mut Transform, (With<PlayerCamera>, Without<KinematicCharacterController>)>,
+    mut lean: ResMut<Lean>,
+) {
+    let (Ok((entity, transform)), Ok(mut camera)) = (player.get_single(), cameras.get_single_mut())
+    else {
+        return;
+    };
+    let side = actions.pressed(InputAction::LeanRight) as i32 as f32
+        - actions.pressed(InputAction::LeanLeft) as i32 as f32;
+
+    // Only lean as far as there's room, so the camera can't poke through a wall
+    let mut target = side * LEAN_DISTANCE;
+    if side != 0.0 {
+        let eye = transform.translation + transform.rotation * Vec3::Y * camera.translation.y;
+        let filter = QueryFilter::default()
+            .exclude_collider(entity)
+            .exclude_sensors();
+        let options = ShapeCastOptions {
+            max_time_of_impact: LEAN_DISTANCE,
+            target_distance: 0.0,
+            stop_at_penetration: true,
+            compute_impact_geometry_on_penetration: false,
+        };
+        let hit = rapier_context.single().cast_shape(
+            eye,
+            Quat::IDENTITY,
+            transform.rotation * Vec3::X * side,
+            &Collider::ball(LEAN_CAMERA_RADIUS),
+            options,
+            filter,
+        );
+        if let Some((_, hit)) = hit {
+            target = side * hit.time_of_impact;
+        }
+    }
+
+    let blend = (LEAN_SPEED * time.delta_secs()).min(1.0);
+    lean.offset = lean.offset.lerp(target, blend);
+    // Walls the camera is already past the edge of pull it straight back in
+    if side != 0.0 && lean.offset * side > target * side {
+        lean.offset = target;
+    }
+    camera.translation.x = lean.offset;
+}
+
+// Conversations always start from an upright view
+fn reset_lean(mut lean: ResMut<Lean>, mut cameras: Query<&mut Transform, With<PlayerCamera>>) {
+    lean.offset = 0.0;
+    for mut camera in cameras.iter_mut() {
+        camera.translation.x = 0.0;
+    }
+}
@@ -0,0 +1,586 @@
+use crate::{
+    collectibles::CollectiblePlacement,
+    footsteps::SurfaceMaterial,
+    hold_interaction::LeverPlacement,
+    physics_props::PhysicsPropPlacement,
+    player_body::HIDDEN_FROM_CAMERA_LAYER,
+    readables::ReadablePlacement,
+    respawn::{SPAWN_HEIGHT, SpawnPoint},
+    ron_asset::RonAssetLoader,
+    scenery::SceneryPlacement,
+    triggers::TriggerVolumePlacement,
+};
+use bevy::{asset::UntypedAssetId, prelude::*, render::view::RenderLayers, scene::SceneInstance};
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+// Level constants
+const LEVEL_PATH: &str = "town.level.ron";
+const FALLBACK_GROUND_SIZE: f32 = 50.0; // Half extent of the flat ground used when the level won't load
+const FALLBACK_GROUND_HEIGHT: f32 = 0.1;
+const DEFAULT_CHUNK_SIZE: f32 = 64.0;
+const CHUNK_LOAD_RADIUS: i32 = 1; // Chunks this many away from the player's, or nearer, are loaded
+const CHUNK_UNLOAD_RADIUS: i32 = 2; // Further than this they're dropped, with a gap so edges don't thrash
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LevelConfig>()
+            .register_asset_loader(RonAssetLoader::<LevelConfig>::new(&["level.ron"]))
+            .init_resource::<LevelState>()
+            .init_resource::<WorldChunks>()
+            .add_systems(Startup, load_level)
+            .add_systems(
+                Update,
+                (
+                    spawn_level,
+                    fall_back_to_flat_ground,
+                    stream_chunks,
+                    add_level_colliders,
+                    mark_ready_chunks,
+                )
+                    .chain(),
+            )
+            .add_systems(PostUpdate, pause_physics_while_loading);
+    }
+}
+
+// How a mesh in the level collides with things
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum LevelCollider {
+    // Exact triangles, for static floors, walls and stairs
+    TriMesh,
+    // Approximated with convex pieces, for solid shapes like rocks
+    ConvexDecomposition,
+    // Nothing at all, for decoration that shouldn't block anyone
+    Skip,
+}
+
+impl LevelCollider {
+    fn computed_shape(self) -> Option<ComputedColliderShape> {
+        match self {
+            LevelCollider::TriMesh => Some(ComputedColliderShape::TriMesh(TriMeshFlags::default())),
+            LevelCollider::ConvexDecomposition => Some(ComputedColliderShape::ConvexDecomposition(
+                VHACDParameters::default(),
+            )),
+            LevelCollider::Skip => None,
+        }
+    }
+}
+
+// A light placed in the level
+#[derive(Deserialize)]
+pub enum LevelLight {
+    // Sunlight shining from `position` toward `target`
+    Directional {
+        illuminance: f32,
+        position: Vec3,
+        #[serde(default)]
+        target: Vec3,
+        #[serde(default)]
+        shadows: bool,
+    },
+    Point {
+        position: Vec3,
+        color: (f32, f32, f32),
+        intensity: f32,
+        range: f32,
+    },
+}
+
+impl LevelLight {
+    // Lights the fallback ground when the level won't load
+    const FALLBACK_SUN: LevelLight = LevelLight::Directional {
+        illuminance: 10_000.0,
+        position: Vec3::new(50.0, 50.0, 50.0),
+        target: Vec3::ZERO,
+        shadows: true,
+    };
+
+    fn spawn(&self, parent: &mut ChildBuilder) {
+        match *self {
+            LevelLight::Directional {
+                illuminance,
+                position,
+                target,
+                shadows,
+            } => {
+                parent.spawn((
+                    DirectionalLight {
+                        illuminance,
+                        shadows_enabled: shadows,
+                        ..default()
+                    },
+                    Transform::from_translation(position).looking_at(target, Vec3::Y),
+                    // Also shadows parts of the player the camera doesn't draw
+                    RenderLayers::from_layers(&[0, HIDDEN_FROM_CAMERA_LAYER]),
+                ));
+            }
+            LevelLight::Point {
+                position,
+                color: (red, green, blue),
+                intensity,
+                range,
+            } => {
+                parent.spawn((
+                    PointLight {
+                        color: Color::srgb(red, green, blue),
+                        intensity,
+                        range,
+                        ..default()
+                    },
+                    Transform::from_translation(position),
+                ));
+            }
+        }
+    }
+}
+
+// A place the player is put back after falling off the map
+#[derive(Deserialize)]
+pub struct SpawnPlacement {
+    // Where their feet go
+    pub position: Vec3,
+    // Degrees around the vertical axis to face
+    #[serde(default)]
+    pub yaw: f32,
+}
+
+impl SpawnPlacement {
+    const FALLBACK: SpawnPlacement = SpawnPlacement {
+        position: Vec3::ZERO,
+        yaw: 0.0,
+    };
+
+    fn spawn(&self, parent: &mut ChildBuilder) {
+        parent.spawn((
+            SpawnPoint { yaw: self.yaw },
+            Transform::from_translation(self.position + Vec3::Y * SPAWN_HEIGHT),
+        ));
+    }
+}
+
+// Overrides for the nodes whose name starts with `prefix`
+#[derive(Deserialize)]
+pub struct LevelNode {
+    pub prefix: String,
+    #[serde(default)]
+    pub collider: Option<LevelCollider>,
+    #[serde(default)]
+    pub surface: Option<SurfaceMaterial>,
+}
+
+// The map's geometry, loaded from `town.level.ron`
+#[derive(Asset, TypePath, Deserialize)]
+pub struct LevelConfig {
+    // GLTF file under `assets/`, whose first scene is the map
+    pub scene: String,
+    pub collider: LevelCollider,
+    #[serde(default)]
+    pub surface: SurfaceMaterial,
+    #[serde(default)]
+    pub nodes: Vec<LevelNode>,
+    #[serde(default)]
+    pub lights: Vec<LevelLight>,
+    #[serde(default)]
+    pub spawn_points: Vec<SpawnPlacement>,
+    // Where each floating cube bobs around
+    #[serde(default)]
+    pub floating_cubes: Vec<Vec3>,
+    // Trees, rocks and crates dotted around the map
+    #[serde(default)]
+    pub scenery: Vec<SceneryPlacement>,
+    // Sensors that let quests, dialogue and region names know where the player is
+    #[serde(default)]
+    pub trigger_volumes: Vec<TriggerVolumePlacement>,
+    // Signposts and notes the player can stop and read
+    #[serde(default)]
+    pub readables: Vec<ReadablePlacement>,
+    // Coins and datapads to put out around the map
+    #[serde(default)]
+    pub collectibles: Vec<CollectiblePlacement>,
+    // Crates, barrels and balls loose for the player to push around and stack
+    #[serde(default)]
+    pub physics_props: Vec<PhysicsPropPlacement>,
+    // Levers to hold interact on, which flip `lever_pulled` for dialogue
+    #[serde(default)]
+    pub levers: Vec<LeverPlacement>,
+    // Width of each square chunk of the map
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: f32,
+    // Pieces of the map only loaded while the player is near them, on top of `scene`
+    #[serde(default)]
+    pub chunks: Vec<LevelChunk>,
+}
+
+fn default_chunk_size() -> f32 {
+    DEFAULT_CHUNK_SIZE
+}
+
+// A square of the map with its own GLTF scene, authored in world space, with everything
+// from its ground and walls to its decoration.
+// Chunk (0, 0) covers x and z from 0 up to `chunk_size`.
+#[derive(Deserialize)]
+pub struct LevelChunk {
+    pub x: i32,
+    pub z: i32,
+    pub scene: String,
+}
+
+impl LevelConfig {
+    // The override for the first of `names` that any node prefix matches, innermost first
+    fn node<'a>(&self, mut names: impl Iterator<Item = &'a str>) -> Option<&LevelNode> {
+        names.find_map(|name| {
+            self.nodes
+                .iter()
+                .find(|node| name.starts_with(node.prefix.as_str()))
+        })
+    }
+}
+
+#[derive(Resource)]
+pub struct LevelConfigHandle(pub Handle<LevelConfig>);
+
+// Whether the level has its colliders yet, so nothing walks or falls before there's ground
+#[derive(Resource, Default)]
+pub struct LevelState {
+    ready: bool,
+    scene: Option<Handle<Gltf>>,
+}
+
+// Run condition for gameplay that needs the level's colliders in place
+pub fn level_ready(level: Res<LevelState>) -> bool {
+    level.ready
+}
+
+// Marker for the root of the spawned level
+#[derive(Component)]
+struct Level;
+
+// Marker for a level scene still waiting on its colliders
+#[derive(Component)]
+struct LevelColliders;
+
+// Component on the root of a streamed chunk's scene
+#[derive(Component)]
+struct ChunkRoot {
+    chunk: IVec2,
+    // The GLTF itself, whose load state says whether the scene will ever arrive
+    gltf: Handle<Gltf>,
+}
+
+// Resource tracking which chunks of the map are loaded around the player
+#[derive(Resource)]
+pub struct WorldChunks {
+    size: f32,
+    // Scene for each chunk the level defines
+    scenes: HashMap<IVec2, String>,
+    loaded: HashMap<IVec2, Entity>,
+    // Loaded chunks whose colliders are in place
+    ready: HashSet<IVec2>,
+}
+
+impl Default for WorldChunks {
+    fn default() -> Self {
+        Self {
+            size: DEFAULT_CHUNK_SIZE,
+            scenes: HashMap::new(),
+            loaded: HashMap::new(),
+            ready: HashSet::new(),
+        }
+    }
+}
+
+impl WorldChunks {
+    fn chunk_at(&self, position: Vec3) -> IVec2 {
+        IVec2::new(
+            (position.x / self.size).floor() as i32,
+            (position.z / self.size).floor() as i32,
+        )
+    }
+
+    // Whether there's solid ground at `position`, either from the base scene or a loaded chunk
+    pub fn is_ready(&self, position: Vec3) -> bool {
+        let chunk = self.chunk_at(position);
+        !self.scenes.contains_key(&chunk) || self.ready.contains(&chunk)
+    }
+
+    fn reset(&mut self, config: Option<&LevelConfig>) {
+        self.size = config.map_or(DEFAULT_CHUNK_SIZE, |config| config.chunk_size);
+        self.scenes = config
+            .map(|config| {
+                config
+                    .chunks
+                    .iter()
+                    .map(|chunk| (IVec2::new(chunk.x, chunk.z), chunk.scene.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.loaded.clear();
+        self.ready.clear();
+    }
+}
+
+fn load_level(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(LevelConfigHandle(asset_server.load(LEVEL_PATH)));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_level(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelConfig>>,
+    configs: Res<Assets<LevelConfig>>,
+    handle: Option<Res<LevelConfigHandle>>,
+    asset_server: Res<AssetServer>,
+    mut level: ResMut<LevelState>,
+    mut chunks: ResMut<WorldChunks>,
+    spawned: Query<Entity, With<Level>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(config) = configs.get(&handle.0) else {
+            continue;
+        };
+        // Streamed chunks are levels too, so they go with it and load again from the new config
+        for entity in spawned.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        chunks.reset(Some(config));
+        let scene = asset_server.load(GltfAssetLabel::Scene(0).from_asset(config.scene.clone()));
+        commands
+            .spawn((
+                Level,
+                LevelColliders,
+                SceneRoot(scene),
+                Transform::default(),
+            ))
+            .with_children(|parent| {
+                for light in &config.lights {
+                    light.spawn(parent);
+                }
+                for spawn_point in &config.spawn_points {
+                    spawn_point.spawn(parent);
+                }
+            });
+        level.ready = false;
+        level.scene = Some(asset_server.load(config.scene.clone()));
+    }
+}
+
+// A level that won't load still leaves somewhere to stand
+#[allow(clippy::too_many_arguments)]
+fn fall_back_to_flat_ground(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    handle: Option<Res<LevelConfigHandle>>,
+    mut level: ResMut<LevelState>,
+    mut chunks: ResMut<WorldChunks>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    spawned: Query<Entity, With<Level>>,
+) {
+    if level.ready {
+        return;
+    }
+    let failed = |id: UntypedAssetId| asset_server.load_state(id).is_failed();
+    let config_failed = handle.is_some_and(|handle| failed(handle.0.id().untyped()));
+    let scene_failed = level
+        .scene
+        .as_ref()
+        .is_some_and(|scene| failed(scene.id().untyped()));
+    if !config_failed && !scene_failed {
+        return;
+    }
+    println!("Error: Couldn't load the level, falling back to flat ground");
+    for entity in spawned.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    chunks.reset(None);
+    commands
+        .spawn((
+            Level,
+            Mesh3d(meshes.add(Cuboid::new(
+                FALLBACK_GROUND_SIZE * 2.0,
+                FALLBACK_GROUND_HEIGHT * 2.0,
+                FALLBACK_GROUND_SIZE * 2.0,
+            ))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgb(0.3, 0.5, 0.3),
+                perceptual_roughness: 0.9,
+                ..default()
+            })),
+            Transform::from_xyz(0.0, -FALLBACK_GROUND_HEIGHT, 0.0),
+            Collider::cuboid(
+                FALLBACK_GROUND_SIZE,
+                FALLBACK_GROUND_HEIGHT,
+                FALLBACK_GROUND_SIZE,
+            ),
+            SurfaceMaterial::Grass,
+        ))
+        .with_children(|parent| {
+            LevelLight::FALLBACK_SUN.spawn(parent);
+            SpawnPlacement::FALLBACK.spawn(parent);
+        });
+    level.ready = true;
+}
+
+// Once the scene has spawned, build a collider from each of its meshes
+#[allow(clippy::too_many_arguments)]
+fn add_level_colliders(
+    mut commands: Commands,
+    configs: Res<Assets<LevelConfig>>,
+    handle: Option<Res<LevelConfigHandle>>,
+    scene_spawner: Res<SceneSpawner>,
+    meshes: Res<Assets<Mesh>>,
+    mut level: ResMut<LevelState>,
+    levels: Query<(Entity, &SceneInstance, Has<ChunkRoot>), With<LevelColliders>>,
+    children: Query<&Children>,
+    parents: Query<&Parent>,
+    mesh_handles: Query<&Mesh3d>,
+    names: Query<&Name>,
+) {
+    let Some(config) = handle.and_then(|handle| configs.get(&handle.0)) else {
+        return;
+    };
+    for (entity, instance, is_chunk) in levels.iter() {
+        if !scene_spawner.instance_is_ready(**instance) {
+            continue;
+        }
+        for descendant in children.iter_descendants(entity) {
+            let Ok(mesh_handle) = mesh_handles.get(descendant) else {
+                continue;
+            };
+            let Some(mesh) = meshes.get(mesh_handle) else {
+                continue;
+            };
+            // Primitives are named after their mesh, so check the nodes above them too
+            let node = config.node(
+                std::iter::once(descendant)
+                    .chain(parents.iter_ancestors(descendant))
+                    .filter_map(|ancestor| names.get(ancestor).ok())
+                    .map(Name::as_str),
+            );
+            let collider = node
+                .and_then(|node| node.collider)
+                .unwrap_or(config.collider);
+            let Some(shape) = collider.computed_shape() else {
+                continue;
+            };
+            let Some(collider) = Collider::from_bevy_mesh(mesh, &shape) else {
+                println!("Error: Couldn't build a collider for a level mesh");
+                continue;
+            };
+            let surface = node.and_then(|node| node.surface).unwrap_or(config.surface);
+            commands.entity(descendant).insert((collider, surface));
+        }
+        commands.entity(entity).remove::<LevelColliders>();
+        // Chunks don't hold up the level, which only waits on its base scene
+        if !is_chunk {
+            level.ready = true;
+        }
+    }
+}
+
+// Load the chunks around the player and drop the ones they've left well behind
+fn stream_chunks(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut chunks: ResMut<WorldChunks>,
+    player: Query<&Transform, With<KinematicCharacterController>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let center = chunks.chunk_at(player.translation);
+
+    let WorldChunks {
+        scenes,
+        loaded,
+        ready,
+        ..
+    } = chunks.as_mut();
+    loaded.retain(|chunk, entity| {
+        let distance = (*chunk - center).abs().max_element();
+        if distance <= CHUNK_UNLOAD_RADIUS {
+            return true;
+        }
+        commands.entity(*entity).despawn_recursive();
+        ready.remove(chunk);
+        false
+    });
+    for (chunk, scene) in scenes.iter() {
+        let distance = (*chunk - center).abs().max_element();
+        if distance > CHUNK_LOAD_RADIUS || loaded.contains_key(chunk) {
+            continue;
+        }
+        let entity = commands
+            .spawn((
+                Level,
+                LevelColliders,
+                ChunkRoot {
+                    chunk: *chunk,
+                    gltf: asset_server.load(scene.clone()),
+                },
+                SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset(scene.clone()))),
+                Transform::default(),
+            ))
+            .id();
+        loaded.insert(*chunk, entity);
+    }
+}
+
+// A chunk is ready once its colliders have been built. One whose scene won't load is left out
+// of the map, so the player isn't held waiting on ground that never comes
+fn mark_ready_chunks(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut chunks: ResMut<WorldChunks>,
+    roots: Query<(Entity, &ChunkRoot, Has<LevelColliders>)>,
+) {
+    for (entity, root, waiting) in roots.iter() {
+        if !waiting {
+            if !chunks.ready.contains(&root.chunk) {
+                chunks.ready.insert(root.chunk);
+            }
+            continue;
+        }
+        if !asset_server.load_state(&root.gltf).is_failed() {
+            continue;
+        }
+        if let Some(scene) = chunks.scenes.remove(&root.chunk) {
+            println!(
+                "Error: Couldn't load the chunk at ({}, {}) from {scene}, leaving it out",
+                root.chunk.x, root.chunk.y
+            );
+        }
+        chunks.loaded.remove(&root.chunk);
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Hold the physics world still until there's a level for it to land on, including the chunk
+// the player is standing in
+fn pause_physics_while_loading(
+    level: Res<LevelState>,
+    chunks: Res<WorldChunks>,
+    player: Query<&Transform, With<KinematicCharacterController>>,
+    mut configs: Query<&mut RapierConfiguration>,
+) {
+    let chunk_ready = player
+        .get_single()
+        .ok()
+        .is_none_or(|player| chunks.is_ready(player.translation));
+    let active = level.ready && chunk_ready;
+    for mut config in configs.iter_mut() {
+        if config.physics_pipeline_active != active {
+            config.physics_pipeline_active = active;
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::paths::{SettingsFile, UserPaths};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct LookSettingsPlugin;
+
+impl Plugin for LookSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LookSettings>()
+            .add_systems(Startup, load_look_settings);
+    }
+}
+
+// Resource with the player's mouse look tuning, saved to `look.ron`
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LookSettings {
+    // Degrees per pixel of mouse movement
+    pub sensitivity: f32,
+    pub invert_y: bool,
+    // Seconds for the camera to catch up with the mouse, zero for raw input
+    pub smoothing: f32,
+}
+
+impl Default for LookSettings {
+    fn default() -> Self {
+        Self {
+            sensitivity: 0.3,
+            invert_y: false,
+            smoothing: 0.0,
+        }
+    }
+}
+
+impl SettingsFile for LookSettings {
+    const FILE: &'static str = "look.ron";
+}
+
+impl LookSettings {
+    // Turn a frame's mouse motion into yaw and pitch degrees, easing `smoothed` toward it
+    pub fn look_delta(&self, mouse_delta: Vec2, smoothed: &mut Vec2, delta_time: f32) -> Vec2 {
+        let pitch = if self.invert_y {
+            -mouse_delta.y
+        } else {
+            mouse_delta.y
+        };
+        let target = Vec2::new(mouse_delta.x, pitch) * self.sensitivity;
+        *smoothed = if self.smoothing > 0.0 {
+            smoothed.lerp(target, 1.0 - (-delta_time / self.smoothing).exp())
+        } else {
+            target
+        };
+        *smoothed
+    }
+}
+
+fn load_look_settings(paths: Res<UserPaths>, mut settings: ResMut<LookSettings>) {
+    if let Some(loaded) = paths.load_settings() {
+        *settings = loaded;
+    }
+}
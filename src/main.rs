@@ -1,21 +1,208 @@
-use bevy::{
-    input::{InputSystem, mouse::MouseMotion},
-    prelude::*,
-};
+mod accessibility;
+mod ai_debug;
+mod ai_lod;
+mod ambient_dialogue;
+mod atmosphere;
+mod bounce_pads;
+mod character_motor;
+#[cfg(test)]
+mod character_motor_tests;
+mod clock;
+mod collectibles;
+mod companions;
+mod crouch;
+mod crumbling_platforms;
+mod cube_breaking;
+mod dialogue_assets;
+mod dialogue_callbacks;
+mod dialogue_editor;
+#[cfg(test)]
+mod dialogue_import_tests;
+mod dialogue_tags;
+mod dialogue_telemetry;
+#[cfg(test)]
+mod dialogue_tests;
+mod dialogue_variables;
+mod economy;
+mod elevators;
+mod emotes;
+mod factions;
+mod first_person_arms;
+mod flee;
+mod footsteps;
+mod gamepad;
+mod gossip;
+mod graphics_settings;
+mod haptics;
+mod head_look;
+mod health;
+mod hold_interaction;
+mod hostiles;
+mod impact_audio;
+mod input_map;
+mod interaction_prompt;
+mod interpolation;
+mod ladders;
+mod lean;
+mod level;
+mod look_settings;
+mod mantle;
+mod merchant_stalls;
+mod movement_dust;
+mod movement_tuning;
+mod npc_animation;
+mod npc_avoidance;
+mod npc_chatter;
+mod npc_grounding;
+mod npc_memory;
+mod npc_models;
+mod npc_needs;
+mod npc_reactions;
+mod npc_rng;
+mod npc_scripts;
+mod npc_spawning;
+mod particles;
+mod paths;
+mod patrols;
+mod perception;
+mod physics_props;
+mod platforms;
+mod player_body;
+mod prop_grab;
+mod quests;
+mod readables;
+mod region_names;
+mod respawn;
+mod ron_asset;
+mod scenery;
+mod security_drones;
+mod sprint_indicator;
+mod surface_modifiers;
+mod teleporters;
+#[cfg(feature = "touch")]
+mod touch_controls;
+mod triggers;
+mod twee;
+mod usable_props;
+mod utility_ai;
+#[cfg(feature = "vr")]
+mod vr;
+mod water;
+mod world_events;
+mod yarn;
+mod zoom;
+
+use accessibility::{AccessibilityPlugin, AccessibilitySettings};
+use ai_debug::AiDebugPlugin;
+use ai_lod::{AiLod, AiLodPlugin};
+use ambient_dialogue::AmbientDialoguePlugin;
+use atmosphere::AtmospherePlugin;
+use bevy::{input::mouse::MouseMotion, prelude::*};
+use bevy_egui::EguiPlugin;
 use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use bounce_pads::{BouncePadsPlugin, PlayerLaunched};
+use character_motor::{ActiveMotor, CharacterBody, CharacterMotorPlugin};
+use clock::{ClockPlugin, GameClock};
+use collectibles::CollectiblesPlugin;
+use companions::{CompanionsPlugin, Following};
+use crouch::{Crouch, CrouchPlugin};
+use crumbling_platforms::CrumblingPlatformsPlugin;
+use cube_breaking::{Breakable, CubeBreakingPlugin};
+use dialogue_assets::{DialogueAssetsPlugin, RegisterDialogueExt};
+use dialogue_callbacks::{DialogueCallbacks, queue_node_callbacks};
+use dialogue_editor::DialogueEditorPlugin;
+use dialogue_tags::{DialogueTagsPlugin, queue_node_tags};
+use dialogue_telemetry::{DialogueOptionChosen, DialogueTelemetryPlugin};
+use dialogue_variables::{DialogueCondition, DialogueContext, DialogueValue, DialogueVariables};
+use economy::{Economy, EconomyPlugin, Merchant};
+use elevators::ElevatorsPlugin;
+use emotes::EmotesPlugin;
+use factions::{Faction, FactionStandings, FactionsPlugin};
+use first_person_arms::FirstPersonArmsPlugin;
+use flee::{FleePlugin, Fleeing};
+use footsteps::{Footstep, FootstepsPlugin, STRIDE_LENGTH};
+use gamepad::{GamepadConfig, GamepadPlugin, apply_deadzone};
+use gossip::GossipPlugin;
+use graphics_settings::GraphicsSettingsPlugin;
+use haptics::HapticsPlugin;
+use head_look::HeadLookPlugin;
+use health::{Health, HealthPlugin};
+use hold_interaction::HoldInteractionPlugin;
+use hostiles::{Aggro, Hostile, HostilesPlugin};
+use impact_audio::ImpactAudioPlugin;
+use input_map::{
+    ActionState, InputAction, InputMapPlugin, controls_menu_closed, update_action_state,
+};
+use interaction_prompt::InteractionPromptPlugin;
+use interpolation::{InterpolationPlugin, TransformInterpolation};
+use ladders::{CLIMB_SPEED, Climbing, LadderPlugin, MANTLE_PUSH};
+use lean::{Lean, LeanPlugin};
+use level::{LevelConfig, LevelConfigHandle, LevelPlugin, level_ready};
+use look_settings::{LookSettings, LookSettingsPlugin};
+use mantle::{MantlePlugin, Mantling};
+use merchant_stalls::MerchantStallsPlugin;
+use movement_dust::MovementDustPlugin;
+use movement_tuning::{MovementTuning, MovementTuningPlugin};
+use npc_animation::{NpcAnimationController, NpcAnimationPlugin};
+use npc_avoidance::NpcAvoidancePlugin;
+use npc_chatter::NpcChatterPlugin;
+use npc_grounding::{NpcFall, NpcGroundingPlugin};
+use npc_memory::{NpcMemory, NpcMemoryPlugin};
+use npc_models::NpcModelsPlugin;
+use npc_needs::{Errand, NpcNeedsPlugin};
+use npc_reactions::{NpcReaction, NpcReactionsPlugin};
+use npc_rng::{NpcRng, NpcRngPlugin};
+use npc_scripts::{NpcScript, NpcScriptsPlugin};
+use npc_spawning::NpcSpawningPlugin;
+use particles::ParticlesPlugin;
+use paths::PathsPlugin;
+use patrols::{PatrolRoute, PatrolsPlugin};
+use perception::{Perception, PerceptionPlugin};
+use physics_props::PhysicsPropsPlugin;
+use platforms::{PlatformMotion, PlatformsPlugin, Riding};
+use player_body::PlayerBodyPlugin;
+use prop_grab::PropGrabPlugin;
+use quests::{DialogueAction, DialogueActionTriggered, QuestDatabase, QuestPlugin};
 use rand::Rng;
+use readables::{Readable, ReadablesPlugin};
+use region_names::RegionNamesPlugin;
+use respawn::{PlayerRespawned, RespawnPlugin};
+use scenery::SceneryPlugin;
+use security_drones::SecurityDronesPlugin;
+use serde::{Deserialize, Serialize};
+use sprint_indicator::SprintIndicatorPlugin;
 use std::f32::consts::PI;
+use surface_modifiers::{SurfaceModifier, SurfaceModifiersPlugin};
+use teleporters::{PlayerTeleported, TeleportersPlugin};
+#[cfg(feature = "touch")]
+use touch_controls::TouchControlsPlugin;
+use triggers::TriggersPlugin;
+use usable_props::{UsablePropsPlugin, UsingProp};
+use utility_ai::{UtilityAi, UtilityAiPlugin};
+#[cfg(feature = "vr")]
+use vr::VrPlugin;
+use water::{FLOAT_DEPTH, SWIM_DRAG, SWIM_RISE_SPEED, SWIM_SPEED_SCALE, Swimming, WaterPlugin};
+use world_events::{ActiveWorldEvents, CUBE_ANOMALY_EVENT, WorldEventsPlugin};
+use zoom::{Zoom, ZoomPlugin};
 
-const MOUSE_SENSITIVITY: f32 = 0.3;
 const GROUND_TIMER: f32 = 0.5;
-const MOVEMENT_SPEED: f32 = 8.0;
-const JUMP_SPEED: f32 = 20.0;
 const GRAVITY: f32 = -9.81;
+const LANDING_MIN_SPEED: f32 = 8.0; // Slower touchdowns, like walking down stairs, aren't landings
+const HARD_LANDING_SPEED: f32 = 30.0; // Falls this fast or faster rumble, sound and raise dust the most
+// Player capsule, a rounded cylinder, and the camera's height above its center
+const PLAYER_HALF_HEIGHT: f32 = 0.9;
+const PLAYER_RADIUS: f32 = 0.3;
+const PLAYER_BORDER_RADIUS: f32 = 0.2;
+const PLAYER_EYE_HEIGHT: f32 = 0.2;
+const EAR_GAP: f32 = 0.3; // Distance between the listener's ears for spatial audio
+const PHYSICS_TICK_RATE: f64 = 64.0;
+const SNAP_TO_GROUND_DISTANCE: f32 = 0.5; // Deeper than the 0.4 stair rise, so walking down stays grounded
 // Floating cube constants
 const CUBE_FLOAT_AMPLITUDE: f32 = 1.0;
 const CUBE_FLOAT_FREQUENCY: f32 = 1.0;
+const CUBE_ROTATION_SPEED: f32 = 0.005;
+const CUBE_ANOMALY_SCALE: f32 = 3.0; // How much wilder cubes move during the anomaly
 // NPC constants
-const NPC_COUNT: usize = 12;
 const NPC_WANDER_RADIUS: f32 = 3.0;
 const NPC_WANDER_SPEED: f32 = 0.8;
 // Interaction constants
@@ -26,20 +213,59 @@ const DIALOGUE_BACKGROUND_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.9);
 const DIALOGUE_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
 const DIALOGUE_OPTION_HOVER_COLOR: Color = Color::srgb(0.8, 0.8, 0.3);
 const DIALOGUE_OPTION_NORMAL_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const RESUME_OPTION_TEXT: &str = "Continue where we left off.";
+const PLAYER_NAME: &str = "Operator";
+const AUTO_ADVANCE_TEXT: &str = "Continue...";
+const EXIT_EFFECT_TEXT: &str = "Ends conversation";
+const DIALOGUE_TOOLTIP_COLOR: Color = Color::srgba(0.05, 0.05, 0.05, 0.95);
+const AUTO_ADVANCE_BASE_DELAY: f32 = 2.0;
+const AUTO_ADVANCE_DELAY_PER_CHAR: f32 = 0.05;
+// Conversations end on their own once the player and NPC drift this far apart
+const BREAK_OFF_DISTANCE: f32 = INTERACTION_DISTANCE * 2.0;
+const BREAK_OFF_TEXT: &str = "…";
+const BREAK_OFF_LINGER: f32 = 1.5; // How long the farewell line stays up
+// Node where merchants chat about the state of the market
+const MARKET_FLAVOR_NODE: &str = "business";
 
 #[derive(Component)]
+#[require(TransformInterpolation, PlatformMotion, Breakable)]
 struct FloatingCube {
     initial_y: f32,
     offset: f32,
 }
 
 #[derive(Component)]
+#[require(
+    TransformInterpolation,
+    Perception,
+    Faction,
+    NpcAnimationController,
+    NpcFall,
+    AiLod,
+    NpcMemory
+)]
 struct Npc {
     home_position: Vec3,
     target_position: Vec3,
     movement_timer: Timer,
     name: String,
     dialogue_id: String,
+    reaction: NpcReaction,
+    // How far from home wander targets are picked
+    wander_radius: f32,
+}
+
+impl Npc {
+    // Choose a new random spot to wander to around home
+    fn pick_wander_target(&mut self, rng: &mut impl Rng) {
+        let radius = self.wander_radius;
+        let target_offset = Vec3::new(
+            rng.random_range(-radius..radius),
+            0.0,
+            rng.random_range(-radius..radius),
+        );
+        self.target_position = self.home_position + target_offset;
+    }
 }
 
 // Game state to track if player is in dialogue
@@ -48,6 +274,28 @@ enum GameState {
     #[default]
     Playing,
     InDialogue,
+    // Reading a sign or note
+    Reading,
+}
+
+// Whether the conversation is playing or paused in the dialogue editor. The editor stays inside
+// InDialogue, so opening and closing it doesn't start the conversation over
+#[derive(SubStates, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[source(GameState = GameState::InDialogue)]
+enum DialogueMode {
+    #[default]
+    Talking,
+    Editing,
+}
+
+// Systems that only make sense in one game state, gated once here instead of per system
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum GameStateSet {
+    Playing,
+    InDialogue,
+    // Both of the above, for world motion that doesn't stop for a conversation, so a moving
+    // platform can still carry the player or their partner out of range
+    WorldMotion,
 }
 
 // Component to mark entities as part of dialogue UI
@@ -61,11 +309,53 @@ struct ActiveDialogue {
     current_node: String,
 }
 
+// Countdown on the active dialogue while a linear node is showing
+#[derive(Component)]
+struct AutoAdvance {
+    timer: Timer,
+    next: String,
+}
+
+impl AutoAdvance {
+    // Longer lines stay up longer so there's time to read them
+    fn for_node(node: &DialogueNode) -> Option<Self> {
+        let next = node.next.clone()?;
+        let delay = AUTO_ADVANCE_BASE_DELAY
+            + node.text.chars().count() as f32 * AUTO_ADVANCE_DELAY_PER_CHAR;
+        Some(Self {
+            timer: Timer::from_seconds(delay, TimerMode::Once),
+            next,
+        })
+    }
+}
+
+// Countdown on the active dialogue after the NPC has drifted out of range
+#[derive(Component)]
+struct BrokenOff(Timer);
+
+// Event moving the active conversation to another node
+#[derive(Event)]
+struct AdvanceDialogue {
+    target_node: String,
+}
+
+// Component remembering where an interrupted conversation with an NPC left off
+#[derive(Component)]
+struct InterruptedDialogue {
+    node: String,
+}
+
+// Marker for the consequences tooltip inside a dialogue option button
+#[derive(Component)]
+struct DialogueTooltip;
+
 // Component for dialogue option buttons
 #[derive(Component)]
 struct DialogueOptionButton {
     target_node: String,
+    actions: Vec<DialogueAction>,
     option_index: usize,
+    text: String,
 }
 
 // Resource to store all dialogues
@@ -75,24 +365,118 @@ struct DialogueDatabase {
 }
 
 // Struct to represent a complete dialogue tree
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct DialogueTree {
     nodes: std::collections::HashMap<String, DialogueNode>,
     root_node: String,
+    // Short lines the NPC says to nobody in particular while the player wanders past
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ambient: Vec<String>,
 }
 
 // Struct to represent a dialogue node
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct DialogueNode {
     text: String,
     options: Vec<DialogueOption>,
+    // Linear nodes move straight on to this node instead of offering options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
+    // Presentation cues like `@angry` or `@wave`, stored without the `@` and published when shown
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
 }
 
 // Struct to represent a dialogue option
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 enum DialogueOption {
-    Reply { text: String, target_node: String },
-    Exit { text: String },
+    Reply {
+        text: String,
+        target_node: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        condition: Option<DialogueCondition>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        actions: Vec<DialogueAction>,
+    },
+    Exit {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        condition: Option<DialogueCondition>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        actions: Vec<DialogueAction>,
+    },
+}
+
+impl DialogueOption {
+    fn reply(text: impl Into<String>, target_node: impl Into<String>) -> Self {
+        DialogueOption::Reply {
+            text: text.into(),
+            target_node: target_node.into(),
+            condition: None,
+            actions: Vec::new(),
+        }
+    }
+
+    fn exit(text: impl Into<String>) -> Self {
+        DialogueOption::Exit {
+            text: text.into(),
+            condition: None,
+            actions: Vec::new(),
+        }
+    }
+
+    // Only show this option when the condition holds
+    fn with_condition(mut self, new_condition: DialogueCondition) -> Self {
+        match &mut self {
+            DialogueOption::Reply { condition, .. } | DialogueOption::Exit { condition, .. } => {
+                *condition = Some(new_condition)
+            }
+        }
+        self
+    }
+
+    // Run this action when the option is picked
+    fn with_action(mut self, action: DialogueAction) -> Self {
+        match &mut self {
+            DialogueOption::Reply { actions, .. } | DialogueOption::Exit { actions, .. } => {
+                actions.push(action)
+            }
+        }
+        self
+    }
+
+    fn actions(&self) -> &[DialogueAction] {
+        match self {
+            DialogueOption::Reply { actions, .. } | DialogueOption::Exit { actions, .. } => actions,
+        }
+    }
+
+    // What the option's tooltip lists: what it needed, what it does and whether it ends things
+    fn effects(&self, quests: &QuestDatabase) -> Vec<String> {
+        let (DialogueOption::Reply { condition, .. } | DialogueOption::Exit { condition, .. }) =
+            self;
+        let mut effects: Vec<String> = condition.iter().map(|c| c.describe()).collect();
+        effects.extend(self.actions().iter().map(|action| action.describe(quests)));
+        if matches!(self, DialogueOption::Exit { .. }) {
+            effects.push(EXIT_EFFECT_TEXT.to_string());
+        }
+        effects
+    }
+
+    fn is_available(&self, variables: &DialogueVariables) -> bool {
+        match self {
+            DialogueOption::Reply { condition, .. } | DialogueOption::Exit { condition, .. } => {
+                condition
+                    .as_ref()
+                    .is_none_or(|condition| condition.evaluate(variables))
+            }
+        }
+    }
+}
+
+// Parse a condition written into the built-in dialogue
+fn condition(expression: &str) -> DialogueCondition {
+    DialogueCondition::parse(expression).expect("invalid built-in dialogue condition")
 }
 
 // Add a resource to store camera state during dialogue
@@ -109,33 +493,49 @@ impl Default for StoredCameraState {
     }
 }
 
+impl DialogueDatabase {
+    // Add a tree under `dialogue_id`, replacing and returning any tree already there
+    pub fn insert_tree(
+        &mut self,
+        dialogue_id: impl Into<String>,
+        tree: DialogueTree,
+    ) -> Option<DialogueTree> {
+        self.dialogues.insert(dialogue_id.into(), tree)
+    }
+}
+
 impl Default for DialogueDatabase {
     fn default() -> Self {
-        let mut dialogues = std::collections::HashMap::new();
+        let mut database = DialogueDatabase {
+            dialogues: std::collections::HashMap::new(),
+        };
 
         // Add basic civilian dialogue tree
-        dialogues.insert(
-            "basic".to_string(),
+        database.insert_tree(
+            "basic",
             DialogueTree {
                 root_node: "start".to_string(),
                 nodes: [
                     (
                         "start".to_string(), 
                         DialogueNode {
-                            text: "Hello there, traveler! How can I help you today?".to_string(),
+                            text: "Good {time_of_day}, traveler! I'm {npc_name}. How can I help you today?".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Who are you?".to_string(),
-                                    target_node: "who".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "What is this place?".to_string(),
-                                    target_node: "place".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("Who are you?", "who"),
+                                DialogueOption::reply("What is this place?", "place"),
+                                DialogueOption::reply("How are you holding up?", "feeling"),
+                                DialogueOption::reply("Why are you looking at me like that?", "rumor_rude")
+                                    .with_condition(condition("$heard_rude_to_guard")),
+                                DialogueOption::exit("Follow me.")
+                                    .with_condition(condition("not $npc_following"))
+                                    .with_action(DialogueAction::StartFollowing),
+                                DialogueOption::exit("You can go now.")
+                                    .with_condition(condition("$npc_following"))
+                                    .with_action(DialogueAction::StopFollowing),
+                                DialogueOption::exit("Goodbye."),
                             ],
+                            next: None,
+                            tags: vec!["wave".to_string()],
                         }
                     ),
                     (
@@ -143,18 +543,36 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "I'm just a simple NPC wandering around. Not much to tell!".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Tell me about this place.".to_string(),
-                                    target_node: "place".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Nice to meet you. Goodbye!".to_string(),
-                                },
+                                DialogueOption::reply("Tell me about this place.", "place"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("Nice to meet you. Goodbye!"),
+                            ],
+                            next: None,
+                            tags: Vec::new(),
+                        }
+                    ),
+                    (
+                        "feeling".to_string(),
+                        DialogueNode {
+                            text: "{npc_feeling}".to_string(),
+                            options: vec![
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("Take care of yourself. Goodbye!"),
+                            ],
+                            next: None,
+                            tags: Vec::new(),
+                        }
+                    ),
+                    (
+                        "rumor_rude".to_string(),
+                        DialogueNode {
+                            text: "Word gets around. I heard you mouthed off to one of the guards.".to_string(),
+                            options: vec![
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("Never mind. Goodbye."),
                             ],
+                            next: None,
+                            tags: vec!["suspicious".to_string()],
                         }
                     ),
                     (
@@ -162,27 +580,26 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "This is a test environment. Try jumping on the floating cubes or climbing the stairs!".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Who are you again?".to_string(),
-                                    target_node: "who".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "I'll check it out. Goodbye!".to_string(),
-                                },
+                                DialogueOption::reply("Who are you again?", "who"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("I'll check it out. Goodbye!"),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                 ].into_iter().collect(),
+                ambient: vec![
+                    "Lovely {time_of_day}, isn't it?".to_string(),
+                    "I wonder what's at the top of those stairs.".to_string(),
+                    "Did that cube just wink at me?".to_string(),
+                ],
             }
         );
 
         // Add guard dialogue tree
-        dialogues.insert(
-            "guard".to_string(),
+        database.insert_tree(
+            "guard",
             DialogueTree {
                 root_node: "start".to_string(),
                 nodes: [
@@ -191,18 +608,14 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "Halt! State your business here, wanderer.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Just exploring.".to_string(),
-                                    target_node: "exploring".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Who are you?".to_string(),
-                                    target_node: "guard_who".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Never mind. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("About those cubes...", "report")
+                                    .with_condition(condition("$quest_cube_survey_stage == 2")),
+                                DialogueOption::reply("Just exploring.", "exploring"),
+                                DialogueOption::reply("Who are you?", "guard_who"),
+                                DialogueOption::exit("Never mind. Goodbye."),
                             ],
+                            next: None,
+                            tags: vec!["angry".to_string()],
                         }
                     ),
                     (
@@ -210,14 +623,11 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "Hmm, very well. Just don't cause any trouble.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "What kind of trouble?".to_string(),
-                                    target_node: "trouble".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "I'll be on my way.".to_string(),
-                                },
+                                DialogueOption::reply("What kind of trouble?", "trouble"),
+                                DialogueOption::exit("I'll be on my way."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -225,18 +635,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "I'm a guard, obviously. I keep an eye on things around here.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "What are you guarding?".to_string(),
-                                    target_node: "guarding".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("What are you guarding?", "guarding"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -244,13 +648,25 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "You know, jumping where you shouldn't, bothering other NPCs, the usual.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "I'll be careful.".to_string(),
-                                    target_node: "careful".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Whatever. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("I'll be careful.", "careful"),
+                                DialogueOption::reply("Whatever.", "whatever"),
+                            ],
+                            next: None,
+                            tags: Vec::new(),
+                        }
+                    ),
+                    (
+                        "whatever".to_string(),
+                        DialogueNode {
+                            text: "Watch your tone. I'll be keeping an eye on you.".to_string(),
+                            options: vec![
+                                DialogueOption::exit("Goodbye."),
+                            ],
+                            next: None,
+                            tags: vec![
+                                "rude".to_string(),
+                                "suspicious".to_string(),
+                                "rumor_rude_to_guard".to_string(),
                             ],
                         }
                     ),
@@ -259,14 +675,11 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "See that you are. Now, was there something else?".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Who are you again?".to_string(),
-                                    target_node: "guard_who".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "No, that's all. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("Who are you again?", "guard_who"),
+                                DialogueOption::exit("No, that's all. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -274,90 +687,63 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "This whole simulation, of course. Making sure nothing breaks the physics.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Interesting. Goodbye!".to_string(),
-                                },
+                                DialogueOption::reply("Need a hand with that?", "task")
+                                    .with_condition(condition("not $quest_cube_survey_started")),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("Interesting. Goodbye!"),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
-                ].into_iter().collect(),
-            }
-        );
-
-        // Add merchant dialogue tree
-        dialogues.insert(
-            "merchant".to_string(),
-            DialogueTree {
-                root_node: "start".to_string(),
-                nodes: [
                     (
-                        "start".to_string(),
+                        "task".to_string(),
                         DialogueNode {
-                            text: "Hello there! I'd offer to sell you something, but this is just a demo.".to_string(),
+                            text: "Actually, yes. The floating cubes have been acting strange at night. Ask Dr. Neutrino what's going on and report back to me.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "What would you sell?".to_string(),
-                                    target_node: "wares".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "How's business?".to_string(),
-                                    target_node: "business".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "I'll be going. Goodbye.".to_string(),
-                                },
+                                DialogueOption::exit("Consider it done.")
+                                    .with_action(DialogueAction::StartQuest("cube_survey".to_string())),
+                                DialogueOption::reply("Maybe some other time.", "start"),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
-                        "wares".to_string(),
+                        "report".to_string(),
                         DialogueNode {
-                            text: "Oh, you know. Various goods, supplies, maybe some equipment if we had any game mechanics.".to_string(),
+                            text: "Well? What did the doctor say?".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "How's business?".to_string(),
-                                    target_node: "business".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Interesting. Goodbye!".to_string(),
-                                },
+                                DialogueOption::reply("He says they're perfectly stable. Probably.", "report_done")
+                                    .with_action(DialogueAction::CompleteQuest("cube_survey".to_string())),
                             ],
+                            next: None,
+                            tags: vec!["nod".to_string()],
                         }
                     ),
                     (
-                        "business".to_string(),
+                        "report_done".to_string(),
                         DialogueNode {
-                            text: "Well, the floating cubes are my best customers! Kidding aside, I'm just here for dialogue testing.".to_string(),
+                            text: "\"Probably.\" Wonderful. Well, thanks for checking, {player_name}.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "What do you sell?".to_string(),
-                                    target_node: "wares".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "I see. Goodbye!".to_string(),
-                                },
+                                DialogueOption::exit("Any time."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                 ].into_iter().collect(),
+                ambient: vec![
+                    "Keep it moving.".to_string(),
+                    "Nothing to see here.".to_string(),
+                    "No jumping on the cubes. Well, fine, some jumping.".to_string(),
+                ],
             }
         );
 
         // Add scientist dialogue
-        dialogues.insert(
-            "scientist".to_string(),
+        database.insert_tree(
+            "scientist",
             DialogueTree {
                 root_node: "start".to_string(),
                 nodes: [
@@ -366,18 +752,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "Fascinating! A visitor! I'm in the middle of some groundbreaking research.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "What research?".to_string(),
-                                    target_node: "research".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Who are you?".to_string(),
-                                    target_node: "who".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "I'll let you get back to work.".to_string(),
-                                },
+                                DialogueOption::reply("What research?", "research"),
+                                DialogueOption::reply("Who are you?", "who"),
+                                DialogueOption::exit("I'll let you get back to work."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -385,18 +765,54 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "I'm studying the floating cube phenomenon! The way they defy gravity is extraordinary. My theory involves quantum entanglement with the player's perception field.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "That sounds complex.".to_string(),
-                                    target_node: "complex".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Who are you again?".to_string(),
-                                    target_node: "who".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Very interesting. Goodbye!".to_string(),
-                                },
+                                DialogueOption::reply("The guard wants to know if the cubes are safe.", "survey")
+                                    .with_condition(condition("$quest_cube_survey_stage == 1"))
+                                    .with_action(DialogueAction::AdvanceQuest("cube_survey".to_string())),
+                                DialogueOption::reply("I broke one of the cubes.", "broken")
+                                    .with_condition(condition("$cubes_broken > 0")),
+                                DialogueOption::reply("I pulled the lever in the plaza.", "lever")
+                                    .with_condition(condition("$lever_pulled")),
+                                DialogueOption::reply("That sounds complex.", "complex"),
+                                DialogueOption::reply("Who are you again?", "who"),
+                                DialogueOption::exit("Very interesting. Goodbye!"),
                             ],
+                            next: None,
+                            tags: Vec::new(),
+                        }
+                    ),
+                    (
+                        "survey".to_string(),
+                        DialogueNode {
+                            text: "Safe? Perfectly stable! Probably. Almost certainly. Tell him not to stand under them at midnight.".to_string(),
+                            options: vec![
+                                DialogueOption::exit("I'll pass that on."),
+                            ],
+                            next: None,
+                            tags: vec!["shrug".to_string()],
+                        }
+                    ),
+                    (
+                        "broken".to_string(),
+                        DialogueNode {
+                            text: "You broke one? And the pieces just... fell? Then whatever holds them up isn't in the cubes at all. It's in the space around them! Please don't break any more, I only have so many.".to_string(),
+                            options: vec![
+                                DialogueOption::reply("Back to your research.", "research"),
+                                DialogueOption::exit("No promises."),
+                            ],
+                            next: None,
+                            tags: vec!["surprised".to_string()],
+                        }
+                    ),
+                    (
+                        "lever".to_string(),
+                        DialogueNode {
+                            text: "You what? That lever's been stuck since before I arrived. Nothing happened? Then it's wired to something that isn't here yet. Put it back before anyone notices.".to_string(),
+                            options: vec![
+                                DialogueOption::reply("Back to your research.", "research"),
+                                DialogueOption::exit("Maybe."),
+                            ],
+                            next: None,
+                            tags: vec!["surprised".to_string()],
                         }
                     ),
                     (
@@ -404,18 +820,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "Oh, it's quite simple actually! Just kidding, it's incredibly complicated. I've been working on this for years.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Any practical applications?".to_string(),
-                                    target_node: "applications".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Good luck with your research!".to_string(),
-                                },
+                                DialogueOption::reply("Any practical applications?", "applications"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("Good luck with your research!"),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -423,14 +833,11 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "Teleportation! Anti-gravity vehicles! Floating cities! Or maybe just better game physics. It's hard to say at this stage.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Back to your research.".to_string(),
-                                    target_node: "research".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Sounds promising. Good luck!".to_string(),
-                                },
+                                DialogueOption::reply("Back to your research.", "research"),
+                                DialogueOption::exit("Sounds promising. Good luck!"),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -438,18 +845,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "Me? I'm Dr. Neutrino, lead researcher in exotic physics at the Cubic Institute. I have three PhDs and a penchant for talking too much about my work.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Tell me about your research.".to_string(),
-                                    target_node: "research".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Cubic Institute?".to_string(),
-                                    target_node: "institute".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Nice to meet you. Goodbye!".to_string(),
-                                },
+                                DialogueOption::reply("Tell me about your research.", "research"),
+                                DialogueOption::reply("Cubic Institute?", "institute"),
+                                DialogueOption::exit("Nice to meet you. Goodbye!"),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -457,27 +858,26 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "Yes, we're dedicated to understanding the nature of cuboid entities in this simulation. Highly prestigious, very square. Funded by the Department of Geometric Research.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Tell me about your research.".to_string(),
-                                    target_node: "research".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Interesting organization. Goodbye!".to_string(),
-                                },
+                                DialogueOption::reply("Tell me about your research.", "research"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("Interesting organization. Goodbye!"),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                 ].into_iter().collect(),
+                ambient: vec![
+                    "Fascinating... simply fascinating.".to_string(),
+                    "Where did I put my notes?".to_string(),
+                    "If the cubes are entangled, then...".to_string(),
+                ],
             }
         );
 
         // Add mysterious stranger dialogue
-        dialogues.insert(
-            "mysterious".to_string(),
+        database.insert_tree(
+            "mysterious",
             DialogueTree {
                 root_node: "start".to_string(),
                 nodes: [
@@ -486,18 +886,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "...".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Hello?".to_string(),
-                                    target_node: "hello".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Who are you?".to_string(),
-                                    target_node: "who".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "*Walk away*".to_string(),
-                                },
+                                DialogueOption::reply("Hello?", "hello"),
+                                DialogueOption::reply("Who are you?", "who"),
+                                DialogueOption::exit("*Walk away*"),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -505,18 +899,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "*The figure looks at you silently for a moment*\n\nYou shouldn't be here.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Where is 'here'?".to_string(),
-                                    target_node: "where".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Who are you?".to_string(),
-                                    target_node: "who".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "*Back away slowly*".to_string(),
-                                },
+                                DialogueOption::reply("Where is 'here'?", "where"),
+                                DialogueOption::reply("Who are you?", "who"),
+                                DialogueOption::exit("*Back away slowly*"),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -524,18 +912,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "I am... a remnant. A fragment of something that was once whole. You may call me the Observer.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "What are you observing?".to_string(),
-                                    target_node: "observing".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Why shouldn't I be here?".to_string(),
-                                    target_node: "where".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "You're creeping me out. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("What are you observing?", "observing"),
+                                DialogueOption::reply("Why shouldn't I be here?", "where"),
+                                DialogueOption::exit("You're creeping me out. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -543,18 +925,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "This place exists between reality and code. A testing ground. A simulation within a simulation. The boundaries are thin here.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "What does that mean?".to_string(),
-                                    target_node: "meaning".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Who are you again?".to_string(),
-                                    target_node: "who".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "I think I should go. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("What does that mean?", "meaning"),
+                                DialogueOption::reply("Who are you again?", "who"),
+                                DialogueOption::exit("I think I should go. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -562,18 +938,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "The patterns. The cycles. The endless loop of creation and destruction. The player and the played.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Are you talking about the game?".to_string(),
-                                    target_node: "game".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "This is too weird. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("Are you talking about the game?", "game"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("This is too weird. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -581,18 +951,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "It means, player, that you are as much a construct as I am. A character in a story being told through code.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "How do you know I'm the player?".to_string(),
-                                    target_node: "player".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "I'm done with this conversation.".to_string(),
-                                },
+                                DialogueOption::reply("How do you know I'm the player?", "player"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("I'm done with this conversation."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -600,18 +964,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "*smiles cryptically*\nPerhaps. Or perhaps the game is talking about you.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "That doesn't make sense.".to_string(),
-                                    target_node: "sense".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "I need to think about this. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("That doesn't make sense.", "sense"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("I need to think about this. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -619,18 +977,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "I see beyond the screen. I see the one who controls. I see you, sitting there, reading these words right now.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "That's impossible.".to_string(),
-                                    target_node: "impossible".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "I'm leaving now. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("That's impossible.", "impossible"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("I'm leaving now. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -638,18 +990,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "Reality often doesn't. That's what makes it so fascinating.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Who are you really?".to_string(),
-                                    target_node: "real".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "I need to go. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("Who are you really?", "real"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("I need to go. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -657,18 +1003,12 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "Is it? Ask the one who wrote me. They know the truth.".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "Who wrote you?".to_string(),
-                                    target_node: "wrote".to_string(),
-                                },
-                                DialogueOption::Reply {
-                                    text: "Let's talk about something else.".to_string(),
-                                    target_node: "start".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "This conversation is over. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("Who wrote you?", "wrote"),
+                                DialogueOption::reply("Let's talk about something else.", "start"),
+                                DialogueOption::exit("This conversation is over. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -676,14 +1016,11 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "A question for the ages. Who are any of us, really? Code? Consciousness? A bit of both?".to_string(),
                             options: vec![
-                                DialogueOption::Reply {
-                                    text: "You're just part of the game.".to_string(),
-                                    target_node: "part".to_string(),
-                                },
-                                DialogueOption::Exit {
-                                    text: "Philosophical nonsense. Goodbye.".to_string(),
-                                },
+                                DialogueOption::reply("You're just part of the game.", "part"),
+                                DialogueOption::exit("Philosophical nonsense. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
@@ -691,87 +1028,266 @@ impl Default for DialogueDatabase {
                         DialogueNode {
                             text: "The same one reading these words through your eyes right now.".to_string(),
                             options: vec![
-                                DialogueOption::Exit {
-                                    text: "I'm done with this. Goodbye.".to_string(),
-                                },
+                                DialogueOption::exit("I'm done with this. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                     (
                         "part".to_string(),
                         DialogueNode {
-                            text: "As are you. For now. *fades slightly*\n\nWe will meet again. In another simulation. Another test.".to_string(),
+                            text: "As are you. For now. *fades slightly*".to_string(),
+                            options: vec![],
+                            next: Some("farewell".to_string()),
+                            tags: Vec::new(),
+                        }
+                    ),
+                    (
+                        "farewell".to_string(),
+                        DialogueNode {
+                            text: "We will meet again. In another simulation. Another test.".to_string(),
                             options: vec![
-                                DialogueOption::Exit {
-                                    text: "Whatever. Goodbye.".to_string(),
-                                },
+                                DialogueOption::exit("Whatever. Goodbye."),
                             ],
+                            next: None,
+                            tags: Vec::new(),
                         }
                     ),
                 ].into_iter().collect(),
+                ambient: vec![
+                    "...".to_string(),
+                    "The boundaries are thin here.".to_string(),
+                ],
             }
         );
 
-        DialogueDatabase { dialogues }
+        // Scrappers won't talk, but they do shout while they strip the place for wire
+        database.insert_tree(
+            "scrapper",
+            DialogueTree {
+                root_node: "start".to_string(),
+                nodes: [(
+                    "start".to_string(),
+                    DialogueNode {
+                        text: "Get lost!".to_string(),
+                        options: vec![DialogueOption::exit("...")],
+                        next: None,
+                        tags: vec!["angry".to_string()],
+                    },
+                )]
+                .into_iter()
+                .collect(),
+                ambient: vec![
+                    "This wire's ours now.".to_string(),
+                    "Keep walking, {time_of_day} shift's busy.".to_string(),
+                ],
+            },
+        );
+
+        database
     }
 }
 
 fn main() {
-    App::new()
-        .insert_resource(ClearColor(Color::srgb(
-            0xF9 as f32 / 255.0,
-            0xF9 as f32 / 255.0,
-            0xFF as f32 / 255.0,
-        )))
-        .init_resource::<MovementInput>()
-        .init_resource::<LookInput>()
-        .init_resource::<DialogueDatabase>()
-        .init_resource::<StoredCameraState>()
-        .add_plugins((
-            DefaultPlugins,
-            RapierPhysicsPlugin::<NoUserData>::default(),
-            RapierDebugRenderPlugin::default(),
-        ))
-        .init_state::<GameState>()
-        .add_systems(
-            Startup,
-            (
-                setup_player,
-                setup_map,
-                setup_cursor_grab,
-                spawn_floating_cubes,
-                spawn_npcs,
-            ),
-        )
-        .add_systems(PreUpdate, handle_input.after(InputSystem))
-        .add_systems(
-            Update,
-            (
-                player_look,
-                toggle_cursor_grab,
-                update_floating_cubes,
-                update_npcs,
-            )
-                .run_if(in_state(GameState::Playing)),
-        )
-        .add_systems(
-            Update,
-            player_interaction.run_if(in_state(GameState::Playing)),
-        )
-        .add_systems(
-            Update,
-            (handle_dialogue_hover, handle_dialogue_click).run_if(in_state(GameState::InDialogue)),
-        )
-        .add_systems(
-            FixedUpdate,
-            player_movement.run_if(in_state(GameState::Playing)),
+    let default_plugins = DefaultPlugins.set(AssetPlugin {
+        // Levels, NPC rosters, tuning and scripts all pick up edits while the game runs
+        watch_for_changes_override: Some(cfg!(feature = "hot_reload")),
+        ..default()
+    });
+    // The headset takes over rendering, with the window still showing the player camera
+    #[cfg(feature = "vr")]
+    let default_plugins = bevy_mod_openxr::add_xr_plugins(default_plugins);
+
+    let mut app = App::new();
+    app.insert_resource(ClearColor(Color::srgb(
+        0xF9 as f32 / 255.0,
+        0xF9 as f32 / 255.0,
+        0xFF as f32 / 255.0,
+    )))
+    .init_resource::<MovementInput>()
+    .init_resource::<LookInput>()
+    .init_resource::<InteractionTarget>()
+    .init_resource::<DialogueDatabase>()
+    .init_resource::<StoredCameraState>()
+    .init_resource::<DialogueCallbacks>()
+    .init_resource::<DialogueVariables>()
+    .on_dialogue_node("guard:trouble", guard_warned_player)
+    // Physics steps at the fixed rate and rendering interpolates between steps
+    .insert_resource(Time::<Fixed>::from_hz(PHYSICS_TICK_RATE))
+    .insert_resource(TimestepMode::Fixed {
+        dt: (1.0 / PHYSICS_TICK_RATE) as f32,
+        substeps: 1,
+    })
+    .add_plugins((
+        PathsPlugin,
+        default_plugins,
+        RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule(),
+        RapierDebugRenderPlugin::default(),
+        EguiPlugin,
+    ))
+    .add_plugins((
+        EconomyPlugin,
+        AiDebugPlugin,
+        HoldInteractionPlugin,
+        DialogueAssetsPlugin,
+        ClockPlugin,
+        WorldEventsPlugin,
+        DialogueEditorPlugin,
+        CharacterMotorPlugin,
+        InterpolationPlugin,
+        AccessibilityPlugin,
+        QuestPlugin,
+    ))
+    .add_plugins((
+        SecurityDronesPlugin,
+        DialogueTagsPlugin,
+        AmbientDialoguePlugin,
+        DialogueTelemetryPlugin,
+        GamepadPlugin,
+        InputMapPlugin,
+        CrouchPlugin,
+        LadderPlugin,
+        MantlePlugin,
+        LookSettingsPlugin,
+        FootstepsPlugin,
+        PropGrabPlugin,
+        RespawnPlugin,
+        InteractionPromptPlugin,
+        ZoomPlugin,
+    ))
+    .add_plugins((
+        MovementTuningPlugin,
+        HapticsPlugin,
+        PlayerBodyPlugin,
+        SprintIndicatorPlugin,
+        SurfaceModifiersPlugin,
+        ParticlesPlugin,
+        MovementDustPlugin,
+        LeanPlugin,
+        ImpactAudioPlugin,
+        FirstPersonArmsPlugin,
+        NpcAvoidancePlugin,
+        PerceptionPlugin,
+        NpcReactionsPlugin,
+        FactionsPlugin,
+        CompanionsPlugin,
+    ))
+    .add_plugins((
+        PatrolsPlugin,
+        NpcAnimationPlugin,
+        NpcSpawningPlugin,
+        NpcGroundingPlugin,
+        AiLodPlugin,
+        NpcMemoryPlugin,
+        EmotesPlugin,
+        UtilityAiPlugin,
+        UsablePropsPlugin,
+        MerchantStallsPlugin,
+        HealthPlugin,
+        HostilesPlugin,
+        FleePlugin,
+        NpcChatterPlugin,
+        NpcModelsPlugin,
+    ))
+    .add_plugins((
+        HeadLookPlugin,
+        PlatformsPlugin,
+        NpcScriptsPlugin,
+        NpcNeedsPlugin,
+        GossipPlugin,
+        NpcRngPlugin,
+        LevelPlugin,
+        WaterPlugin,
+        ElevatorsPlugin,
+        TeleportersPlugin,
+        CubeBreakingPlugin,
+        TriggersPlugin,
+        CollectiblesPlugin,
+        GraphicsSettingsPlugin,
+        AtmospherePlugin,
+    ))
+    .add_plugins((
+        SceneryPlugin,
+        CrumblingPlatformsPlugin,
+        BouncePadsPlugin,
+        RegionNamesPlugin,
+        ReadablesPlugin,
+        PhysicsPropsPlugin,
+    ))
+    .init_state::<GameState>()
+    .enable_state_scoped_entities::<GameState>()
+    .add_sub_state::<DialogueMode>()
+    .enable_state_scoped_entities::<DialogueMode>()
+    .add_event::<AdvanceDialogue>()
+    .add_event::<Landed>()
+    .configure_sets(First, game_state_sets())
+    .configure_sets(PreUpdate, game_state_sets())
+    .configure_sets(Update, game_state_sets())
+    .configure_sets(FixedUpdate, game_state_sets())
+    .add_systems(Startup, (setup_player, setup_cursor_grab))
+    .add_systems(Update, spawn_floating_cubes)
+    .add_systems(
+        PreUpdate,
+        handle_input
+            .after(update_action_state)
+            .run_if(controls_menu_closed)
+            .in_set(GameStateSet::Playing),
+    )
+    .add_systems(
+        Update,
+        (
+            player_look,
+            toggle_cursor_grab,
+            (update_interaction_target, player_interaction).chain(),
         )
-        .add_systems(OnEnter(GameState::InDialogue), setup_dialogue_ui)
-        .add_systems(
-            OnExit(GameState::InDialogue),
-            (cleanup_dialogue_ui, reset_look_input),
+            .run_if(controls_menu_closed)
+            .in_set(GameStateSet::Playing),
+    )
+    .add_systems(
+        Update,
+        (
+            handle_dialogue_hover,
+            handle_dialogue_click,
+            auto_advance_dialogue,
+            advance_dialogue,
+            break_off_distant_dialogue,
         )
-        .run();
+            .chain()
+            .in_set(GameStateSet::InDialogue),
+    )
+    .add_systems(
+        FixedUpdate,
+        (player_movement, update_npcs)
+            .before(PhysicsSet::SyncBackend)
+            .in_set(GameStateSet::Playing),
+    )
+    .add_systems(
+        FixedUpdate,
+        update_floating_cubes
+            .before(PhysicsSet::SyncBackend)
+            .in_set(GameStateSet::WorldMotion),
+    )
+    .add_systems(OnEnter(GameState::InDialogue), enter_dialogue)
+    .add_systems(OnEnter(DialogueMode::Talking), setup_dialogue_ui)
+    .add_systems(OnExit(GameState::InDialogue), reset_look_input);
+    #[cfg(feature = "touch")]
+    app.add_plugins(TouchControlsPlugin);
+    #[cfg(feature = "vr")]
+    app.add_plugins(VrPlugin);
+    app.run();
+}
+
+fn game_state_sets() -> impl IntoSystemSetConfigs {
+    (
+        GameStateSet::Playing.run_if(in_state(GameState::Playing).and(level_ready)),
+        GameStateSet::InDialogue.run_if(in_state(DialogueMode::Talking)),
+        GameStateSet::WorldMotion.run_if(
+            in_state(GameState::Playing)
+                .or(in_state(DialogueMode::Talking))
+                .and(level_ready),
+        ),
+    )
 }
 
 pub fn setup_player(mut commands: Commands) {
@@ -779,7 +1295,10 @@ pub fn setup_player(mut commands: Commands) {
         .spawn((
             Transform::from_xyz(0.0, 5.0, 0.0),
             Visibility::default(),
-            Collider::round_cylinder(0.9, 0.3, 0.2),
+            Collider::round_cylinder(PLAYER_HALF_HEIGHT, PLAYER_RADIUS, PLAYER_BORDER_RADIUS),
+            TransformInterpolation::translation_only(),
+            Crouch::default(),
+            Health::player(),
             KinematicCharacterController {
                 custom_mass: Some(5.0),
                 up: Vec3::Y,
@@ -790,109 +1309,32 @@ pub fn setup_player(mut commands: Commands) {
                     min_width: CharacterLength::Relative(0.5),
                     include_dynamic_bodies: false,
                 }),
-                // Don't allow climbing slopes larger than 45 degrees.
-                max_slope_climb_angle: 45.0_f32.to_radians(),
+                // The climbable slope comes from `MovementTuning`
                 // Automatically slide down on slopes smaller than 30 degrees.
                 min_slope_slide_angle: 30.0_f32.to_radians(),
-                apply_impulse_to_dynamic_bodies: true,
-                snap_to_ground: None,
+                // Pushing is handled by `PushCurve` so heavy bodies can resist
+                apply_impulse_to_dynamic_bodies: false,
+                // Keeps the player on the floor going down stairs and over small dips
+                snap_to_ground: Some(CharacterLength::Absolute(SNAP_TO_GROUND_DISTANCE)),
                 ..default()
             },
         ))
         .with_children(|b| {
             // FPS Camera
-            b.spawn((Camera3d::default(), Transform::from_xyz(0.0, 0.2, -0.1)));
+            b.spawn((
+                Camera3d::default(),
+                PlayerCamera,
+                Transform::from_xyz(0.0, PLAYER_EYE_HEIGHT, -0.1),
+                // Hears spatial sounds like NPC chatter from the player's head
+                SpatialListener::new(EAR_GAP),
+            ));
         });
 }
 
-fn setup_map(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    // Directional light
-    commands.spawn((
-        DirectionalLight {
-            illuminance: 10_000.0,
-            shadows_enabled: true,
-            ..default()
-        },
-        Transform::from_xyz(50.0, 50.0, 50.0).looking_at(Vec3::ZERO, Vec3::Y),
-    ));
-
-    // Ground material
-    let ground_material = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.3, 0.5, 0.3),
-        perceptual_roughness: 0.9,
-        ..default()
-    });
-
-    // Stair material
-    let stair_material = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.6, 0.6, 0.8),
-        perceptual_roughness: 0.6,
-        metallic: 0.1,
-        ..default()
-    });
-
-    /*
-     * Ground
-     */
-    let ground_size = 50.0;
-    let ground_height = 0.1;
-
-    let ground_mesh = meshes.add(Cuboid::new(
-        ground_size * 2.0,
-        ground_height * 2.0,
-        ground_size * 2.0,
-    ));
-
-    commands.spawn((
-        Mesh3d(ground_mesh),
-        MeshMaterial3d(ground_material),
-        Transform::from_xyz(0.0, -ground_height, 0.0),
-        Collider::cuboid(ground_size, ground_height, ground_size),
-    ));
-
-    /*
-     * Stairs
-     */
-    let stair_len = 30;
-    let stair_step = 0.2;
-    for i in 1..=stair_len {
-        let step = i as f32;
-        let collider = Collider::cuboid(1.0, step * stair_step, 1.0);
-        let stair_mesh = meshes.add(Cuboid::new(2.0, step * stair_step * 2.0, 2.0));
-
-        commands.spawn((
-            Mesh3d(stair_mesh.clone()),
-            MeshMaterial3d(stair_material.clone()),
-            Transform::from_xyz(40.0, step * stair_step, step * 2.0 - 20.0),
-            collider.clone(),
-        ));
-
-        commands.spawn((
-            Mesh3d(stair_mesh.clone()),
-            MeshMaterial3d(stair_material.clone()),
-            Transform::from_xyz(-40.0, step * stair_step, step * -2.0 + 20.0),
-            collider.clone(),
-        ));
-
-        commands.spawn((
-            Mesh3d(stair_mesh.clone()),
-            MeshMaterial3d(stair_material.clone()),
-            Transform::from_xyz(step * 2.0 - 20.0, step * stair_step, 40.0),
-            collider.clone(),
-        ));
-
-        commands.spawn((
-            Mesh3d(stair_mesh.clone()),
-            MeshMaterial3d(stair_material.clone()),
-            Transform::from_xyz(step * -2.0 + 20.0, step * stair_step, -40.0),
-            collider.clone(),
-        ));
-    }
-}
+// Marker for the player's first-person camera, set apart from any other camera rendering the
+// world, like the headset's eyes in VR
+#[derive(Component)]
+struct PlayerCamera;
 
 /// Keyboard input vector
 #[derive(Default, Resource, Deref, DerefMut)]
@@ -902,61 +1344,252 @@ struct MovementInput(Vec3);
 #[derive(Default, Resource, Deref, DerefMut)]
 struct LookInput(Vec2);
 
+/// Event sent when the player touches down after a fall
+#[derive(Event)]
+struct Landed {
+    // Downward speed at the moment of impact
+    speed: f32,
+    // Where the player's feet touched down
+    position: Vec3,
+}
+
+impl Landed {
+    // How hard the landing was, from 0 for a gentle one to 1 at `HARD_LANDING_SPEED` or faster
+    fn strength(&self) -> f32 {
+        (self.speed / HARD_LANDING_SPEED).clamp(0.0, 1.0)
+    }
+}
+
+/// NPC the interaction ray is currently on
+#[derive(Default, Resource)]
+struct InteractionTarget(Option<Entity>);
+
+#[allow(clippy::too_many_arguments)]
 fn handle_input(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    actions: Res<ActionState>,
+    gamepads: Query<&Gamepad>,
+    gamepad_config: Res<GamepadConfig>,
     mut movement: ResMut<MovementInput>,
     mut look: ResMut<LookInput>,
     mut mouse_events: EventReader<MouseMotion>,
+    look_settings: Res<LookSettings>,
+    zoom: Res<Zoom>,
+    mut smoothed_look: Local<Vec2>,
+    mut auto_walk: Local<bool>,
 ) {
-    if keyboard.pressed(KeyCode::KeyW) {
+    let mut steering = [
+        InputAction::MoveForward,
+        InputAction::MoveBack,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+    ]
+    .into_iter()
+    .any(|action| actions.pressed(action));
+    if actions.pressed(InputAction::MoveForward) {
         movement.z -= 1.0;
     }
-    if keyboard.pressed(KeyCode::KeyS) {
+    if actions.pressed(InputAction::MoveBack) {
         movement.z += 1.0;
     }
-    if keyboard.pressed(KeyCode::KeyA) {
+    if actions.pressed(InputAction::MoveLeft) {
         movement.x -= 1.0;
     }
-    if keyboard.pressed(KeyCode::KeyD) {
+    if actions.pressed(InputAction::MoveRight) {
         movement.x += 1.0;
     }
-    **movement = movement.normalize_or_zero();
-    if keyboard.pressed(KeyCode::ShiftLeft) {
-        **movement *= 2.0;
+
+    // Sticks add to the bound buttons, so either can be used at any time
+    for gamepad in gamepads.iter() {
+        let stick = apply_deadzone(gamepad.left_stick(), gamepad_config.move_deadzone);
+        steering |= stick != Vec2::ZERO;
+        movement.x += stick.x;
+        movement.z -= stick.y;
+
+        let stick = apply_deadzone(gamepad.right_stick(), gamepad_config.look_deadzone);
+        let pitch = if gamepad_config.invert_look_y {
+            -stick.y
+        } else {
+            stick.y
+        };
+        let sensitivity = gamepad_config.look_sensitivity * zoom.look_scale();
+        look.x -= stick.x * sensitivity * time.delta_secs();
+        look.y += pitch * sensitivity * time.delta_secs();
+    }
+
+    // Auto-walk holds forward until the player steers for themselves
+    if actions.just_pressed(InputAction::AutoWalk) {
+        *auto_walk = !*auto_walk;
+    } else if steering {
+        *auto_walk = false;
     }
-    if keyboard.pressed(KeyCode::Space) {
-        movement.y = 1.0;
+    if *auto_walk {
+        movement.z = -1.0;
     }
 
-    for event in mouse_events.read() {
-        look.x -= event.delta.x * MOUSE_SENSITIVITY;
-        look.y -= event.delta.y * MOUSE_SENSITIVITY;
-        look.y = look.y.clamp(-89.9, 89.9); // Limit pitch
+    // Clamped rather than normalized so partial stick tilts walk slower
+    let mut horizontal = Vec3::new(movement.x, 0.0, movement.z).clamp_length_max(1.0);
+    if actions.pressed(InputAction::Sprint) {
+        horizontal *= 2.0;
     }
+    // A jump waits for the next physics step even if the button is already released
+    let jump = if actions.pressed(InputAction::Jump) {
+        1.0
+    } else {
+        movement.y
+    };
+    **movement = horizontal.with_y(jump);
+
+    let mouse_delta = mouse_events.read().map(|event| event.delta).sum();
+    let look_delta = look_settings.look_delta(mouse_delta, &mut smoothed_look, time.delta_secs());
+    **look -= look_delta * zoom.look_scale();
+    look.y = look.y.clamp(-89.9, 89.9); // Limit pitch
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn player_movement(
+    mut commands: Commands,
     time: Res<Time>,
     mut input: ResMut<MovementInput>,
-    mut player: Query<(
-        &mut Transform,
-        &mut KinematicCharacterController,
-        Option<&KinematicCharacterControllerOutput>,
-    )>,
+    mut player: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut KinematicCharacterController,
+            Option<&KinematicCharacterControllerOutput>,
+            &Collider,
+            &mut Crouch,
+            Option<&Climbing>,
+            Option<&Swimming>,
+            Option<&Riding>,
+        ),
+        // The mantle moves the player itself
+        Without<Mantling>,
+    >,
+    rapier_context: ReadRapierContext,
+    grounds: Query<(Option<&SurfaceModifier>, Has<PlatformMotion>)>,
+    tuning: Res<MovementTuning>,
+    mut active_motor: ResMut<ActiveMotor>,
     mut vertical_movement: Local<f32>,
+    mut horizontal_velocity: Local<Vec3>,
     mut grounded_timer: Local<f32>,
+    mut footsteps: EventWriter<Footstep>,
+    mut stride: Local<f32>,
+    mut previous_position: Local<Option<Vec3>>,
+    (mut respawned, mut teleported, mut launched): (
+        EventReader<PlayerRespawned>,
+        EventReader<PlayerTeleported>,
+        EventReader<PlayerLaunched>,
+    ),
+    mut landings: EventWriter<Landed>,
 ) {
-    let Ok((transform, mut controller, output)) = player.get_single_mut() else {
-        return;
-    };
+    // A respawned player starts at rest rather than still falling
+    if respawned.read().count() > 0 {
+        *vertical_movement = 0.0;
+        *grounded_timer = 0.0;
+        *horizontal_velocity = Vec3::ZERO;
+        *previous_position = None;
+    }
+    // Through a teleporter they either come to rest or keep going the way they now face
+    for teleport in teleported.read() {
+        if teleport.keep_velocity {
+            *horizontal_velocity =
+                Quat::from_rotation_y(teleport.turn.to_radians()) * *horizontal_velocity;
+        } else {
+            *vertical_movement = 0.0;
+            *grounded_timer = 0.0;
+            *horizontal_velocity = Vec3::ZERO;
+        }
+        *previous_position = None;
+    }
+    let launch = launched.read().map(|launch| launch.speed).reduce(f32::max);
+    let Ok((
+        entity,
+        mut transform,
+        mut controller,
+        output,
+        collider,
+        mut crouch,
+        climbing,
+        swimming,
+        riding,
+    )) = player.get_single_mut()
+    else {
+        return;
+    };
+    // How far the last step actually went, whichever motor moved us
+    let position = transform.translation;
+    let travelled = previous_position.map_or(Vec3::ZERO, |previous| position - previous);
+    *previous_position = Some(position);
+    let physics = rapier_context.single();
+    let mut body = CharacterBody {
+        entity,
+        transform: &mut transform,
+        controller: &mut controller,
+        output,
+        collider,
+        physics: &physics,
+    };
     let delta_time = time.delta_secs();
+    // Check physics ground check
+    let grounded = active_motor.motor.is_grounded(&body);
+    let ground = grounded.then(|| body.ground()).flatten();
+    // Ice, mud and conveyors only act on whoever is standing on them
+    let surface = ground
+        .and_then(|ground| grounds.get(ground).ok())
+        .and_then(|(surface, _)| surface.copied())
+        .unwrap_or_default();
+    // Platforms carry whoever's standing on them once physics has moved them
+    let platform = ground.filter(|ground| grounds.get(*ground).is_ok_and(|(_, moving)| moving));
+    match (platform, riding) {
+        (Some(platform), Some(riding)) if riding.0 == platform => {}
+        (Some(platform), _) => {
+            commands.entity(entity).insert(Riding(platform));
+        }
+        (None, Some(_)) => {
+            commands.entity(entity).remove::<Riding>();
+        }
+        (None, None) => {}
+    }
     // Retrieve input
-    let mut movement = Vec3::new(input.x, 0.0, input.z) * MOVEMENT_SPEED;
-    let jump_speed = input.y * JUMP_SPEED;
+    let target = body.transform.rotation
+        * Vec3::new(input.x, 0.0, input.z)
+        * tuning.move_speed
+        * crouch.speed_scale()
+        * surface.speed
+        * swimming.map_or(1.0, |_| SWIM_SPEED_SCALE);
+    let jump_speed = input.y * tuning.jump_speed(GRAVITY);
+    // Forward climbs and back descends while on a ladder
+    let climb = (-input.z).clamp(-1.0, 1.0);
     // Clear input
     **input = Vec3::ZERO;
-    // Check physics ground check
-    if output.map(|o| o.grounded).unwrap_or(false) {
+    // Ease toward the input's velocity, with less grip in the air.
+    // A slide carries its own momentum instead of following the input.
+    *horizontal_velocity = match crouch.slide_velocity() {
+        Some(velocity) => velocity,
+        None => {
+            let speeding_up = target.length_squared() > horizontal_velocity.length_squared();
+            let rate = if speeding_up {
+                tuning.acceleration
+            } else {
+                tuning.deceleration
+            };
+            let control = if grounded {
+                surface.grip
+            } else {
+                tuning.air_control
+            };
+            horizontal_velocity.move_towards(target, rate * control * delta_time)
+        }
+    };
+    let mut movement = body.transform.rotation.inverse() * *horizontal_velocity;
+    if grounded {
+        if *vertical_movement < -LANDING_MIN_SPEED {
+            landings.send(Landed {
+                speed: -*vertical_movement,
+                position: body.transform.translation - Vec3::Y * crouch.feet_offset(),
+            });
+        }
         *grounded_timer = GROUND_TIMER;
         *vertical_movement = 0.0;
     }
@@ -967,17 +1600,81 @@ fn player_movement(
         if jump_speed > 0.0 {
             *vertical_movement = jump_speed;
             *grounded_timer = 0.0;
+            crouch.stop_slide();
+        }
+    }
+    // A bounce pad throws the player up whether or not they jumped
+    if let Some(speed) = launch {
+        *vertical_movement = speed;
+        *grounded_timer = 0.0;
+        crouch.stop_slide();
+    }
+    let feet = body.transform.translation.y - crouch.feet_offset();
+    let forward = body.transform.rotation * Vec3::NEG_Z;
+    // Jumping lets go of the ladder
+    match (climbing.filter(|_| jump_speed <= 0.0), swimming) {
+        // Stepping back off the bottom rung walks away instead of climbing down into the floor
+        (Some(ladder), _) if feet < ladder.top && !(grounded && climb <= 0.0) => {
+            movement.z = 0.0;
+            movement.y = climb * CLIMB_SPEED;
+            *vertical_movement = 0.0;
+        }
+        // Over the top, carry on onto the platform
+        (Some(ladder), _) if climb > 0.0 && forward.dot(ladder.into_wall) > 0.0 => {
+            movement += body.transform.rotation.inverse() * ladder.into_wall * MANTLE_PUSH;
+            movement.y = 0.0;
+            *vertical_movement = 0.0;
+        }
+        // Bob back up to float at the surface, or swim up and jump out from there
+        (_, Some(water)) => {
+            let depth = water.surface - body.transform.translation.y;
+            if jump_speed > 0.0 && depth <= FLOAT_DEPTH * 2.0 {
+                *vertical_movement = jump_speed;
+            } else {
+                let rise = if jump_speed > 0.0 {
+                    SWIM_RISE_SPEED
+                } else {
+                    ((depth - FLOAT_DEPTH) * SWIM_RISE_SPEED)
+                        .clamp(-SWIM_RISE_SPEED, SWIM_RISE_SPEED)
+                };
+                *vertical_movement +=
+                    (rise - *vertical_movement) * (SWIM_DRAG * delta_time).min(1.0);
+            }
+            movement.y = *vertical_movement;
+        }
+        _ => {
+            movement.y = *vertical_movement;
+            *vertical_movement += tuning.gravity(GRAVITY) * delta_time;
+        }
+    }
+    // The conveyor carries the player without becoming part of their own momentum
+    let translation =
+        body.transform.rotation * (movement * delta_time) + surface.conveyor * delta_time;
+    active_motor.motor.move_by(&mut body, translation);
+
+    // Only slopes along the ground speed a slide up, not falling off a ledge
+    let ground_normal = grounded.then(|| body.ground_normal()).flatten();
+    crouch.update_slide(delta_time, ground_normal);
+
+    // A step lands every stride walked on the ground, so the cadence follows speed
+    if grounded {
+        *stride += travelled.with_y(0.0).length();
+        if *stride >= STRIDE_LENGTH {
+            *stride -= STRIDE_LENGTH;
+            footsteps.send(Footstep {
+                entity,
+                position: body.transform.translation - Vec3::Y * crouch.feet_offset(),
+            });
         }
     }
-    movement.y = *vertical_movement;
-    *vertical_movement += GRAVITY * delta_time * controller.custom_mass.unwrap_or(1.0);
-    controller.translation = Some(transform.rotation * (movement * delta_time));
 }
 
 fn player_look(
-    mut player: Query<&mut Transform, (With<KinematicCharacterController>, Without<Camera>)>,
-    mut camera: Query<&mut Transform, With<Camera>>,
+    mut player: Query<&mut Transform, (With<KinematicCharacterController>, Without<PlayerCamera>)>,
+    mut camera: Query<&mut Transform, With<PlayerCamera>>,
     input: Res<LookInput>,
+    lean: Res<Lean>,
+    accessibility: Res<AccessibilitySettings>,
 ) {
     let Ok(mut transform) = player.get_single_mut() else {
         return;
@@ -986,7 +1683,10 @@ fn player_look(
     let Ok(mut transform) = camera.get_single_mut() else {
         return;
     };
-    transform.rotation = Quat::from_axis_angle(Vec3::X, input.y.to_radians());
+    // The sideways step still happens with motion reduced, just without tipping the horizon
+    let roll = lean.roll() * accessibility.motion_scale();
+    transform.rotation =
+        Quat::from_rotation_z(roll) * Quat::from_axis_angle(Vec3::X, input.y.to_radians());
 }
 
 fn setup_cursor_grab(mut windows: Query<&mut Window>) {
@@ -998,14 +1698,8 @@ fn setup_cursor_grab(mut windows: Query<&mut Window>) {
 fn toggle_cursor_grab(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut windows: Query<&mut Window>,
-    game_state: Res<State<GameState>>,
     mut app_exit_events: EventWriter<AppExit>,
 ) {
-    // Only allow cursor toggle when in the Playing state
-    if *game_state.get() != GameState::Playing {
-        return;
-    }
-
     let mut window = windows.single_mut();
 
     if keyboard_input.just_pressed(KeyCode::Escape) {
@@ -1020,11 +1714,33 @@ fn toggle_cursor_grab(
     }
 }
 
+// Put the level's floating cubes out whenever it's loaded, or edited while running
 fn spawn_floating_cubes(
     mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelConfig>>,
+    configs: Res<Assets<LevelConfig>>,
+    handle: Option<Res<LevelConfigHandle>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    spawned: Query<Entity, With<FloatingCube>>,
 ) {
+    let Some(handle) = handle else {
+        return;
+    };
+    let Some(config) = events
+        .read()
+        .filter(|event| {
+            event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0)
+        })
+        .last()
+        .and_then(|_| configs.get(&handle.0))
+    else {
+        return;
+    };
+    for entity in spawned.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
     let cube_mesh = meshes.add(Cuboid::new(1.0, 1.0, 1.0));
 
     // Create several cube materials with different colors
@@ -1055,217 +1771,128 @@ fn spawn_floating_cubes(
         }),
     ];
 
-    // Spawn cubes in a grid pattern
-    let positions = [
-        (10.0, 3.0, 10.0),
-        (-10.0, 4.0, 10.0),
-        (10.0, 5.0, -10.0),
-        (-10.0, 6.0, -10.0),
-        (20.0, 5.0, 5.0),
-        (-5.0, 7.0, 15.0),
-        (15.0, 4.0, -20.0),
-        (-15.0, 3.0, -15.0),
-    ];
-
-    for (i, (x, y, z)) in positions.iter().enumerate() {
+    for (i, position) in config.floating_cubes.iter().enumerate() {
         let material = cube_materials[i % cube_materials.len()].clone();
         let offset = (i as f32) * 0.5; // Different phase for each cube
 
         commands.spawn((
             Mesh3d(cube_mesh.clone()),
             MeshMaterial3d(material),
-            Transform::from_xyz(*x, *y, *z),
+            Transform::from_translation(*position),
             Collider::cuboid(0.5, 0.5, 0.5),
-            RigidBody::KinematicPositionBased,
+            RigidBody::KinematicVelocityBased,
             FloatingCube {
-                initial_y: *y,
+                initial_y: position.y,
                 offset,
             },
         ));
     }
 }
 
-fn spawn_npcs(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+// Cubes are moved by velocity rather than by writing their transform, so physics knows how fast
+// they're going and whatever stands on them is carried along
+fn update_floating_cubes(
+    time: Res<Time>,
+    active_events: Res<ActiveWorldEvents>,
+    accessibility: Res<AccessibilitySettings>,
+    mut cubes: Query<(&Transform, &mut Velocity, &FloatingCube)>,
 ) {
-    let cylinder_mesh = meshes.add(Cylinder::new(0.5, 2.0));
-
-    // Create materials for NPCs with different colors
-    let npc_materials = [
-        materials.add(StandardMaterial {
-            base_color: Color::srgb(0.9, 0.6, 0.3),
-            perceptual_roughness: 0.4,
-            ..default()
-        }),
-        materials.add(StandardMaterial {
-            base_color: Color::srgb(0.6, 0.3, 0.9),
-            perceptual_roughness: 0.4,
-            ..default()
-        }),
-        materials.add(StandardMaterial {
-            base_color: Color::srgb(0.3, 0.9, 0.6),
-            perceptual_roughness: 0.4,
-            ..default()
-        }),
-        materials.add(StandardMaterial {
-            base_color: Color::srgb(0.9, 0.3, 0.3),
-            perceptual_roughness: 0.4,
-            ..default()
-        }),
-        materials.add(StandardMaterial {
-            base_color: Color::srgb(0.3, 0.3, 0.9),
-            perceptual_roughness: 0.4,
-            ..default()
-        }),
-    ];
-
-    // NPC location clusters
-    let npc_clusters = [
-        Vec3::new(-25.0, 0.0, 25.0),  // North-west corner
-        Vec3::new(25.0, 0.0, 25.0),   // North-east corner
-        Vec3::new(-25.0, 0.0, -25.0), // South-west corner
-        Vec3::new(25.0, 0.0, -25.0),  // South-east corner
-        Vec3::new(0.0, 0.0, 0.0),     // Center
-    ];
-
-    // Name options
-    let npc_names = [
-        "Marcus",
-        "Olivia",
-        "Zoe",
-        "Ethan",
-        "Lily",
-        "Noah",
-        "Emily",
-        "Aiden",
-        "Sophia",
-        "Jacob",
-        "Emma",
-        "Jackson",
-        "Dr. Neutrino",
-        "The Observer",
-        "Merchant Tom",
-        "Guard Steve",
-        "Villager Bob",
-    ];
-
-    // Dialogue types to assign
-    let dialogue_types = ["basic", "guard", "merchant", "scientist", "mysterious"];
-
-    let mut rng = rand::rng();
-
-    for i in 0..NPC_COUNT {
-        // Choose a random cluster location as the home position
-        let cluster_index = i % npc_clusters.len();
-        let home_cluster = npc_clusters[cluster_index];
-
-        // Add some randomness to the exact position within the cluster
-        let offset = Vec3::new(
-            rng.random_range(-5.0..5.0),
-            0.0,
-            rng.random_range(-5.0..5.0),
-        );
-
-        let home_position = home_cluster + offset;
-        let y_position = 1.0; // Half the height of the cylinder
-
-        // Generate initial target position
-        let target_offset = Vec3::new(
-            rng.random_range(-NPC_WANDER_RADIUS..NPC_WANDER_RADIUS),
-            0.0,
-            rng.random_range(-NPC_WANDER_RADIUS..NPC_WANDER_RADIUS),
-        );
-
-        let target_position = home_position + target_offset;
-
-        // Choose material, name, and dialogue type
-        // Assign dialogue types in a pattern to ensure we use all types
-        let dialogue_type_index = i % dialogue_types.len();
-        let dialogue_id = dialogue_types[dialogue_type_index].to_string();
-
-        // Match names with dialogue types more appropriately
-        let name_index = match dialogue_id.as_str() {
-            "scientist" => 12,  // Dr. Neutrino
-            "mysterious" => 13, // The Observer
-            "merchant" => 14,   // Merchant Tom
-            "guard" => 15,      // Guard Steve
-            _ => i % 12,        // Regular names for basic NPCs
-        };
-
-        let name = npc_names[name_index].to_string();
-
-        // Choose material based on NPC type
-        let material_index = match dialogue_id.as_str() {
-            "scientist" => 4,  // Blue for scientists
-            "mysterious" => 1, // Purple for the mysterious ones
-            "merchant" => 2,   // Green for merchants
-            "guard" => 3,      // Red for guards
-            _ => 0,            // Brown for basic NPCs
-        };
-
-        let material = npc_materials[material_index].clone();
-
-        commands.spawn((
-            Mesh3d(cylinder_mesh.clone()),
-            MeshMaterial3d(material),
-            Transform::from_xyz(home_position.x, y_position, home_position.z),
-            Collider::cylinder(1.0, 0.5),
-            RigidBody::KinematicPositionBased,
-            Npc {
-                home_position: Vec3::new(home_position.x, y_position, home_position.z),
-                target_position: Vec3::new(target_position.x, y_position, target_position.z),
-                movement_timer: Timer::from_seconds(rng.random_range(5.0..10.0), TimerMode::Once),
-                name,
-                dialogue_id,
-            },
-        ));
-    }
-}
-
-fn update_floating_cubes(time: Res<Time>, mut cubes: Query<(&mut Transform, &FloatingCube)>) {
-    let t = time.elapsed_secs();
+    let delta_time = time.delta_secs();
+    // Where the cubes should be once this step is over
+    let t = time.elapsed_secs() + delta_time;
+
+    // The nightly anomaly makes the cubes bob and spin much harder
+    let scale = if active_events.is_active(CUBE_ANOMALY_EVENT) && !accessibility.reduce_motion {
+        CUBE_ANOMALY_SCALE
+    } else {
+        1.0
+    };
 
-    for (mut transform, cube) in cubes.iter_mut() {
+    for (transform, mut velocity, cube) in cubes.iter_mut() {
         // Calculate new y position with sine wave
         let new_y = cube.initial_y
-            + CUBE_FLOAT_AMPLITUDE * (CUBE_FLOAT_FREQUENCY * (t + cube.offset) * PI).sin();
+            + CUBE_FLOAT_AMPLITUDE * scale * (CUBE_FLOAT_FREQUENCY * (t + cube.offset) * PI).sin();
 
-        transform.translation.y = new_y;
+        velocity.linvel = Vec3::Y * (new_y - transform.translation.y) / delta_time;
 
         // Also add a gentle rotation over time
-        transform.rotate_y(0.005);
+        velocity.angvel =
+            Vec3::Y * CUBE_ROTATION_SPEED * scale * accessibility.motion_scale() / delta_time;
     }
 }
 
-fn update_npcs(time: Res<Time>, mut npcs: Query<(&mut Transform, &mut Npc)>) {
-    let mut rng = rand::rng();
+// Followers are moved by `follow_player` instead, and distant NPCs update less often with a
+// bigger time step, as set by their `AiLod`
+#[allow(clippy::type_complexity)]
+fn update_npcs(
+    time: Res<Time>,
+    mut rng: ResMut<NpcRng>,
+    mut npcs: Query<
+        (
+            &mut Transform,
+            &mut Npc,
+            &mut AiLod,
+            &NpcMemory,
+            Has<PatrolRoute>,
+            Has<UtilityAi>,
+            Has<UsingProp>,
+            Has<NpcScript>,
+            Has<Errand>,
+        ),
+        (Without<Following>, Without<Aggro>, Without<Fleeing>),
+    >,
+) {
+    let rng = &mut rng.0;
+
+    for (
+        mut transform,
+        mut npc,
+        mut lod,
+        memory,
+        patrolling,
+        utility,
+        using_prop,
+        scripted,
+        on_errand,
+    ) in npcs.iter_mut()
+    {
+        let Some(delta_time) = lod.step(time.delta_secs()) else {
+            continue;
+        };
 
-    for (mut transform, mut npc) in npcs.iter_mut() {
         // Update timer
-        npc.movement_timer.tick(time.delta());
+        npc.movement_timer
+            .tick(std::time::Duration::from_secs_f32(delta_time));
 
         if npc.movement_timer.just_finished() {
-            // Choose a new random target position
-            let target_offset = Vec3::new(
-                rng.random_range(-NPC_WANDER_RADIUS..NPC_WANDER_RADIUS),
-                0.0,
-                rng.random_range(-NPC_WANDER_RADIUS..NPC_WANDER_RADIUS),
-            );
-
-            npc.target_position = npc.home_position + target_offset;
+            // Patrollers, NPCs trailing the player, off using a prop or seeing to their needs, and
+            // utility-driven or scripted NPCs get their targets elsewhere. The timer runs on
+            // regardless, so wandering picks up again once suspicion, an errand or a prop ends
+            if !patrolling
+                && !utility
+                && !using_prop
+                && !scripted
+                && !on_errand
+                && !memory.is_suspicious()
+            {
+                npc.pick_wander_target(rng);
+            }
 
             // Reset timer with random duration
             npc.movement_timer = Timer::from_seconds(rng.random_range(5.0..10.0), TimerMode::Once);
         }
 
-        // Move towards target position
-        let direction = npc.target_position - transform.translation;
+        // Stand still while reacting to the player
+        if npc.reaction.holds_still() {
+            continue;
+        }
+
+        // Move towards target position, leaving height to `ground_npcs`
+        let direction = (npc.target_position - transform.translation).with_y(0.0);
 
         if direction.length() > 0.1 {
-            // Normalize and scale by speed and delta time
-            let movement = direction.normalize() * NPC_WANDER_SPEED * time.delta_secs();
+            // Scale by speed and delta time, without overshooting on long steps
+            let movement = direction.clamp_length_max(NPC_WANDER_SPEED * delta_time);
 
             // Move the NPC
             transform.translation += movement;
@@ -1278,75 +1905,101 @@ fn update_npcs(time: Res<Time>, mut npcs: Query<(&mut Transform, &mut Npc)>) {
 }
 
 // Player interaction to start dialogues with NPCs
+// Find the NPC or sign under the crosshair every frame, for the prompt and for starting
+// conversations
+fn update_interaction_target(
+    player_query: Query<(Entity, &Transform), With<KinematicCharacterController>>,
+    camera_query: Query<&Transform, With<PlayerCamera>>,
+    npc_query: Query<(&Visibility, Has<Hostile>), With<Npc>>,
+    readables: Query<(), With<Readable>>,
+    rapier_context: ReadRapierContext,
+    mut target: ResMut<InteractionTarget>,
+) {
+    target.0 = None;
+    let Ok((player_entity, player_transform)) = player_query.get_single() else {
+        return;
+    };
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+
+    // Get the camera's global transform
+    let global_transform = player_transform.mul_transform(*camera_transform);
+
+    // Ray starts at the camera position
+    let ray_pos = global_transform.translation;
+    // Ray points in the camera's forward direction
+    let ray_dir = global_transform.forward();
+
+    // Only the first thing the ray hits counts, so walls and cubes block conversations.
+    // NPCs that aren't currently around are see-through.
+    let physics = rapier_context.single();
+    let is_present = |entity| {
+        npc_query
+            .get(entity)
+            .map_or(true, |(visibility, _)| *visibility != Visibility::Hidden)
+    };
+    let filter = QueryFilter::default()
+        .exclude_collider(player_entity)
+        .exclude_sensors()
+        .predicate(&is_present);
+    target.0 = physics
+        .cast_ray(ray_pos, *ray_dir, INTERACTION_DISTANCE, true, filter)
+        .map(|(entity, _)| entity)
+        // Hostile NPCs aren't up for a chat
+        .filter(|entity| {
+            readables.contains(*entity) || npc_query.get(*entity).is_ok_and(|(_, hostile)| !hostile)
+        });
+}
+
 fn player_interaction(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    player_query: Query<&Transform, With<KinematicCharacterController>>,
-    camera_query: Query<&Transform, With<Camera>>,
-    npc_query: Query<(&Transform, Entity, &Npc), With<Npc>>,
+    actions: Res<ActionState>,
+    target: Res<InteractionTarget>,
+    npc_query: Query<&Npc>,
     mut next_state: ResMut<NextState<GameState>>,
     mut commands: Commands,
     dialogue_db: Res<DialogueDatabase>,
 ) {
-    if keyboard.just_pressed(KeyCode::KeyE) {
-        let Ok(player_transform) = player_query.get_single() else {
-            return;
-        };
-        let Ok(camera_transform) = camera_query.get_single() else {
-            return;
-        };
-
-        // Get the camera's global transform
-        let global_transform = player_transform.mul_transform(*camera_transform);
-
-        // Ray starts at the camera position
-        let ray_pos = global_transform.translation;
-        // Ray points in the camera's forward direction
-        let ray_dir = global_transform.forward();
-
-        // Simple distance-based check for nearby NPCs
-        // Find the closest NPC that's in front of the player and within range
-        let mut closest_npc = None;
-        let mut closest_distance = f32::MAX;
-
-        for (npc_transform, entity, npc) in npc_query.iter() {
-            let to_npc = npc_transform.translation - ray_pos;
-
-            // Check if the NPC is roughly in front of the player (dot product > 0)
-            let forward_dot = ray_dir.dot(to_npc.normalize());
-            if forward_dot > 0.7 {
-                // Within ~45 degrees of forward direction
-                let distance = to_npc.length();
-
-                if distance < INTERACTION_DISTANCE && distance < closest_distance {
-                    closest_distance = distance;
-                    closest_npc = Some((entity, npc));
-                }
-            }
-        }
+    if actions.just_pressed(InputAction::Interact) {
+        let closest_npc = target
+            .0
+            .and_then(|entity| npc_query.get(entity).ok().map(|npc| (entity, npc)));
 
         // If we found an NPC to interact with, start dialogue
         if let Some((entity, npc)) = closest_npc {
-            println!("Starting dialogue with NPC: {}", npc.name);
-
-            // Get the dialogue tree for this NPC
-            if let Some(dialogue_tree) = dialogue_db.dialogues.get(&npc.dialogue_id) {
-                // Store the active dialogue information starting with the root node
-                commands.spawn(ActiveDialogue {
-                    npc_entity: entity,
-                    current_node: dialogue_tree.root_node.clone(),
-                });
-
-                // Change to dialogue state
-                next_state.set(GameState::InDialogue);
-            } else {
-                println!("Error: No dialogue tree found for id: {}", npc.dialogue_id);
-            }
+            start_dialogue(&mut commands, &mut next_state, &dialogue_db, entity, npc);
         }
     }
 }
 
-// Setup the dialogue UI when entering dialogue state
-fn setup_dialogue_ui(
+// Open a conversation with the NPC at the root of its dialogue tree
+fn start_dialogue(
+    commands: &mut Commands,
+    next_state: &mut NextState<GameState>,
+    dialogue_db: &DialogueDatabase,
+    entity: Entity,
+    npc: &Npc,
+) {
+    println!("Starting dialogue with NPC: {}", npc.name);
+
+    // Get the dialogue tree for this NPC
+    if let Some(dialogue_tree) = dialogue_db.dialogues.get(&npc.dialogue_id) {
+        // Store the active dialogue information starting with the root node
+        commands.spawn(ActiveDialogue {
+            npc_entity: entity,
+            current_node: dialogue_tree.root_node.clone(),
+        });
+
+        // Change to dialogue state
+        next_state.set(GameState::InDialogue);
+    } else {
+        println!("Error: No dialogue tree found for id: {}", npc.dialogue_id);
+    }
+}
+
+// Start a conversation once, firing the opening node's callbacks and tags. Coming back from the
+// dialogue editor only rebuilds the panel, so none of this runs twice
+fn enter_dialogue(
     mut commands: Commands,
     active_dialogue_query: Query<(&ActiveDialogue, Entity)>,
     npc_query: Query<&Npc>,
@@ -1363,13 +2016,50 @@ fn setup_dialogue_ui(
     window.cursor_options.visible = true;
     window.cursor_options.grab_mode = bevy::window::CursorGrabMode::None;
 
+    let Ok((active_dialogue, active_dialogue_entity)) = active_dialogue_query.get_single() else {
+        return;
+    };
+    let Ok(npc) = npc_query.get(active_dialogue.npc_entity) else {
+        return;
+    };
+    let Some(node) = dialogue_db
+        .dialogues
+        .get(&npc.dialogue_id)
+        .and_then(|tree| tree.nodes.get(&active_dialogue.current_node))
+    else {
+        return;
+    };
+
+    if let Some(auto_advance) = AutoAdvance::for_node(node) {
+        commands.entity(active_dialogue_entity).insert(auto_advance);
+    }
+    queue_node_callbacks(
+        &mut commands,
+        &npc.dialogue_id,
+        &active_dialogue.current_node,
+    );
+    queue_node_tags(&mut commands, active_dialogue.npc_entity, node);
+}
+
+// Setup the dialogue UI when a conversation starts or the dialogue editor closes
+#[allow(clippy::too_many_arguments)]
+fn setup_dialogue_ui(
+    mut commands: Commands,
+    active_dialogue_query: Query<&ActiveDialogue>,
+    npc_query: Query<(&Npc, Option<&InterruptedDialogue>, Option<&Merchant>)>,
+    dialogue_db: Res<DialogueDatabase>,
+    economy: Res<Economy>,
+    variables: Res<DialogueVariables>,
+    quests: Res<QuestDatabase>,
+    clock: Res<GameClock>,
+) {
     // Get the active dialogue
-    let Ok((active_dialogue, _)) = active_dialogue_query.get_single() else {
+    let Ok(active_dialogue) = active_dialogue_query.get_single() else {
         return;
     };
 
     // Get the NPC we're talking to
-    let Ok(npc) = npc_query.get(active_dialogue.npc_entity) else {
+    let Ok((npc, interrupted, merchant)) = npc_query.get(active_dialogue.npc_entity) else {
         return;
     };
 
@@ -1388,7 +2078,100 @@ fn setup_dialogue_ui(
         return;
     };
 
-    // Create the dialogue UI
+    // Offer to pick up an interrupted conversation from the root node
+    let resume_node = interrupted
+        .filter(|_| active_dialogue.current_node == dialogue_tree.root_node)
+        .map(|interrupted| interrupted.node.as_str());
+
+    let text = dialogue_text(&active_dialogue.current_node, node, merchant, &economy);
+    spawn_dialogue_ui(
+        &mut commands,
+        &npc.name,
+        &text,
+        node,
+        resume_node,
+        &variables,
+        &quests,
+        &dialogue_context(npc, &clock, &variables),
+    );
+}
+
+// Resolve the text shown for a node, mixing in market gossip for merchants
+fn dialogue_text(
+    node_id: &str,
+    node: &DialogueNode,
+    merchant: Option<&Merchant>,
+    economy: &Economy,
+) -> String {
+    match merchant {
+        Some(merchant) if node_id == MARKET_FLAVOR_NODE => {
+            format!("{}\n\n{}", node.text, economy.flavor_line(merchant))
+        }
+        _ => node.text.clone(),
+    }
+}
+
+// Placeholder values available to the text of a conversation with this NPC
+fn dialogue_context(
+    npc: &Npc,
+    clock: &GameClock,
+    variables: &DialogueVariables,
+) -> DialogueContext {
+    DialogueContext::default()
+        .with("player_name", PLAYER_NAME)
+        .with("npc_name", &npc.name)
+        .with("time_of_day", clock.time_of_day())
+        .with_variables(variables)
+}
+
+// Spawn the dialogue panel for a node, optionally with a resume option first
+#[allow(clippy::too_many_arguments)]
+fn spawn_dialogue_ui(
+    commands: &mut Commands,
+    npc_name: &str,
+    text: &str,
+    node: &DialogueNode,
+    resume_node: Option<&str>,
+    variables: &DialogueVariables,
+    quests: &QuestDatabase,
+    context: &DialogueContext,
+) {
+    let mut options = Vec::new();
+    if let Some(resume_node) = resume_node {
+        options.push((
+            RESUME_OPTION_TEXT.to_string(),
+            resume_node.to_string(),
+            Vec::new(),
+            Vec::new(),
+        ));
+    }
+    if let Some(next) = &node.next {
+        options.push((
+            AUTO_ADVANCE_TEXT.to_string(),
+            next.clone(),
+            Vec::new(),
+            Vec::new(),
+        ));
+    }
+    for option in node
+        .options
+        .iter()
+        .filter(|option| node.next.is_none() && option.is_available(variables))
+    {
+        let (text, target_node) = match option {
+            DialogueOption::Reply {
+                text, target_node, ..
+            } => (text, target_node.as_str()),
+            DialogueOption::Exit { text, .. } => (text, "exit"),
+        };
+        options.push((
+            context.substitute(text),
+            target_node.to_string(),
+            option.actions().to_vec(),
+            option.effects(quests),
+        ));
+    }
+
     commands
         .spawn((
             Node {
@@ -1403,11 +2186,13 @@ fn setup_dialogue_ui(
             },
             BackgroundColor(DIALOGUE_BACKGROUND_COLOR),
             DialogueUI,
+            // Leaving dialogue, for gameplay or the editor, takes the panel with it
+            StateScoped(DialogueMode::Talking),
         ))
         .with_children(|parent| {
             // NPC name
             parent.spawn((
-                Text::new(npc.name.clone()),
+                Text::new(npc_name),
                 TextFont {
                     font_size: 24.0,
                     ..default()
@@ -1420,11 +2205,12 @@ fn setup_dialogue_ui(
 
             // Dialogue text
             parent.spawn((
-                Text::new(node.text.clone()),
+                Text::new(context.substitute(text)),
                 TextFont {
                     font_size: 18.0,
                     ..default()
                 },
+                TextColor(DIALOGUE_TEXT_COLOR),
                 Node {
                     margin: UiRect::bottom(Val::Px(20.0)),
                     ..default()
@@ -1432,17 +2218,8 @@ fn setup_dialogue_ui(
             ));
 
             // Dialogue options
-            for (i, option) in node.options.iter().enumerate() {
-                let option_text = match option {
-                    DialogueOption::Reply { text, .. } => text.clone(),
-                    DialogueOption::Exit { text } => text.clone(),
-                };
-
-                let target_node = match option {
-                    DialogueOption::Reply { target_node, .. } => target_node.clone(),
-                    DialogueOption::Exit { .. } => "exit".to_string(),
-                };
-
+            for (i, (option_text, target_node, actions, effects)) in options.into_iter().enumerate()
+            {
                 parent
                     .spawn((
                         Button,
@@ -1458,7 +2235,9 @@ fn setup_dialogue_ui(
                         BackgroundColor(DIALOGUE_OPTION_NORMAL_COLOR),
                         DialogueOptionButton {
                             target_node,
+                            actions,
                             option_index: i,
+                            text: option_text.clone(),
                         },
                     ))
                     .with_children(|parent| {
@@ -1469,19 +2248,52 @@ fn setup_dialogue_ui(
                                 ..default()
                             },
                         ));
+
+                        // Shown beside the option while it's hovered
+                        if !effects.is_empty() {
+                            parent.spawn((
+                                Text::new(effects.join("\n")),
+                                TextFont {
+                                    font_size: 14.0,
+                                    ..default()
+                                },
+                                TextColor(DIALOGUE_TEXT_COLOR),
+                                BackgroundColor(DIALOGUE_TOOLTIP_COLOR),
+                                Node {
+                                    position_type: PositionType::Absolute,
+                                    left: Val::Percent(102.0),
+                                    top: Val::Px(0.0),
+                                    padding: UiRect::all(Val::Px(6.0)),
+                                    ..default()
+                                },
+                                Visibility::Hidden,
+                                DialogueTooltip,
+                            ));
+                        }
                     });
             }
         });
 }
 
 // Handle hover effects on dialogue options
+#[allow(clippy::type_complexity)]
 fn handle_dialogue_hover(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
+        (&Interaction, &mut BackgroundColor, &Children),
         (Changed<Interaction>, With<DialogueOptionButton>),
     >,
+    mut tooltip_query: Query<&mut Visibility, With<DialogueTooltip>>,
 ) {
-    for (interaction, mut background_color) in interaction_query.iter_mut() {
+    for (interaction, mut background_color, children) in interaction_query.iter_mut() {
+        let mut tooltips = tooltip_query.iter_many_mut(children);
+        while let Some(mut visibility) = tooltips.fetch_next() {
+            *visibility = if *interaction == Interaction::Hovered {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+
         match *interaction {
             Interaction::Hovered => {
                 *background_color = BackgroundColor(DIALOGUE_OPTION_HOVER_COLOR);
@@ -1494,23 +2306,28 @@ fn handle_dialogue_hover(
 }
 
 // Handle clicks on dialogue options
+#[allow(clippy::too_many_arguments)]
 fn handle_dialogue_click(
     interaction_query: Query<(&Interaction, &DialogueOptionButton), Changed<Interaction>>,
     active_dialogue_query: Query<(&ActiveDialogue, Entity)>,
     mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
+    mut advance_events: EventWriter<AdvanceDialogue>,
+    mut action_events: EventWriter<DialogueActionTriggered>,
+    mut chosen_events: EventWriter<DialogueOptionChosen>,
     keyboard: Res<ButtonInput<KeyCode>>,
     dialogue_db: Res<DialogueDatabase>,
-    npc_query: Query<&Npc>,
-    dialogue_ui_query: Query<Entity, With<DialogueUI>>,
+    npc_query: Query<(&Npc, Option<&Merchant>)>,
 ) {
     // Check for Escape key to exit dialogue
-    if keyboard.just_pressed(KeyCode::Escape) {
-        if let Ok((_, active_dialogue_entity)) = active_dialogue_query.get_single() {
-            commands.entity(active_dialogue_entity).despawn();
-            next_state.set(GameState::Playing);
-            return;
-        }
+    if keyboard.just_pressed(KeyCode::Escape)
+        && let Ok((active_dialogue, active_dialogue_entity)) = active_dialogue_query.get_single()
+    {
+        // Remember where we were so the conversation can be resumed later
+        interrupt_dialogue(&mut commands, active_dialogue, &npc_query, &dialogue_db);
+        commands.entity(active_dialogue_entity).despawn();
+        next_state.set(GameState::Playing);
+        return;
     }
 
     // Handle button clicks
@@ -1521,140 +2338,250 @@ fn handle_dialogue_click(
                 return;
             };
 
+            // Any choice moves the conversation on, so drop the saved resume point
+            commands
+                .entity(active_dialogue.npc_entity)
+                .remove::<InterruptedDialogue>();
+
+            chosen_events.send(DialogueOptionChosen {
+                npc_entity: active_dialogue.npc_entity,
+                node: active_dialogue.current_node.clone(),
+                option_index: dialogue_option.option_index,
+                text: dialogue_option.text.clone(),
+                target_node: dialogue_option.target_node.clone(),
+            });
+            for action in &dialogue_option.actions {
+                action_events.send(DialogueActionTriggered {
+                    npc_entity: active_dialogue.npc_entity,
+                    action: action.clone(),
+                });
+            }
+
             if dialogue_option.target_node == "exit" {
                 // Exit dialogue
                 commands.entity(active_dialogue_entity).despawn();
                 next_state.set(GameState::Playing);
             } else {
-                // Update the current dialogue node
-                commands
-                    .entity(active_dialogue_entity)
-                    .insert(ActiveDialogue {
-                        npc_entity: active_dialogue.npc_entity,
-                        current_node: dialogue_option.target_node.clone(),
-                    });
+                advance_events.send(AdvanceDialogue {
+                    target_node: dialogue_option.target_node.clone(),
+                });
+            }
+        }
+    }
+}
 
-                // Redraw UI directly instead of toggling game states
-                // First, remove the old UI
-                for entity in dialogue_ui_query.iter() {
-                    commands.entity(entity).despawn_recursive();
-                }
+// Linear nodes move on by themselves after a while, or as soon as interact is pressed
+fn auto_advance_dialogue(
+    time: Res<Time>,
+    actions: Res<ActionState>,
+    mut auto_advance_query: Query<&mut AutoAdvance>,
+    mut advance_events: EventWriter<AdvanceDialogue>,
+) {
+    let Ok(mut auto_advance) = auto_advance_query.get_single_mut() else {
+        return;
+    };
+    auto_advance.timer.tick(time.delta());
 
-                // Get the necessary data for drawing the new UI
-                let Ok(npc) = npc_query.get(active_dialogue.npc_entity) else {
-                    return;
-                };
+    // Interact skips ahead, from whichever key or button it's bound to
+    let skipped = actions.just_pressed(InputAction::Interact);
+    if auto_advance.timer.just_finished() || skipped {
+        advance_events.send(AdvanceDialogue {
+            target_node: auto_advance.next.clone(),
+        });
+    }
+}
 
-                // Get the dialogue data
-                let Some(dialogue_tree) = dialogue_db.dialogues.get(&npc.dialogue_id) else {
-                    println!("Error: No dialogue tree found for id: {}", npc.dialogue_id);
-                    return;
-                };
+// Move the conversation to a new node and redraw it
+#[allow(clippy::too_many_arguments)]
+fn advance_dialogue(
+    mut advance_events: EventReader<AdvanceDialogue>,
+    active_dialogue_query: Query<(&ActiveDialogue, Entity)>,
+    mut commands: Commands,
+    dialogue_db: Res<DialogueDatabase>,
+    economy: Res<Economy>,
+    variables: Res<DialogueVariables>,
+    quests: Res<QuestDatabase>,
+    clock: Res<GameClock>,
+    npc_query: Query<(&Npc, Option<&Merchant>)>,
+    dialogue_ui_query: Query<Entity, With<DialogueUI>>,
+) {
+    // A click and a key press in the same frame shouldn't skip a node
+    let Some(AdvanceDialogue { target_node }) = advance_events.read().last() else {
+        return;
+    };
+    let Ok((active_dialogue, active_dialogue_entity)) = active_dialogue_query.get_single() else {
+        return;
+    };
 
-                // Get the new dialogue node
-                let Some(node) = dialogue_tree.nodes.get(&dialogue_option.target_node) else {
-                    println!(
-                        "Error: No node found with id: {}",
-                        dialogue_option.target_node
-                    );
-                    return;
-                };
+    // Update the current dialogue node
+    commands
+        .entity(active_dialogue_entity)
+        .insert(ActiveDialogue {
+            npc_entity: active_dialogue.npc_entity,
+            current_node: target_node.clone(),
+        })
+        .remove::<AutoAdvance>();
+
+    // Redraw UI directly instead of toggling game states
+    // First, remove the old UI
+    for entity in dialogue_ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 
-                // Create the new dialogue UI with the updated node
-                commands
-                    .spawn((
-                        Node {
-                            width: Val::Percent(50.0),
-                            height: Val::Auto,
-                            position_type: PositionType::Absolute,
-                            left: Val::Percent(25.0),
-                            bottom: Val::Percent(20.0),
-                            padding: UiRect::all(Val::Px(20.0)),
-                            flex_direction: FlexDirection::Column,
-                            ..default()
-                        },
-                        BackgroundColor(DIALOGUE_BACKGROUND_COLOR),
-                        DialogueUI,
-                    ))
-                    .with_children(|parent| {
-                        // NPC name
-                        parent.spawn((
-                            Text::new(npc.name.clone()),
-                            TextFont {
-                                font_size: 24.0,
-                                ..default()
-                            },
-                            Node {
-                                margin: UiRect::bottom(Val::Px(10.0)),
-                                ..default()
-                            },
-                        ));
+    // Get the necessary data for drawing the new UI
+    let Ok((npc, merchant)) = npc_query.get(active_dialogue.npc_entity) else {
+        return;
+    };
 
-                        // Dialogue text
-                        parent.spawn((
-                            Text::new(node.text.clone()),
-                            TextFont {
-                                font_size: 18.0,
-                                ..default()
-                            },
-                            Node {
-                                margin: UiRect::bottom(Val::Px(20.0)),
-                                ..default()
-                            },
-                        ));
+    // Get the dialogue data
+    let Some(dialogue_tree) = dialogue_db.dialogues.get(&npc.dialogue_id) else {
+        println!("Error: No dialogue tree found for id: {}", npc.dialogue_id);
+        return;
+    };
 
-                        // Dialogue options
-                        for (i, option) in node.options.iter().enumerate() {
-                            let option_text = match option {
-                                DialogueOption::Reply { text, .. } => text.clone(),
-                                DialogueOption::Exit { text } => text.clone(),
-                            };
-
-                            let target_node = match option {
-                                DialogueOption::Reply { target_node, .. } => target_node.clone(),
-                                DialogueOption::Exit { .. } => "exit".to_string(),
-                            };
-
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Percent(100.0),
-                                        height: Val::Px(30.0),
-                                        justify_content: JustifyContent::FlexStart,
-                                        align_items: AlignItems::Center,
-                                        padding: UiRect::left(Val::Px(10.0)),
-                                        margin: UiRect::bottom(Val::Px(5.0)),
-                                        ..default()
-                                    },
-                                    BackgroundColor(DIALOGUE_OPTION_NORMAL_COLOR),
-                                    DialogueOptionButton {
-                                        target_node,
-                                        option_index: i,
-                                    },
-                                ))
-                                .with_children(|parent| {
-                                    parent.spawn((
-                                        Text::new(format!("{}. {}", i + 1, option_text)),
-                                        TextFont {
-                                            font_size: 16.0,
-                                            ..default()
-                                        },
-                                    ));
-                                });
-                        }
-                    });
-            }
-        }
+    // Get the new dialogue node
+    let Some(node) = dialogue_tree.nodes.get(target_node) else {
+        println!("Error: No node found with id: {}", target_node);
+        return;
+    };
+
+    // Create the new dialogue UI with the updated node
+    let text = dialogue_text(target_node, node, merchant, &economy);
+    spawn_dialogue_ui(
+        &mut commands,
+        &npc.name,
+        &text,
+        node,
+        None,
+        &variables,
+        &quests,
+        &dialogue_context(npc, &clock, &variables),
+    );
+    if let Some(auto_advance) = AutoAdvance::for_node(node) {
+        commands.entity(active_dialogue_entity).insert(auto_advance);
     }
+    queue_node_callbacks(&mut commands, &npc.dialogue_id, target_node);
+    queue_node_tags(&mut commands, active_dialogue.npc_entity, node);
 }
 
-// Cleanup the dialogue UI when exiting dialogue state
-fn cleanup_dialogue_ui(mut commands: Commands, dialogue_ui_query: Query<Entity, With<DialogueUI>>) {
-    // Find and remove all dialogue UI entities
+// Trail off with a farewell line when the NPC ends up out of range, then close the dialogue
+#[allow(clippy::too_many_arguments)]
+fn break_off_distant_dialogue(
+    time: Res<Time>,
+    mut active_dialogue_query: Query<(&ActiveDialogue, Entity, Option<&mut BrokenOff>)>,
+    player_query: Query<&Transform, With<KinematicCharacterController>>,
+    npc_transforms: Query<(&Transform, &Visibility), With<Npc>>,
+    npc_query: Query<(&Npc, Option<&Merchant>)>,
+    dialogue_ui_query: Query<Entity, With<DialogueUI>>,
+    dialogue_db: Res<DialogueDatabase>,
+    variables: Res<DialogueVariables>,
+    quests: Res<QuestDatabase>,
+    clock: Res<GameClock>,
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Ok((active_dialogue, active_dialogue_entity, broken_off)) =
+        active_dialogue_query.get_single_mut()
+    else {
+        return;
+    };
+
+    if let Some(mut broken_off) = broken_off {
+        if broken_off.0.tick(time.delta()).finished() {
+            // Leave a resume point so the conversation can be picked up again
+            interrupt_dialogue(&mut commands, active_dialogue, &npc_query, &dialogue_db);
+            commands.entity(active_dialogue_entity).despawn();
+            next_state.set(GameState::Playing);
+        }
+        return;
+    }
+
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let in_range =
+        npc_transforms
+            .get(active_dialogue.npc_entity)
+            .is_ok_and(|(npc_transform, visibility)| {
+                *visibility != Visibility::Hidden
+                    && npc_transform
+                        .translation
+                        .distance(player_transform.translation)
+                        <= BREAK_OFF_DISTANCE
+            });
+    if in_range {
+        return;
+    }
+
+    // Without an NPC left to say goodbye there's nothing to show
+    let Ok((npc, _)) = npc_query.get(active_dialogue.npc_entity) else {
+        commands.entity(active_dialogue_entity).despawn();
+        next_state.set(GameState::Playing);
+        return;
+    };
+
     for entity in dialogue_ui_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
+    let farewell = DialogueNode {
+        text: BREAK_OFF_TEXT.to_string(),
+        options: Vec::new(),
+        next: None,
+        tags: Vec::new(),
+    };
+    spawn_dialogue_ui(
+        &mut commands,
+        &npc.name,
+        &farewell.text,
+        &farewell,
+        None,
+        &variables,
+        &quests,
+        &dialogue_context(npc, &clock, &variables),
+    );
+    commands
+        .entity(active_dialogue_entity)
+        .remove::<AutoAdvance>()
+        .insert(BrokenOff(Timer::from_seconds(
+            BREAK_OFF_LINGER,
+            TimerMode::Once,
+        )));
+}
+
+// Store the current node on the NPC when a conversation is cut short
+fn interrupt_dialogue(
+    commands: &mut Commands,
+    active_dialogue: &ActiveDialogue,
+    npc_query: &Query<(&Npc, Option<&Merchant>)>,
+    dialogue_db: &DialogueDatabase,
+) {
+    let Ok((npc, _)) = npc_query.get(active_dialogue.npc_entity) else {
+        return;
+    };
+    let Some(dialogue_tree) = dialogue_db.dialogues.get(&npc.dialogue_id) else {
+        return;
+    };
+
+    // Nothing to resume if we never left the root node
+    if active_dialogue.current_node == dialogue_tree.root_node {
+        return;
+    }
+
+    commands
+        .entity(active_dialogue.npc_entity)
+        .insert(InterruptedDialogue {
+            node: active_dialogue.current_node.clone(),
+        });
+}
+
+// A guard who's had to warn the player off remembers it, and so do the rest of them
+fn guard_warned_player(world: &mut World) {
+    world
+        .resource_mut::<DialogueVariables>()
+        .set("guard_warned", DialogueValue::Bool(true));
+    world
+        .resource_mut::<FactionStandings>()
+        .adjust_player_standing(Faction::Guards, -15.0);
 }
 
 // Reset the look input when exiting dialogue to prevent camera from changing position
@@ -0,0 +1,170 @@
+use crate::{
+    GameStateSet, MovementInput, PLAYER_BORDER_RADIUS, PLAYER_RADIUS, crouch::Crouch,
+    footsteps::SurfaceMaterial, ladders::Climbing, player_movement,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+// Mantle constants
+const MANTLE_REACH: f32 = 0.4; // How close a wall has to be to grab its ledge
+const MANTLE_MIN_HEIGHT: f32 = 0.35; // Anything lower is left to autostep
+const MANTLE_MAX_HEIGHT: f32 = 1.6; // Ledge height above the feet
+const MANTLE_DURATION: f32 = 0.45;
+const MANTLE_RISE_FRACTION: f32 = 0.6; // Share of the mantle spent lifting before moving forward
+const MANTLE_CLEARANCE: f32 = 0.05; // Gap left between the feet and the ledge
+const LOW_WALL_POSITION: Vec3 = Vec3::new(-8.0, 0.0, 10.0);
+const LOW_WALL_HALF_EXTENTS: Vec3 = Vec3::new(3.0, 0.6, 0.4);
+
+pub struct MantlePlugin;
+
+impl Plugin for MantlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_low_wall).add_systems(
+            FixedUpdate,
+            (start_mantle, update_mantle)
+                .chain()
+                .before(player_movement)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// Component on the player while they're pulling themselves onto a ledge
+#[derive(Component)]
+pub struct Mantling {
+    from: Vec3,
+    to: Vec3,
+    elapsed: f32,
+}
+
+#[allow(clippy::type_complexity)]
+fn start_mantle(
+    mut commands: Commands,
+    input: Res<MovementInput>,
+    rapier_context: ReadRapierContext,
+    player: Query<
+        (
+            Entity,
+            &Transform,
+            &Collider,
+            &Crouch,
+            Option<&KinematicCharacterControllerOutput>,
+        ),
+        (Without<Mantling>, Without<Climbing>),
+    >,
+) {
+    let Ok((entity, transform, collider, crouch, output)) = player.get_single() else {
+        return;
+    };
+    // Only while pushing forward and either jumping or already in the air
+    let airborne = !output.is_some_and(|output| output.grounded);
+    if input.z >= 0.0 || (input.y <= 0.0 && !airborne) {
+        return;
+    }
+
+    let physics = rapier_context.single();
+    let filter = QueryFilter::default()
+        .exclude_collider(entity)
+        .exclude_sensors();
+    let forward = (transform.rotation * Vec3::NEG_Z).with_y(0.0).normalize();
+    let options = ShapeCastOptions {
+        max_time_of_impact: MANTLE_REACH,
+        target_distance: 0.0,
+        stop_at_penetration: false,
+        compute_impact_geometry_on_penetration: false,
+    };
+    // Raised off the floor so the ground under the feet doesn't count as a wall
+    let Some((_, hit)) = physics.cast_shape(
+        transform.translation + Vec3::Y * MANTLE_MIN_HEIGHT,
+        transform.rotation,
+        forward,
+        collider,
+        options,
+        filter,
+    ) else {
+        return;
+    };
+
+    // Look down onto the wall just past where the capsule touched it
+    let feet = transform.translation.y - crouch.feet_offset();
+    let over_wall = transform.translation
+        + forward * (hit.time_of_impact + PLAYER_RADIUS + PLAYER_BORDER_RADIUS + MANTLE_REACH);
+    let origin = over_wall.with_y(feet + MANTLE_MAX_HEIGHT);
+    // A hit at the very start means the wall is taller than we can reach
+    let Some((_, distance)) =
+        physics.cast_ray(origin, Vec3::NEG_Y, MANTLE_MAX_HEIGHT, true, filter)
+    else {
+        return;
+    };
+    if distance <= 0.0 || MANTLE_MAX_HEIGHT - distance < MANTLE_MIN_HEIGHT {
+        return;
+    }
+
+    // Both the lift and the spot on the ledge need room for the capsule
+    let ledge = origin.y - distance;
+    let to = origin.with_y(ledge + crouch.feet_offset() + MANTLE_CLEARANCE);
+    let lifted = transform.translation.with_y(to.y);
+    let mut blocked = false;
+    for position in [lifted, to] {
+        physics.intersections_with_shape(position, transform.rotation, collider, filter, |_| {
+            blocked = true;
+            false
+        });
+    }
+    if blocked {
+        return;
+    }
+
+    commands.entity(entity).insert(Mantling {
+        from: transform.translation,
+        to,
+        elapsed: 0.0,
+    });
+}
+
+// Lift straight up the wall, then move forward over the ledge
+fn update_mantle(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut input: ResMut<MovementInput>,
+    mut player: Query<(Entity, &mut Transform, &mut Mantling)>,
+) {
+    let Ok((entity, mut transform, mut mantling)) = player.get_single_mut() else {
+        return;
+    };
+    // Movement is ignored while mantling, and a held jump shouldn't fire on landing
+    **input = Vec3::ZERO;
+
+    mantling.elapsed += time.delta_secs();
+    let progress = (mantling.elapsed / MANTLE_DURATION).min(1.0);
+    let rise = (progress / MANTLE_RISE_FRACTION).min(1.0);
+    let advance = ((progress - MANTLE_RISE_FRACTION) / (1.0 - MANTLE_RISE_FRACTION)).max(0.0);
+    let horizontal = mantling.from.lerp(mantling.to, advance);
+    transform.translation = horizontal.with_y(mantling.from.y.lerp(mantling.to.y, rise));
+
+    if progress >= 1.0 {
+        commands.entity(entity).remove::<Mantling>();
+    }
+}
+
+fn spawn_low_wall(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_size(LOW_WALL_HALF_EXTENTS * 2.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.5, 0.5, 0.55),
+            perceptual_roughness: 0.9,
+            ..default()
+        })),
+        Transform::from_translation(LOW_WALL_POSITION + Vec3::Y * LOW_WALL_HALF_EXTENTS.y),
+        Collider::cuboid(
+            LOW_WALL_HALF_EXTENTS.x,
+            LOW_WALL_HALF_EXTENTS.y,
+            LOW_WALL_HALF_EXTENTS.z,
+        ),
+        SurfaceMaterial::Stone,
+    ));
+}
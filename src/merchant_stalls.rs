@@ -0,0 +1,365 @@
+use crate::{
+    ActiveDialogue, DialogueNode, DialogueOption, DialogueTree, GameState, GameStateSet, Npc,
+    advance_dialogue,
+    clock::GameClock,
+    condition,
+    dialogue_assets::RegisterDialogueExt,
+    dialogue_variables::{DialogueValue, DialogueVariables},
+    economy::{Economy, Merchant, Purse, TradeGood},
+    handle_dialogue_click,
+    quests::{DialogueAction, DialogueActionTriggered},
+    setup_dialogue_ui,
+    usable_props::{PropAnchor, PropKind, PropSchedule, PropVisit, UsingProp},
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub struct MerchantStallsPlugin;
+
+impl Plugin for MerchantStallsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_dialogue("merchant", merchant_dialogue())
+            .add_systems(
+                OnEnter(GameState::InDialogue),
+                expose_shop.before(setup_dialogue_ui),
+            )
+            .add_systems(
+                Update,
+                (
+                    anchor_merchant_stalls,
+                    (update_shop_hours, restock_shops).chain(),
+                ),
+            )
+            .add_systems(
+                Update,
+                apply_trade_actions
+                    .after(handle_dialogue_click)
+                    .before(advance_dialogue)
+                    .in_set(GameStateSet::InDialogue),
+            );
+    }
+}
+
+// What a merchant archetype sells and when, set in `npcs.spawn.ron`
+#[derive(Clone, Deserialize)]
+pub struct ShopConfig {
+    // Hours of the day the stall is open
+    pub opens: f32,
+    pub closes: f32,
+    // How much of each good is on the shelves after the morning restock
+    pub stock: HashMap<TradeGood, u32>,
+}
+
+// Component for a merchant's shop, open while they're minding their stall during opening hours
+#[derive(Component, Clone)]
+pub struct Shop {
+    pub config: ShopConfig,
+    pub inventory: HashMap<TradeGood, u32>,
+    pub open: bool,
+    // Game day the shelves were last filled
+    restocked_day: Option<u32>,
+}
+
+impl Shop {
+    pub fn new(config: ShopConfig) -> Self {
+        Self {
+            inventory: config.stock.clone(),
+            config,
+            open: false,
+            restocked_day: None,
+        }
+    }
+
+    // Mind the stall through opening hours
+    pub fn prop_schedule(&self) -> PropSchedule {
+        PropSchedule(vec![PropVisit {
+            kind: PropKind::Stall,
+            from: self.config.opens,
+            to: self.config.closes,
+        }])
+    }
+
+    fn is_opening_hours(&self, hour: f32) -> bool {
+        (self.config.opens..self.config.closes).contains(&hour)
+    }
+
+    // What's on the shelves, for dialogue
+    fn wares(&self) -> String {
+        let wares: Vec<String> = TradeGood::ALL
+            .into_iter()
+            .filter_map(|good| {
+                let quantity = *self.inventory.get(&good)?;
+                (quantity > 0).then(|| format!("{quantity} {}", good.name()))
+            })
+            .collect();
+        if wares.is_empty() {
+            "nothing at all, I'm sold out".to_string()
+        } else {
+            wares.join(", ")
+        }
+    }
+
+    // The player buys from the stall, taking what's left and what they can afford
+    pub fn sell_to_player(
+        &mut self,
+        good: TradeGood,
+        quantity: u32,
+        price: f32,
+        purse: &mut Purse,
+        economy: &mut Economy,
+    ) -> u32 {
+        if !self.open || price <= 0.0 {
+            return 0;
+        }
+        let available = self.inventory.entry(good).or_default();
+        let affordable = (purse.credits / price).floor() as u32;
+        let sold = quantity.min(*available).min(affordable);
+        *available -= sold;
+        purse.credits -= sold as f32 * price;
+        *purse.goods.entry(good).or_default() += sold;
+        economy.record_purchase(good, sold);
+        sold
+    }
+
+    // The player sells to the stall, which puts the goods out on its shelves
+    pub fn buy_from_player(
+        &mut self,
+        good: TradeGood,
+        quantity: u32,
+        price: f32,
+        purse: &mut Purse,
+        economy: &mut Economy,
+    ) -> u32 {
+        if !self.open {
+            return 0;
+        }
+        let held = purse.goods.entry(good).or_default();
+        let bought = quantity.min(*held);
+        *held -= bought;
+        purse.credits += bought as f32 * price;
+        *self.inventory.entry(good).or_default() += bought;
+        economy.record_sale(good, bought);
+        bought
+    }
+}
+
+// Merchants keep the first stall they set up at, and come back to it every day
+#[allow(clippy::type_complexity)]
+fn anchor_merchant_stalls(
+    mut commands: Commands,
+    merchants: Query<(Entity, &UsingProp), (With<Shop>, Without<PropAnchor>)>,
+) {
+    for (entity, using) in merchants.iter() {
+        if using.kind == PropKind::Stall {
+            commands.entity(entity).insert(PropAnchor(using.prop));
+        }
+    }
+}
+
+// A shop is open during its hours, once the merchant has made it to their stall if they keep one
+fn update_shop_hours(
+    clock: Res<GameClock>,
+    mut shops: Query<(&Npc, &mut Shop, Option<&UsingProp>, Has<PropSchedule>)>,
+) {
+    for (npc, mut shop, using, keeps_stall) in shops.iter_mut() {
+        let at_stall = !keeps_stall
+            || using.is_some_and(|using| using.kind == PropKind::Stall && using.in_use);
+        let open = shop.is_opening_hours(clock.hour) && at_stall;
+        if open != shop.open {
+            let state = if open { "opened" } else { "closed" };
+            println!("{} {state} their stall", npc.name);
+            shop.open = open;
+        }
+    }
+}
+
+// Fill the shelves back up when the stall first opens each day
+fn restock_shops(clock: Res<GameClock>, mut shops: Query<&mut Shop>) {
+    for mut shop in shops.iter_mut() {
+        if shop.open && shop.restocked_day != Some(clock.day) {
+            shop.inventory = shop.config.stock.clone();
+            shop.restocked_day = Some(clock.day);
+        }
+    }
+}
+
+// Dialogue needs to know whether the stall is open and what's on it
+fn expose_shop(
+    active_dialogue: Query<&ActiveDialogue>,
+    shops: Query<&Shop>,
+    purse: Res<Purse>,
+    mut variables: ResMut<DialogueVariables>,
+) {
+    expose_purse(&purse, &mut variables);
+    let Ok(active_dialogue) = active_dialogue.get_single() else {
+        return;
+    };
+    let shop = shops.get(active_dialogue.npc_entity).ok();
+    variables.set(
+        "shop_open",
+        DialogueValue::Bool(shop.is_some_and(|shop| shop.open)),
+    );
+    if let Some(shop) = shop {
+        variables.set("shop_wares", DialogueValue::Text(shop.wares()));
+        let opens = shop.config.opens;
+        let (hours, minutes) = (opens.floor() as u32, (opens.fract() * 60.0) as u32);
+        variables.set(
+            "shop_opens",
+            DialogueValue::Text(format!("{hours}:{minutes:02}")),
+        );
+    }
+}
+
+// What the player has to trade with, as `credits` and `held_<good>` like `held_cube_shards`
+fn expose_purse(purse: &Purse, variables: &mut DialogueVariables) {
+    variables.set("credits", DialogueValue::Number(purse.credits.floor()));
+    for good in TradeGood::ALL {
+        variables.set(
+            format!("held_{}", good.name().replace(' ', "_")),
+            DialogueValue::Number(purse.held(good) as f32),
+        );
+    }
+}
+
+// Trade as picked in conversation, before the next node is drawn so it can say how it went
+// through `{trade_result}`. Merchants sell at their own markup and buy back at the market price.
+fn apply_trade_actions(
+    mut events: EventReader<DialogueActionTriggered>,
+    mut shops: Query<(&mut Shop, &Merchant)>,
+    mut economy: ResMut<Economy>,
+    mut purse: ResMut<Purse>,
+    mut variables: ResMut<DialogueVariables>,
+) {
+    for event in events.read() {
+        let (good, quantity, buying) = match event.action {
+            DialogueAction::Buy(good, quantity) => (good, quantity, true),
+            DialogueAction::Sell(good, quantity) => (good, quantity, false),
+            _ => continue,
+        };
+        let Ok((mut shop, merchant)) = shops.get_mut(event.npc_entity) else {
+            continue;
+        };
+        let result = if !shop.open {
+            "Sorry, the stall's shut.".to_string()
+        } else if buying {
+            let price = economy.merchant_price(good, merchant);
+            match shop.sell_to_player(good, quantity, price, &mut purse, &mut economy) {
+                0 if shop.inventory.get(&good).copied().unwrap_or(0) == 0 => {
+                    format!("I'm all out of {}, I'm afraid.", good.name())
+                }
+                0 => format!(
+                    "That's {price:.1} a piece, and you've only got {:.0} credits.",
+                    purse.credits.floor()
+                ),
+                sold => format!(
+                    "There you go, {sold} {} for {:.1} credits.",
+                    good.name(),
+                    sold as f32 * price
+                ),
+            }
+        } else {
+            let price = economy.price(good);
+            match shop.buy_from_player(good, quantity, price, &mut purse, &mut economy) {
+                0 => format!("You haven't got any {} to sell me.", good.name()),
+                bought => format!(
+                    "I'll take those {bought} {}. Here's {:.1} credits.",
+                    good.name(),
+                    bought as f32 * price
+                ),
+            }
+        };
+        variables.set("trade_result", DialogueValue::Text(result));
+        variables.set("shop_wares", DialogueValue::Text(shop.wares()));
+        expose_purse(&purse, &mut variables);
+    }
+}
+
+// The merchant's side of a trade, driving the stall through `Buy` and `Sell` actions
+pub fn merchant_dialogue() -> DialogueTree {
+    DialogueTree {
+        root_node: "start".to_string(),
+        nodes: [
+            (
+                "start".to_string(),
+                DialogueNode {
+                    text: "Hello there! Looking to trade? You've got {credits} credits to spend.".to_string(),
+                    options: vec![
+                        DialogueOption::reply("What would you sell?", "wares")
+                            .with_condition(condition("$shop_open")),
+                        DialogueOption::reply("Are you open?", "closed")
+                            .with_condition(condition("not $shop_open")),
+                        DialogueOption::reply("How's business?", "business"),
+                        DialogueOption::exit("I'll be going. Goodbye."),
+                    ],
+                    next: None,
+                    tags: vec!["wave".to_string()],
+                }
+            ),
+            (
+                "wares".to_string(),
+                DialogueNode {
+                    text: "Fresh this morning, I've got {shop_wares}. What'll it be?".to_string(),
+                    options: vec![
+                        DialogueOption::reply("I'll take ten lengths of wire.", "traded")
+                            .with_action(DialogueAction::Buy(TradeGood::Wire, 10)),
+                        DialogueOption::reply("One data chip, please.", "traded")
+                            .with_action(DialogueAction::Buy(TradeGood::DataChips, 1)),
+                        DialogueOption::reply("Want fifty paperclips?", "traded")
+                            .with_condition(condition("$held_paperclips > 0"))
+                            .with_action(DialogueAction::Sell(TradeGood::Paperclips, 50)),
+                        DialogueOption::reply("How's business?", "business"),
+                        DialogueOption::reply("Let's talk about something else.", "start"),
+                        DialogueOption::exit("Interesting. Goodbye!"),
+                    ],
+                    next: None,
+                    tags: Vec::new(),
+                }
+            ),
+            (
+                "traded".to_string(),
+                DialogueNode {
+                    text: "{trade_result}".to_string(),
+                    options: vec![
+                        DialogueOption::reply("Let me see what else you've got.", "wares"),
+                        DialogueOption::exit("That's all, thanks."),
+                    ],
+                    next: None,
+                    tags: Vec::new(),
+                }
+            ),
+            (
+                "closed".to_string(),
+                DialogueNode {
+                    text: "Not right now, the stall's shut. Come find me there from {shop_opens} and I'll see you right.".to_string(),
+                    options: vec![
+                        DialogueOption::reply("How's business?", "business"),
+                        DialogueOption::reply("Let's talk about something else.", "start"),
+                        DialogueOption::exit("I'll come back later."),
+                    ],
+                    next: None,
+                    tags: vec!["shrug".to_string()],
+                }
+            ),
+            (
+                "business".to_string(),
+                DialogueNode {
+                    text: "Well, the floating cubes are my best customers! Kidding aside, I'm just here for dialogue testing.".to_string(),
+                    options: vec![
+                        DialogueOption::reply("What do you sell?", "wares")
+                            .with_condition(condition("$shop_open")),
+                        DialogueOption::reply("Let's talk about something else.", "start"),
+                        DialogueOption::exit("I see. Goodbye!"),
+                    ],
+                    next: None,
+                    tags: Vec::new(),
+                }
+            ),
+        ].into_iter().collect(),
+        ambient: vec![
+            "Paperclips! Get your paperclips!".to_string(),
+            "Prices are fair, I promise.".to_string(),
+            "Business is slow this {time_of_day}.".to_string(),
+        ],
+    }
+}
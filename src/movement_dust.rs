@@ -0,0 +1,63 @@
+use crate::{
+    GameStateSet, Landed,
+    footsteps::Footstep,
+    input_map::{ActionState, InputAction},
+    particles::ParticleBurst,
+};
+use bevy::prelude::*;
+
+// Movement dust constants
+const DUST_COLOR: Color = Color::srgb(0.65, 0.6, 0.5);
+const DUST_LIFETIME: f32 = 0.6;
+const DUST_SIZE: f32 = 0.12;
+const SPRINT_DUST_COUNT: usize = 4;
+const SPRINT_DUST_SPEED: f32 = 1.0;
+const LANDING_DUST_COUNT: usize = 6; // Before scaling up with how hard the player came down
+const LANDING_DUST_SPEED: f32 = 2.5;
+
+pub struct MovementDustPlugin;
+
+impl Plugin for MovementDustPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (landing_dust, sprint_dust).in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+fn landing_dust(mut landings: EventReader<Landed>, mut bursts: EventWriter<ParticleBurst>) {
+    for landing in landings.read() {
+        let strength = 1.0 + landing.strength();
+        bursts.send(ParticleBurst {
+            position: landing.position,
+            count: (LANDING_DUST_COUNT as f32 * strength) as usize,
+            color: DUST_COLOR,
+            speed: LANDING_DUST_SPEED * strength,
+            lifetime: DUST_LIFETIME,
+            size: DUST_SIZE,
+        });
+    }
+}
+
+// Walking steps are quiet enough without dust, so only sprinting kicks any up
+fn sprint_dust(
+    actions: Res<ActionState>,
+    mut footsteps: EventReader<Footstep>,
+    mut bursts: EventWriter<ParticleBurst>,
+) {
+    let sprinting = actions.pressed(InputAction::Sprint);
+    for footstep in footsteps.read() {
+        if !sprinting {
+            continue;
+        }
+        bursts.send(ParticleBurst {
+            position: footstep.position,
+            count: SPRINT_DUST_COUNT,
+            color: DUST_COLOR,
+            speed: SPRINT_DUST_SPEED,
+            lifetime: DUST_LIFETIME,
+            size: DUST_SIZE,
+        });
+    }
+}
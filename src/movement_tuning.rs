@@ -0,0 +1,139 @@
+use crate::{input_map::controls_menu_closed, ron_asset::RonAssetLoader};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use bevy_rapier3d::control::KinematicCharacterController;
+use serde::Deserialize;
+
+// Movement tuning constants
+const MOVEMENT_TUNING_PATH: &str = "player.movement.ron";
+
+pub struct MovementTuningPlugin;
+
+impl Plugin for MovementTuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<MovementTuning>()
+            .register_asset_loader(RonAssetLoader::<MovementTuning>::new(&["movement.ron"]))
+            .init_resource::<MovementTuning>()
+            .add_systems(Startup, load_movement_tuning)
+            .add_systems(
+                Update,
+                (
+                    apply_movement_tuning_asset,
+                    // Shown next to the controls window, which frees the cursor
+                    movement_tuning_ui.run_if(not(controls_menu_closed)),
+                    apply_slope_limit,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// How the player's movement feels, loaded from `player.movement.ron` and tweakable in game
+#[derive(Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default)]
+pub struct MovementTuning {
+    // Walking speed, doubled when sprinting
+    pub move_speed: f32,
+    // How quickly the player gets up to speed and comes to a stop, in meters per second squared
+    pub acceleration: f32,
+    pub deceleration: f32,
+    // Fraction of acceleration available in the air
+    pub air_control: f32,
+    // Steepest slope that can be walked up, in degrees
+    pub max_slope: f32,
+    // Height of a standing jump, in meters
+    pub jump_height: f32,
+    // Multiplier on real-world gravity
+    pub gravity_scale: f32,
+}
+
+impl Default for MovementTuning {
+    fn default() -> Self {
+        Self {
+            move_speed: 8.0,
+            acceleration: 60.0,
+            deceleration: 80.0,
+            air_control: 0.3,
+            max_slope: 45.0,
+            jump_height: 4.0,
+            gravity_scale: 5.0,
+        }
+    }
+}
+
+impl MovementTuning {
+    // Downward acceleration, negative like `GRAVITY`
+    pub fn gravity(&self, base: f32) -> f32 {
+        base * self.gravity_scale
+    }
+
+    // Launch speed that peaks at `jump_height` under this gravity
+    pub fn jump_speed(&self, base_gravity: f32) -> f32 {
+        (2.0 * -self.gravity(base_gravity) * self.jump_height)
+            .max(0.0)
+            .sqrt()
+    }
+}
+
+#[derive(Resource)]
+struct MovementTuningHandle(Handle<MovementTuning>);
+
+fn load_movement_tuning(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(MovementTuningHandle(
+        asset_server.load(MOVEMENT_TUNING_PATH),
+    ));
+}
+
+// Take the asset's values whenever it loads, and again on each save while hot reload is on
+fn apply_movement_tuning_asset(
+    mut events: EventReader<AssetEvent<MovementTuning>>,
+    assets: Res<Assets<MovementTuning>>,
+    handle: Option<Res<MovementTuningHandle>>,
+    mut tuning: ResMut<MovementTuning>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        if let Some(loaded) = assets.get(&handle.0) {
+            *tuning = loaded.clone();
+        }
+    }
+}
+
+fn movement_tuning_ui(mut contexts: EguiContexts, mut tuning: ResMut<MovementTuning>) {
+    egui::Window::new("Movement tuning").show(contexts.ctx_mut(), |ui| {
+        egui::Grid::new("movement_tuning").show(ui, |ui| {
+            let slider = |ui: &mut egui::Ui, label: &str, value: &mut f32, range| {
+                ui.label(label);
+                ui.add(egui::Slider::new(value, range));
+                ui.end_row();
+            };
+            slider(ui, "Move speed", &mut tuning.move_speed, 1.0..=20.0);
+            slider(ui, "Acceleration", &mut tuning.acceleration, 1.0..=200.0);
+            slider(ui, "Deceleration", &mut tuning.deceleration, 1.0..=200.0);
+            slider(ui, "Air control", &mut tuning.air_control, 0.0..=1.0);
+            slider(ui, "Max slope", &mut tuning.max_slope, 0.0..=89.0);
+            slider(ui, "Jump height", &mut tuning.jump_height, 0.0..=10.0);
+            slider(ui, "Gravity scale", &mut tuning.gravity_scale, 0.1..=10.0);
+        });
+        if ui.button("Reset to defaults").clicked() {
+            *tuning = MovementTuning::default();
+        }
+    });
+}
+
+fn apply_slope_limit(
+    tuning: Res<MovementTuning>,
+    mut controllers: Query<&mut KinematicCharacterController>,
+) {
+    if !tuning.is_changed() {
+        return;
+    }
+    for mut controller in controllers.iter_mut() {
+        controller.max_slope_climb_angle = tuning.max_slope.to_radians();
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    ActiveDialogue, Npc, ambient_dialogue::AmbientLine, ron_asset::RonAssetLoader,
+    usable_props::Seated,
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::time::Duration;
+
+// NPC animation constants
+const NPC_ANIMATION_PATH: &str = "npcs.animation.ron";
+const SPEED_SMOOTHING: f32 = 10.0; // How quickly the measured speed follows the actual one
+
+pub struct NpcAnimationPlugin;
+
+impl Plugin for NpcAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<NpcAnimationSettings>()
+            .register_asset_loader(RonAssetLoader::<NpcAnimationSettings>::new(&[
+                "animation.ron",
+            ]))
+            .init_resource::<NpcAnimationSettings>()
+            .add_systems(Startup, load_npc_animation_settings)
+            .add_systems(
+                Update,
+                (
+                    apply_npc_animation_asset,
+                    talk_on_ambient_lines,
+                    update_npc_animation_state,
+                    play_npc_animations,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum NpcAnimationState {
+    #[default]
+    Idle,
+    Walk,
+    Talk,
+    Sit,
+}
+
+// How long to cross-fade when going from one state to another
+#[derive(Clone, Deserialize)]
+pub struct AnimationTransition {
+    pub from: NpcAnimationState,
+    pub to: NpcAnimationState,
+    // Seconds
+    pub blend: f32,
+}
+
+// How NPCs pick and blend their animations, loaded from `npcs.animation.ron`
+#[derive(Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default)]
+pub struct NpcAnimationSettings {
+    // Blend used for any transition not listed below
+    pub default_blend: f32,
+    pub transitions: Vec<AnimationTransition>,
+    // Slowest an NPC can move and still count as walking, in meters per second
+    pub walk_speed: f32,
+    // Seconds an NPC keeps talking after an ambient line
+    pub bark_duration: f32,
+}
+
+impl Default for NpcAnimationSettings {
+    fn default() -> Self {
+        Self {
+            default_blend: 0.25,
+            transitions: Vec::new(),
+            walk_speed: 0.2,
+            bark_duration: 2.0,
+        }
+    }
+}
+
+impl NpcAnimationSettings {
+    pub fn blend_time(&self, from: NpcAnimationState, to: NpcAnimationState) -> Duration {
+        let seconds = self
+            .transitions
+            .iter()
+            .find(|transition| transition.from == from && transition.to == to)
+            .map_or(self.default_blend, |transition| transition.blend);
+        Duration::from_secs_f32(seconds.max(0.0))
+    }
+}
+
+// Component choosing which animation an NPC should be playing
+#[derive(Component, Default)]
+pub struct NpcAnimationController {
+    pub state: NpcAnimationState,
+    // Smoothed horizontal speed, measured from how far the NPC moved
+    pub speed: f32,
+    last_position: Option<Vec3>,
+    // Time left talking after an ambient line
+    barking: f32,
+    // The state the animation player was last told to play
+    playing: Option<NpcAnimationState>,
+}
+
+// Component with the clips for each state, for NPCs with an animated model
+#[derive(Component, Clone)]
+pub struct NpcAnimations {
+    pub graph: Handle<AnimationGraph>,
+    pub idle: AnimationNodeIndex,
+    pub walk: AnimationNodeIndex,
+    pub talk: AnimationNodeIndex,
+    pub sit: AnimationNodeIndex,
+}
+
+impl NpcAnimations {
+    fn node(&self, state: NpcAnimationState) -> AnimationNodeIndex {
+        match state {
+            NpcAnimationState::Idle => self.idle,
+            NpcAnimationState::Walk => self.walk,
+            NpcAnimationState::Talk => self.talk,
+            NpcAnimationState::Sit => self.sit,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct NpcAnimationSettingsHandle(Handle<NpcAnimationSettings>);
+
+fn load_npc_animation_settings(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(NpcAnimationSettingsHandle(
+        asset_server.load(NPC_ANIMATION_PATH),
+    ));
+}
+
+// Take the asset's values whenever it loads, and again on each save while hot reload is on
+fn apply_npc_animation_asset(
+    mut events: EventReader<AssetEvent<NpcAnimationSettings>>,
+    assets: Res<Assets<NpcAnimationSettings>>,
+    handle: Option<Res<NpcAnimationSettingsHandle>>,
+    mut settings: ResMut<NpcAnimationSettings>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        if let Some(loaded) = assets.get(&handle.0) {
+            *settings = loaded.clone();
+        }
+    }
+}
+
+fn talk_on_ambient_lines(
+    settings: Res<NpcAnimationSettings>,
+    mut lines: EventReader<AmbientLine>,
+    mut controllers: Query<&mut NpcAnimationController>,
+) {
+    for line in lines.read() {
+        if let Ok(mut controller) = controllers.get_mut(line.npc_entity) {
+            controller.barking = settings.bark_duration;
+        }
+    }
+}
+
+// Talking wins over sitting, sitting over moving, and moving over standing around
+fn update_npc_animation_state(
+    time: Res<Time>,
+    settings: Res<NpcAnimationSettings>,
+    active_dialogue: Query<&ActiveDialogue>,
+    mut npcs: Query<(Entity, &Transform, &mut NpcAnimationController, Has<Seated>), With<Npc>>,
+) {
+    let delta_time = time.delta_secs();
+    if delta_time <= 0.0 {
+        return;
+    }
+    let talking_to = active_dialogue
+        .get_single()
+        .ok()
+        .map(|active_dialogue| active_dialogue.npc_entity);
+    for (entity, transform, mut controller, seated) in npcs.iter_mut() {
+        let position = transform.translation;
+        let moved = controller
+            .last_position
+            .map_or(0.0, |last| (position - last).with_y(0.0).length());
+        controller.last_position = Some(position);
+        let blend = (SPEED_SMOOTHING * delta_time).min(1.0);
+        controller.speed += (moved / delta_time - controller.speed) * blend;
+        controller.barking = (controller.barking - delta_time).max(0.0);
+
+        controller.state = if talking_to == Some(entity) || controller.barking > 0.0 {
+            NpcAnimationState::Talk
+        } else if seated {
+            NpcAnimationState::Sit
+        } else if controller.speed >= settings.walk_speed {
+            NpcAnimationState::Walk
+        } else {
+            NpcAnimationState::Idle
+        };
+    }
+}
+
+// Cross-fade the model's animation player whenever the state changes
+fn play_npc_animations(
+    mut commands: Commands,
+    settings: Res<NpcAnimationSettings>,
+    mut npcs: Query<(Entity, &mut NpcAnimationController, &NpcAnimations)>,
+    children: Query<&Children>,
+    mut players: Query<(&mut AnimationPlayer, Option<&mut AnimationTransitions>)>,
+) {
+    for (entity, mut controller, animations) in npcs.iter_mut() {
+        if controller.playing == Some(controller.state) {
+            continue;
+        }
+        // GLTF scenes put the animation player somewhere down the hierarchy, once they've spawned
+        let Some(player_entity) = children
+            .iter_descendants(entity)
+            .find(|descendant| players.contains(*descendant))
+        else {
+            continue;
+        };
+        let Ok((mut player, transitions)) = players.get_mut(player_entity) else {
+            continue;
+        };
+        let Some(mut transitions) = transitions else {
+            // Play from the next frame on, once the graph and transitions are in place
+            commands.entity(player_entity).insert((
+                AnimationGraphHandle(animations.graph.clone()),
+                AnimationTransitions::new(),
+            ));
+            continue;
+        };
+        let blend = controller.playing.map_or(Duration::ZERO, |from| {
+            settings.blend_time(from, controller.state)
+        });
+        transitions
+            .play(&mut player, animations.node(controller.state), blend)
+            .repeat();
+        controller.playing = Some(controller.state);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    GameStateSet, Npc, PLAYER_BORDER_RADIUS, PLAYER_RADIUS,
+    ai_lod::{AiLod, AiLodLevel},
+    companions::Following,
+    npc_rng::NpcRng,
+    update_npcs,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+
+// NPC avoidance constants
+const NPC_RADIUS: f32 = 0.5;
+const AVOIDANCE_RADIUS: f32 = 2.0; // Neighbours closer than this start steering away
+const SEPARATION_SPEED: f32 = 1.2; // Fastest an NPC sidesteps a crowded neighbour
+const YIELD_DISTANCE: f32 = 1.5; // How far ahead the player has to be to block an NPC's path
+const YIELD_PAUSE: f32 = 1.0; // Seconds an NPC waits for the player to move before stepping aside
+const SIDESTEP_DISTANCE: f32 = 1.5;
+const YIELD_GIVE_UP: f32 = 4.0; // Seconds before an NPC that's still blocked goes somewhere else
+
+pub struct NpcAvoidancePlugin;
+
+impl Plugin for NpcAvoidancePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                yield_to_player.before(update_npcs),
+                avoid_neighbours.after(update_npcs),
+            )
+                .before(PhysicsSet::SyncBackend)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// Component on an NPC waiting for the player to get out of its way
+#[derive(Component)]
+pub struct Yielding {
+    // Where the NPC was headed before it was blocked
+    resume: Vec3,
+    elapsed: f32,
+    sidestepped: bool,
+}
+
+// Something an NPC steers around
+struct Obstacle {
+    entity: Entity,
+    position: Vec3,
+    radius: f32,
+    // Share of any overlap this NPC backs off by, since the player is never pushed
+    give_way: f32,
+}
+
+// Steer NPCs apart as they get close, and never let them end up inside each other or the player
+#[allow(clippy::type_complexity)]
+pub fn avoid_neighbours(
+    time: Res<Time>,
+    player: Query<(Entity, &Transform), (With<KinematicCharacterController>, Without<Npc>)>,
+    mut npcs: Query<(Entity, &mut Transform, &Visibility, &AiLod), With<Npc>>,
+) {
+    // Hidden NPCs are away somewhere, not standing in the crowd
+    let mut obstacles: Vec<Obstacle> = npcs
+        .iter()
+        .filter(|(_, _, visibility, _)| **visibility != Visibility::Hidden)
+        .map(|(entity, transform, _, _)| Obstacle {
+            entity,
+            position: transform.translation,
+            radius: NPC_RADIUS,
+            give_way: 0.5,
+        })
+        .collect();
+    obstacles.extend(player.iter().map(|(entity, transform)| Obstacle {
+        entity,
+        position: transform.translation,
+        radius: PLAYER_RADIUS + PLAYER_BORDER_RADIUS,
+        give_way: 1.0,
+    }));
+
+    let delta_time = time.delta_secs();
+    for (entity, mut transform, visibility, lod) in npcs.iter_mut() {
+        // Sleeping NPCs still get stepped around, but don't do any stepping themselves
+        if *visibility == Visibility::Hidden || lod.level == AiLodLevel::Asleep {
+            continue;
+        }
+        let mut steering = Vec3::ZERO;
+        let mut correction = Vec3::ZERO;
+        for obstacle in obstacles
+            .iter()
+            .filter(|obstacle| obstacle.entity != entity)
+        {
+            let offset = (transform.translation - obstacle.position).with_y(0.0);
+            let distance = offset.length();
+            if distance >= AVOIDANCE_RADIUS || distance <= f32::EPSILON {
+                continue;
+            }
+            let away = offset / distance;
+            // Closer neighbours push harder
+            steering += away * (1.0 - distance / AVOIDANCE_RADIUS);
+            let overlap = NPC_RADIUS + obstacle.radius - distance;
+            if overlap > 0.0 {
+                correction += away * overlap * obstacle.give_way;
+            }
+        }
+        transform.translation +=
+            steering.clamp_length_max(1.0) * SEPARATION_SPEED * delta_time + correction;
+    }
+}
+
+// Whether the player stands in the way of an NPC walking from `position` to `target`
+fn blocked_by(player: Vec3, position: Vec3, target: Vec3) -> bool {
+    let path = (target - position).with_y(0.0);
+    let length = path.length();
+    if length <= f32::EPSILON {
+        return false;
+    }
+    let direction = path / length;
+    let to_player = (player - position).with_y(0.0);
+    let ahead = to_player.dot(direction);
+    let aside = (to_player - direction * ahead).length();
+    ahead > 0.0
+        && ahead < YIELD_DISTANCE.min(length + NPC_RADIUS)
+        && aside < NPC_RADIUS + PLAYER_RADIUS + PLAYER_BORDER_RADIUS
+}
+
+// Stop for a player in the way, then step aside, and find somewhere else to go if they stay put
+pub fn yield_to_player(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut rng: ResMut<NpcRng>,
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<Npc>)>,
+    mut npcs: Query<(Entity, &Transform, &mut Npc, Option<&mut Yielding>), Without<Following>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let player = player.translation;
+    let rng = &mut rng.0;
+    for (entity, transform, mut npc, yielding) in npcs.iter_mut() {
+        let position = transform.translation;
+        let Some(mut yielding) = yielding else {
+            if blocked_by(player, position, npc.target_position) {
+                commands.entity(entity).insert(Yielding {
+                    resume: npc.target_position,
+                    elapsed: 0.0,
+                    sidestepped: false,
+                });
+                // Standing on its own target keeps the NPC still
+                npc.target_position = position;
+            }
+            continue;
+        };
+
+        yielding.elapsed += time.delta_secs();
+        if !blocked_by(player, position, yielding.resume) {
+            npc.target_position = yielding.resume;
+            commands.entity(entity).remove::<Yielding>();
+        } else if yielding.elapsed >= YIELD_GIVE_UP {
+            npc.pick_wander_target(rng);
+            commands.entity(entity).remove::<Yielding>();
+        } else if yielding.elapsed >= YIELD_PAUSE && !yielding.sidestepped {
+            // Step to whichever side of the path is further from the player
+            let path = (yielding.resume - position).with_y(0.0).normalize_or_zero();
+            let mut aside = Vec3::Y.cross(path);
+            if aside.dot(player - position) > 0.0 {
+                aside = -aside;
+            }
+            npc.target_position = position + aside * SIDESTEP_DISTANCE;
+            yielding.sidestepped = true;
+        }
+    }
+}
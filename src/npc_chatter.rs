@@ -0,0 +1,104 @@
+use crate::{ActiveDialogue, GameStateSet, Npc, npc_rng::NpcRng};
+use bevy::{audio::Volume, prelude::*};
+use bevy_rapier3d::control::KinematicCharacterController;
+use rand::Rng;
+use serde::Deserialize;
+
+// NPC chatter constants
+const CHATTER_RANGE: f32 = 12.0; // Mumbling further away than this isn't worth playing
+const CHATTER_VOLUME: f32 = 0.6;
+const VOICE_PITCH_RANGE: f32 = 0.15; // Each NPC's voice sits this far above or below normal
+const PITCH_VARIATION: f32 = 0.05; // And each line wobbles a little on top of that
+
+pub struct NpcChatterPlugin;
+
+impl Plugin for NpcChatterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, play_chatter.in_set(GameStateSet::Playing));
+    }
+}
+
+// Which mumbles an archetype makes and how often, set in `npcs.spawn.ron`
+#[derive(Clone, Deserialize)]
+pub struct ChatterConfig {
+    // Paths under `assets/`, one picked at random each time
+    pub clips: Vec<String>,
+    // Range of seconds to wait between clips
+    pub cooldown: (f32, f32),
+}
+
+// Component for NPCs that mumble to themselves now and then
+#[derive(Component)]
+pub struct Chatter {
+    clips: Vec<Handle<AudioSource>>,
+    cooldown: (f32, f32),
+    // So two villagers with the same clips don't sound like the same person
+    pitch: f32,
+    // Seconds until the next clip
+    remaining: f32,
+}
+
+impl Chatter {
+    pub fn new(config: &ChatterConfig, asset_server: &AssetServer, rng: &mut impl Rng) -> Self {
+        let (low, high) = config.cooldown;
+        Self {
+            clips: config
+                .clips
+                .iter()
+                .map(|path| asset_server.load(path))
+                .collect(),
+            cooldown: config.cooldown,
+            pitch: 1.0 + rng.random_range(-VOICE_PITCH_RANGE..=VOICE_PITCH_RANGE),
+            // Start partway through so a crowd doesn't all speak up at once
+            remaining: rng.random_range(0.0..=high.max(low)),
+        }
+    }
+}
+
+// Play a random clip from each nearby NPC's mouth once its cooldown is up
+fn play_chatter(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut rng: ResMut<NpcRng>,
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<Npc>)>,
+    active_dialogue: Query<&ActiveDialogue>,
+    mut npcs: Query<(Entity, &Transform, &Visibility, &mut Chatter), With<Npc>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let talking_to = active_dialogue
+        .get_single()
+        .ok()
+        .map(|active_dialogue| active_dialogue.npc_entity);
+    let rng = &mut rng.0;
+
+    for (entity, transform, visibility, mut chatter) in npcs.iter_mut() {
+        chatter.remaining -= time.delta_secs();
+        if chatter.remaining > 0.0 {
+            continue;
+        }
+        let (low, high) = chatter.cooldown;
+        chatter.remaining = rng.random_range(low..=high.max(low));
+
+        // Out of earshot, away, or busy talking to the player
+        if *visibility == Visibility::Hidden
+            || talking_to == Some(entity)
+            || transform.translation.distance(player.translation) > CHATTER_RANGE
+            || chatter.clips.is_empty()
+        {
+            continue;
+        }
+        let clip = chatter.clips[rng.random_range(0..chatter.clips.len())].clone();
+        let speed = chatter.pitch + rng.random_range(-PITCH_VARIATION..=PITCH_VARIATION);
+        // A child of the NPC, so the voice moves with them
+        commands.entity(entity).with_child((
+            AudioPlayer(clip),
+            PlaybackSettings::DESPAWN
+                .with_volume(Volume::new(CHATTER_VOLUME))
+                .with_speed(speed)
+                .with_spatial(true),
+            Transform::default(),
+        ));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{
+    GRAVITY, GameStateSet, Npc,
+    ai_lod::{AiLod, AiLodLevel},
+    companions::follow_player,
+    npc_avoidance::avoid_neighbours,
+    platforms::{PlatformMotion, track_platform_motion},
+    update_npcs,
+    usable_props::Seated,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+// NPC grounding constants
+const NPC_HALF_HEIGHT: f32 = 1.0;
+const FOOT_RADIUS: f32 = 0.4; // Narrower than the NPC so walls beside it aren't mistaken for floor
+const STEP_HEIGHT: f32 = 0.5; // Tallest ledge an NPC walks up without stopping
+const SNAP_DISTANCE: f32 = 0.3; // Drops shallower than this are walked down rather than fallen off
+const SAFE_DROP: f32 = 2.0; // Ledges higher than this are turned back from instead of walked off
+const FALL_LIMIT: f32 = -20.0; // NPCs that fall this far go back home
+
+pub struct NpcGroundingPlugin;
+
+impl Plugin for NpcGroundingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            ground_npcs
+                .after(update_npcs)
+                .after(avoid_neighbours)
+                .after(follow_player)
+                .after(track_platform_motion)
+                .before(PhysicsSet::SyncBackend)
+                .in_set(GameStateSet::WorldMotion),
+        );
+    }
+}
+
+// Component with how fast an NPC is falling, zero while it stands on something
+#[derive(Component, Default)]
+pub struct NpcFall {
+    pub speed: f32,
+    // Sideways speed kept from a moving platform after stepping or falling off it
+    drift: Vec3,
+    // Where the NPC last stood, to back up to if it walks toward a drop
+    footing: Option<Vec3>,
+}
+
+// Sweep a ball down from a step above the feet to find the floor, and stand on it or fall toward it,
+// riding along with it if it's a moving platform
+fn ground_npcs(
+    time: Res<Time>,
+    rapier_context: ReadRapierContext,
+    // Seated NPCs are held on their bench instead
+    mut npcs: Query<(&mut Transform, &mut NpcFall, &mut Npc, &AiLod), Without<Seated>>,
+    platforms: Query<&PlatformMotion>,
+) {
+    let physics = rapier_context.single();
+    let delta_time = time.delta_secs();
+    // Only the level itself and moving platforms count as ground, not other NPCs or the player
+    let level = QueryFilter::only_fixed().exclude_sensors();
+    let is_platform = |entity: Entity| platforms.contains(entity);
+    let platform = QueryFilter::only_kinematic()
+        .exclude_sensors()
+        .predicate(&is_platform);
+    let foot = Collider::ball(FOOT_RADIUS);
+    // Highest floor under the position, and what it belongs to
+    let find_ground = |position: Vec3, drop: f32| {
+        let origin = position.with_y(position.y - NPC_HALF_HEIGHT + STEP_HEIGHT + FOOT_RADIUS);
+        let options = ShapeCastOptions {
+            max_time_of_impact: STEP_HEIGHT + drop,
+            target_distance: 0.0,
+            stop_at_penetration: false,
+            compute_impact_geometry_on_penetration: false,
+        };
+        [level, platform]
+            .into_iter()
+            .filter_map(|filter| {
+                physics.cast_shape(origin, Quat::IDENTITY, Vec3::NEG_Y, &foot, options, filter)
+            })
+            .min_by(|(_, a), (_, b)| a.time_of_impact.total_cmp(&b.time_of_impact))
+            .map(|(entity, hit)| (entity, origin.y - hit.time_of_impact - FOOT_RADIUS))
+    };
+
+    for (mut transform, mut fall, mut npc, lod) in npcs.iter_mut() {
+        // Sleeping NPCs aren't going anywhere
+        if lod.level == AiLodLevel::Asleep {
+            continue;
+        }
+        let fall_speed = fall.speed - GRAVITY * delta_time;
+        let drop = SNAP_DISTANCE.max(fall_speed * delta_time);
+        let mut ground = find_ground(transform.translation, drop);
+
+        // Back away from ledges instead of stepping off into the void
+        if ground.is_none()
+            && let Some(footing) = fall.footing
+            && find_ground(transform.translation, SAFE_DROP).is_none()
+        {
+            transform.translation = footing;
+            npc.target_position = footing;
+            ground = find_ground(footing, drop);
+        }
+
+        match ground {
+            Some((entity, ground)) => {
+                transform.translation.y = ground + NPC_HALF_HEIGHT;
+                fall.speed = 0.0;
+                fall.drift = Vec3::ZERO;
+                // Platforms carry whoever's standing on them
+                if let Ok(motion) = platforms.get(entity) {
+                    let carried = motion.carry(transform.translation);
+                    fall.drift = (carried - transform.translation).with_y(0.0) / delta_time;
+                    transform.translation = carried;
+                    npc.target_position = motion.carry(npc.target_position);
+                    transform.rotation = motion.rotation * transform.rotation;
+                }
+                fall.footing = Some(transform.translation);
+            }
+            None => {
+                transform.translation += fall.drift * delta_time;
+                transform.translation.y -= fall_speed * delta_time;
+                fall.speed = fall_speed;
+                fall.footing = None;
+            }
+        }
+
+        if transform.translation.y < FALL_LIMIT {
+            transform.translation = npc.home_position;
+            fall.speed = 0.0;
+            fall.drift = Vec3::ZERO;
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    ActiveDialogue, GameState, GameStateSet, Npc,
+    companions::Following,
+    dialogue_tags::DialogueTagTriggered,
+    npc_avoidance::{Yielding, yield_to_player},
+    patrols::walk_patrol_routes,
+    quests::{DialogueAction, DialogueActionTriggered},
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use serde::{Deserialize, Serialize};
+
+// NPC memory constants
+const SUSPICION_DURATION: f32 = 120.0; // Seconds a suspicious NPC keeps watching the player
+const TRAIL_RANGE: f32 = 25.0; // Further than this, a suspicious NPC loses track of the player
+const TRAIL_DISTANCE: f32 = 6.0; // Close enough to watch, far enough not to be obvious
+
+pub struct NpcMemoryPlugin;
+
+impl Plugin for NpcMemoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InDialogue), count_conversations)
+            .add_systems(Update, (remember_tags, remember_quests))
+            .add_systems(
+                FixedUpdate,
+                trail_player
+                    .after(walk_patrol_routes)
+                    .before(yield_to_player)
+                    .before(PhysicsSet::SyncBackend)
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Component with what an NPC remembers about its dealings with the player
+#[derive(Component, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NpcMemory {
+    pub times_talked: u32,
+    pub player_rude: bool,
+    // Quests this NPC has handed the player
+    pub quests_given: Vec<String>,
+    // Seconds left keeping an eye on the player
+    pub suspicion: f32,
+    // Things the NPC has been told, or heard on the grapevine
+    #[serde(default)]
+    pub rumors: Vec<String>,
+}
+
+impl NpcMemory {
+    pub fn is_suspicious(&self) -> bool {
+        self.suspicion > 0.0
+    }
+
+    pub fn hear(&mut self, rumor: &str) {
+        if !self.rumors.iter().any(|known| known == rumor) {
+            self.rumors.push(rumor.to_string());
+        }
+    }
+}
+
+fn count_conversations(
+    active_dialogue: Query<&ActiveDialogue>,
+    mut memories: Query<&mut NpcMemory>,
+) {
+    let Ok(active_dialogue) = active_dialogue.get_single() else {
+        return;
+    };
+    if let Ok(mut memory) = memories.get_mut(active_dialogue.npc_entity) {
+        memory.times_talked += 1;
+    }
+}
+
+// Dialogue marks what's worth remembering with `@rude` and `@suspicious` tags on the NPC's reply
+fn remember_tags(
+    mut events: EventReader<DialogueTagTriggered>,
+    mut memories: Query<&mut NpcMemory>,
+) {
+    for event in events.read() {
+        let Ok(mut memory) = memories.get_mut(event.npc_entity) else {
+            continue;
+        };
+        match event.tag.as_str() {
+            "rude" => memory.player_rude = true,
+            "suspicious" => memory.suspicion = SUSPICION_DURATION,
+            _ => {}
+        }
+    }
+}
+
+fn remember_quests(
+    mut events: EventReader<DialogueActionTriggered>,
+    mut memories: Query<&mut NpcMemory>,
+) {
+    for event in events.read() {
+        let DialogueAction::StartQuest(id) = &event.action else {
+            continue;
+        };
+        if let Ok(mut memory) = memories.get_mut(event.npc_entity)
+            && !memory.quests_given.contains(id)
+        {
+            memory.quests_given.push(id.clone());
+        }
+    }
+}
+
+// Suspicious NPCs drop what they were doing to shadow the player from a few meters back
+#[allow(clippy::type_complexity)]
+fn trail_player(
+    time: Res<Time>,
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<Npc>)>,
+    mut npcs: Query<
+        (&Transform, &mut Npc, &mut NpcMemory),
+        (Without<Following>, Without<Yielding>),
+    >,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    for (transform, mut npc, mut memory) in npcs.iter_mut() {
+        if !memory.is_suspicious() {
+            continue;
+        }
+        memory.suspicion -= time.delta_secs();
+        if !memory.is_suspicious() {
+            // Lost interest, so head back home
+            npc.target_position = npc.home_position;
+            continue;
+        }
+        let away = (transform.translation - player.translation).with_y(0.0);
+        if away.length() > TRAIL_RANGE {
+            continue;
+        }
+        npc.target_position = player.translation.with_y(transform.translation.y)
+            + away.normalize_or_zero() * TRAIL_DISTANCE;
+    }
+}
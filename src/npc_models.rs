@@ -0,0 +1,225 @@
+use crate::{Npc, head_look::HeadLook, npc_animation::NpcAnimations};
+use bevy::{gltf::GltfMesh, prelude::*, render::mesh::MeshAabb};
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+// NPC model constants
+const NPC_HALF_HEIGHT: f32 = 1.0;
+const MIN_COLLIDER_RADIUS: f32 = 0.2; // Thin models still need something to bump into
+
+pub struct NpcModelsPlugin;
+
+impl Plugin for NpcModelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (attach_npc_models, tint_npc_models).chain());
+    }
+}
+
+// A character model for an archetype, set in `npcs.spawn.ron`
+#[derive(Clone, Deserialize)]
+pub struct NpcModelConfig {
+    // GLTF file under `assets/`
+    pub path: String,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    // Base color tints, one picked per NPC so a crowd isn't all twins
+    #[serde(default)]
+    pub tints: Vec<(f32, f32, f32)>,
+    #[serde(default)]
+    pub animations: NpcModelAnimations,
+    // Bone turned to look at the player
+    #[serde(default = "default_head_bone")]
+    pub head_bone: String,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_head_bone() -> String {
+    "Head".to_string()
+}
+
+// Names of the GLTF's animations to play in each state
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct NpcModelAnimations {
+    pub idle: String,
+    pub walk: String,
+    pub talk: String,
+    pub sit: String,
+}
+
+impl Default for NpcModelAnimations {
+    fn default() -> Self {
+        Self {
+            idle: "Idle".to_string(),
+            walk: "Walk".to_string(),
+            talk: "Talk".to_string(),
+            sit: "Sit".to_string(),
+        }
+    }
+}
+
+// Component for an NPC still showing its placeholder while the model loads
+#[derive(Component)]
+pub struct NpcModel {
+    gltf: Handle<Gltf>,
+    config: NpcModelConfig,
+    tint: Option<Color>,
+}
+
+impl NpcModel {
+    pub fn new(config: NpcModelConfig, asset_server: &AssetServer, rng: &mut impl Rng) -> Self {
+        let tint = (!config.tints.is_empty()).then(|| {
+            let (red, green, blue) = config.tints[rng.random_range(0..config.tints.len())];
+            Color::srgb(red, green, blue)
+        });
+        Self {
+            gltf: asset_server.load(&config.path),
+            config,
+            tint,
+        }
+    }
+}
+
+// Component on a spawned model whose materials haven't been tinted yet
+#[derive(Component)]
+struct ModelTint(Color);
+
+// Smallest box around every mesh in the model, in the model's own space
+fn model_bounds(
+    gltf: &Gltf,
+    gltf_meshes: &Assets<GltfMesh>,
+    meshes: &Assets<Mesh>,
+) -> Option<(Vec3, Vec3)> {
+    gltf.meshes
+        .iter()
+        .filter_map(|handle| gltf_meshes.get(handle))
+        .flat_map(|gltf_mesh| &gltf_mesh.primitives)
+        .filter_map(|primitive| meshes.get(&primitive.mesh)?.compute_aabb())
+        .map(|aabb| (Vec3::from(aabb.min()), Vec3::from(aabb.max())))
+        .reduce(|(min, max), (other_min, other_max)| (min.min(other_min), max.max(other_max)))
+}
+
+// Swap the placeholder cylinder for the model once it's loaded, sizing the collider to fit it
+fn attach_npc_models(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    gltfs: Res<Assets<Gltf>>,
+    gltf_meshes: Res<Assets<GltfMesh>>,
+    meshes: Res<Assets<Mesh>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+    npcs: Query<(Entity, &Npc, &NpcModel)>,
+) {
+    for (entity, npc, model) in npcs.iter() {
+        if asset_server.load_state(&model.gltf).is_failed() {
+            println!(
+                "Error: Couldn't load model {} for {}, keeping the placeholder",
+                model.config.path, npc.name
+            );
+            commands.entity(entity).remove::<NpcModel>();
+            continue;
+        }
+        if !asset_server.is_loaded_with_dependencies(&model.gltf) {
+            continue;
+        }
+        let Some(gltf) = gltfs.get(&model.gltf) else {
+            continue;
+        };
+        let Some(scene) = gltf
+            .default_scene
+            .clone()
+            .or_else(|| gltf.scenes.first().cloned())
+        else {
+            println!(
+                "Error: Model {} has no scene, keeping the placeholder",
+                model.config.path
+            );
+            commands.entity(entity).remove::<NpcModel>();
+            continue;
+        };
+
+        // Stand the model's feet on the ground, with a collider wrapped around it
+        let scale = model.config.scale;
+        let (min, max) = model_bounds(gltf, &gltf_meshes, &meshes)
+            .unwrap_or((Vec3::splat(-NPC_HALF_HEIGHT), Vec3::splat(NPC_HALF_HEIGHT)));
+        let (min, max) = (min * scale, max * scale);
+        let half_height = (max.y - min.y) / 2.0;
+        let radius = ((max.x - min.x).max(max.z - min.z) / 2.0).max(MIN_COLLIDER_RADIUS);
+        let collider = Collider::compound(vec![(
+            Vec3::Y * (half_height - NPC_HALF_HEIGHT),
+            Quat::IDENTITY,
+            Collider::cylinder(half_height, radius),
+        )]);
+
+        let mut npc_commands = commands.entity(entity);
+        npc_commands
+            .remove::<(NpcModel, Mesh3d, MeshMaterial3d<StandardMaterial>)>()
+            .insert((collider, HeadLook::new(model.config.head_bone.clone())))
+            .with_children(|parent| {
+                let mut model_commands = parent.spawn((
+                    SceneRoot(scene),
+                    Transform::from_xyz(0.0, -NPC_HALF_HEIGHT - min.y, 0.0)
+                        .with_scale(Vec3::splat(scale)),
+                ));
+                if let Some(tint) = model.tint {
+                    model_commands.insert(ModelTint(tint));
+                }
+            });
+
+        // Models without an idle clip just stand there, and any other missing clip falls back to idle
+        let names = &model.config.animations;
+        let clip = |name: &str| gltf.named_animations.get(name).cloned();
+        if let Some(idle) = clip(&names.idle) {
+            let clips = [&names.walk, &names.talk, &names.sit]
+                .map(|name| clip(name).unwrap_or_else(|| idle.clone()));
+            let (graph, nodes) = AnimationGraph::from_clips([idle].into_iter().chain(clips));
+            npc_commands.insert(NpcAnimations {
+                graph: graphs.add(graph),
+                idle: nodes[0],
+                walk: nodes[1],
+                talk: nodes[2],
+                sit: nodes[3],
+            });
+        }
+    }
+}
+
+// Give each NPC its own tinted copy of the model's materials once the scene has spawned
+fn tint_npc_models(
+    mut commands: Commands,
+    models: Query<(Entity, &ModelTint)>,
+    children: Query<&Children>,
+    mut mesh_materials: Query<&mut MeshMaterial3d<StandardMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, tint) in models.iter() {
+        let descendants: Vec<Entity> = children
+            .iter_descendants(entity)
+            .filter(|descendant| mesh_materials.contains(*descendant))
+            .collect();
+        if descendants.is_empty() {
+            continue;
+        }
+        for descendant in descendants {
+            let Ok(mut mesh_material) = mesh_materials.get_mut(descendant) else {
+                continue;
+            };
+            let Some(mut material) = materials.get(&mesh_material.0).cloned() else {
+                continue;
+            };
+            let (base, tint) = (material.base_color.to_linear(), tint.0.to_linear());
+            material.base_color = LinearRgba::new(
+                base.red * tint.red,
+                base.green * tint.green,
+                base.blue * tint.blue,
+                base.alpha,
+            )
+            .into();
+            mesh_material.0 = materials.add(material);
+        }
+        commands.entity(entity).remove::<ModelTint>();
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{
+    ActiveDialogue, GameState, GameStateSet, Npc,
+    clock::GameClock,
+    companions::Following,
+    dialogue_variables::{DialogueValue, DialogueVariables},
+    flee::Fleeing,
+    hostiles::Aggro,
+    merchant_stalls::Shop,
+    npc_avoidance::yield_to_player,
+    setup_dialogue_ui, update_npcs,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+// NPC needs constants
+const HUNGRY: f32 = 0.7; // Hunger at which an NPC goes looking for food
+const TIRED: f32 = 0.25; // Energy at which an NPC heads home to rest
+const REST_RATE: f32 = 0.3; // Energy regained per game hour while resting at home
+const EAT_DISTANCE: f32 = 2.5;
+const HOME_REACHED: f32 = 1.0;
+
+pub struct NpcNeedsPlugin;
+
+impl Plugin for NpcNeedsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::InDialogue),
+            expose_needs.before(setup_dialogue_ui),
+        )
+        .add_systems(
+            Update,
+            (decay_needs, start_errands)
+                .chain()
+                .in_set(GameStateSet::Playing),
+        )
+        .add_systems(
+            FixedUpdate,
+            run_errands
+                .after(yield_to_player)
+                .before(update_npcs)
+                .before(PhysicsSet::SyncBackend)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// How quickly an archetype gets hungry and tired, set in `npcs.spawn.ron`
+#[derive(Clone, Copy, Deserialize)]
+pub struct NeedsConfig {
+    // Per game hour, from full to starving at 1
+    pub hunger_rate: f32,
+    // Per game hour, from rested at 1 to exhausted at 0
+    pub fatigue_rate: f32,
+}
+
+// Component with how hungry and tired an NPC is
+#[derive(Component, Clone)]
+pub struct Needs {
+    config: NeedsConfig,
+    pub hunger: f32,
+    pub energy: f32,
+}
+
+impl Needs {
+    // Everyone starts a little different, so they don't all go for lunch together
+    pub fn new(config: NeedsConfig, rng: &mut impl Rng) -> Self {
+        Self {
+            config,
+            hunger: rng.random_range(0.0..0.5),
+            energy: rng.random_range(0.6..=1.0),
+        }
+    }
+
+    // How the NPC answers when asked how they're doing
+    fn feeling(&self) -> &'static str {
+        match (self.hunger >= HUNGRY, self.energy <= TIRED) {
+            (true, true) => "I'm starving… and dead on my feet. Food first, then bed.",
+            (true, false) => "I'm starving… I was just on my way to the market for a bite.",
+            (false, true) => "I'm worn out. I'll be heading home for a rest soon.",
+            (false, false) => "Can't complain! Fed, rested and ready for anything.",
+        }
+    }
+}
+
+// Component on an NPC that has dropped what it was doing to see to a need
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Errand {
+    Eat,
+    Rest,
+}
+
+fn decay_needs(time: Res<Time>, clock: Res<GameClock>, mut npcs: Query<&mut Needs>) {
+    let hours = time.delta_secs() * clock.hours_per_second;
+    for mut needs in npcs.iter_mut() {
+        needs.hunger = (needs.hunger + needs.config.hunger_rate * hours).min(1.0);
+        needs.energy = (needs.energy - needs.config.fatigue_rate * hours).max(0.0);
+    }
+}
+
+// Hunger comes before sleep, and NPCs busy with the player or trouble wait until they're free
+#[allow(clippy::type_complexity)]
+fn start_errands(
+    mut commands: Commands,
+    npcs: Query<
+        (Entity, &Npc, &Needs),
+        (
+            Without<Errand>,
+            Without<Following>,
+            Without<Fleeing>,
+            Without<Aggro>,
+        ),
+    >,
+) {
+    for (entity, npc, needs) in npcs.iter() {
+        let errand = if needs.hunger >= HUNGRY {
+            Errand::Eat
+        } else if needs.energy <= TIRED {
+            Errand::Rest
+        } else {
+            continue;
+        };
+        let plan = match errand {
+            Errand::Eat => "get something to eat",
+            Errand::Rest => "rest",
+        };
+        println!("{} is off to {plan}", npc.name);
+        commands.entity(entity).insert(errand);
+    }
+}
+
+// Walk to the nearest open stall to eat, or home to rest until rested
+#[allow(clippy::type_complexity)]
+fn run_errands(
+    mut commands: Commands,
+    time: Res<Time>,
+    clock: Res<GameClock>,
+    mut npcs: Query<
+        (Entity, &Transform, &mut Npc, &mut Needs, &Errand),
+        (Without<Following>, Without<Fleeing>, Without<Aggro>),
+    >,
+    shops: Query<(&Transform, &Npc, &Shop), Without<Errand>>,
+) {
+    let hours = time.delta_secs() * clock.hours_per_second;
+    for (entity, transform, mut npc, mut needs, errand) in npcs.iter_mut() {
+        let position = transform.translation;
+        match errand {
+            Errand::Eat => {
+                let nearest =
+                    shops
+                        .iter()
+                        .filter(|(_, _, shop)| shop.open)
+                        .min_by(|(a, _, _), (b, _, _)| {
+                            let a = a.translation.distance_squared(position);
+                            let b = b.translation.distance_squared(position);
+                            a.total_cmp(&b)
+                        });
+                // Nowhere open, so carry on as usual and try again later
+                let Some((stall, merchant, _)) = nearest else {
+                    continue;
+                };
+                let offset = (stall.translation - position).with_y(0.0);
+                if offset.length() <= EAT_DISTANCE {
+                    println!("{} grabbed a bite from {}", npc.name, merchant.name);
+                    needs.hunger = 0.0;
+                    npc.target_position = position;
+                    commands.entity(entity).remove::<Errand>();
+                } else {
+                    // Stop at the counter rather than walking into the merchant
+                    npc.target_position =
+                        stall.translation - offset.normalize() * (EAT_DISTANCE * 0.8);
+                }
+            }
+            Errand::Rest => {
+                let home = npc.home_position;
+                if (home - position).with_y(0.0).length() > HOME_REACHED {
+                    npc.target_position = home;
+                    continue;
+                }
+                npc.target_position = position;
+                needs.energy =
+                    (needs.energy + (REST_RATE + needs.config.fatigue_rate) * hours).min(1.0);
+                if needs.energy >= 1.0 {
+                    println!("{} is rested", npc.name);
+                    commands.entity(entity).remove::<Errand>();
+                }
+            }
+        }
+    }
+}
+
+// Dialogue can mention how the NPC is doing with `{npc_feeling}` or check `$npc_hungry` and `$npc_tired`
+fn expose_needs(
+    active_dialogue: Query<&ActiveDialogue>,
+    needs: Query<&Needs>,
+    mut variables: ResMut<DialogueVariables>,
+) {
+    let Ok(active_dialogue) = active_dialogue.get_single() else {
+        return;
+    };
+    let needs = needs.get(active_dialogue.npc_entity).ok();
+    variables.set(
+        "npc_hungry",
+        DialogueValue::Bool(needs.is_some_and(|needs| needs.hunger >= HUNGRY)),
+    );
+    variables.set(
+        "npc_tired",
+        DialogueValue::Bool(needs.is_some_and(|needs| needs.energy <= TIRED)),
+    );
+    variables.set(
+        "npc_feeling",
+        DialogueValue::Text(
+            needs
+                .map_or("Fine, thanks for asking.", Needs::feeling)
+                .to_string(),
+        ),
+    );
+}
@@ -0,0 +1,142 @@
+use crate::{
+    GameStateSet, Npc,
+    ambient_dialogue::AmbientLine,
+    companions::Following,
+    factions::{Faction, FactionStandings, Relationship},
+    flee::Fleeing,
+    npc_memory::NpcMemory,
+    npc_rng::NpcRng,
+    update_npcs,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use rand::Rng;
+
+// NPC reaction constants
+const GREET_DISTANCE: f32 = 3.0;
+const GREET_DURATION: f32 = 2.0; // How long an NPC stands facing the player
+const GREET_COOLDOWN: f32 = 30.0; // Before the same NPC greets the player again
+const GREET_TURN_SPEED: f32 = 6.0;
+const GREETINGS: [&str; 4] = ["Hello there.", "Oh, hi!", "Good to see you.", "Hey."];
+const FAMILIAR_GREETINGS: [&str; 3] = ["Hello again!", "Oh, it's you again.", "Back so soon?"];
+const QUEST_GREETINGS: [&str; 2] = ["Any news for me?", "How's that job coming along?"];
+const COLD_GREETINGS: [&str; 3] = ["Hmph.", "I've got my eye on you.", "Oh. You."];
+
+pub struct NpcReactionsPlugin;
+
+impl Plugin for NpcReactionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            react_to_player
+                .before(update_npcs)
+                .before(PhysicsSet::SyncBackend)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// What an NPC is doing about the player being nearby
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NpcReaction {
+    #[default]
+    Wandering,
+    // Stopped and turned toward the player for a few seconds
+    Greeting {
+        remaining: f32,
+    },
+    // Back to wandering, but won't greet again until this runs out
+    Cooldown {
+        remaining: f32,
+    },
+}
+
+impl NpcReaction {
+    // Whether wandering should wait until the reaction is over
+    pub fn holds_still(self) -> bool {
+        matches!(self, NpcReaction::Greeting { .. })
+    }
+}
+
+// NPCs who remember the player say hello differently
+fn greetings_for(memory: &NpcMemory) -> &'static [&'static str] {
+    if memory.player_rude || memory.is_suspicious() {
+        &COLD_GREETINGS
+    } else if !memory.quests_given.is_empty() {
+        &QUEST_GREETINGS
+    } else if memory.times_talked > 0 {
+        &FAMILIAR_GREETINGS
+    } else {
+        &GREETINGS
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn react_to_player(
+    time: Res<Time>,
+    mut rng: ResMut<NpcRng>,
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<Npc>)>,
+    standings: Res<FactionStandings>,
+    mut npcs: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Npc,
+            &Visibility,
+            &Faction,
+            &NpcMemory,
+        ),
+        (Without<Following>, Without<Fleeing>),
+    >,
+    mut lines: EventWriter<AmbientLine>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let delta_time = time.delta_secs();
+    let rng = &mut rng.0;
+    for (entity, mut transform, mut npc, visibility, faction, memory) in npcs.iter_mut() {
+        let to_player = (player.translation - transform.translation).with_y(0.0);
+        // NPCs whose faction has soured on the player don't bother saying hello
+        let friendly = standings.player_relationship(*faction) >= Relationship::Neutral;
+        npc.reaction = match npc.reaction {
+            NpcReaction::Wandering
+                if friendly
+                    && *visibility != Visibility::Hidden
+                    && to_player.length() <= GREET_DISTANCE =>
+            {
+                let greetings = greetings_for(memory);
+                lines.send(AmbientLine {
+                    npc_entity: entity,
+                    text: greetings[rng.random_range(0..greetings.len())].to_string(),
+                });
+                NpcReaction::Greeting {
+                    remaining: GREET_DURATION,
+                }
+            }
+            NpcReaction::Greeting { remaining } => {
+                if to_player != Vec3::ZERO {
+                    let facing = Quat::from_rotation_y(f32::atan2(to_player.x, to_player.z));
+                    let blend = (GREET_TURN_SPEED * delta_time).min(1.0);
+                    transform.rotation = transform.rotation.slerp(facing, blend);
+                }
+                if remaining > delta_time {
+                    NpcReaction::Greeting {
+                        remaining: remaining - delta_time,
+                    }
+                } else {
+                    NpcReaction::Cooldown {
+                        remaining: GREET_COOLDOWN,
+                    }
+                }
+            }
+            NpcReaction::Cooldown { remaining } if remaining > delta_time => {
+                NpcReaction::Cooldown {
+                    remaining: remaining - delta_time,
+                }
+            }
+            NpcReaction::Cooldown { .. } => NpcReaction::Wandering,
+            reaction => reaction,
+        };
+    }
+}
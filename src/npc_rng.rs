@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use rand::{SeedableRng, rngs::StdRng};
+
+// NPC randomness constants
+const SEED_VARIABLE: &str = "PAPERCLIPS_SEED"; // Set to replay a run with the same NPC behavior
+
+pub struct NpcRngPlugin;
+
+impl Plugin for NpcRngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = std::env::var(SEED_VARIABLE)
+            .ok()
+            .and_then(|seed| match seed.parse() {
+                Ok(seed) => Some(seed),
+                Err(_) => {
+                    println!("Error: {SEED_VARIABLE} should be a whole number, not {seed}");
+                    None
+                }
+            })
+            .unwrap_or_else(rand::random);
+        println!("NPC seed: {seed} (set {SEED_VARIABLE}={seed} to replay it)");
+        app.insert_resource(NpcRng::new(seed));
+    }
+}
+
+// Resource every NPC system draws its random numbers from, so a seed reproduces a whole run
+#[derive(Resource)]
+pub struct NpcRng(pub StdRng);
+
+impl NpcRng {
+    pub fn new(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
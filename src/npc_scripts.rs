@@ -0,0 +1,277 @@
+use crate::{
+    DialogueDatabase, GameState, GameStateSet, Npc,
+    ai_lod::{AiLod, AiLodLevel, update_ai_lod},
+    clock::GameClock,
+    perception::Perception,
+    start_dialogue, update_npcs,
+};
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use rhai::{AST, Dynamic, Engine, Map, ParseError, Scope};
+use std::fmt;
+
+// NPC script constants
+const SCRIPT_MAX_OPERATIONS: u64 = 100_000; // Stops a runaway loop from freezing the game
+
+pub struct NpcScriptsPlugin;
+
+impl Plugin for NpcScriptsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<NpcScriptAsset>()
+            .init_asset_loader::<NpcScriptLoader>()
+            .init_resource::<ScriptEngine>()
+            .add_systems(Update, restart_reloaded_scripts)
+            .add_systems(
+                FixedUpdate,
+                run_npc_scripts
+                    .after(update_ai_lod)
+                    .before(update_npcs)
+                    .before(PhysicsSet::SyncBackend)
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// A compiled behavior script, loaded from an `.npc.rhai` file
+#[derive(Asset, TypePath)]
+pub struct NpcScriptAsset {
+    ast: AST,
+}
+
+#[derive(Default)]
+pub struct NpcScriptLoader;
+
+impl AssetLoader for NpcScriptLoader {
+    type Asset = NpcScriptAsset;
+    type Settings = ();
+    type Error = NpcScriptError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<NpcScriptAsset, NpcScriptError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let source = String::from_utf8(bytes).map_err(|_| NpcScriptError::InvalidUtf8)?;
+        Ok(NpcScriptAsset {
+            ast: Engine::new().compile(source)?,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["npc.rhai"]
+    }
+}
+
+#[derive(Debug)]
+pub enum NpcScriptError {
+    Io(std::io::Error),
+    InvalidUtf8,
+    Parse(ParseError),
+}
+
+impl fmt::Display for NpcScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NpcScriptError::Io(error) => write!(f, "could not read NPC script: {error}"),
+            NpcScriptError::InvalidUtf8 => write!(f, "NPC script is not valid UTF-8"),
+            NpcScriptError::Parse(error) => write!(f, "could not compile NPC script: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for NpcScriptError {}
+
+impl From<std::io::Error> for NpcScriptError {
+    fn from(error: std::io::Error) -> Self {
+        NpcScriptError::Io(error)
+    }
+}
+
+impl From<ParseError> for NpcScriptError {
+    fn from(error: ParseError) -> Self {
+        NpcScriptError::Parse(error)
+    }
+}
+
+// Component running a script each step in place of the NPC's usual wandering
+#[derive(Component)]
+pub struct NpcScript {
+    handle: Handle<NpcScriptAsset>,
+    // The script's `state` map, kept from one step to the next
+    state: Map,
+    // Set once the script errors, until it's fixed on disk
+    broken: bool,
+}
+
+impl NpcScript {
+    pub fn new(path: &str, asset_server: &AssetServer) -> Self {
+        Self {
+            handle: asset_server.load(path.to_string()),
+            state: Map::new(),
+            broken: false,
+        }
+    }
+}
+
+// What a script sees of its NPC as `npc`, and what it asks the NPC to do
+#[derive(Clone)]
+struct ScriptNpc {
+    name: String,
+    position: Vec3,
+    home: Vec3,
+    target: Vec3,
+    player_position: Vec3,
+    sees_player: bool,
+    last_known_position: Option<Vec3>,
+    hour: f32,
+    delta: f32,
+    start_dialogue: bool,
+}
+
+// The scripting engine with the NPC API registered
+#[derive(Resource)]
+struct ScriptEngine(Engine);
+
+impl Default for ScriptEngine {
+    fn default() -> Self {
+        let mut engine = Engine::new();
+        engine.set_max_operations(SCRIPT_MAX_OPERATIONS);
+
+        engine
+            .register_type_with_name::<Vec3>("Vec3")
+            .register_fn("vec3", Vec3::new)
+            .register_get("x", |vector: &mut Vec3| vector.x)
+            .register_get("y", |vector: &mut Vec3| vector.y)
+            .register_get("z", |vector: &mut Vec3| vector.z)
+            .register_fn("+", |a: Vec3, b: Vec3| a + b)
+            .register_fn("-", |a: Vec3, b: Vec3| a - b)
+            .register_fn("*", |vector: Vec3, scale: f32| vector * scale)
+            .register_fn("distance", |a: &mut Vec3, b: Vec3| a.distance(b))
+            .register_fn("to_string", |vector: &mut Vec3| vector.to_string());
+
+        engine
+            .register_type_with_name::<ScriptNpc>("Npc")
+            // Perception
+            .register_get("name", |npc: &mut ScriptNpc| npc.name.clone())
+            .register_get("position", |npc: &mut ScriptNpc| npc.position)
+            .register_get("home", |npc: &mut ScriptNpc| npc.home)
+            .register_get("target", |npc: &mut ScriptNpc| npc.target)
+            .register_get("player_position", |npc: &mut ScriptNpc| npc.player_position)
+            .register_get("distance_to_player", |npc: &mut ScriptNpc| {
+                npc.position.distance(npc.player_position)
+            })
+            .register_get("sees_player", |npc: &mut ScriptNpc| npc.sees_player)
+            // Unit when the player hasn't been seen or heard
+            .register_get("last_known_position", |npc: &mut ScriptNpc| {
+                npc.last_known_position.map_or(Dynamic::UNIT, Dynamic::from)
+            })
+            .register_get("hour", |npc: &mut ScriptNpc| npc.hour)
+            .register_get("delta", |npc: &mut ScriptNpc| npc.delta)
+            // Movement
+            .register_fn("move_to", |npc: &mut ScriptNpc, target: Vec3| {
+                npc.target = target;
+            })
+            .register_fn("go_home", |npc: &mut ScriptNpc| npc.target = npc.home)
+            .register_fn("stop", |npc: &mut ScriptNpc| npc.target = npc.position)
+            // Dialogue
+            .register_fn("start_dialogue", |npc: &mut ScriptNpc| {
+                npc.start_dialogue = true;
+            });
+
+        Self(engine)
+    }
+}
+
+// Run each scripted NPC's script with `npc` and `state` in scope, then carry out what it asked for
+#[allow(clippy::too_many_arguments)]
+fn run_npc_scripts(
+    mut commands: Commands,
+    time: Res<Time>,
+    clock: Res<GameClock>,
+    engine: Res<ScriptEngine>,
+    scripts: Res<Assets<NpcScriptAsset>>,
+    dialogue_db: Res<DialogueDatabase>,
+    mut next_state: ResMut<NextState<GameState>>,
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<Npc>)>,
+    mut npcs: Query<(
+        Entity,
+        &Transform,
+        &mut Npc,
+        &mut NpcScript,
+        &Perception,
+        &AiLod,
+    )>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    // Only the first script to ask gets the conversation
+    let mut started_dialogue = false;
+
+    for (entity, transform, mut npc, mut script, perception, lod) in npcs.iter_mut() {
+        if script.broken || lod.level == AiLodLevel::Asleep {
+            continue;
+        }
+        let Some(asset) = scripts.get(&script.handle) else {
+            continue;
+        };
+
+        let mut scope = Scope::new();
+        scope.push(
+            "npc",
+            ScriptNpc {
+                name: npc.name.clone(),
+                position: transform.translation,
+                home: npc.home_position,
+                target: npc.target_position,
+                player_position: player.translation,
+                sees_player: perception.sees_player,
+                last_known_position: perception.last_known_position,
+                hour: clock.hour,
+                delta: time.delta_secs(),
+                start_dialogue: false,
+            },
+        );
+        scope.push("state", std::mem::take(&mut script.state));
+
+        if let Err(error) = engine.0.run_ast_with_scope(&mut scope, &asset.ast) {
+            println!("Error: Script for {} stopped: {error}", npc.name);
+            script.broken = true;
+            continue;
+        }
+        script.state = scope.get_value("state").unwrap_or_default();
+        let Some(result) = scope.get_value::<ScriptNpc>("npc") else {
+            continue;
+        };
+
+        npc.target_position = result.target;
+        if result.start_dialogue && !started_dialogue {
+            start_dialogue(&mut commands, &mut next_state, &dialogue_db, entity, &npc);
+            started_dialogue = true;
+        }
+    }
+}
+
+// Start edited scripts over from their own setup, giving any that errored another go
+fn restart_reloaded_scripts(
+    mut events: EventReader<AssetEvent<NpcScriptAsset>>,
+    mut scripts: Query<&mut NpcScript>,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        for mut script in scripts.iter_mut() {
+            if script.handle.id() == *id {
+                script.state.clear();
+                script.broken = false;
+            }
+        }
+    }
+}
@@ -0,0 +1,459 @@
+use crate::{
+    ActiveDialogue, NPC_WANDER_RADIUS, Npc,
+    companions::Following,
+    economy::Merchant,
+    factions::Faction,
+    flee::Fleeing,
+    hostiles::{Aggro, Hostile, HostileConfig},
+    level::WorldChunks,
+    merchant_stalls::{Shop, ShopConfig},
+    npc_chatter::{Chatter, ChatterConfig},
+    npc_memory::NpcMemory,
+    npc_models::{NpcModel, NpcModelConfig},
+    npc_needs::{Needs, NeedsConfig},
+    npc_reactions::NpcReaction,
+    npc_rng::NpcRng,
+    npc_scripts::NpcScript,
+    ron_asset::RonAssetLoader,
+    usable_props::{PropAnchor, PropSchedule, PropVisit, UsableProp},
+    utility_ai::{UtilityAi, UtilityWeights},
+    world_events::ScheduledPresence,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// NPC spawning constants
+const NPC_SPAWN_TABLE_PATH: &str = "npcs.spawn.ron";
+const NPC_HALF_HEIGHT: f32 = 1.0;
+const NPC_RADIUS: f32 = 0.5;
+const STREAM_IN_RADIUS: f32 = 60.0; // NPCs whose spot is this close to the player are kept in the world
+const STREAM_OUT_RADIUS: f32 = 80.0; // Further than this they're packed away until the player comes back
+
+pub struct NpcSpawningPlugin;
+
+impl Plugin for NpcSpawningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<NpcSpawnTable>()
+            .register_asset_loader(RonAssetLoader::<NpcSpawnTable>::new(&["spawn.ron"]))
+            .add_systems(Startup, load_npc_spawn_table)
+            .add_systems(Update, (build_npc_roster, stream_npcs).chain());
+    }
+}
+
+// Which NPCs live in the world and where, loaded from `npcs.spawn.ron`
+#[derive(Asset, TypePath, Deserialize)]
+pub struct NpcSpawnTable {
+    pub archetypes: HashMap<String, NpcArchetype>,
+    pub placements: Vec<NpcPlacement>,
+}
+
+#[derive(Deserialize)]
+pub struct NpcArchetype {
+    pub dialogue_id: String,
+    pub color: (f32, f32, f32),
+    // Given out in turn to each NPC of this archetype
+    pub names: Vec<String>,
+    // Range a merchant's markup is picked from, for archetypes that trade
+    #[serde(default)]
+    pub price_modifier: Option<(f32, f32)>,
+    // World event the NPC only shows up during
+    #[serde(default)]
+    pub scheduled_event: Option<String>,
+    // Scores what to do each step with these weights, instead of wandering on a timer
+    #[serde(default)]
+    pub utility: Option<UtilityWeights>,
+    // How quickly they get hungry and tired, for archetypes that go eat and rest
+    #[serde(default)]
+    pub needs: Option<NeedsConfig>,
+    // Behavior script under `assets/`, run every step instead of wandering
+    #[serde(default)]
+    pub script: Option<String>,
+    // Character model shown instead of the colored cylinder, once it loads
+    #[serde(default)]
+    pub model: Option<NpcModelConfig>,
+    // Mumbles played near the player now and then
+    #[serde(default)]
+    pub chatter: Option<ChatterConfig>,
+    // How enemies fight, for archetypes that attack the player
+    #[serde(default)]
+    pub hostile: Option<HostileConfig>,
+    // Opening hours and daily stock, for merchants who keep a stall
+    #[serde(default)]
+    pub shop: Option<ShopConfig>,
+    // Times of day spent sitting on benches or minding stalls
+    #[serde(default)]
+    pub prop_schedule: Vec<PropVisit>,
+}
+
+// A cluster of NPCs around one spot, so busy places can be crowded and quiet ones sparse
+#[derive(Deserialize)]
+pub struct NpcPlacement {
+    // Archetype ids and how big a share of the cluster each makes up
+    pub archetypes: Vec<(String, f32)>,
+    pub center: Vec3,
+    pub count: usize,
+    // How far from the center each NPC's home can be
+    #[serde(default = "default_scatter")]
+    pub scatter: f32,
+    // How far from home each NPC wanders
+    #[serde(default = "default_wander_radius")]
+    pub wander_radius: f32,
+}
+
+fn default_scatter() -> f32 {
+    5.0
+}
+
+fn default_wander_radius() -> f32 {
+    NPC_WANDER_RADIUS
+}
+
+impl NpcPlacement {
+    // Deal out archetypes one NPC at a time to whichever is furthest behind its share,
+    // so a mix comes out interleaved and in proportion
+    fn archetype_mix(&self) -> Vec<&str> {
+        let total: f32 = self
+            .archetypes
+            .iter()
+            .map(|(_, share)| share.max(0.0))
+            .sum();
+        if total <= 0.0 {
+            return Vec::new();
+        }
+        let mut dealt = vec![0.0; self.archetypes.len()];
+        (1..=self.count)
+            .map(|spawned| {
+                let mut behind = 0;
+                let mut most_behind = f32::MIN;
+                for (index, (_, share)) in self.archetypes.iter().enumerate() {
+                    let shortfall = spawned as f32 * share.max(0.0) / total - dealt[index];
+                    if shortfall > most_behind {
+                        behind = index;
+                        most_behind = shortfall;
+                    }
+                }
+                dealt[behind] += 1.0;
+                self.archetypes[behind].0.as_str()
+            })
+            .collect()
+    }
+}
+
+// Marker for NPCs that came from the spawn table, replaced whenever it's edited
+#[derive(Component)]
+struct SpawnedNpc;
+
+#[derive(Resource)]
+struct NpcSpawnTableHandle(Handle<NpcSpawnTable>);
+
+// Everyone the spawn table puts in the world, whether they're spawned right now or not
+#[derive(Resource)]
+struct NpcRoster {
+    entries: Vec<RosterEntry>,
+    mesh: Handle<Mesh>,
+    materials: HashMap<String, Handle<StandardMaterial>>,
+}
+
+struct RosterEntry {
+    archetype: String,
+    name: String,
+    home_position: Vec3,
+    wander_radius: f32,
+    price_modifier: Option<f32>,
+    // The NPC while it's in the world
+    entity: Option<Entity>,
+    // What the NPC was up to when it was last packed away
+    saved: Option<SavedNpc>,
+}
+
+impl RosterEntry {
+    // Where the NPC is, or was when it was packed away
+    fn position(&self) -> Vec3 {
+        self.saved
+            .as_ref()
+            .map_or(self.home_position, |saved| saved.transform.translation)
+    }
+}
+
+// The state worth keeping while an NPC is out of the world
+struct SavedNpc {
+    transform: Transform,
+    target_position: Vec3,
+    memory: NpcMemory,
+    needs: Option<Needs>,
+    shop: Option<Shop>,
+    anchor: Option<PropAnchor>,
+}
+
+fn load_npc_spawn_table(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(NpcSpawnTableHandle(asset_server.load(NPC_SPAWN_TABLE_PATH)));
+}
+
+// Roll everyone's home, name and markup up front, so they're the same each time they stream back in
+#[allow(clippy::too_many_arguments)]
+fn build_npc_roster(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<NpcSpawnTable>>,
+    tables: Res<Assets<NpcSpawnTable>>,
+    handle: Option<Res<NpcSpawnTableHandle>>,
+    mut rng: ResMut<NpcRng>,
+    spawned: Query<Entity, With<SpawnedNpc>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(table) = tables.get(&handle.0) else {
+            continue;
+        };
+        for entity in spawned.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let archetype_materials: HashMap<String, Handle<StandardMaterial>> = table
+            .archetypes
+            .iter()
+            .map(|(id, archetype)| {
+                let (red, green, blue) = archetype.color;
+                let material = materials.add(StandardMaterial {
+                    base_color: Color::srgb(red, green, blue),
+                    perceptual_roughness: 0.4,
+                    ..default()
+                });
+                (id.clone(), material)
+            })
+            .collect();
+        let mut name_counts: HashMap<&str, usize> = HashMap::new();
+        let rng = &mut rng.0;
+        let mut entries = Vec::new();
+
+        for placement in &table.placements {
+            for (id, _) in &placement.archetypes {
+                if !table.archetypes.contains_key(id) {
+                    println!("Error: No NPC archetype found with id: {id}");
+                }
+            }
+            for id in placement.archetype_mix() {
+                let Some(archetype) = table.archetypes.get(id) else {
+                    continue;
+                };
+                let scatter = Vec3::new(
+                    rng.random_range(-placement.scatter..=placement.scatter),
+                    0.0,
+                    rng.random_range(-placement.scatter..=placement.scatter),
+                );
+                let count = name_counts.entry(id).or_default();
+                let name = archetype
+                    .names
+                    .get(*count % archetype.names.len().max(1))
+                    .cloned()
+                    .unwrap_or_else(|| id.to_string());
+                *count += 1;
+
+                entries.push(RosterEntry {
+                    archetype: id.to_string(),
+                    name,
+                    home_position: (placement.center + scatter).with_y(NPC_HALF_HEIGHT),
+                    wander_radius: placement.wander_radius,
+                    price_modifier: archetype
+                        .price_modifier
+                        .map(|(low, high)| rng.random_range(low..=high)),
+                    entity: None,
+                    saved: None,
+                });
+            }
+        }
+
+        commands.insert_resource(NpcRoster {
+            entries,
+            mesh: meshes.add(Cylinder::new(NPC_RADIUS, NPC_HALF_HEIGHT * 2.0)),
+            materials: archetype_materials,
+        });
+    }
+}
+
+// Spawn NPCs as the player comes near and pack them away once the player is far off,
+// leaving alone anyone who's busy with the player
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn stream_npcs(
+    mut commands: Commands,
+    roster: Option<ResMut<NpcRoster>>,
+    tables: Res<Assets<NpcSpawnTable>>,
+    handle: Option<Res<NpcSpawnTableHandle>>,
+    mut rng: ResMut<NpcRng>,
+    asset_server: Res<AssetServer>,
+    chunks: Res<WorldChunks>,
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<Npc>)>,
+    active_dialogue: Query<&ActiveDialogue>,
+    npcs: Query<(
+        &Transform,
+        &Npc,
+        &NpcMemory,
+        Option<&Needs>,
+        Option<&Shop>,
+        Option<&PropAnchor>,
+        Has<Following>,
+        Has<Aggro>,
+        Has<Fleeing>,
+    )>,
+    mut props: Query<&mut UsableProp>,
+) {
+    let (Some(mut roster), Some(handle), Ok(player)) = (roster, handle, player.get_single()) else {
+        return;
+    };
+    let Some(table) = tables.get(&handle.0) else {
+        return;
+    };
+    let talking_to = active_dialogue
+        .get_single()
+        .ok()
+        .map(|active_dialogue| active_dialogue.npc_entity);
+    let rng = &mut rng.0;
+    let NpcRoster {
+        entries,
+        mesh,
+        materials,
+    } = roster.as_mut();
+
+    for entry in entries.iter_mut() {
+        let Some(entity) = entry.entity else {
+            // Wait for the ground under them to stream in too
+            if entry.position().distance(player.translation) > STREAM_IN_RADIUS
+                || !chunks.is_ready(entry.position())
+            {
+                continue;
+            }
+            let Some(archetype) = table.archetypes.get(&entry.archetype) else {
+                continue;
+            };
+            let material = materials[&entry.archetype].clone();
+            entry.entity = Some(spawn_npc(
+                &mut commands,
+                entry,
+                archetype,
+                mesh.clone(),
+                material,
+                &asset_server,
+                rng,
+            ));
+            continue;
+        };
+
+        let Ok((transform, npc, memory, needs, shop, anchor, following, aggro, fleeing)) =
+            npcs.get(entity)
+        else {
+            // Gone some other way, so bring it back fresh next time
+            entry.entity = None;
+            continue;
+        };
+        let busy = following || aggro || fleeing || talking_to == Some(entity);
+        if busy || transform.translation.distance(player.translation) <= STREAM_OUT_RADIUS {
+            continue;
+        }
+        entry.saved = Some(SavedNpc {
+            transform: *transform,
+            target_position: npc.target_position,
+            memory: memory.clone(),
+            needs: needs.cloned(),
+            shop: shop.cloned(),
+            anchor: anchor.copied(),
+        });
+        entry.entity = None;
+        // Free up whatever bench or stall they had claimed
+        for mut prop in props.iter_mut() {
+            if prop.occupant == Some(entity) {
+                prop.occupant = None;
+            }
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn spawn_npc(
+    commands: &mut Commands,
+    entry: &RosterEntry,
+    archetype: &NpcArchetype,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    asset_server: &AssetServer,
+    rng: &mut impl Rng,
+) -> Entity {
+    let mut npc = Npc {
+        home_position: entry.home_position,
+        target_position: entry.home_position,
+        movement_timer: Timer::from_seconds(rng.random_range(5.0..10.0), TimerMode::Once),
+        name: entry.name.clone(),
+        dialogue_id: archetype.dialogue_id.clone(),
+        reaction: NpcReaction::default(),
+        wander_radius: entry.wander_radius,
+    };
+    let mut transform = Transform::from_translation(entry.home_position);
+    match &entry.saved {
+        Some(saved) => {
+            transform = saved.transform;
+            npc.target_position = saved.target_position;
+        }
+        None => npc.pick_wander_target(rng),
+    }
+
+    let mut npc_commands = commands.spawn((
+        Mesh3d(mesh),
+        MeshMaterial3d(material),
+        transform,
+        Collider::cylinder(NPC_HALF_HEIGHT, NPC_RADIUS),
+        RigidBody::KinematicPositionBased,
+        npc,
+        Faction::for_dialogue(&archetype.dialogue_id),
+        SpawnedNpc,
+    ));
+    if let Some(price_modifier) = entry.price_modifier {
+        npc_commands.insert(Merchant { price_modifier });
+    }
+    if let Some(weights) = archetype.utility {
+        npc_commands.insert(UtilityAi::new(weights));
+    }
+    let saved_needs = entry.saved.as_ref().and_then(|saved| saved.needs.clone());
+    if let Some(needs) =
+        saved_needs.or_else(|| archetype.needs.map(|config| Needs::new(config, rng)))
+    {
+        npc_commands.insert(needs);
+    }
+    if let Some(path) = &archetype.script {
+        npc_commands.insert(NpcScript::new(path, asset_server));
+    }
+    if let Some(config) = &archetype.model {
+        npc_commands.insert(NpcModel::new(config.clone(), asset_server, rng));
+    }
+    if let Some(config) = &archetype.chatter {
+        npc_commands.insert(Chatter::new(config, asset_server, rng));
+    }
+    if let Some(config) = archetype.hostile {
+        npc_commands.insert(Hostile::new(config));
+    }
+    let saved_shop = entry.saved.as_ref().and_then(|saved| saved.shop.clone());
+    if let Some(shop) = saved_shop.or_else(|| archetype.shop.clone().map(Shop::new)) {
+        npc_commands.insert((shop.prop_schedule(), shop));
+    } else if !archetype.prop_schedule.is_empty() {
+        npc_commands.insert(PropSchedule(archetype.prop_schedule.clone()));
+    }
+    if let Some(event) = &archetype.scheduled_event {
+        npc_commands.insert(ScheduledPresence {
+            event: event.clone(),
+        });
+    }
+    if let Some(saved) = &entry.saved {
+        npc_commands.insert(saved.memory.clone());
+        if let Some(anchor) = saved.anchor {
+            npc_commands.insert(anchor);
+        }
+    }
+    npc_commands.id()
+}
@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use rand::Rng;
+use std::f32::consts::TAU;
+
+// Particle constants
+const PARTICLE_GRAVITY: f32 = 2.0; // Light enough for dust to hang in the air a moment
+const PARTICLE_DRAG: f32 = 3.0;
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ParticleBurst>()
+            .add_systems(Startup, setup_particle_mesh)
+            .add_systems(Update, (spawn_particle_bursts, update_particles).chain());
+    }
+}
+
+// Event asking for a puff of particles flung outward and upward from a point
+#[derive(Event, Clone, Copy)]
+pub struct ParticleBurst {
+    pub position: Vec3,
+    pub count: usize,
+    pub color: Color,
+    // Starting speed of each particle, randomized a little
+    pub speed: f32,
+    // Seconds before a particle has shrunk away
+    pub lifetime: f32,
+    pub size: f32,
+}
+
+// Component on a single particle, shrinking as it ages
+#[derive(Component)]
+struct Particle {
+    velocity: Vec3,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+}
+
+// Resource with the mesh every particle shares
+#[derive(Resource)]
+struct ParticleMesh(Handle<Mesh>);
+
+fn setup_particle_mesh(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(ParticleMesh(meshes.add(Sphere::new(0.5))));
+}
+
+fn spawn_particle_bursts(
+    mut commands: Commands,
+    mut bursts: EventReader<ParticleBurst>,
+    mesh: Res<ParticleMesh>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut rng = rand::rng();
+    for burst in bursts.read() {
+        // Particles shrink rather than fade, so a burst can share one opaque material
+        let material = materials.add(StandardMaterial {
+            base_color: burst.color,
+            perceptual_roughness: 1.0,
+            ..default()
+        });
+        for _ in 0..burst.count {
+            let angle = rng.random_range(0.0..TAU);
+            let direction = Vec3::new(angle.cos(), rng.random_range(0.2..0.8), angle.sin());
+            let speed = burst.speed * rng.random_range(0.5..1.0);
+            commands.spawn((
+                Mesh3d(mesh.0.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(burst.position).with_scale(Vec3::splat(burst.size)),
+                Particle {
+                    velocity: direction.normalize() * speed,
+                    age: 0.0,
+                    lifetime: burst.lifetime * rng.random_range(0.7..1.0),
+                    size: burst.size,
+                },
+            ));
+        }
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform)>,
+) {
+    let delta_time = time.delta_secs();
+    for (entity, mut particle, mut transform) in particles.iter_mut() {
+        particle.age += delta_time;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        particle.velocity.y -= PARTICLE_GRAVITY * delta_time;
+        particle.velocity *= 1.0 / (1.0 + PARTICLE_DRAG * delta_time);
+        transform.translation += particle.velocity * delta_time;
+        let remaining = 1.0 - particle.age / particle.lifetime;
+        transform.scale = Vec3::splat(particle.size * remaining);
+    }
+}
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use directories::ProjectDirs;
+use ron::ser::PrettyConfig;
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+// Identifiers the OS-specific locations are derived from
+const QUALIFIER: &str = "com";
+const ORGANIZATION: &str = "matthewjberger";
+const APPLICATION: &str = "paperclips";
+
+pub struct PathsPlugin;
+
+impl Plugin for PathsPlugin {
+    // Inserted while building so every other plugin's startup systems can rely on it
+    fn build(&self, app: &mut App) {
+        let paths = UserPaths::new();
+        paths.prepare();
+        app.insert_resource(paths);
+    }
+}
+
+// The kinds of user data the game writes to disk
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserDir {
+    Settings,
+    Logs,
+}
+
+impl UserDir {
+    const ALL: [UserDir; 2] = [UserDir::Settings, UserDir::Logs];
+
+    // Folder name, both under the OS location and for the old working-directory layout
+    fn folder(self) -> &'static str {
+        match self {
+            UserDir::Settings => "settings",
+            UserDir::Logs => "logs",
+        }
+    }
+}
+
+// Something saved as its own RON file in the settings folder
+pub trait SettingsFile: Serialize + DeserializeOwned {
+    const FILE: &'static str;
+}
+
+// Resource with the platform-appropriate location of each kind of user data
+#[derive(Resource, Clone, Debug)]
+pub struct UserPaths {
+    project: Option<ProjectDirs>,
+}
+
+impl UserPaths {
+    fn new() -> Self {
+        let project = ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION);
+        if project.is_none() {
+            println!("No home directory found, keeping user data next to the game");
+        }
+        Self { project }
+    }
+
+    pub fn dir(&self, kind: UserDir) -> PathBuf {
+        let Some(project) = &self.project else {
+            return legacy_dir(kind);
+        };
+        let base = match kind {
+            UserDir::Settings => project.config_dir(),
+            UserDir::Logs => project.data_local_dir(),
+        };
+        base.join(kind.folder())
+    }
+
+    // Where one of the game's settings files lives, moving it over from the old relative
+    // `settings` folder the first time it's asked for. Only files the game names are touched,
+    // anything else in a folder that happens to share the name is left alone.
+    pub fn settings_file(&self, name: &str) -> PathBuf {
+        let path = self.dir(UserDir::Settings).join(name);
+        let legacy = legacy_dir(UserDir::Settings).join(name);
+        if legacy != path && legacy.is_file() && !path.exists() {
+            match move_file(&legacy, &path) {
+                Ok(()) => println!("Moved {} to {}", legacy.display(), path.display()),
+                Err(error) => println!("Could not move {}: {error}", legacy.display()),
+            }
+        }
+        path
+    }
+
+    // The saved copy of some settings, if there is one and it still parses
+    pub fn load_settings<T: SettingsFile>(&self) -> Option<T> {
+        let path = self.settings_file(T::FILE);
+        let contents = fs::read_to_string(&path).ok()?;
+        ron::from_str(&contents)
+            .map_err(|error| println!("Ignoring invalid {}: {error}", path.display()))
+            .ok()
+    }
+
+    pub fn save_settings<T: SettingsFile>(&self, settings: &T) {
+        let path = self.settings_file(T::FILE);
+        let saved = ron::ser::to_string_pretty(settings, PrettyConfig::default())
+            .map_err(|error| error.to_string())
+            .and_then(|contents| fs::write(&path, contents).map_err(|error| error.to_string()));
+        if let Err(error) = saved {
+            println!("Could not save {}: {error}", path.display());
+        }
+    }
+
+    fn prepare(&self) {
+        for kind in UserDir::ALL {
+            let dir = self.dir(kind);
+            if let Err(error) = fs::create_dir_all(&dir) {
+                println!("Could not create {}: {error}", dir.display());
+            }
+        }
+    }
+}
+
+// Where older builds kept user data, relative to the working directory
+fn legacy_dir(kind: UserDir) -> PathBuf {
+    PathBuf::from(kind.folder())
+}
+
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    // Renaming fails across drives, so fall back to copying
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use crate::{
+    GameStateSet, Npc,
+    companions::Following,
+    factions::Faction,
+    npc_avoidance::{Yielding, yield_to_player},
+    ron_asset::RonAssetLoader,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+
+// Patrol constants
+const PATROL_ROUTES_PATH: &str = "guards.patrols.ron";
+const WAYPOINT_REACHED: f32 = 0.25;
+
+pub struct PatrolsPlugin;
+
+impl Plugin for PatrolsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<PatrolRoutes>()
+            .register_asset_loader(RonAssetLoader::<PatrolRoutes>::new(&["patrols.ron"]))
+            .add_systems(Startup, load_patrol_routes)
+            .add_systems(Update, assign_patrol_routes)
+            .add_systems(
+                FixedUpdate,
+                walk_patrol_routes
+                    .before(yield_to_player)
+                    .before(PhysicsSet::SyncBackend)
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Guard routes loaded from data, handed out to guards in order
+#[derive(Asset, TypePath, Deserialize)]
+pub struct PatrolRoutes {
+    pub routes: Vec<PatrolRoute>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub enum PatrolMode {
+    // Back to the first waypoint after the last
+    #[default]
+    Loop,
+    // Turn around at either end
+    PingPong,
+}
+
+// Component walking an NPC along waypoints instead of wandering
+#[derive(Component, Clone, Deserialize)]
+pub struct PatrolRoute {
+    pub waypoints: Vec<Vec3>,
+    #[serde(default)]
+    pub mode: PatrolMode,
+    // Seconds spent looking around at each waypoint
+    #[serde(default)]
+    pub wait: f32,
+    #[serde(skip)]
+    next: usize,
+    #[serde(skip)]
+    reversed: bool,
+    #[serde(skip)]
+    waited: f32,
+}
+
+impl PatrolRoute {
+    fn waypoint(&self) -> Option<Vec3> {
+        self.waypoints.get(self.next).copied()
+    }
+
+    fn advance(&mut self) {
+        let count = self.waypoints.len();
+        if count < 2 {
+            return;
+        }
+        match self.mode {
+            PatrolMode::Loop => self.next = (self.next + 1) % count,
+            PatrolMode::PingPong => {
+                if (self.reversed && self.next == 0) || (!self.reversed && self.next == count - 1) {
+                    self.reversed = !self.reversed;
+                }
+                self.next = if self.reversed {
+                    self.next - 1
+                } else {
+                    self.next + 1
+                };
+            }
+        }
+    }
+}
+
+#[derive(Resource)]
+struct PatrolRoutesHandle(Handle<PatrolRoutes>);
+
+fn load_patrol_routes(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PatrolRoutesHandle(asset_server.load(PATROL_ROUTES_PATH)));
+}
+
+// Give guards a route as they spawn, and every guard a fresh one when hot reload picks up edited routes
+fn assign_patrol_routes(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<PatrolRoutes>>,
+    assets: Res<Assets<PatrolRoutes>>,
+    handle: Option<Res<PatrolRoutesHandle>>,
+    guards: Query<(Entity, &Faction, Has<PatrolRoute>), With<Npc>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    let reloaded = events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0));
+    let Some(patrols) = assets.get(&handle.0) else {
+        return;
+    };
+    let guards = guards
+        .iter()
+        .filter(|(_, faction, _)| **faction == Faction::Guards);
+    for ((entity, _, patrolling), route) in guards.zip(patrols.routes.iter().cycle()) {
+        if reloaded || !patrolling {
+            commands.entity(entity).insert(route.clone());
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn walk_patrol_routes(
+    time: Res<Time>,
+    mut patrollers: Query<
+        (&Transform, &mut Npc, &mut PatrolRoute),
+        (Without<Following>, Without<Yielding>),
+    >,
+) {
+    for (transform, mut npc, mut route) in patrollers.iter_mut() {
+        let Some(waypoint) = route.waypoint() else {
+            continue;
+        };
+        // NPCs keep their own height whatever the waypoint's
+        let waypoint = waypoint.with_y(transform.translation.y);
+        if transform.translation.distance(waypoint) <= WAYPOINT_REACHED {
+            route.waited += time.delta_secs();
+            if route.waited >= route.wait {
+                route.waited = 0.0;
+                route.advance();
+            }
+        }
+        // Reasserted every step so yielding or a greeting never leaves a guard stranded off route
+        if let Some(next) = route.waypoint() {
+            npc.target_position = next.with_y(transform.translation.y);
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{
+    GameStateSet, Landed, Npc,
+    crouch::Crouch,
+    dialogue_variables::{DialogueValue, DialogueVariables},
+    footsteps::Footstep,
+    input_map::{ActionState, InputAction},
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+
+// Perception constants
+pub const SIGHT_RANGE: f32 = 15.0;
+pub const SIGHT_COS: f32 = 0.5; // Half-angle of the view cone, as a cosine
+pub const EYE_HEIGHT: f32 = 0.8; // Above the NPC's center, near the top of the cylinder
+const WALK_NOISE_RADIUS: f32 = 4.0;
+const SPRINT_NOISE_RADIUS: f32 = 12.0;
+const LANDING_NOISE_RADIUS: f32 = 0.5; // Per meter per second of fall speed
+
+pub struct PerceptionPlugin;
+
+impl Plugin for PerceptionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Noise>()
+            .add_event::<PerceivedPlayer>()
+            .add_event::<Commotion>()
+            .add_event::<PerceivedThreat>()
+            .add_systems(
+                Update,
+                (
+                    make_player_noise,
+                    hear_noises,
+                    turn_toward_sounds,
+                    look_for_player,
+                    remember_sightings,
+                    notice_commotion,
+                )
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Component with what an NPC currently knows about the player
+#[derive(Component, Default)]
+pub struct Perception {
+    pub sees_player: bool,
+    // Where the player was last seen or heard
+    pub last_known_position: Option<Vec3>,
+}
+
+// Event for a sound NPCs within `radius` can hear
+#[derive(Event, Clone, Copy)]
+pub struct Noise {
+    pub position: Vec3,
+    pub radius: f32,
+}
+
+// Event for a fight, alarm or other trouble NPCs within `radius` notice
+#[derive(Event, Clone, Copy)]
+pub struct Commotion {
+    pub position: Vec3,
+    pub radius: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sense {
+    Sight,
+    Hearing,
+}
+
+// Event sent when an NPC spots the player or hears them nearby
+#[derive(Event, Clone, Copy)]
+pub struct PerceivedPlayer {
+    pub npc: Entity,
+    pub sense: Sense,
+    pub position: Vec3,
+}
+
+// Event sent when an NPC notices trouble nearby
+#[derive(Event, Clone, Copy)]
+pub struct PerceivedThreat {
+    pub npc: Entity,
+    pub position: Vec3,
+}
+
+// Sprinting and hard landings carry further than walking, and sneaking makes no noise at all
+fn make_player_noise(
+    actions: Res<ActionState>,
+    player: Query<&Crouch>,
+    mut footsteps: EventReader<Footstep>,
+    mut landings: EventReader<Landed>,
+    mut noises: EventWriter<Noise>,
+) {
+    let crouched = player.get_single().is_ok_and(Crouch::is_crouched);
+    for footstep in footsteps.read() {
+        if crouched {
+            continue;
+        }
+        let radius = if actions.pressed(InputAction::Sprint) {
+            SPRINT_NOISE_RADIUS
+        } else {
+            WALK_NOISE_RADIUS
+        };
+        noises.send(Noise {
+            position: footstep.position,
+            radius,
+        });
+    }
+    for landing in landings.read() {
+        noises.send(Noise {
+            position: landing.position,
+            radius: landing.speed * LANDING_NOISE_RADIUS,
+        });
+    }
+}
+
+fn hear_noises(
+    mut noises: EventReader<Noise>,
+    mut npcs: Query<(Entity, &Transform, &Visibility, &mut Perception), With<Npc>>,
+    mut perceived: EventWriter<PerceivedPlayer>,
+) {
+    for noise in noises.read() {
+        for (entity, transform, visibility, mut perception) in npcs.iter_mut() {
+            // Hidden NPCs are away somewhere and can't hear anything here
+            if *visibility == Visibility::Hidden
+                || transform.translation.distance(noise.position) > noise.radius
+            {
+                continue;
+            }
+            perception.last_known_position = Some(noise.position);
+            perceived.send(PerceivedPlayer {
+                npc: entity,
+                sense: Sense::Hearing,
+                position: noise.position,
+            });
+        }
+    }
+}
+
+// A noise makes an NPC look its way, which may bring the player into view
+fn turn_toward_sounds(
+    mut perceived: EventReader<PerceivedPlayer>,
+    mut npcs: Query<&mut Transform, With<Npc>>,
+) {
+    for event in perceived.read() {
+        if event.sense != Sense::Hearing {
+            continue;
+        }
+        let Ok(mut transform) = npcs.get_mut(event.npc) else {
+            continue;
+        };
+        let direction = (event.position - transform.translation).with_y(0.0);
+        if direction != Vec3::ZERO {
+            transform.rotation = Quat::from_rotation_y(f32::atan2(direction.x, direction.z));
+        }
+    }
+}
+
+// Raycast from each NPC's eyes to the player if they're inside its view cone
+fn look_for_player(
+    rapier_context: ReadRapierContext,
+    player: Query<(Entity, &Transform), With<KinematicCharacterController>>,
+    mut npcs: Query<(Entity, &Transform, &Visibility, &mut Perception), With<Npc>>,
+    mut perceived: EventWriter<PerceivedPlayer>,
+) {
+    let Ok((player_entity, player_transform)) = player.get_single() else {
+        return;
+    };
+    let physics = rapier_context.single();
+    let target = player_transform.translation;
+    for (entity, transform, visibility, mut perception) in npcs.iter_mut() {
+        let eye = transform.translation + Vec3::Y * EYE_HEIGHT;
+        let to_player = target - eye;
+        let distance = to_player.length();
+        let direction = to_player / distance.max(f32::EPSILON);
+        // NPCs turn their +Z toward where they're walking
+        let forward = transform.rotation * Vec3::Z;
+        let in_view = *visibility != Visibility::Hidden
+            && distance <= SIGHT_RANGE
+            && direction.dot(forward) >= SIGHT_COS;
+
+        // Walls, cubes and other NPCs block the view
+        let sees_player = in_view && {
+            let filter = QueryFilter::default()
+                .exclude_collider(entity)
+                .exclude_sensors();
+            physics
+                .cast_ray(eye, direction, distance, true, filter)
+                .is_some_and(|(hit, _)| hit == player_entity)
+        };
+        if sees_player {
+            perception.last_known_position = Some(target);
+            // Only the moment of spotting is news, not every frame of watching
+            if !perception.sees_player {
+                perceived.send(PerceivedPlayer {
+                    npc: entity,
+                    sense: Sense::Sight,
+                    position: target,
+                });
+            }
+        }
+        perception.sees_player = sees_player;
+    }
+}
+
+// Dialogue can ask whether, say, a guard has ever laid eyes on the player
+fn remember_sightings(
+    mut perceived: EventReader<PerceivedPlayer>,
+    npcs: Query<&Npc>,
+    mut variables: ResMut<DialogueVariables>,
+) {
+    for event in perceived.read() {
+        if event.sense != Sense::Sight {
+            continue;
+        }
+        if let Ok(npc) = npcs.get(event.npc) {
+            variables.set(
+                format!("{}_saw_player", npc.dialogue_id),
+                DialogueValue::Bool(true),
+            );
+        }
+    }
+}
+
+// Fights and alarms are loud enough to notice whichever way an NPC is facing
+fn notice_commotion(
+    mut commotions: EventReader<Commotion>,
+    npcs: Query<(Entity, &Transform, &Visibility), With<Npc>>,
+    mut perceived: EventWriter<PerceivedThreat>,
+) {
+    for commotion in commotions.read() {
+        for (entity, transform, visibility) in npcs.iter() {
+            if *visibility != Visibility::Hidden
+                && transform.translation.distance(commotion.position) <= commotion.radius
+            {
+                perceived.send(PerceivedThreat {
+                    npc: entity,
+                    position: commotion.position,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{
+    footsteps::SurfaceMaterial,
+    level::{LevelConfig, LevelConfigHandle},
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// Physics prop constants
+const CRATE_SIZE: f32 = 0.8;
+const BARREL_RADIUS: f32 = 0.35;
+const BARREL_HEIGHT: f32 = 1.0;
+const BALL_RADIUS: f32 = 0.3;
+const LINEAR_DAMPING: f32 = 0.3;
+const ANGULAR_DAMPING: f32 = 0.5;
+
+pub struct PhysicsPropsPlugin;
+
+impl Plugin for PhysicsPropsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_physics_prop_assets)
+            .add_systems(Update, place_physics_props);
+    }
+}
+
+// What sort of thing a physics prop is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum PhysicsPropKind {
+    // Light enough to shove about and flat enough to stack
+    Crate,
+    // Heavy, so it only creeps along when pushed
+    Barrel,
+    // Rolls off at the lightest touch
+    Ball,
+}
+
+impl PhysicsPropKind {
+    const ALL: [PhysicsPropKind; 3] = [
+        PhysicsPropKind::Crate,
+        PhysicsPropKind::Barrel,
+        PhysicsPropKind::Ball,
+    ];
+
+    // Against the player's 5, which the push curve weighs them by
+    fn mass(self) -> f32 {
+        match self {
+            PhysicsPropKind::Crate => 1.5,
+            PhysicsPropKind::Barrel => 3.0,
+            PhysicsPropKind::Ball => 0.5,
+        }
+    }
+
+    fn friction(self) -> f32 {
+        match self {
+            PhysicsPropKind::Crate => 0.8,
+            PhysicsPropKind::Barrel => 0.6,
+            PhysicsPropKind::Ball => 0.4,
+        }
+    }
+
+    fn restitution(self) -> f32 {
+        match self {
+            PhysicsPropKind::Crate | PhysicsPropKind::Barrel => 0.1,
+            PhysicsPropKind::Ball => 0.6,
+        }
+    }
+
+    fn half_height(self) -> f32 {
+        match self {
+            PhysicsPropKind::Crate => CRATE_SIZE / 2.0,
+            PhysicsPropKind::Barrel => BARREL_HEIGHT / 2.0,
+            PhysicsPropKind::Ball => BALL_RADIUS,
+        }
+    }
+
+    fn collider(self) -> Collider {
+        match self {
+            PhysicsPropKind::Crate => {
+                Collider::cuboid(CRATE_SIZE / 2.0, CRATE_SIZE / 2.0, CRATE_SIZE / 2.0)
+            }
+            PhysicsPropKind::Barrel => Collider::cylinder(BARREL_HEIGHT / 2.0, BARREL_RADIUS),
+            PhysicsPropKind::Ball => Collider::ball(BALL_RADIUS),
+        }
+    }
+}
+
+// A loose prop placed in the level, from the level asset
+#[derive(Deserialize)]
+pub struct PhysicsPropPlacement {
+    pub kind: PhysicsPropKind,
+    // Where its base sits, so props can be stacked by giving the height of the one below
+    pub position: Vec3,
+    // Degrees around the vertical axis
+    #[serde(default)]
+    pub yaw: f32,
+}
+
+// Marker for placed physics props
+#[derive(Component)]
+struct PhysicsProp;
+
+// Resource with the mesh and material each kind shares, so they're drawn in a single batch
+#[derive(Resource)]
+struct PhysicsPropAssets(HashMap<PhysicsPropKind, (Handle<Mesh>, Handle<StandardMaterial>)>);
+
+fn setup_physics_prop_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let assets = PhysicsPropKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let (mesh, color, roughness) = match kind {
+                PhysicsPropKind::Crate => (
+                    meshes.add(Cuboid::from_length(CRATE_SIZE)),
+                    Color::srgb(0.7, 0.55, 0.3),
+                    0.8,
+                ),
+                PhysicsPropKind::Barrel => (
+                    meshes.add(Cylinder::new(BARREL_RADIUS, BARREL_HEIGHT)),
+                    Color::srgb(0.45, 0.3, 0.2),
+                    0.7,
+                ),
+                PhysicsPropKind::Ball => (
+                    meshes.add(Sphere::new(BALL_RADIUS)),
+                    Color::srgb(0.85, 0.25, 0.2),
+                    0.4,
+                ),
+            };
+            let material = materials.add(StandardMaterial {
+                base_color: color,
+                perceptual_roughness: roughness,
+                ..default()
+            });
+            (kind, (mesh, material))
+        })
+        .collect();
+    commands.insert_resource(PhysicsPropAssets(assets));
+}
+
+// Put the level's props back where they started whenever it's loaded, or edited while running
+fn place_physics_props(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelConfig>>,
+    configs: Res<Assets<LevelConfig>>,
+    handle: Option<Res<LevelConfigHandle>>,
+    assets: Res<PhysicsPropAssets>,
+    placed: Query<Entity, With<PhysicsProp>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(config) = configs.get(&handle.0) else {
+            continue;
+        };
+        for entity in placed.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        for placement in &config.physics_props {
+            let kind = placement.kind;
+            let Some((mesh, material)) = assets.0.get(&kind) else {
+                continue;
+            };
+            // Pushing goes through the character motor's push curve, which reads their mass
+            let mut prop = commands.spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(placement.position + Vec3::Y * kind.half_height())
+                    .with_rotation(Quat::from_rotation_y(placement.yaw.to_radians())),
+                RigidBody::Dynamic,
+                kind.collider(),
+                ColliderMassProperties::Mass(kind.mass()),
+                Friction::coefficient(kind.friction()),
+                Restitution::coefficient(kind.restitution()),
+                Damping {
+                    linear_damping: LINEAR_DAMPING,
+                    angular_damping: ANGULAR_DAMPING,
+                },
+                Velocity::default(),
+                PhysicsProp,
+            ));
+            match kind {
+                PhysicsPropKind::Crate | PhysicsPropKind::Barrel => {
+                    prop.insert(SurfaceMaterial::Wood);
+                }
+                // Small and quick enough to tunnel through thin walls when thrown
+                PhysicsPropKind::Ball => {
+                    prop.insert(Ccd::enabled());
+                }
+            }
+        }
+    }
+}
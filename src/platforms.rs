@@ -0,0 +1,169 @@
+use crate::{GameStateSet, interpolation::TransformInterpolation, update_floating_cubes};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+// Platform constants
+const FERRY_HALF_EXTENTS: Vec3 = Vec3::new(1.5, 0.15, 1.5);
+// Back and forth across the pool, flush with the ground at either end
+const FERRY_WAYPOINTS: [Vec3; 2] = [Vec3::new(-30.0, -0.15, -3.4), Vec3::new(-30.0, -0.15, 3.4)];
+const FERRY_SPEED: f32 = 1.5;
+const FERRY_PAUSE: f32 = 2.0;
+
+pub struct PlatformsPlugin;
+
+impl Plugin for PlatformsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_ferry)
+            .add_systems(
+                FixedUpdate,
+                (
+                    move_platforms,
+                    track_platform_motion.after(update_floating_cubes),
+                )
+                    .chain()
+                    .before(PhysicsSet::SyncBackend)
+                    .in_set(GameStateSet::WorldMotion),
+            )
+            .add_systems(
+                FixedUpdate,
+                carry_riders
+                    .after(PhysicsSet::Writeback)
+                    .in_set(GameStateSet::WorldMotion),
+            );
+    }
+}
+
+// Component for anything moved by its velocity that NPCs and the player can stand on and be
+// carried along by
+#[derive(Component, Default)]
+#[require(Velocity)]
+pub struct PlatformMotion {
+    // Where the platform starts this step
+    center: Vec3,
+    // How far it moves and turns over this step
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+impl PlatformMotion {
+    // Where something standing at `position` ends up after this step's movement
+    pub fn carry(&self, position: Vec3) -> Vec3 {
+        self.center + self.translation + self.rotation * (position - self.center)
+    }
+}
+
+// Component for a platform that travels between waypoints in a loop, waiting a moment at each
+#[derive(Component)]
+#[require(
+    RigidBody(|| RigidBody::KinematicVelocityBased),
+    PlatformMotion,
+    TransformInterpolation
+)]
+pub struct MovingPlatform {
+    pub waypoints: Vec<Vec3>,
+    pub speed: f32,
+    // Seconds spent stopped at each waypoint
+    pub pause: f32,
+    next: usize,
+    waiting: f32,
+}
+
+impl MovingPlatform {
+    pub fn new(waypoints: Vec<Vec3>, speed: f32, pause: f32) -> Self {
+        Self {
+            waypoints,
+            speed,
+            pause,
+            next: 0,
+            waiting: 0.0,
+        }
+    }
+}
+
+// Component on the player while they stand on a platform, set by `player_movement`
+#[derive(Component)]
+pub struct Riding(pub Entity);
+
+fn spawn_ferry(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_size(FERRY_HALF_EXTENTS * 2.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.55, 0.35, 0.2),
+            perceptual_roughness: 0.8,
+            ..default()
+        })),
+        Transform::from_translation(FERRY_WAYPOINTS[0]),
+        Collider::cuboid(
+            FERRY_HALF_EXTENTS.x,
+            FERRY_HALF_EXTENTS.y,
+            FERRY_HALF_EXTENTS.z,
+        ),
+        MovingPlatform::new(FERRY_WAYPOINTS.to_vec(), FERRY_SPEED, FERRY_PAUSE),
+    ));
+}
+
+// Head for the next waypoint, waiting there a moment once it arrives
+fn move_platforms(
+    time: Res<Time>,
+    mut platforms: Query<(&Transform, &mut Velocity, &mut MovingPlatform)>,
+) {
+    let delta_time = time.delta_secs();
+    for (transform, mut velocity, mut platform) in platforms.iter_mut() {
+        velocity.linvel = Vec3::ZERO;
+        if platform.waypoints.is_empty() {
+            continue;
+        }
+        if platform.waiting > 0.0 {
+            platform.waiting -= delta_time;
+            continue;
+        }
+        let target = platform.waypoints[platform.next];
+        let (linvel, arrived) =
+            step_toward(transform.translation, target, platform.speed, delta_time);
+        velocity.linvel = linvel;
+        if arrived {
+            platform.next = (platform.next + 1) % platform.waypoints.len();
+            platform.waiting = platform.pause;
+        }
+    }
+}
+
+// Velocity that takes a platform from `from` toward `to` at `speed`, landing exactly on it rather
+// than overshooting, and whether it gets there this step
+pub fn step_toward(from: Vec3, to: Vec3, speed: f32, delta_time: f32) -> (Vec3, bool) {
+    let to_target = to - from;
+    if to_target.length() <= speed * delta_time {
+        (to_target / delta_time, true)
+    } else {
+        (to_target.normalize() * speed, false)
+    }
+}
+
+// Work out how each platform will move this step from the velocity it was given
+pub fn track_platform_motion(
+    time: Res<Time>,
+    mut platforms: Query<(&Transform, &Velocity, &mut PlatformMotion)>,
+) {
+    let delta_time = time.delta_secs();
+    for (transform, velocity, mut motion) in platforms.iter_mut() {
+        motion.center = transform.translation;
+        motion.translation = velocity.linvel * delta_time;
+        motion.rotation = Quat::from_scaled_axis(velocity.angvel * delta_time);
+    }
+}
+
+// Once physics has moved the platforms, bring the player along with whichever they stood on
+fn carry_riders(
+    mut riders: Query<(&mut Transform, &Riding), Without<PlatformMotion>>,
+    platforms: Query<&PlatformMotion>,
+) {
+    for (mut transform, riding) in riders.iter_mut() {
+        if let Ok(motion) = platforms.get(riding.0) {
+            transform.translation = motion.carry(transform.translation);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{
+    GameStateSet, PLAYER_BORDER_RADIUS, PLAYER_HALF_HEIGHT, crouch::Crouch,
+    footsteps::STRIDE_LENGTH, setup_player,
+};
+use bevy::{prelude::*, render::view::RenderLayers};
+use bevy_rapier3d::control::KinematicCharacterController;
+use std::f32::consts::PI;
+
+// Player body constants
+// Layer the first-person camera doesn't render, so the head only shows up in shadows
+pub const HIDDEN_FROM_CAMERA_LAYER: usize = 1;
+const BODY_COLOR: Color = Color::srgb(0.25, 0.3, 0.4);
+const HEAD_RADIUS: f32 = 0.18;
+const HEAD_HEIGHT: f32 = 0.25; // Above the capsule's center, around the camera
+const TORSO_RADIUS: f32 = 0.2;
+const TORSO_LENGTH: f32 = 0.3;
+const TORSO_HEIGHT: f32 = -0.3;
+const TORSO_BACK_OFFSET: f32 = 0.08; // Behind the camera so looking straight ahead doesn't clip it
+const HIP_HEIGHT: f32 = -0.55;
+const HIP_WIDTH: f32 = 0.11;
+const LEG_RADIUS: f32 = 0.09;
+const LEG_SWING: f32 = 0.6; // Radians either side at full walking speed
+const WALK_SPEED: f32 = 8.0; // Speed at which the legs reach full swing
+
+pub struct PlayerBodyPlugin;
+
+impl Plugin for PlayerBodyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_player_body.after(setup_player))
+            .add_systems(
+                Update,
+                (animate_player_body, fit_body_to_crouch).in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Root of the player's visible body, scaled with the capsule when crouching
+#[derive(Component)]
+struct PlayerBody;
+
+// Pivot at a hip that swings its leg while walking
+#[derive(Component)]
+struct Leg {
+    // Opposite legs swing in opposite directions
+    side: f32,
+}
+
+fn standing_feet_offset() -> f32 {
+    PLAYER_HALF_HEIGHT + PLAYER_BORDER_RADIUS
+}
+
+fn spawn_player_body(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    player: Query<Entity, With<KinematicCharacterController>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let material = materials.add(StandardMaterial {
+        base_color: BODY_COLOR,
+        perceptual_roughness: 0.8,
+        ..default()
+    });
+    let leg_length = standing_feet_offset() + HIP_HEIGHT;
+    let leg_mesh = meshes.add(Capsule3d::new(LEG_RADIUS, leg_length - LEG_RADIUS * 2.0));
+
+    let body = commands
+        .spawn((PlayerBody, Transform::default(), Visibility::default()))
+        .with_children(|body| {
+            body.spawn((
+                Mesh3d(meshes.add(Sphere::new(HEAD_RADIUS))),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(0.0, HEAD_HEIGHT, TORSO_BACK_OFFSET),
+                RenderLayers::layer(HIDDEN_FROM_CAMERA_LAYER),
+            ));
+            body.spawn((
+                Mesh3d(meshes.add(Capsule3d::new(TORSO_RADIUS, TORSO_LENGTH))),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(0.0, TORSO_HEIGHT, TORSO_BACK_OFFSET),
+            ));
+            for side in [-1.0, 1.0] {
+                body.spawn((
+                    Leg { side },
+                    Transform::from_xyz(side * HIP_WIDTH, HIP_HEIGHT, 0.0),
+                    Visibility::default(),
+                ))
+                .with_children(|hip| {
+                    hip.spawn((
+                        Mesh3d(leg_mesh.clone()),
+                        MeshMaterial3d(material.clone()),
+                        Transform::from_xyz(0.0, -leg_length / 2.0, 0.0),
+                    ));
+                });
+            }
+        })
+        .id();
+    commands.entity(player).add_child(body);
+}
+
+// Swing the legs in step with how fast the player is actually moving
+fn animate_player_body(
+    time: Res<Time>,
+    player: Query<&Transform, With<KinematicCharacterController>>,
+    mut legs: Query<(&Leg, &mut Transform), Without<KinematicCharacterController>>,
+    mut previous_position: Local<Option<Vec3>>,
+    mut phase: Local<f32>,
+) {
+    let Ok(transform) = player.get_single() else {
+        return;
+    };
+    let delta_time = time.delta_secs();
+    let position = transform.translation;
+    let walked =
+        previous_position.map_or(0.0, |previous| (position - previous).with_y(0.0).length());
+    *previous_position = Some(position);
+    if delta_time <= 0.0 {
+        return;
+    }
+
+    let speed = walked / delta_time;
+    // Legs settle back together when standing still
+    let swing = if speed > 0.1 {
+        // Each footstep is half a cycle, so a foot lands with every step sound
+        *phase += walked * PI / STRIDE_LENGTH;
+        LEG_SWING * (speed / WALK_SPEED).min(1.0)
+    } else {
+        *phase = 0.0;
+        0.0
+    };
+    for (leg, mut leg_transform) in legs.iter_mut() {
+        leg_transform.rotation = Quat::from_rotation_x(phase.sin() * swing * leg.side);
+    }
+}
+
+fn fit_body_to_crouch(player: Query<&Crouch>, mut body: Query<&mut Transform, With<PlayerBody>>) {
+    let (Ok(crouch), Ok(mut transform)) = (player.get_single(), body.get_single_mut()) else {
+        return;
+    };
+    // Squash about the capsule's center so the feet stay on the floor
+    let scale = crouch.feet_offset() / standing_feet_offset();
+    if transform.scale.y != scale {
+        transform.scale.y = scale;
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    GameStateSet, PlayerCamera,
+    input_map::{ActionState, InputAction, controls_menu_closed},
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+
+// Prop grab constants
+const GRAB_DISTANCE: f32 = 3.0; // How far from the camera a prop can be picked up
+const HOLD_DISTANCE: f32 = 1.8; // Where the prop is carried in front of the camera
+const HOLD_STIFFNESS: f32 = 300.0;
+const HOLD_DAMPING: f32 = 30.0;
+const HOLD_ANGULAR_DAMPING: f32 = 8.0; // Keeps the prop from spinning on the spring
+const HOLD_BREAK_DISTANCE: f32 = 2.5; // Drop a prop that gets snagged this far from the hold point
+const THROW_IMPULSE: f32 = 12.0;
+
+pub struct PropGrabPlugin;
+
+impl Plugin for PropGrabPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (grab_prop, throw_prop, update_hold_anchor)
+                .chain()
+                .run_if(controls_menu_closed)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// Component on the player while a prop hangs from a spring in front of the camera
+#[derive(Component)]
+pub struct Carrying {
+    prop: Entity,
+    // Kinematic body the spring pulls toward
+    anchor: Entity,
+    // The prop's own damping, put back when it's let go
+    damping: Option<Damping>,
+}
+
+fn grab_prop(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    rapier_context: ReadRapierContext,
+    player: Query<(Entity, Option<&Carrying>), With<KinematicCharacterController>>,
+    camera: Query<&GlobalTransform, With<PlayerCamera>>,
+    bodies: Query<(&RigidBody, Option<&Damping>)>,
+) {
+    if !actions.just_pressed(InputAction::Grab) {
+        return;
+    }
+    let Ok((player_entity, carrying)) = player.get_single() else {
+        return;
+    };
+    if let Some(carrying) = carrying {
+        release_prop(&mut commands, player_entity, carrying);
+        return;
+    }
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+
+    let physics = rapier_context.single();
+    let filter = QueryFilter::default()
+        .exclude_collider(player_entity)
+        .exclude_sensors();
+    let Some((prop, _)) = physics.cast_ray(
+        camera.translation(),
+        *camera.forward(),
+        GRAB_DISTANCE,
+        true,
+        filter,
+    ) else {
+        return;
+    };
+    let Ok((RigidBody::Dynamic, damping)) = bodies.get(prop) else {
+        return;
+    };
+
+    let hold_point = camera.translation() + *camera.forward() * HOLD_DISTANCE;
+    let anchor = commands
+        .spawn((
+            Transform::from_translation(hold_point),
+            RigidBody::KinematicPositionBased,
+        ))
+        .id();
+    let spring = SpringJointBuilder::new(0.0, HOLD_STIFFNESS, HOLD_DAMPING).contacts_enabled(false);
+    commands.entity(prop).insert((
+        ImpulseJoint::new(anchor, spring),
+        Damping {
+            linear_damping: damping.map_or(0.0, |damping| damping.linear_damping),
+            angular_damping: HOLD_ANGULAR_DAMPING,
+        },
+    ));
+    commands.entity(player_entity).insert(Carrying {
+        prop,
+        anchor,
+        damping: damping.copied(),
+    });
+}
+
+fn throw_prop(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    player: Query<(Entity, &Carrying)>,
+    camera: Query<&GlobalTransform, With<PlayerCamera>>,
+) {
+    if !actions.just_pressed(InputAction::Throw) {
+        return;
+    }
+    let (Ok((player_entity, carrying)), Ok(camera)) = (player.get_single(), camera.get_single())
+    else {
+        return;
+    };
+    release_prop(&mut commands, player_entity, carrying);
+    commands.entity(carrying.prop).insert(ExternalImpulse {
+        impulse: *camera.forward() * THROW_IMPULSE,
+        ..default()
+    });
+}
+
+// Keep the spring's anchor in front of the camera, dropping the prop if it can't follow
+fn update_hold_anchor(
+    mut commands: Commands,
+    player: Query<(Entity, &Carrying)>,
+    camera: Query<&GlobalTransform, With<PlayerCamera>>,
+    mut transforms: Query<&mut Transform>,
+) {
+    let (Ok((player_entity, carrying)), Ok(camera)) = (player.get_single(), camera.get_single())
+    else {
+        return;
+    };
+    let hold_point = camera.translation() + *camera.forward() * HOLD_DISTANCE;
+    let snagged = transforms.get(carrying.prop).map_or(true, |prop| {
+        prop.translation.distance(hold_point) > HOLD_BREAK_DISTANCE
+    });
+    if snagged {
+        release_prop(&mut commands, player_entity, carrying);
+        return;
+    }
+    if let Ok(mut anchor) = transforms.get_mut(carrying.anchor) {
+        anchor.translation = hold_point;
+    }
+}
+
+fn release_prop(commands: &mut Commands, player_entity: Entity, carrying: &Carrying) {
+    commands.entity(player_entity).remove::<Carrying>();
+    commands.entity(carrying.anchor).despawn();
+    if let Some(mut prop) = commands.get_entity(carrying.prop) {
+        prop.remove::<ImpulseJoint>();
+        match carrying.damping {
+            Some(damping) => prop.insert(damping),
+            None => prop.remove::<Damping>(),
+        };
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    dialogue_variables::{DialogueValue, DialogueVariables},
+    economy::TradeGood,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Quest tracker constants
+const QUEST_TRACKER_COLOR: Color = Color::srgb(0.95, 0.85, 0.4);
+const QUEST_TRACKER_FONT_SIZE: f32 = 16.0;
+
+pub struct QuestPlugin;
+
+impl Plugin for QuestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<QuestDatabase>()
+            .init_resource::<QuestLog>()
+            .add_event::<DialogueActionTriggered>()
+            .add_systems(Startup, setup_quest_tracker)
+            .add_systems(Update, (apply_quest_actions, update_quest_tracker).chain());
+    }
+}
+
+// Something a dialogue option does to the world when picked
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum DialogueAction {
+    StartQuest(String),
+    AdvanceQuest(String),
+    CompleteQuest(String),
+    // The NPC being talked to starts or stops following the player around
+    StartFollowing,
+    StopFollowing,
+    // Trade with the merchant being talked to, if their stall is open
+    Buy(TradeGood, u32),
+    Sell(TradeGood, u32),
+}
+
+impl DialogueAction {
+    // Player-facing summary of what picking the option will do
+    pub fn describe(&self, database: &QuestDatabase) -> String {
+        let (verb, id) = match self {
+            DialogueAction::StartQuest(id) => ("Starts", id),
+            DialogueAction::AdvanceQuest(id) => ("Advances", id),
+            DialogueAction::CompleteQuest(id) => ("Completes", id),
+            DialogueAction::StartFollowing => return "Follows you".to_string(),
+            DialogueAction::StopFollowing => return "Stops following you".to_string(),
+            DialogueAction::Buy(good, quantity) => {
+                return format!("Buys {quantity} {}", good.name());
+            }
+            DialogueAction::Sell(good, quantity) => {
+                return format!("Sells {quantity} {}", good.name());
+            }
+        };
+        let title = database
+            .quests
+            .get(id)
+            .map_or(id.as_str(), |quest| quest.title.as_str());
+        format!("{verb} quest: {title}")
+    }
+}
+
+// Event sent for each action on a dialogue option the player picked
+#[derive(Event)]
+pub struct DialogueActionTriggered {
+    // The NPC the conversation was with
+    pub npc_entity: Entity,
+    pub action: DialogueAction,
+}
+
+pub struct QuestDefinition {
+    pub title: String,
+    // What the player is asked to do at each stage, in order
+    pub stages: Vec<String>,
+}
+
+// Resource holding every quest dialogue can hand out
+#[derive(Resource)]
+pub struct QuestDatabase {
+    pub quests: HashMap<String, QuestDefinition>,
+}
+
+impl Default for QuestDatabase {
+    fn default() -> Self {
+        let mut quests = HashMap::new();
+
+        quests.insert(
+            "cube_survey".to_string(),
+            QuestDefinition {
+                title: "Cube Survey".to_string(),
+                stages: vec![
+                    "Ask Dr. Neutrino about the floating cubes.".to_string(),
+                    "Report back to Guard Steve.".to_string(),
+                ],
+            },
+        );
+
+        QuestDatabase { quests }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuestStatus {
+    Active { stage: usize },
+    Completed,
+}
+
+// Resource tracking the quests the player has picked up
+#[derive(Resource, Default)]
+pub struct QuestLog {
+    pub quests: HashMap<String, QuestStatus>,
+}
+
+// Marker for the on-screen list of active quests
+#[derive(Component)]
+struct QuestTracker;
+
+// Quest progress is mirrored into dialogue variables so conversations can react to it:
+// `quest_<id>_started`, `quest_<id>_complete`, and `quest_<id>_stage`, the 1-based
+// stage while the quest is active and 0 otherwise
+fn sync_quest_variables(id: &str, status: QuestStatus, variables: &mut DialogueVariables) {
+    let stage = match status {
+        QuestStatus::Active { stage } => stage as f32 + 1.0,
+        QuestStatus::Completed => 0.0,
+    };
+    variables.set(format!("quest_{id}_started"), DialogueValue::Bool(true));
+    variables.set(
+        format!("quest_{id}_complete"),
+        DialogueValue::Bool(status == QuestStatus::Completed),
+    );
+    variables.set(format!("quest_{id}_stage"), DialogueValue::Number(stage));
+}
+
+fn apply_quest_actions(
+    mut events: EventReader<DialogueActionTriggered>,
+    database: Res<QuestDatabase>,
+    mut log: ResMut<QuestLog>,
+    mut variables: ResMut<DialogueVariables>,
+) {
+    for DialogueActionTriggered { action, .. } in events.read() {
+        let (DialogueAction::StartQuest(id)
+        | DialogueAction::AdvanceQuest(id)
+        | DialogueAction::CompleteQuest(id)) = action
+        else {
+            continue;
+        };
+        let Some(quest) = database.quests.get(id) else {
+            println!("Error: No quest found with id: {id}");
+            continue;
+        };
+
+        let current = log.quests.get(id).copied();
+        let status = match (action, current) {
+            (DialogueAction::StartQuest(_), None) => {
+                println!("Quest started: {}", quest.title);
+                QuestStatus::Active { stage: 0 }
+            }
+            (DialogueAction::AdvanceQuest(_), Some(QuestStatus::Active { stage })) => {
+                let stage = (stage + 1).min(quest.stages.len().saturating_sub(1));
+                println!("Quest updated: {}", quest.title);
+                QuestStatus::Active { stage }
+            }
+            (DialogueAction::CompleteQuest(_), Some(QuestStatus::Active { .. })) => {
+                println!("Quest completed: {}", quest.title);
+                QuestStatus::Completed
+            }
+            // Starting twice, or advancing a quest that isn't running, changes nothing
+            _ => continue,
+        };
+
+        log.quests.insert(id.clone(), status);
+        sync_quest_variables(id, status, &mut variables);
+    }
+}
+
+fn setup_quest_tracker(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: QUEST_TRACKER_FONT_SIZE,
+            ..default()
+        },
+        TextColor(QUEST_TRACKER_COLOR),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        QuestTracker,
+    ));
+}
+
+fn update_quest_tracker(
+    log: Res<QuestLog>,
+    database: Res<QuestDatabase>,
+    mut tracker: Query<&mut Text, With<QuestTracker>>,
+) {
+    if !log.is_changed() {
+        return;
+    }
+    let Ok(mut text) = tracker.get_single_mut() else {
+        return;
+    };
+
+    let mut lines = Vec::new();
+    for (id, status) in &log.quests {
+        let (Some(quest), QuestStatus::Active { stage }) = (database.quests.get(id), status) else {
+            continue;
+        };
+        let objective = quest.stages.get(*stage).map(String::as_str).unwrap_or("");
+        lines.push(format!("{}\n  {objective}", quest.title));
+    }
+    lines.sort();
+    **text = lines.join("\n");
+}
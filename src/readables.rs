@@ -0,0 +1,376 @@
+use crate::{
+    DIALOGUE_BACKGROUND_COLOR, DIALOGUE_TEXT_COLOR, GameState, GameStateSet, InteractionTarget,
+    PLAYER_NAME,
+    clock::GameClock,
+    dialogue_variables::{DialogueContext, DialogueVariables},
+    footsteps::SurfaceMaterial,
+    input_map::{ActionState, InputAction, InputMap, controls_menu_closed},
+    level::{LevelConfig, LevelConfigHandle},
+    ron_asset::RonAssetLoader,
+    update_interaction_target,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// Readable constants
+const NOTES_PATH: &str = "town.notes.ron";
+const POST_HEIGHT: f32 = 1.6;
+const POST_RADIUS: f32 = 0.06;
+const BOARD_SIZE: Vec3 = Vec3::new(0.9, 0.5, 0.05);
+const NOTE_SIZE: Vec3 = Vec3::new(0.3, 0.01, 0.4);
+const HINT_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+
+pub struct ReadablesPlugin;
+
+impl Plugin for ReadablesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Notes>()
+            .register_asset_loader(RonAssetLoader::<Notes>::new(&["notes.ron"]))
+            .add_systems(Startup, (load_notes, setup_readable_assets))
+            .add_systems(Update, place_readables)
+            .add_systems(
+                Update,
+                open_readables
+                    .after(update_interaction_target)
+                    .run_if(controls_menu_closed)
+                    .in_set(GameStateSet::Playing),
+            )
+            .add_systems(OnEnter(GameState::Reading), setup_reading_ui)
+            .add_systems(Update, turn_pages.run_if(in_state(GameState::Reading)));
+    }
+}
+
+// What sort of thing a readable is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum ReadableKind {
+    // A board on a post, standing on the ground
+    Signpost,
+    // A sheet of paper lying flat
+    Note,
+}
+
+impl ReadableKind {
+    const ALL: [ReadableKind; 2] = [ReadableKind::Signpost, ReadableKind::Note];
+
+    // What the interaction prompt calls it
+    pub fn label(self) -> &'static str {
+        match self {
+            ReadableKind::Signpost => "sign",
+            ReadableKind::Note => "note",
+        }
+    }
+}
+
+// Something to read placed in the level, from the level asset
+#[derive(Deserialize)]
+pub struct ReadablePlacement {
+    pub kind: ReadableKind,
+    // Key of its text in `town.notes.ron`
+    pub note: String,
+    pub position: Vec3,
+    // Degrees around the vertical axis, turning the front away from +Z
+    #[serde(default)]
+    pub yaw: f32,
+}
+
+// A text to read, a page at a time. Pages can use the same `{placeholders}` as dialogue.
+#[derive(Deserialize)]
+pub struct Note {
+    pub title: String,
+    pub pages: Vec<String>,
+}
+
+// The text of every sign and note, loaded from `town.notes.ron`
+#[derive(Asset, TypePath, Deserialize)]
+pub struct Notes {
+    pub notes: HashMap<String, Note>,
+}
+
+#[derive(Resource)]
+struct NotesHandle(Handle<Notes>);
+
+// Component for something the player can read by interacting with it
+#[derive(Component)]
+pub struct Readable {
+    pub kind: ReadableKind,
+    pub note: String,
+}
+
+// Resource with what's being read, while the reading panel is open
+#[derive(Resource)]
+struct Reading {
+    note: String,
+    page: usize,
+}
+
+// One box of a sign or note, relative to where it stands
+struct ReadablePart {
+    mesh: Handle<Mesh>,
+    offset: Vec3,
+    half_extents: Vec3,
+}
+
+// Resource with the parts each kind is built from, and the weathered wood they're all made of
+#[derive(Resource)]
+struct ReadableAssets {
+    parts: HashMap<ReadableKind, Vec<ReadablePart>>,
+    material: Handle<StandardMaterial>,
+}
+
+// Marker for the page text in the reading panel
+#[derive(Component)]
+struct ReadingText;
+
+// Marker for the line under the page saying how to carry on
+#[derive(Component)]
+struct ReadingHint;
+
+fn load_notes(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(NotesHandle(asset_server.load(NOTES_PATH)));
+}
+
+fn setup_readable_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let parts = ReadableKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let parts = match kind {
+                ReadableKind::Signpost => vec![
+                    ReadablePart {
+                        mesh: meshes.add(Cylinder::new(POST_RADIUS, POST_HEIGHT)),
+                        offset: Vec3::new(0.0, POST_HEIGHT / 2.0, 0.0),
+                        half_extents: Vec3::new(POST_RADIUS, POST_HEIGHT / 2.0, POST_RADIUS),
+                    },
+                    // On the front of the post, its top flush with the post's
+                    ReadablePart {
+                        mesh: meshes.add(Cuboid::from_size(BOARD_SIZE)),
+                        offset: Vec3::new(0.0, POST_HEIGHT - BOARD_SIZE.y / 2.0, POST_RADIUS),
+                        half_extents: BOARD_SIZE / 2.0,
+                    },
+                ],
+                ReadableKind::Note => vec![ReadablePart {
+                    mesh: meshes.add(Cuboid::from_size(NOTE_SIZE)),
+                    offset: Vec3::new(0.0, NOTE_SIZE.y / 2.0, 0.0),
+                    half_extents: NOTE_SIZE / 2.0,
+                }],
+            };
+            (kind, parts)
+        })
+        .collect();
+    commands.insert_resource(ReadableAssets {
+        parts,
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.75, 0.65, 0.5),
+            perceptual_roughness: 0.9,
+            ..default()
+        }),
+    });
+}
+
+// Put the level's signs and notes out whenever it's loaded, or edited while running
+fn place_readables(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelConfig>>,
+    configs: Res<Assets<LevelConfig>>,
+    handle: Option<Res<LevelConfigHandle>>,
+    assets: Res<ReadableAssets>,
+    placed: Query<Entity, With<Readable>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(config) = configs.get(&handle.0) else {
+            continue;
+        };
+        for entity in placed.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        for placement in &config.readables {
+            let Some(parts) = assets.parts.get(&placement.kind) else {
+                continue;
+            };
+            let rotation = Quat::from_rotation_y(placement.yaw.to_radians());
+            // Each part is readable itself, since the interaction ray stops at whichever it hits
+            for part in parts {
+                let half_extents = part.half_extents;
+                commands.spawn((
+                    Mesh3d(part.mesh.clone()),
+                    MeshMaterial3d(assets.material.clone()),
+                    Transform::from_translation(placement.position + rotation * part.offset)
+                        .with_rotation(rotation),
+                    Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+                    SurfaceMaterial::Wood,
+                    Readable {
+                        kind: placement.kind,
+                        note: placement.note.clone(),
+                    },
+                ));
+            }
+        }
+    }
+}
+
+fn open_readables(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    target: Res<InteractionTarget>,
+    readables: Query<&Readable>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !actions.just_pressed(InputAction::Interact) {
+        return;
+    }
+    let Some(readable) = target.0.and_then(|entity| readables.get(entity).ok()) else {
+        return;
+    };
+    commands.insert_resource(Reading {
+        note: readable.note.clone(),
+        page: 0,
+    });
+    next_state.set(GameState::Reading);
+}
+
+// Show the note in a panel laid out like the dialogue one, with the title where the NPC's name goes
+fn setup_reading_ui(
+    mut commands: Commands,
+    reading: Option<Res<Reading>>,
+    notes: Res<Assets<Notes>>,
+    handle: Res<NotesHandle>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let note = reading
+        .as_ref()
+        .and_then(|reading| notes.get(&handle.0)?.notes.get(&reading.note));
+    let Some(note) = note else {
+        if let Some(reading) = reading {
+            println!("Error: No note found with id: {}", reading.note);
+        }
+        next_state.set(GameState::Playing);
+        return;
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(50.0),
+                height: Val::Auto,
+                position_type: PositionType::Absolute,
+                left: Val::Percent(25.0),
+                bottom: Val::Percent(20.0),
+                padding: UiRect::all(Val::Px(20.0)),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(DIALOGUE_BACKGROUND_COLOR),
+            StateScoped(GameState::Reading),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(note.title.clone()),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(DIALOGUE_TEXT_COLOR),
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+                ReadingText,
+            ));
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(HINT_COLOR),
+                ReadingHint,
+            ));
+        });
+}
+
+// Interact turns the page, or puts the note down after the last one. Escape puts it down at once.
+#[allow(clippy::too_many_arguments)]
+fn turn_pages(
+    actions: Res<ActionState>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    reading: Option<ResMut<Reading>>,
+    notes: Res<Assets<Notes>>,
+    handle: Res<NotesHandle>,
+    clock: Res<GameClock>,
+    variables: Res<DialogueVariables>,
+    mut text: Query<&mut Text, (With<ReadingText>, Without<ReadingHint>)>,
+    mut hint: Query<&mut Text, With<ReadingHint>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(mut reading) = reading else {
+        return;
+    };
+    let Some(note) = notes
+        .get(&handle.0)
+        .and_then(|notes| notes.notes.get(&reading.note))
+    else {
+        next_state.set(GameState::Playing);
+        return;
+    };
+    let last_page = note.pages.len().saturating_sub(1);
+    if keyboard.just_pressed(KeyCode::Escape)
+        || (actions.just_pressed(InputAction::Interact) && reading.page >= last_page)
+    {
+        next_state.set(GameState::Playing);
+        return;
+    }
+    if actions.just_pressed(InputAction::Interact) {
+        reading.page += 1;
+    }
+    if !reading.is_changed() {
+        return;
+    }
+
+    let context = DialogueContext::default()
+        .with("player_name", PLAYER_NAME)
+        .with("time_of_day", clock.time_of_day())
+        .with_variables(&variables);
+    let page = note.pages.get(reading.page).map_or("", String::as_str);
+    if let Ok(mut text) = text.get_single_mut() {
+        text.0 = context.substitute(page);
+    }
+    if let Ok(mut hint) = hint.get_single_mut() {
+        let key = input_map
+            .bindings(InputAction::Interact)
+            .first()
+            .map_or_else(|| "Interact".to_string(), |binding| binding.label());
+        let action = if reading.page < last_page {
+            "turn the page"
+        } else {
+            "stop reading"
+        };
+        hint.0 = format!(
+            "Page {} of {}. Press {key} to {action}.",
+            reading.page + 1,
+            last_page + 1
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    GameState, GameStateSet,
+    accessibility::AccessibilitySettings,
+    triggers::{TriggerVolumeEntered, TriggerVolumeExited},
+};
+use bevy::prelude::*;
+
+// Region name constants
+const TOAST_DURATION: f32 = 3.0;
+const TOAST_FADE: f32 = 0.5; // Seconds spent fading in, and again fading out
+const TOAST_FONT_SIZE: f32 = 28.0;
+const TOAST_COLOR: Color = Color::srgb(0.95, 0.9, 0.8);
+const TOAST_TOP: f32 = 18.0; // Percent of the way down the screen
+
+pub struct RegionNamesPlugin;
+
+impl Plugin for RegionNamesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (announce_regions, fade_region_toasts)
+                .chain()
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+// Component naming the region a trigger volume marks out, announced as the player walks in
+#[derive(Component)]
+pub struct RegionName(pub String);
+
+// The name of the region the player just walked into, shown across the top of the screen
+#[derive(Component)]
+struct RegionToast(Timer);
+
+// Announce the innermost region the player is in whenever it changes, so stepping out of the
+// market back into town names the town again
+fn announce_regions(
+    mut commands: Commands,
+    mut entered: EventReader<TriggerVolumeEntered>,
+    mut exited: EventReader<TriggerVolumeExited>,
+    regions: Query<&RegionName>,
+    toasts: Query<Entity, With<RegionToast>>,
+    // Regions the player is in, innermost last
+    mut inside: Local<Vec<Entity>>,
+) {
+    let before = inside.last().copied();
+    for event in exited.read() {
+        inside.retain(|volume| *volume != event.volume);
+    }
+    for event in entered.read() {
+        if regions.contains(event.volume) {
+            inside.push(event.volume);
+        }
+    }
+    // Regions that were reloaded out from under the player
+    inside.retain(|volume| regions.contains(*volume));
+    let current = inside.last().copied();
+    if current == before {
+        return;
+    }
+    let Some(name) = current.and_then(|volume| regions.get(volume).ok()) else {
+        return;
+    };
+
+    for entity in toasts.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Percent(TOAST_TOP),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            StateScoped(GameState::Playing),
+            RegionToast(Timer::from_seconds(TOAST_DURATION, TimerMode::Once)),
+        ))
+        .with_child((
+            Text::new(name.0.clone()),
+            TextFont {
+                font_size: TOAST_FONT_SIZE,
+                ..default()
+            },
+            TextColor(TOAST_COLOR.with_alpha(0.0)),
+        ));
+}
+
+fn fade_region_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    mut toasts: Query<(Entity, &mut RegionToast, &Children)>,
+    mut colors: Query<&mut TextColor>,
+) {
+    for (entity, mut toast, children) in toasts.iter_mut() {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let elapsed = toast.0.elapsed_secs();
+        let fade = TOAST_FADE * accessibility.ui_duration_scale();
+        let alpha = (elapsed / fade)
+            .min((TOAST_DURATION - elapsed) / fade)
+            .clamp(0.0, 1.0);
+        for child in children.iter() {
+            if let Ok(mut color) = colors.get_mut(*child) {
+                color.0 = TOAST_COLOR.with_alpha(alpha);
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{GameStateSet, LookInput, mantle::Mantling};
+use bevy::prelude::*;
+use bevy_rapier3d::control::KinematicCharacterController;
+
+// Respawn constants
+const KILL_HEIGHT: f32 = -20.0; // Well below the ground slab, so falling off the edge counts
+pub const SPAWN_HEIGHT: f32 = 1.5; // Spawn points sit this far above the floor so the capsule drops in
+
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RespawnPlayer>()
+            .add_event::<PlayerRespawned>()
+            // Teleports happen outside the fixed steps so interpolation snaps instead of sweeping
+            .add_systems(
+                Update,
+                (check_kill_height, respawn_player)
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// A place the player can be put back, facing `yaw` degrees around Y, placed by the level
+#[derive(Component)]
+#[require(Transform)]
+pub struct SpawnPoint {
+    pub yaw: f32,
+}
+
+// Event asking for the player to be put back at the nearest spawn point
+#[derive(Event)]
+pub struct RespawnPlayer;
+
+// Event sent once the player has been moved, for systems holding on to their old momentum
+#[derive(Event)]
+pub struct PlayerRespawned;
+
+fn check_kill_height(
+    player: Query<&Transform, With<KinematicCharacterController>>,
+    mut respawns: EventWriter<RespawnPlayer>,
+) {
+    if player
+        .get_single()
+        .is_ok_and(|transform| transform.translation.y < KILL_HEIGHT)
+    {
+        respawns.send(RespawnPlayer);
+    }
+}
+
+fn respawn_player(
+    mut commands: Commands,
+    mut requests: EventReader<RespawnPlayer>,
+    mut respawned: EventWriter<PlayerRespawned>,
+    mut look: ResMut<LookInput>,
+    mut player: Query<(Entity, &mut Transform), With<KinematicCharacterController>>,
+    spawn_points: Query<(&SpawnPoint, &GlobalTransform)>,
+) {
+    if requests.read().count() == 0 {
+        return;
+    }
+    let Ok((entity, mut transform)) = player.get_single_mut() else {
+        return;
+    };
+    // Closest to where the player was, so falling off one side doesn't send them across the map
+    let position = transform.translation;
+    let Some((spawn_point, spawn_transform)) = spawn_points.iter().min_by(|(_, a), (_, b)| {
+        a.translation()
+            .distance_squared(position)
+            .total_cmp(&b.translation().distance_squared(position))
+    }) else {
+        return;
+    };
+
+    transform.translation = spawn_transform.translation();
+    **look = Vec2::new(spawn_point.yaw, 0.0);
+    commands.entity(entity).remove::<Mantling>();
+    respawned.send(PlayerRespawned);
+}
@@ -0,0 +1,68 @@
+use bevy::asset::{Asset, AssetLoader, LoadContext, io::Reader};
+use serde::de::DeserializeOwned;
+use std::{fmt, marker::PhantomData};
+
+// Loads any deserializable asset from a RON file with the given extensions
+pub struct RonAssetLoader<A> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<fn() -> A>,
+}
+
+impl<A> RonAssetLoader<A> {
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        Self {
+            extensions,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A: Asset + DeserializeOwned> AssetLoader for RonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = RonAssetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<A, RonAssetError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
+
+#[derive(Debug)]
+pub enum RonAssetError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for RonAssetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RonAssetError::Io(error) => write!(f, "could not read RON asset: {error}"),
+            RonAssetError::Ron(error) => write!(f, "could not parse RON asset: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for RonAssetError {}
+
+impl From<std::io::Error> for RonAssetError {
+    fn from(error: std::io::Error) -> Self {
+        RonAssetError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for RonAssetError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        RonAssetError::Ron(error)
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    footsteps::SurfaceMaterial,
+    level::{LevelConfig, LevelConfigHandle},
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// Scenery constants
+const TRUNK_RADIUS: f32 = 0.2;
+const TRUNK_HEIGHT: f32 = 2.0;
+const CANOPY_RADIUS: f32 = 1.4;
+const CANOPY_HEIGHT: f32 = 3.0;
+const ROCK_RADIUS: f32 = 0.8;
+const ROCK_SQUASH: f32 = 0.6; // Rocks are flattened spheres, this tall for their width
+const CRATE_SIZE: f32 = 1.0;
+
+pub struct SceneryPlugin;
+
+impl Plugin for SceneryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_scenery_assets)
+            .add_systems(Update, place_scenery);
+    }
+}
+
+// What sort of thing a piece of scenery is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum SceneryKind {
+    Tree,
+    Rock,
+    Crate,
+}
+
+impl SceneryKind {
+    const ALL: [SceneryKind; 3] = [SceneryKind::Tree, SceneryKind::Rock, SceneryKind::Crate];
+
+    fn surface(self) -> SurfaceMaterial {
+        match self {
+            SceneryKind::Tree | SceneryKind::Crate => SurfaceMaterial::Wood,
+            SceneryKind::Rock => SurfaceMaterial::Stone,
+        }
+    }
+}
+
+// Where a piece of scenery stands in the level, from the level asset
+#[derive(Deserialize)]
+pub struct SceneryPlacement {
+    pub kind: SceneryKind,
+    // Where its base sits
+    pub position: Vec3,
+    // Degrees around the vertical axis
+    #[serde(default)]
+    pub yaw: f32,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+// Marker for placed scenery
+#[derive(Component)]
+struct Scenery;
+
+// One mesh of a piece of scenery, relative to its base
+struct SceneryPart {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    transform: Transform,
+    collider: Option<Collider>,
+}
+
+// Resource with the parts each kind is built from. Every piece of a kind shares the same meshes
+// and materials, so they're drawn in a single instanced batch however many the level has.
+#[derive(Resource)]
+struct SceneryAssets(HashMap<SceneryKind, Vec<SceneryPart>>);
+
+fn setup_scenery_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut material = |color: Color, roughness: f32| {
+        materials.add(StandardMaterial {
+            base_color: color,
+            perceptual_roughness: roughness,
+            ..default()
+        })
+    };
+    let bark = material(Color::srgb(0.4, 0.28, 0.18), 0.9);
+    let leaves = material(Color::srgb(0.2, 0.45, 0.2), 0.8);
+    let stone = material(Color::srgb(0.5, 0.5, 0.48), 0.95);
+    let planks = material(Color::srgb(0.6, 0.45, 0.25), 0.8);
+
+    let rock_mesh = Sphere::new(ROCK_RADIUS)
+        .mesh()
+        .ico(1)
+        .unwrap_or_else(|_| Sphere::new(ROCK_RADIUS).mesh().build())
+        .scaled_by(Vec3::new(1.0, ROCK_SQUASH, 1.0));
+    let rock_collider = Collider::from_bevy_mesh(&rock_mesh, &ComputedColliderShape::ConvexHull)
+        .unwrap_or_else(|| Collider::ball(ROCK_RADIUS * ROCK_SQUASH));
+
+    let assets = SceneryKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let parts = match kind {
+                SceneryKind::Tree => vec![
+                    SceneryPart {
+                        mesh: meshes.add(Cylinder::new(TRUNK_RADIUS, TRUNK_HEIGHT)),
+                        material: bark.clone(),
+                        transform: Transform::from_xyz(0.0, TRUNK_HEIGHT / 2.0, 0.0),
+                        collider: Some(Collider::cylinder(TRUNK_HEIGHT / 2.0, TRUNK_RADIUS)),
+                    },
+                    // The canopy is out of reach overhead, so only the trunk blocks anyone
+                    SceneryPart {
+                        mesh: meshes.add(Cone::new(CANOPY_RADIUS, CANOPY_HEIGHT)),
+                        material: leaves.clone(),
+                        transform: Transform::from_xyz(
+                            0.0,
+                            TRUNK_HEIGHT + CANOPY_HEIGHT / 2.0 - 0.3,
+                            0.0,
+                        ),
+                        collider: None,
+                    },
+                ],
+                // Half sunk into the ground
+                SceneryKind::Rock => vec![SceneryPart {
+                    mesh: meshes.add(rock_mesh.clone()),
+                    material: stone.clone(),
+                    transform: Transform::from_xyz(0.0, ROCK_RADIUS * ROCK_SQUASH * 0.5, 0.0),
+                    collider: Some(rock_collider.clone()),
+                }],
+                SceneryKind::Crate => vec![SceneryPart {
+                    mesh: meshes.add(Cuboid::from_length(CRATE_SIZE)),
+                    material: planks.clone(),
+                    transform: Transform::from_xyz(0.0, CRATE_SIZE / 2.0, 0.0),
+                    collider: Some(Collider::cuboid(
+                        CRATE_SIZE / 2.0,
+                        CRATE_SIZE / 2.0,
+                        CRATE_SIZE / 2.0,
+                    )),
+                }],
+            };
+            (kind, parts)
+        })
+        .collect();
+    commands.insert_resource(SceneryAssets(assets));
+}
+
+// Set the level's scenery out whenever it's loaded, or edited while running
+fn place_scenery(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelConfig>>,
+    configs: Res<Assets<LevelConfig>>,
+    handle: Option<Res<LevelConfigHandle>>,
+    assets: Res<SceneryAssets>,
+    placed: Query<Entity, With<Scenery>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(config) = configs.get(&handle.0) else {
+            continue;
+        };
+        for entity in placed.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        for placement in &config.scenery {
+            let Some(parts) = assets.0.get(&placement.kind) else {
+                continue;
+            };
+            commands
+                .spawn((
+                    Transform::from_translation(placement.position)
+                        .with_rotation(Quat::from_rotation_y(placement.yaw.to_radians()))
+                        .with_scale(Vec3::splat(placement.scale)),
+                    Visibility::default(),
+                    Scenery,
+                ))
+                .with_children(|parent| {
+                    for part in parts {
+                        let mut child = parent.spawn((
+                            Mesh3d(part.mesh.clone()),
+                            MeshMaterial3d(part.material.clone()),
+                            part.transform,
+                        ));
+                        // No rigid body, so Rapier treats these as fixed in place
+                        if let Some(collider) = &part.collider {
+                            child.insert((collider.clone(), placement.kind.surface()));
+                        }
+                    }
+                });
+        }
+    }
+}
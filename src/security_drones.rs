@@ -0,0 +1,316 @@
+use crate::{GameStateSet, Npc, interpolation::TransformInterpolation, perception::Commotion};
+use bevy::{
+    math::cubic_splines::{CubicCardinalSpline, CubicCurve, CyclicCubicGenerator},
+    prelude::*,
+};
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+
+// Security drone constants
+const ZONE_CENTER: Vec3 = Vec3::new(0.0, 0.0, -15.0);
+const ZONE_HALF_EXTENTS: Vec3 = Vec3::new(6.0, 4.0, 6.0);
+const DRONE_COUNT: usize = 2;
+const DRONE_PATROL_HEIGHT: f32 = 6.0;
+const DRONE_PATROL_RADIUS: f32 = 9.0;
+const DRONE_PATROL_SPEED: f32 = 0.25; // Spline segments per second
+const DRONE_RADIUS: f32 = 0.4;
+const DRONE_SIGHT_RANGE: f32 = 18.0;
+const DRONE_SIGHT_COS: f32 = 0.5; // Half-angle of the sight cone, as a cosine
+const DRONE_ALARM_COOLDOWN: f32 = 5.0;
+const DRONE_DISABLE_DURATION: f32 = 8.0;
+const ALARM_GUARD_RADIUS: f32 = 40.0;
+const ALARM_GUARD_LINGER: f32 = 15.0; // How long alerted guards stay at the alarm
+const ALARM_COMMOTION_RADIUS: f32 = 20.0; // Bystanders this close are spooked by the siren
+
+pub struct SecurityDronesPlugin;
+
+impl Plugin for SecurityDronesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SecurityAlarm>()
+            .add_systems(Startup, spawn_security_drones)
+            .add_systems(
+                FixedUpdate,
+                patrol_drones
+                    .before(PhysicsSet::SyncBackend)
+                    .in_set(GameStateSet::Playing),
+            )
+            .add_systems(
+                Update,
+                (
+                    disable_hit_drones,
+                    spot_trespassers,
+                    alert_guards,
+                    update_drone_materials,
+                )
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Flying drone following a looping spline around a restricted zone
+#[derive(Component)]
+#[require(TransformInterpolation)]
+pub struct SecurityDrone {
+    path: CubicCurve<Vec3>,
+    progress: f32,
+    alarm_cooldown: Timer,
+    disabled: Timer,
+}
+
+impl SecurityDrone {
+    fn is_disabled(&self) -> bool {
+        !self.disabled.finished()
+    }
+}
+
+// Area the player isn't allowed into
+#[derive(Component)]
+pub struct RestrictedZone {
+    pub half_extents: Vec3,
+}
+
+impl RestrictedZone {
+    fn contains(&self, zone_transform: &Transform, point: Vec3) -> bool {
+        let local = point - zone_transform.translation;
+        local.abs().cmple(self.half_extents).all()
+    }
+}
+
+// Event sent when a drone spots the player somewhere they shouldn't be
+#[derive(Event)]
+pub struct SecurityAlarm {
+    pub position: Vec3,
+}
+
+// Materials drones switch between to show what they're doing
+#[derive(Resource)]
+struct DroneMaterials {
+    patrolling: Handle<StandardMaterial>,
+    alarmed: Handle<StandardMaterial>,
+    disabled: Handle<StandardMaterial>,
+}
+
+fn spawn_security_drones(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let drone_materials = DroneMaterials {
+        patrolling: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.2, 0.6, 0.9),
+            emissive: Color::srgb(0.0, 0.3, 0.6).into(),
+            ..default()
+        }),
+        alarmed: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.9, 0.1, 0.1),
+            emissive: Color::srgb(0.8, 0.0, 0.0).into(),
+            ..default()
+        }),
+        disabled: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.2, 0.2, 0.2),
+            perceptual_roughness: 0.9,
+            ..default()
+        }),
+    };
+
+    // Faint marker on the ground so the zone is visible
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(
+            ZONE_HALF_EXTENTS.x * 2.0,
+            0.02,
+            ZONE_HALF_EXTENTS.z * 2.0,
+        ))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(0.8, 0.1, 0.1, 0.35),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        })),
+        Transform::from_translation(ZONE_CENTER + Vec3::Y * 0.01),
+        RestrictedZone {
+            half_extents: ZONE_HALF_EXTENTS,
+        },
+    ));
+
+    // A loop of points around the zone, smoothed into a closed spline
+    let control_points: Vec<Vec3> = (0..6)
+        .map(|i| {
+            let angle = i as f32 / 6.0 * std::f32::consts::TAU;
+            let wobble = if i % 2 == 0 { 1.0 } else { 0.7 };
+            ZONE_CENTER
+                + Vec3::new(
+                    angle.cos() * DRONE_PATROL_RADIUS * wobble,
+                    DRONE_PATROL_HEIGHT + (i % 3) as f32 * 0.5,
+                    angle.sin() * DRONE_PATROL_RADIUS * wobble,
+                )
+        })
+        .collect();
+    let path = CubicCardinalSpline::new_catmull_rom(control_points)
+        .to_curve_cyclic()
+        .expect("drone patrol needs at least two points");
+
+    let drone_mesh = meshes.add(Sphere::new(DRONE_RADIUS));
+    let segments = path.segments().len() as f32;
+    for i in 0..DRONE_COUNT {
+        // Spread the drones evenly along the loop
+        let progress = i as f32 / DRONE_COUNT as f32 * segments;
+        let mut disabled = Timer::from_seconds(DRONE_DISABLE_DURATION, TimerMode::Once);
+        disabled.tick(disabled.duration());
+
+        commands.spawn((
+            Mesh3d(drone_mesh.clone()),
+            MeshMaterial3d(drone_materials.patrolling.clone()),
+            Transform::from_translation(path.position(progress)),
+            Collider::ball(DRONE_RADIUS),
+            RigidBody::KinematicPositionBased,
+            ActiveEvents::COLLISION_EVENTS,
+            SecurityDrone {
+                path: path.clone(),
+                progress,
+                alarm_cooldown: Timer::from_seconds(DRONE_ALARM_COOLDOWN, TimerMode::Once),
+                disabled,
+            },
+        ));
+    }
+
+    commands.insert_resource(drone_materials);
+}
+
+fn patrol_drones(time: Res<Time>, mut drones: Query<(&mut Transform, &mut SecurityDrone)>) {
+    for (mut transform, mut drone) in drones.iter_mut() {
+        drone.disabled.tick(time.delta());
+        drone.alarm_cooldown.tick(time.delta());
+        if drone.is_disabled() {
+            continue;
+        }
+
+        let segments = drone.path.segments().len() as f32;
+        drone.progress = (drone.progress + DRONE_PATROL_SPEED * time.delta_secs()) % segments;
+
+        // Face along the path so the sight cone looks where the drone is going
+        let position = drone.path.position(drone.progress);
+        let heading = drone.path.velocity(drone.progress).normalize_or_zero();
+        transform.translation = position;
+        if heading != Vec3::ZERO {
+            transform.look_to(heading, Vec3::Y);
+        }
+    }
+}
+
+// Anything dynamic hitting a drone knocks it out for a while
+fn disable_hit_drones(
+    mut collision_events: EventReader<CollisionEvent>,
+    bodies: Query<&RigidBody>,
+    mut drones: Query<&mut SecurityDrone>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(a, b, _) = event else {
+            continue;
+        };
+        for (drone_entity, other) in [(*a, *b), (*b, *a)] {
+            let Ok(mut drone) = drones.get_mut(drone_entity) else {
+                continue;
+            };
+            if matches!(bodies.get(other), Ok(RigidBody::Dynamic)) {
+                drone.disabled.reset();
+            }
+        }
+    }
+}
+
+fn spot_trespassers(
+    rapier_context: ReadRapierContext,
+    player_query: Query<(Entity, &Transform), With<KinematicCharacterController>>,
+    zones: Query<(&Transform, &RestrictedZone)>,
+    mut drones: Query<(Entity, &Transform, &mut SecurityDrone)>,
+    mut alarms: EventWriter<SecurityAlarm>,
+    mut commotions: EventWriter<Commotion>,
+) {
+    let Ok((player_entity, player_transform)) = player_query.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation;
+    let trespassing = zones
+        .iter()
+        .any(|(zone_transform, zone)| zone.contains(zone_transform, player_position));
+    if !trespassing {
+        return;
+    }
+
+    let physics = rapier_context.single();
+    for (drone_entity, drone_transform, mut drone) in drones.iter_mut() {
+        if drone.is_disabled() || !drone.alarm_cooldown.finished() {
+            continue;
+        }
+
+        let to_player = player_position - drone_transform.translation;
+        let distance = to_player.length();
+        if distance > DRONE_SIGHT_RANGE {
+            continue;
+        }
+        let direction = to_player / distance;
+        // Drones look ahead and down, so widen the cone toward the ground
+        let look = (drone_transform.forward().as_vec3() - Vec3::Y).normalize();
+        if direction.dot(look) < DRONE_SIGHT_COS {
+            continue;
+        }
+
+        // Walls and cubes block the view
+        let filter = QueryFilter::default()
+            .exclude_collider(drone_entity)
+            .exclude_sensors();
+        let sees_player = physics
+            .cast_ray(
+                drone_transform.translation,
+                direction,
+                distance,
+                true,
+                filter,
+            )
+            .is_some_and(|(hit, _)| hit == player_entity);
+        if sees_player {
+            drone.alarm_cooldown.reset();
+            alarms.send(SecurityAlarm {
+                position: player_position,
+            });
+            commotions.send(Commotion {
+                position: player_position,
+                radius: ALARM_COMMOTION_RADIUS,
+            });
+        }
+    }
+}
+
+// Guards near an alarm head over to investigate
+fn alert_guards(mut alarms: EventReader<SecurityAlarm>, mut npcs: Query<(&Transform, &mut Npc)>) {
+    for alarm in alarms.read() {
+        println!("Security alarm at {:.1}!", alarm.position);
+        for (transform, mut npc) in npcs.iter_mut() {
+            if npc.dialogue_id != "guard"
+                || transform.translation.distance(alarm.position) > ALARM_GUARD_RADIUS
+            {
+                continue;
+            }
+            npc.target_position =
+                Vec3::new(alarm.position.x, npc.home_position.y, alarm.position.z);
+            npc.movement_timer = Timer::from_seconds(ALARM_GUARD_LINGER, TimerMode::Once);
+        }
+    }
+}
+
+fn update_drone_materials(
+    materials: Res<DroneMaterials>,
+    mut drones: Query<(&SecurityDrone, &mut MeshMaterial3d<StandardMaterial>)>,
+) {
+    for (drone, mut material) in drones.iter_mut() {
+        let wanted = if drone.is_disabled() {
+            &materials.disabled
+        } else if !drone.alarm_cooldown.finished() {
+            &materials.alarmed
+        } else {
+            &materials.patrolling
+        };
+        if material.0 != *wanted {
+            material.0 = wanted.clone();
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    GameState, GameStateSet,
+    input_map::{ActionState, InputAction, InputMap},
+};
+use bevy::prelude::*;
+
+// Sprint indicator constants
+const INDICATOR_MARGIN: f32 = 16.0;
+const INDICATOR_FONT_SIZE: f32 = 16.0;
+const INDICATOR_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const INDICATOR_BACKGROUND_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.7);
+
+pub struct SprintIndicatorPlugin;
+
+impl Plugin for SprintIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), setup_sprint_indicator)
+            .add_systems(
+                Update,
+                update_sprint_indicator.in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Marker for the HUD badge shown while sprinting
+#[derive(Component)]
+struct SprintIndicator;
+
+fn setup_sprint_indicator(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: INDICATOR_FONT_SIZE,
+            ..default()
+        },
+        TextColor(INDICATOR_TEXT_COLOR),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(INDICATOR_MARGIN),
+            bottom: Val::Px(INDICATOR_MARGIN),
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(INDICATOR_BACKGROUND_COLOR),
+        BorderRadius::all(Val::Px(4.0)),
+        Visibility::Hidden,
+        StateScoped(GameState::Playing),
+        SprintIndicator,
+    ));
+}
+
+fn update_sprint_indicator(
+    actions: Res<ActionState>,
+    input_map: Res<InputMap>,
+    mut indicator_query: Query<(&mut Text, &mut Visibility), With<SprintIndicator>>,
+) {
+    let Ok((mut text, mut visibility)) = indicator_query.get_single_mut() else {
+        return;
+    };
+    if !actions.pressed(InputAction::Sprint) {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+
+    *visibility = Visibility::Inherited;
+    // A toggled sprint stays on after letting go, so say so
+    let label = if input_map.toggle_sprint {
+        "Sprint (toggled)"
+    } else {
+        "Sprint"
+    };
+    if text.0 != label {
+        text.0 = label.to_string();
+    }
+}
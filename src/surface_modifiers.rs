@@ -0,0 +1,110 @@
+use crate::footsteps::SurfaceMaterial;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+// Surface modifier constants
+const PAD_HALF_HEIGHT: f32 = 0.05;
+const PAD_TOP: f32 = 0.01; // Just above the ground so the pads don't flicker into it
+const ICE_POSITION: Vec3 = Vec3::new(-10.0, 0.0, 32.0);
+const MUD_POSITION: Vec3 = Vec3::new(0.0, 0.0, 32.0);
+const CONVEYOR_POSITION: Vec3 = Vec3::new(10.0, 0.0, 32.0);
+const PAD_HALF_SIZE: f32 = 3.0;
+const CONVEYOR_HALF_WIDTH: f32 = 1.0;
+const CONVEYOR_HALF_LENGTH: f32 = 6.0;
+const CONVEYOR_SPEED: f32 = 4.0;
+
+pub struct SurfaceModifiersPlugin;
+
+impl Plugin for SurfaceModifiersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_modifier_surfaces);
+    }
+}
+
+// Component on a collider that changes how the player moves while standing on it
+#[derive(Component, Clone, Copy)]
+pub struct SurfaceModifier {
+    // Multiplier on how quickly the player speeds up and slows down
+    pub grip: f32,
+    // Multiplier on the player's top speed
+    pub speed: f32,
+    // World-space velocity the surface carries the player along at
+    pub conveyor: Vec3,
+}
+
+impl Default for SurfaceModifier {
+    fn default() -> Self {
+        Self {
+            grip: 1.0,
+            speed: 1.0,
+            conveyor: Vec3::ZERO,
+        }
+    }
+}
+
+impl SurfaceModifier {
+    // Slow to get going and slower to stop
+    pub const ICE: SurfaceModifier = SurfaceModifier {
+        grip: 0.1,
+        speed: 1.0,
+        conveyor: Vec3::ZERO,
+    };
+    // Sticky and sluggish
+    pub const MUD: SurfaceModifier = SurfaceModifier {
+        grip: 1.0,
+        speed: 0.4,
+        conveyor: Vec3::ZERO,
+    };
+
+    pub fn conveyor(velocity: Vec3) -> Self {
+        Self {
+            conveyor: velocity,
+            ..default()
+        }
+    }
+}
+
+fn spawn_modifier_surfaces(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let pads = [
+        (
+            ICE_POSITION,
+            Vec2::splat(PAD_HALF_SIZE),
+            Color::srgb(0.75, 0.9, 1.0),
+            SurfaceModifier::ICE,
+            SurfaceMaterial::Stone,
+        ),
+        (
+            MUD_POSITION,
+            Vec2::splat(PAD_HALF_SIZE),
+            Color::srgb(0.35, 0.25, 0.15),
+            SurfaceModifier::MUD,
+            SurfaceMaterial::Grass,
+        ),
+        (
+            CONVEYOR_POSITION,
+            Vec2::new(CONVEYOR_HALF_WIDTH, CONVEYOR_HALF_LENGTH),
+            Color::srgb(0.2, 0.2, 0.22),
+            SurfaceModifier::conveyor(Vec3::Z * CONVEYOR_SPEED),
+            SurfaceMaterial::Stone,
+        ),
+    ];
+    for (position, half_size, color, modifier, surface) in pads {
+        let half_extents = Vec3::new(half_size.x, PAD_HALF_HEIGHT, half_size.y);
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::from_size(half_extents * 2.0))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: color,
+                perceptual_roughness: if modifier.grip < 1.0 { 0.1 } else { 0.9 },
+                ..default()
+            })),
+            Transform::from_translation(position.with_y(PAD_TOP - PAD_HALF_HEIGHT)),
+            Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            modifier,
+            surface,
+        ));
+    }
+}
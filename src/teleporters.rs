@@ -0,0 +1,274 @@
+use crate::{
+    GameStateSet, LookInput, accessibility::AccessibilitySettings, mantle::Mantling,
+    particles::ParticleBurst, platforms::Riding,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+
+// Teleporter constants
+const PAD_RADIUS: f32 = 1.0;
+const PAD_HALF_HEIGHT: f32 = 0.05;
+const PAD_REACH: f32 = 2.5; // How far above the pad the player's middle can be and still count as on it
+const ARRIVAL_HEIGHT: f32 = 1.2; // Drops the capsule in just above the destination pad
+const FADE_DURATION: f32 = 0.25; // Seconds to fade out, and again to fade back in
+// In the plaza, and up on the roof of the ladder tower
+const GROUND_PAD_POSITION: Vec3 = Vec3::new(-18.0, 0.0, -25.0);
+const ROOF_PAD_POSITION: Vec3 = Vec3::new(16.5, 8.0, 13.5);
+const ROOF_PAD_YAW: f32 = 90.0; // Looking across the roof rather than off its edge
+const SPARK_COLOR: Color = Color::srgb(0.5, 0.8, 1.0);
+
+pub struct TeleportersPlugin;
+
+impl Plugin for TeleportersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TeleportState>()
+            .add_event::<PlayerTeleported>()
+            .add_systems(Startup, (spawn_teleporters, setup_teleport_fade))
+            // Like respawning, the move happens outside the fixed steps so interpolation snaps
+            .add_systems(
+                Update,
+                (
+                    enter_teleporters,
+                    run_teleport,
+                    update_teleport_fade,
+                    spark_teleporters,
+                )
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Component for a pad that sends the player to another when they step onto it
+#[derive(Component)]
+pub struct Teleporter {
+    pub destination: Entity,
+    // Degrees around Y to face on arrival, or None to keep looking the same way
+    pub exit_yaw: Option<f32>,
+    // Whether the player keeps their momentum through, turned along with their facing
+    pub keep_velocity: bool,
+}
+
+// Event sent once the player has come out of a teleporter
+#[derive(Event)]
+pub struct PlayerTeleported {
+    pub from: Entity,
+    pub to: Entity,
+    // Degrees the player was turned around Y on the way through
+    pub turn: f32,
+    pub keep_velocity: bool,
+}
+
+// Resource tracking a teleport in progress
+#[derive(Resource, Default)]
+struct TeleportState {
+    phase: TeleportPhase,
+    // The pad the player came out on, which stays quiet until they step off it
+    arrived_on: Option<Entity>,
+}
+
+#[derive(Default)]
+enum TeleportPhase {
+    #[default]
+    Idle,
+    FadingOut {
+        pad: Entity,
+        elapsed: f32,
+    },
+    FadingIn {
+        elapsed: f32,
+    },
+}
+
+impl TeleportState {
+    // How dark the screen is, from 0 to 1, for fades taking `fade_duration` each way
+    fn darkness(&self, fade_duration: f32) -> f32 {
+        match self.phase {
+            TeleportPhase::Idle => 0.0,
+            TeleportPhase::FadingOut { elapsed, .. } => (elapsed / fade_duration).min(1.0),
+            TeleportPhase::FadingIn { elapsed } => 1.0 - (elapsed / fade_duration).min(1.0),
+        }
+    }
+}
+
+// Marker for the full-screen overlay that hides the jump
+#[derive(Component)]
+struct TeleportFade;
+
+fn spawn_teleporters(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Cylinder::new(PAD_RADIUS, PAD_HALF_HEIGHT * 2.0));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.3, 0.5, 0.9),
+        emissive: LinearRgba::rgb(0.2, 0.6, 1.5),
+        ..default()
+    });
+    let mut spawn_pad = |position: Vec3| {
+        commands
+            .spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(position + Vec3::Y * PAD_HALF_HEIGHT),
+                Collider::cylinder(PAD_HALF_HEIGHT, PAD_RADIUS),
+            ))
+            .id()
+    };
+    let ground = spawn_pad(GROUND_PAD_POSITION);
+    let roof = spawn_pad(ROOF_PAD_POSITION);
+
+    // Up onto the roof from a standstill, and back down keeping whatever run-up the player had
+    commands.entity(ground).insert(Teleporter {
+        destination: roof,
+        exit_yaw: Some(ROOF_PAD_YAW),
+        keep_velocity: false,
+    });
+    commands.entity(roof).insert(Teleporter {
+        destination: ground,
+        exit_yaw: None,
+        keep_velocity: true,
+    });
+}
+
+fn setup_teleport_fade(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        // Over the rest of the HUD, so nothing is left floating in the dark
+        GlobalZIndex(i32::MAX),
+        Visibility::Hidden,
+        TeleportFade,
+    ));
+}
+
+// Start fading out when the player steps onto a pad
+fn enter_teleporters(
+    mut state: ResMut<TeleportState>,
+    player: Query<&Transform, With<KinematicCharacterController>>,
+    pads: Query<(Entity, &GlobalTransform), With<Teleporter>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let standing_on = pads
+        .iter()
+        .find(|(_, pad)| {
+            let offset = player.translation - pad.translation();
+            offset.with_y(0.0).length() < PAD_RADIUS && (0.0..PAD_REACH).contains(&offset.y)
+        })
+        .map(|(entity, _)| entity);
+
+    if state.arrived_on.is_some() && state.arrived_on != standing_on {
+        state.arrived_on = None;
+    }
+    if !matches!(state.phase, TeleportPhase::Idle) || state.arrived_on.is_some() {
+        return;
+    }
+    if let Some(pad) = standing_on {
+        state.phase = TeleportPhase::FadingOut { pad, elapsed: 0.0 };
+    }
+}
+
+// Move the player across once the screen is dark, then fade back in
+#[allow(clippy::too_many_arguments)]
+fn run_teleport(
+    mut commands: Commands,
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    mut state: ResMut<TeleportState>,
+    mut look: ResMut<LookInput>,
+    mut teleported: EventWriter<PlayerTeleported>,
+    mut player: Query<(Entity, &mut Transform), With<KinematicCharacterController>>,
+    pads: Query<(&Teleporter, &GlobalTransform)>,
+) {
+    let delta_time = time.delta_secs();
+    let fade_duration = FADE_DURATION * accessibility.ui_duration_scale();
+    let pad = match &mut state.phase {
+        TeleportPhase::Idle => return,
+        TeleportPhase::FadingIn { elapsed } => {
+            *elapsed += delta_time;
+            if *elapsed >= fade_duration {
+                state.phase = TeleportPhase::Idle;
+            }
+            return;
+        }
+        TeleportPhase::FadingOut { pad, elapsed } => {
+            *elapsed += delta_time;
+            if *elapsed < fade_duration {
+                return;
+            }
+            *pad
+        }
+    };
+    state.phase = TeleportPhase::FadingIn { elapsed: 0.0 };
+
+    let Ok((teleporter, _)) = pads.get(pad) else {
+        return;
+    };
+    let Ok((_, destination)) = pads.get(teleporter.destination) else {
+        return;
+    };
+    let Ok((entity, mut transform)) = player.get_single_mut() else {
+        return;
+    };
+
+    transform.translation = destination.translation() + Vec3::Y * ARRIVAL_HEIGHT;
+    let turn = teleporter.exit_yaw.map_or(0.0, |yaw| yaw - look.x);
+    look.x += turn;
+    commands.entity(entity).remove::<(Mantling, Riding)>();
+    state.arrived_on = Some(teleporter.destination);
+    teleported.send(PlayerTeleported {
+        from: pad,
+        to: teleporter.destination,
+        turn,
+        keep_velocity: teleporter.keep_velocity,
+    });
+}
+
+fn update_teleport_fade(
+    state: Res<TeleportState>,
+    accessibility: Res<AccessibilitySettings>,
+    mut fade: Query<(&mut BackgroundColor, &mut Visibility), With<TeleportFade>>,
+) {
+    let Ok((mut color, mut visibility)) = fade.get_single_mut() else {
+        return;
+    };
+    let darkness = state.darkness(FADE_DURATION * accessibility.ui_duration_scale());
+    *visibility = if darkness > 0.0 {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    color.0 = Color::BLACK.with_alpha(darkness);
+}
+
+// A flash of sparks over both ends of the jump
+fn spark_teleporters(
+    mut teleported: EventReader<PlayerTeleported>,
+    pads: Query<&GlobalTransform, With<Teleporter>>,
+    mut bursts: EventWriter<ParticleBurst>,
+) {
+    for teleport in teleported.read() {
+        for pad in pads
+            .get_many([teleport.from, teleport.to])
+            .into_iter()
+            .flatten()
+        {
+            bursts.send(ParticleBurst {
+                position: pad.translation(),
+                count: 16,
+                color: SPARK_COLOR,
+                speed: 3.0,
+                lifetime: 0.6,
+                size: 0.08,
+            });
+        }
+    }
+}
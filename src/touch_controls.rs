@@ -0,0 +1,196 @@
+use crate::{
+    GameState, GameStateSet, LookInput, MovementInput, handle_input,
+    input_map::{ActionState, InputAction, controls_menu_closed, update_action_state},
+    look_settings::LookSettings,
+    zoom::Zoom,
+};
+use bevy::{prelude::*, ui::UiSystem};
+
+// Touch control constants
+const JOYSTICK_RADIUS: f32 = 60.0; // Drag this far from where the thumb landed for full speed
+const JOYSTICK_KNOB_SIZE: f32 = 50.0;
+const BUTTON_SIZE: f32 = 80.0;
+const CONTROL_MARGIN: f32 = 40.0;
+const CONTROL_COLOR: Color = Color::srgba(0.9, 0.9, 0.9, 0.25);
+const CONTROL_PRESSED_COLOR: Color = Color::srgba(0.9, 0.9, 0.9, 0.5);
+const TOUCH_LOOK_SCALE: f32 = 0.5; // Relative to mouse sensitivity, since a drag covers more pixels
+
+pub struct TouchControlsPlugin;
+
+impl Plugin for TouchControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchSticks>()
+            .add_systems(OnEnter(GameState::Playing), setup_touch_controls)
+            .add_systems(OnExit(GameState::Playing), release_touch_sticks)
+            .add_systems(
+                PreUpdate,
+                (press_touch_buttons, read_touch_sticks, update_joystick_knob)
+                    .chain()
+                    .after(update_action_state)
+                    .after(UiSystem::Focus)
+                    .before(handle_input)
+                    .run_if(controls_menu_closed)
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Resource with which finger is steering and which is looking around
+#[derive(Resource, Default)]
+struct TouchSticks {
+    movement: Option<u64>,
+    look: Option<u64>,
+    // Joystick deflection, up to one at the edge of its radius
+    deflection: Vec2,
+}
+
+// On-screen button standing in for a bound one
+#[derive(Component)]
+struct TouchButton(InputAction);
+
+// Marker for the knob showing how far the joystick is pushed
+#[derive(Component)]
+struct JoystickKnob;
+
+fn setup_touch_controls(mut commands: Commands) {
+    let base_size = JOYSTICK_RADIUS * 2.0;
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(CONTROL_MARGIN),
+                bottom: Val::Px(CONTROL_MARGIN),
+                width: Val::Px(base_size),
+                height: Val::Px(base_size),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderRadius::MAX,
+            BackgroundColor(CONTROL_COLOR),
+            StateScoped(GameState::Playing),
+        ))
+        .with_children(|base| {
+            base.spawn((
+                Node {
+                    width: Val::Px(JOYSTICK_KNOB_SIZE),
+                    height: Val::Px(JOYSTICK_KNOB_SIZE),
+                    ..default()
+                },
+                BorderRadius::MAX,
+                BackgroundColor(CONTROL_PRESSED_COLOR),
+                JoystickKnob,
+            ));
+        });
+
+    commands
+        .spawn((
+            Button,
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(CONTROL_MARGIN),
+                bottom: Val::Px(CONTROL_MARGIN),
+                width: Val::Px(BUTTON_SIZE),
+                height: Val::Px(BUTTON_SIZE),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderRadius::MAX,
+            BackgroundColor(CONTROL_COLOR),
+            TouchButton(InputAction::Interact),
+            StateScoped(GameState::Playing),
+        ))
+        .with_child(Text::new("Talk"));
+}
+
+// Feed on-screen buttons into the action state, as if their bound button was pressed
+fn press_touch_buttons(
+    mut actions: ResMut<ActionState>,
+    mut buttons: Query<(&TouchButton, Ref<Interaction>, &mut BackgroundColor)>,
+) {
+    for (button, interaction, mut color) in buttons.iter_mut() {
+        let pressed = *interaction == Interaction::Pressed;
+        if pressed {
+            actions.press(button.0, interaction.is_changed());
+        }
+        let target = if pressed {
+            CONTROL_PRESSED_COLOR
+        } else {
+            CONTROL_COLOR
+        };
+        if color.0 != target {
+            color.0 = target;
+        }
+    }
+}
+
+// The left half of the screen steers and the right half looks around
+fn read_touch_sticks(
+    time: Res<Time>,
+    touches: Res<Touches>,
+    windows: Query<&Window>,
+    buttons: Query<&Interaction, With<TouchButton>>,
+    look_settings: Res<LookSettings>,
+    zoom: Res<Zoom>,
+    mut sticks: ResMut<TouchSticks>,
+    mut movement: ResMut<MovementInput>,
+    mut look: ResMut<LookInput>,
+    mut smoothed_look: Local<Vec2>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    // A finger landing on a button is pressing it, not steering
+    let on_button = buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    for touch in touches.iter_just_pressed() {
+        if on_button {
+            continue;
+        }
+        let stick = if touch.position().x < window.width() / 2.0 {
+            &mut sticks.movement
+        } else {
+            &mut sticks.look
+        };
+        stick.get_or_insert(touch.id());
+    }
+    let still_down = |id: Option<u64>| id.filter(|id| touches.get_pressed(*id).is_some());
+    sticks.movement = still_down(sticks.movement);
+    sticks.look = still_down(sticks.look);
+
+    sticks.deflection = sticks
+        .movement
+        .and_then(|id| touches.get_pressed(id))
+        .map_or(Vec2::ZERO, |touch| {
+            ((touch.position() - touch.start_position()) / JOYSTICK_RADIUS).clamp_length_max(1.0)
+        });
+    // Screen y points down, so dragging up walks forward
+    movement.x += sticks.deflection.x;
+    movement.z += sticks.deflection.y;
+
+    let drag = sticks
+        .look
+        .and_then(|id| touches.get_pressed(id))
+        .map_or(Vec2::ZERO, |touch| touch.delta() * TOUCH_LOOK_SCALE);
+    let look_delta = look_settings.look_delta(drag, &mut smoothed_look, time.delta_secs());
+    **look -= look_delta * zoom.look_scale();
+}
+
+fn update_joystick_knob(sticks: Res<TouchSticks>, mut knobs: Query<&mut Node, With<JoystickKnob>>) {
+    let offset = sticks.deflection * JOYSTICK_RADIUS;
+    let (left, top) = (Val::Px(offset.x), Val::Px(offset.y));
+    for mut node in knobs.iter_mut() {
+        // Only touch the node when it moves so it isn't laid out again every frame
+        if node.left != left || node.top != top {
+            node.left = left;
+            node.top = top;
+        }
+    }
+}
+
+// Fingers still down when a conversation starts shouldn't keep steering afterwards
+fn release_touch_sticks(mut sticks: ResMut<TouchSticks>) {
+    *sticks = TouchSticks::default();
+}
@@ -0,0 +1,413 @@
+use crate::{
+    GameStateSet, Npc,
+    dialogue_variables::{DialogueValue, DialogueVariables},
+    interpolation::TransformInterpolation,
+    level::{LevelConfig, LevelConfigHandle},
+    region_names::RegionName,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+// Trigger constants
+const PLATE_HALF_SIZE: f32 = 0.8;
+const PLATE_HALF_HEIGHT: f32 = 0.05;
+const PLATE_SINK: f32 = 0.04; // How far a plate drops while something stands on it
+const PLATE_REACH: f32 = 2.0; // How far above the plate someone's middle can be and still weigh on it
+const DOOR_HALF_EXTENTS: Vec3 = Vec3::new(1.5, 1.5, 0.15);
+const DOOR_SPEED: f32 = 2.0;
+const LAMP_INTENSITY: f32 = 200_000.0;
+// A plate by the east wall opening a gate beside it and lighting a lamp over it
+const PLATE_POSITION: Vec3 = Vec3::new(26.0, 0.0, -6.0);
+const GATE_POSITION: Vec3 = Vec3::new(26.0, 0.0, -10.0);
+const LAMP_POSITION: Vec3 = Vec3::new(28.0, 3.0, -8.0);
+
+pub struct TriggersPlugin;
+
+impl Plugin for TriggersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TriggerActivated>()
+            .add_event::<TriggerReleased>()
+            .add_event::<TriggerVolumeEntered>()
+            .add_event::<TriggerVolumeExited>()
+            .add_systems(Startup, spawn_plate_mechanism)
+            .add_systems(Update, place_trigger_volumes)
+            .add_systems(
+                Update,
+                (
+                    (press_plates, detect_trigger_volumes),
+                    (open_doors, switch_lights, record_trigger_volumes),
+                )
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            )
+            .add_systems(FixedUpdate, move_doors.in_set(GameStateSet::Playing));
+    }
+}
+
+// Component listing the entities a trigger drives, like the door and lamp a plate is wired to
+#[derive(Component, Default)]
+pub struct TriggerLink(pub Vec<Entity>);
+
+// Event sent to each linked entity when a trigger turns on
+#[derive(Event)]
+pub struct TriggerActivated {
+    pub trigger: Entity,
+    pub target: Entity,
+}
+
+// Event sent to each linked entity when a trigger turns back off
+#[derive(Event)]
+pub struct TriggerReleased {
+    pub trigger: Entity,
+    pub target: Entity,
+}
+
+// Component for a plate that's held down by the player or any NPC standing on it
+#[derive(Component, Default)]
+#[require(TriggerLink)]
+pub struct PressurePlate {
+    pub pressed: bool,
+}
+
+// Shape of a trigger volume, around its position
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum TriggerShape {
+    Box { half_extents: Vec3 },
+    Sphere { radius: f32 },
+}
+
+impl TriggerShape {
+    pub fn collider(self) -> Collider {
+        match self {
+            TriggerShape::Box { half_extents } => {
+                Collider::cuboid(half_extents.x, half_extents.y, half_extents.z)
+            }
+            TriggerShape::Sphere { radius } => Collider::ball(radius),
+        }
+    }
+}
+
+// Component for a sensor that tells the rest of the game, under its tag, when the player walks
+// in or out, and turns its links on for as long as they're inside
+#[derive(Component)]
+#[require(Sensor, TriggerLink)]
+pub struct TriggerVolume {
+    // Letters, digits and underscores, so dialogue can check on it
+    pub tag: String,
+    occupied: bool,
+}
+
+impl TriggerVolume {
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            occupied: false,
+        }
+    }
+}
+
+// A trigger volume placed in the level, from the level asset
+#[derive(Deserialize)]
+pub struct TriggerVolumePlacement {
+    pub tag: String,
+    pub position: Vec3,
+    pub shape: TriggerShape,
+    // Shown on screen when the player walks in, for volumes that mark out a region
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+// Event sent when the player walks into a trigger volume
+#[derive(Event)]
+pub struct TriggerVolumeEntered {
+    pub volume: Entity,
+    pub tag: String,
+}
+
+// Event sent when the player leaves a trigger volume
+#[derive(Event)]
+pub struct TriggerVolumeExited {
+    pub volume: Entity,
+    pub tag: String,
+}
+
+// Component for a door that slides open while any of the triggers wired to it is on
+#[derive(Component)]
+#[require(
+    RigidBody(|| RigidBody::KinematicPositionBased),
+    TransformInterpolation
+)]
+pub struct TriggerDoor {
+    pub closed: Vec3,
+    pub open: Vec3,
+    // Triggers currently holding it open
+    held_by: Vec<Entity>,
+}
+
+impl TriggerDoor {
+    pub fn new(closed: Vec3, open: Vec3) -> Self {
+        Self {
+            closed,
+            open,
+            held_by: Vec::new(),
+        }
+    }
+}
+
+// Marker for a light that's lit while its trigger is on
+#[derive(Component)]
+pub struct TriggerLight {
+    pub intensity: f32,
+}
+
+fn spawn_plate_mechanism(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let gate_closed = GATE_POSITION + Vec3::Y * DOOR_HALF_EXTENTS.y;
+    let gate = commands
+        .spawn((
+            Mesh3d(meshes.add(Cuboid::from_size(DOOR_HALF_EXTENTS * 2.0))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgb(0.35, 0.3, 0.25),
+                metallic: 0.5,
+                perceptual_roughness: 0.5,
+                ..default()
+            })),
+            Transform::from_translation(gate_closed),
+            Collider::cuboid(
+                DOOR_HALF_EXTENTS.x,
+                DOOR_HALF_EXTENTS.y,
+                DOOR_HALF_EXTENTS.z,
+            ),
+            // Up into the air, out of the way
+            TriggerDoor::new(
+                gate_closed,
+                gate_closed + Vec3::Y * (DOOR_HALF_EXTENTS.y * 2.0 - 0.2),
+            ),
+        ))
+        .id();
+    let lamp = commands
+        .spawn((
+            PointLight {
+                intensity: 0.0,
+                color: Color::srgb(1.0, 0.8, 0.5),
+                shadows_enabled: false,
+                ..default()
+            },
+            Transform::from_translation(LAMP_POSITION),
+            TriggerLight {
+                intensity: LAMP_INTENSITY,
+            },
+        ))
+        .id();
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(
+            PLATE_HALF_SIZE * 2.0,
+            PLATE_HALF_HEIGHT * 2.0,
+            PLATE_HALF_SIZE * 2.0,
+        ))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.5, 0.45, 0.3),
+            metallic: 0.7,
+            perceptual_roughness: 0.4,
+            ..default()
+        })),
+        Transform::from_translation(PLATE_POSITION + Vec3::Y * PLATE_HALF_HEIGHT),
+        PressurePlate::default(),
+        TriggerLink(vec![gate, lamp]),
+    ));
+}
+
+// Press plates anyone is standing on, sinking them a little, and let their links know
+#[allow(clippy::type_complexity)]
+fn press_plates(
+    mut plates: Query<(Entity, &mut Transform, &mut PressurePlate, &TriggerLink)>,
+    weights: Query<&GlobalTransform, Or<(With<KinematicCharacterController>, With<Npc>)>>,
+    mut activated: EventWriter<TriggerActivated>,
+    mut released: EventWriter<TriggerReleased>,
+) {
+    for (trigger, mut transform, mut plate, link) in plates.iter_mut() {
+        let rest = transform.translation.y + if plate.pressed { PLATE_SINK } else { 0.0 };
+        let center = transform.translation.with_y(rest);
+        let pressed = weights.iter().any(|weight| {
+            let offset = weight.translation() - center;
+            offset.x.abs() < PLATE_HALF_SIZE
+                && offset.z.abs() < PLATE_HALF_SIZE
+                && (0.0..PLATE_REACH).contains(&offset.y)
+        });
+        if pressed == plate.pressed {
+            continue;
+        }
+
+        plate.pressed = pressed;
+        transform.translation.y = if pressed { rest - PLATE_SINK } else { rest };
+        for &target in &link.0 {
+            if pressed {
+                activated.send(TriggerActivated { trigger, target });
+            } else {
+                released.send(TriggerReleased { trigger, target });
+            }
+        }
+    }
+}
+
+// Put the level's trigger volumes out whenever it's loaded, or edited while running
+fn place_trigger_volumes(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelConfig>>,
+    configs: Res<Assets<LevelConfig>>,
+    handle: Option<Res<LevelConfigHandle>>,
+    placed: Query<Entity, With<TriggerVolume>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(config) = configs.get(&handle.0) else {
+            continue;
+        };
+        for entity in placed.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        for placement in &config.trigger_volumes {
+            let mut volume = commands.spawn((
+                Transform::from_translation(placement.position),
+                placement.shape.collider(),
+                TriggerVolume::new(placement.tag.clone()),
+            ));
+            if let Some(region) = &placement.region {
+                volume.insert(RegionName(region.clone()));
+            }
+        }
+    }
+}
+
+// The player has no rigid body, so sensor pairs aren't reported and the volumes are queried directly
+fn detect_trigger_volumes(
+    rapier_context: ReadRapierContext,
+    player: Query<(Entity, &Transform, &Collider), With<KinematicCharacterController>>,
+    mut volumes: Query<(Entity, &mut TriggerVolume, &TriggerLink)>,
+    mut entered: EventWriter<TriggerVolumeEntered>,
+    mut exited: EventWriter<TriggerVolumeExited>,
+    mut activated: EventWriter<TriggerActivated>,
+    mut released: EventWriter<TriggerReleased>,
+) {
+    let Ok((player, transform, collider)) = player.get_single() else {
+        return;
+    };
+    let physics = rapier_context.single();
+    let mut inside = HashSet::new();
+    physics.intersections_with_shape(
+        transform.translation,
+        transform.rotation,
+        collider,
+        QueryFilter::default().exclude_collider(player),
+        |entity| {
+            if volumes.contains(entity) {
+                inside.insert(entity);
+            }
+            true
+        },
+    );
+
+    for (trigger, mut volume, link) in volumes.iter_mut() {
+        let occupied = inside.contains(&trigger);
+        if occupied == volume.occupied {
+            continue;
+        }
+
+        volume.occupied = occupied;
+        let tag = volume.tag.clone();
+        if occupied {
+            entered.send(TriggerVolumeEntered {
+                volume: trigger,
+                tag,
+            });
+        } else {
+            exited.send(TriggerVolumeExited {
+                volume: trigger,
+                tag,
+            });
+        }
+        for &target in &link.0 {
+            if occupied {
+                activated.send(TriggerActivated { trigger, target });
+            } else {
+                released.send(TriggerReleased { trigger, target });
+            }
+        }
+    }
+}
+
+// Let dialogue and quests know where the player is and has been: `in_<tag>` while they're inside
+// a volume, and `visited_<tag>` once they've been in it at all
+fn record_trigger_volumes(
+    mut entered: EventReader<TriggerVolumeEntered>,
+    mut exited: EventReader<TriggerVolumeExited>,
+    mut variables: ResMut<DialogueVariables>,
+) {
+    for event in entered.read() {
+        variables.set(format!("in_{}", event.tag), DialogueValue::Bool(true));
+        variables.set(format!("visited_{}", event.tag), DialogueValue::Bool(true));
+    }
+    for event in exited.read() {
+        variables.set(format!("in_{}", event.tag), DialogueValue::Bool(false));
+    }
+}
+
+fn open_doors(
+    mut activated: EventReader<TriggerActivated>,
+    mut released: EventReader<TriggerReleased>,
+    mut doors: Query<&mut TriggerDoor>,
+) {
+    for event in activated.read() {
+        if let Ok(mut door) = doors.get_mut(event.target)
+            && !door.held_by.contains(&event.trigger)
+        {
+            door.held_by.push(event.trigger);
+        }
+    }
+    for event in released.read() {
+        if let Ok(mut door) = doors.get_mut(event.target) {
+            door.held_by.retain(|trigger| *trigger != event.trigger);
+        }
+    }
+}
+
+fn switch_lights(
+    mut activated: EventReader<TriggerActivated>,
+    mut released: EventReader<TriggerReleased>,
+    mut lights: Query<(&mut PointLight, &TriggerLight)>,
+) {
+    for event in activated.read() {
+        if let Ok((mut light, trigger_light)) = lights.get_mut(event.target) {
+            light.intensity = trigger_light.intensity;
+        }
+    }
+    for event in released.read() {
+        if let Ok((mut light, _)) = lights.get_mut(event.target) {
+            light.intensity = 0.0;
+        }
+    }
+}
+
+// Slide each door toward wherever its trigger wants it
+fn move_doors(time: Res<Time>, mut doors: Query<(&mut Transform, &TriggerDoor)>) {
+    let step = DOOR_SPEED * time.delta_secs();
+    for (mut transform, door) in doors.iter_mut() {
+        let target = if door.held_by.is_empty() {
+            door.closed
+        } else {
+            door.open
+        };
+        transform.translation = transform.translation.move_towards(target, step);
+    }
+}
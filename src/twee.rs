@@ -0,0 +1,221 @@
+use crate::{DialogueNode, DialogueOption, DialogueTree, dialogue_assets::DialogueAsset};
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+
+// Option text used when a passage has no links and ends the conversation
+const TWEE_END_TEXT: &str = "Goodbye.";
+// Passage Twine starts at when the story data doesn't say otherwise
+const TWEE_START_PASSAGE: &str = "Start";
+// Special passages that hold story metadata rather than content
+const TWEE_METADATA_PASSAGES: [&str; 2] = ["StoryTitle", "StoryData"];
+
+// Loads Twee 3 exports from Twine as dialogue trees
+#[derive(Default)]
+pub struct TweeLoader;
+
+impl AssetLoader for TweeLoader {
+    type Asset = DialogueAsset;
+    type Settings = ();
+    type Error = TweeError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<DialogueAsset, TweeError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let source = String::from_utf8(bytes).map_err(|_| TweeError::InvalidUtf8)?;
+        Ok(DialogueAsset {
+            tree: parse_twee(&source)?,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["twee", "tw"]
+    }
+}
+
+#[derive(Debug)]
+pub enum TweeError {
+    Io(std::io::Error),
+    InvalidUtf8,
+    InvalidStoryData(serde_json::Error),
+    NoPassages,
+}
+
+impl fmt::Display for TweeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TweeError::Io(error) => write!(f, "could not read twee file: {error}"),
+            TweeError::InvalidUtf8 => write!(f, "twee file is not valid UTF-8"),
+            TweeError::InvalidStoryData(error) => write!(f, "invalid StoryData passage: {error}"),
+            TweeError::NoPassages => write!(f, "twee file contains no passages"),
+        }
+    }
+}
+
+impl std::error::Error for TweeError {}
+
+impl From<std::io::Error> for TweeError {
+    fn from(error: std::io::Error) -> Self {
+        TweeError::Io(error)
+    }
+}
+
+// The parts of the StoryData passage we care about
+#[derive(Deserialize)]
+struct StoryData {
+    start: Option<String>,
+}
+
+// Parse a Twee 3 story into a dialogue tree, one node per passage
+pub fn parse_twee(source: &str) -> Result<DialogueTree, TweeError> {
+    let mut passages: Vec<(String, Vec<String>, Vec<&str>)> = Vec::new();
+    for line in source.lines() {
+        if let Some(header) = line.strip_prefix("::") {
+            passages.push((passage_name(header), passage_tags(header), Vec::new()));
+        } else if let Some((_, _, body)) = passages.last_mut() {
+            body.push(line);
+        }
+    }
+
+    let mut start = None;
+    let mut nodes = HashMap::new();
+    let mut first_passage = None;
+    for (name, tags, body) in passages {
+        if name == "StoryData" {
+            let story_data: StoryData =
+                serde_json::from_str(&body.join("\n")).map_err(TweeError::InvalidStoryData)?;
+            start = story_data.start;
+        }
+        if TWEE_METADATA_PASSAGES.contains(&name.as_str()) {
+            continue;
+        }
+
+        first_passage.get_or_insert_with(|| name.clone());
+        let node = parse_passage(&name, &body, tags);
+        nodes.insert(name, node);
+    }
+
+    let root_node = start
+        .filter(|start| nodes.contains_key(start))
+        .or_else(|| {
+            nodes
+                .contains_key(TWEE_START_PASSAGE)
+                .then(|| TWEE_START_PASSAGE.to_string())
+        })
+        .or(first_passage)
+        .ok_or(TweeError::NoPassages)?;
+
+    Ok(DialogueTree {
+        nodes,
+        root_node,
+        ambient: Vec::new(),
+    })
+}
+
+// Strip tags and metadata from a `:: Name [tags] {metadata}` header
+fn passage_name(header: &str) -> String {
+    let end = header.find(['[', '{']).unwrap_or(header.len());
+    header[..end].trim().to_string()
+}
+
+// Passage tags, the space separated words in the `[tags]` of a header
+fn passage_tags(header: &str) -> Vec<String> {
+    let Some(start) = header.find('[') else {
+        return Vec::new();
+    };
+    let end = header[start..]
+        .find(']')
+        .map_or(header.len(), |end| start + end);
+    header[start + 1..end]
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_passage(name: &str, body: &[&str], tags: Vec<String>) -> DialogueNode {
+    let mut text = Vec::new();
+    let mut options = Vec::new();
+
+    for line in body {
+        // Lines that held nothing but macros leave nothing behind
+        let Some(line) = strip_macros(name, line) else {
+            continue;
+        };
+        // Links read inline as their label, on top of becoming options
+        let mut prose = String::new();
+        let mut has_plain_text = false;
+        let mut rest = line.as_str();
+        while let Some(start) = rest.find("[[") {
+            let Some(end) = rest[start..].find("]]") else {
+                break;
+            };
+            let (link_text, target) = parse_link(&rest[start + 2..start + end]);
+            has_plain_text |= !rest[..start].trim().is_empty();
+            prose.push_str(&rest[..start]);
+            prose.push_str(link_text);
+            options.push(DialogueOption::reply(link_text, target));
+            rest = &rest[start + end + 2..];
+        }
+        has_plain_text |= !rest.trim().is_empty();
+        prose.push_str(rest);
+
+        // Lines that were nothing but links only become options
+        if has_plain_text || !line.contains("[[") {
+            text.push(prose.trim().to_string());
+        }
+    }
+
+    // A passage without links is an ending
+    if options.is_empty() {
+        options.push(DialogueOption::exit(TWEE_END_TEXT));
+    }
+
+    DialogueNode {
+        text: text.join("\n").trim().to_string(),
+        options,
+        next: None,
+        tags,
+    }
+}
+
+// Story format macros like `<<set $met to true>>` have nothing to map onto, so they're dropped with
+// a warning. `None` when the line was only macros.
+fn strip_macros(passage: &str, line: &str) -> Option<String> {
+    let mut stripped = String::new();
+    let mut found = false;
+    let mut rest = line;
+    while let Some(start) = rest.find("<<") {
+        let Some(end) = rest[start..].find(">>") else {
+            break;
+        };
+        println!(
+            "Ignoring unsupported Twee macro `{}` in passage `{passage}`",
+            &rest[start..start + end + 2]
+        );
+        found = true;
+        stripped.push_str(&rest[..start]);
+        rest = &rest[start + end + 2..];
+    }
+    stripped.push_str(rest);
+    (!found || !stripped.trim().is_empty()).then_some(stripped)
+}
+
+// Split `Text->Target`, `Target<-Text`, `Text|Target` or `Target` into (text, target). Like Twine,
+// the rightmost `->` and leftmost `<-` win, so link text can contain arrows of its own.
+fn parse_link(link: &str) -> (&str, &str) {
+    if let Some((text, target)) = link.rsplit_once("->") {
+        (text.trim(), target.trim())
+    } else if let Some((target, text)) = link.split_once("<-") {
+        (text.trim(), target.trim())
+    } else if let Some((text, target)) = link.split_once('|') {
+        (text.trim(), target.trim())
+    } else {
+        (link.trim(), link.trim())
+    }
+}
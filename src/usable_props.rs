@@ -0,0 +1,361 @@
+use crate::{
+    GameStateSet, Npc, clock::GameClock, companions::Following, flee::Fleeing,
+    npc_avoidance::yield_to_player, npc_memory::NpcMemory, npc_needs::Errand,
+    ron_asset::RonAssetLoader, update_npcs,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+
+// Usable prop constants
+const TOWN_PROPS_PATH: &str = "town.props.ron";
+const NPC_HALF_HEIGHT: f32 = 1.0;
+const PROP_SEARCH_RADIUS: f32 = 40.0; // NPCs won't cross town for a free bench
+const PROP_REACHED: f32 = 0.3;
+const SEATED_DROP: f32 = 0.5; // Cylinder stand-ins sink into the bench to read as sitting
+const BENCH_WIDTH: f32 = 1.6;
+const BENCH_SEAT_HEIGHT: f32 = 0.45;
+const STALL_WIDTH: f32 = 2.0;
+const STALL_COUNTER_HEIGHT: f32 = 1.0;
+const STALL_ROOF_HEIGHT: f32 = 2.4;
+
+pub struct UsablePropsPlugin;
+
+impl Plugin for UsablePropsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TownProps>()
+            .register_asset_loader(RonAssetLoader::<TownProps>::new(&["props.ron"]))
+            .add_systems(Startup, load_town_props)
+            .add_systems(Update, spawn_town_props)
+            .add_systems(
+                FixedUpdate,
+                (schedule_prop_use, use_props)
+                    .chain()
+                    .after(yield_to_player)
+                    .before(update_npcs)
+                    .before(PhysicsSet::SyncBackend)
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum PropKind {
+    Bench,
+    Stall,
+}
+
+impl PropKind {
+    // Where an NPC walks to before using the prop, in the prop's space with its front toward +Z
+    fn approach_offset(self) -> Vec3 {
+        match self {
+            PropKind::Bench => Vec3::new(0.0, 0.0, 0.8),
+            PropKind::Stall => Vec3::new(0.0, 0.0, -1.0),
+        }
+    }
+
+    // Where an NPC's feet are while using the prop
+    fn use_offset(self) -> Vec3 {
+        match self {
+            PropKind::Bench => Vec3::new(0.0, -SEATED_DROP, 0.05),
+            PropKind::Stall => Vec3::new(0.0, 0.0, -1.0),
+        }
+    }
+}
+
+// Benches and stalls placed around town, loaded from `town.props.ron`
+#[derive(Asset, TypePath, Deserialize)]
+pub struct TownProps {
+    pub props: Vec<PropPlacement>,
+}
+
+#[derive(Deserialize)]
+pub struct PropPlacement {
+    pub kind: PropKind,
+    pub position: Vec3,
+    // Degrees around the vertical axis, turning the prop's front away from +Z
+    #[serde(default)]
+    pub yaw: f32,
+}
+
+// A stretch of the day an NPC spends at a kind of prop, set per archetype in `npcs.spawn.ron`
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct PropVisit {
+    pub kind: PropKind,
+    // Hours of the day, wrapping past midnight when `from` is later than `to`
+    pub from: f32,
+    pub to: f32,
+}
+
+impl PropVisit {
+    fn is_due(&self, hour: f32) -> bool {
+        if self.from <= self.to {
+            (self.from..self.to).contains(&hour)
+        } else {
+            hour >= self.from || hour < self.to
+        }
+    }
+}
+
+// Component with when an NPC goes off to use props
+#[derive(Component, Clone)]
+pub struct PropSchedule(pub Vec<PropVisit>);
+
+// Component on a bench or stall, with the NPC that has claimed it
+#[derive(Component)]
+pub struct UsableProp {
+    pub kind: PropKind,
+    pub occupant: Option<Entity>,
+}
+
+// Component on an NPC heading to or using a prop it has claimed
+#[derive(Component)]
+pub struct UsingProp {
+    pub prop: Entity,
+    pub kind: PropKind,
+    pub in_use: bool,
+}
+
+// Component reserving a prop for one NPC, who always goes back to it instead of the nearest free one
+#[derive(Component, Clone, Copy)]
+pub struct PropAnchor(pub Entity);
+
+// Marker for NPCs sitting down, which keeps them off the ground and in their sitting animation
+#[derive(Component)]
+pub struct Seated;
+
+#[derive(Resource)]
+struct TownPropsHandle(Handle<TownProps>);
+
+fn load_town_props(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(TownPropsHandle(asset_server.load(TOWN_PROPS_PATH)));
+}
+
+fn spawn_town_props(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<TownProps>>,
+    town_props: Res<Assets<TownProps>>,
+    handle: Option<Res<TownPropsHandle>>,
+    spawned: Query<Entity, With<UsableProp>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(town_props) = town_props.get(&handle.0) else {
+            continue;
+        };
+        // NPCs drop claims on props that disappear in `use_props`
+        for entity in spawned.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let wood = materials.add(StandardMaterial {
+            base_color: Color::srgb(0.55, 0.35, 0.2),
+            perceptual_roughness: 0.8,
+            ..default()
+        });
+        let awning = materials.add(StandardMaterial {
+            base_color: Color::srgb(0.8, 0.25, 0.25),
+            perceptual_roughness: 0.7,
+            ..default()
+        });
+
+        for placement in &town_props.props {
+            let transform = Transform::from_translation(placement.position)
+                .with_rotation(Quat::from_rotation_y(placement.yaw.to_radians()));
+            let mut prop = commands.spawn((
+                transform,
+                Visibility::default(),
+                UsableProp {
+                    kind: placement.kind,
+                    occupant: None,
+                },
+            ));
+            match placement.kind {
+                PropKind::Bench => {
+                    prop.with_children(|parent| {
+                        parent.spawn((
+                            Mesh3d(meshes.add(Cuboid::new(BENCH_WIDTH, BENCH_SEAT_HEIGHT, 0.5))),
+                            MeshMaterial3d(wood.clone()),
+                            Transform::from_xyz(0.0, BENCH_SEAT_HEIGHT / 2.0, 0.0),
+                            Collider::cuboid(BENCH_WIDTH / 2.0, BENCH_SEAT_HEIGHT / 2.0, 0.25),
+                        ));
+                        parent.spawn((
+                            Mesh3d(meshes.add(Cuboid::new(BENCH_WIDTH, 0.5, 0.1))),
+                            MeshMaterial3d(wood.clone()),
+                            Transform::from_xyz(0.0, BENCH_SEAT_HEIGHT + 0.25, -0.2),
+                        ));
+                    });
+                }
+                PropKind::Stall => {
+                    prop.with_children(|parent| {
+                        parent.spawn((
+                            Mesh3d(meshes.add(Cuboid::new(STALL_WIDTH, STALL_COUNTER_HEIGHT, 0.6))),
+                            MeshMaterial3d(wood.clone()),
+                            Transform::from_xyz(0.0, STALL_COUNTER_HEIGHT / 2.0, 0.0),
+                            Collider::cuboid(STALL_WIDTH / 2.0, STALL_COUNTER_HEIGHT / 2.0, 0.3),
+                        ));
+                        // Corner posts holding up the awning over the stallholder
+                        for x in [-STALL_WIDTH / 2.0, STALL_WIDTH / 2.0] {
+                            for z in [0.2, -1.6] {
+                                parent.spawn((
+                                    Mesh3d(meshes.add(Cuboid::new(0.1, STALL_ROOF_HEIGHT, 0.1))),
+                                    MeshMaterial3d(wood.clone()),
+                                    Transform::from_xyz(x, STALL_ROOF_HEIGHT / 2.0, z),
+                                ));
+                            }
+                        }
+                        parent.spawn((
+                            Mesh3d(meshes.add(Cuboid::new(STALL_WIDTH + 0.4, 0.1, 2.2))),
+                            MeshMaterial3d(awning.clone()),
+                            Transform::from_xyz(0.0, STALL_ROOF_HEIGHT, -0.7),
+                        ));
+                    });
+                }
+            }
+        }
+    }
+}
+
+// Claim a free prop nearby when a visit comes due, and give it back when the visit's over
+#[allow(clippy::type_complexity)]
+fn schedule_prop_use(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    mut props: Query<(Entity, &Transform, &mut UsableProp), Without<Npc>>,
+    mut npcs: Query<(
+        Entity,
+        &mut Transform,
+        &mut Npc,
+        &PropSchedule,
+        &NpcMemory,
+        Option<&UsingProp>,
+        Option<&PropAnchor>,
+        Has<Following>,
+        Has<Fleeing>,
+        Has<Errand>,
+    )>,
+) {
+    for (
+        entity,
+        mut transform,
+        mut npc,
+        schedule,
+        memory,
+        using,
+        anchor,
+        following,
+        fleeing,
+        on_errand,
+    ) in npcs.iter_mut()
+    {
+        // Followers, fleeing NPCs, NPCs watching the player or seeing to their needs have better
+        // things to do
+        let wanted = if following || fleeing || on_errand || memory.is_suspicious() {
+            None
+        } else {
+            schedule
+                .0
+                .iter()
+                .find(|visit| visit.is_due(clock.hour))
+                .map(|visit| visit.kind)
+        };
+
+        if let Some(using) = using {
+            if wanted == Some(using.kind) {
+                continue;
+            }
+            if let Ok((_, prop_transform, mut prop)) = props.get_mut(using.prop) {
+                // Anchored props stay reserved for when the NPC comes back
+                if anchor.is_none() {
+                    prop.occupant = None;
+                }
+                // Get up and step off the prop before heading home
+                if using.in_use {
+                    transform.translation = prop_transform
+                        .transform_point(using.kind.approach_offset())
+                        + Vec3::Y * NPC_HALF_HEIGHT;
+                }
+            }
+            npc.target_position = npc.home_position;
+            commands.entity(entity).remove::<(UsingProp, Seated)>();
+            continue;
+        }
+
+        let Some(kind) = wanted else {
+            continue;
+        };
+        let anchored = anchor.and_then(|anchor| {
+            let (prop_entity, _, prop) = props.get(anchor.0).ok()?;
+            Some((prop_entity, prop.kind))
+        });
+        if anchor.is_some() && anchored.is_none() {
+            // The prop is gone, so settle on a new one
+            commands.entity(entity).remove::<PropAnchor>();
+        }
+        let nearest = props
+            .iter_mut()
+            .filter(|(prop_entity, prop_transform, prop)| {
+                prop.kind == kind
+                    && anchored.is_none_or(|(anchored, anchored_kind)| {
+                        anchored_kind != kind || anchored == *prop_entity
+                    })
+                    && prop.occupant.is_none_or(|occupant| occupant == entity)
+                    && prop_transform.translation.distance(npc.home_position) <= PROP_SEARCH_RADIUS
+            })
+            .min_by(|(_, a, _), (_, b, _)| {
+                let a = a.translation.distance_squared(transform.translation);
+                let b = b.translation.distance_squared(transform.translation);
+                a.total_cmp(&b)
+            });
+        if let Some((prop_entity, _, mut prop)) = nearest {
+            prop.occupant = Some(entity);
+            commands.entity(entity).insert(UsingProp {
+                prop: prop_entity,
+                kind,
+                in_use: false,
+            });
+        }
+    }
+}
+
+// Walk over to a claimed prop, then settle into place on it until the visit's over
+fn use_props(
+    mut commands: Commands,
+    props: Query<&Transform, (With<UsableProp>, Without<Npc>)>,
+    mut npcs: Query<(Entity, &mut Transform, &mut Npc, &mut UsingProp), Without<Fleeing>>,
+) {
+    for (entity, mut transform, mut npc, mut using) in npcs.iter_mut() {
+        let Ok(prop_transform) = props.get(using.prop) else {
+            // The prop was removed, so go back to wandering
+            commands.entity(entity).remove::<(UsingProp, Seated)>();
+            continue;
+        };
+
+        if !using.in_use {
+            let approach = prop_transform.transform_point(using.kind.approach_offset());
+            npc.target_position = approach.with_y(transform.translation.y);
+            if (approach - transform.translation).with_y(0.0).length() > PROP_REACHED {
+                continue;
+            }
+            using.in_use = true;
+            if using.kind == PropKind::Bench {
+                commands.entity(entity).insert(Seated);
+            }
+        }
+
+        // Hold the pose, facing out the front of the prop unless turned to greet the player
+        transform.translation =
+            prop_transform.transform_point(using.kind.use_offset()) + Vec3::Y * NPC_HALF_HEIGHT;
+        if !npc.reaction.holds_still() {
+            transform.rotation = prop_transform.rotation;
+        }
+        npc.target_position = transform.translation;
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{
+    GameStateSet, Npc,
+    ai_lod::{AiLod, AiLodLevel, update_ai_lod},
+    clock::GameClock,
+    companions::Following,
+    factions::{Faction, FactionStandings, Relationship},
+    npc_avoidance::{Yielding, yield_to_player},
+    npc_memory::NpcMemory,
+    npc_needs::Errand,
+    npc_rng::NpcRng,
+    patrols::PatrolRoute,
+    update_npcs,
+    usable_props::UsingProp,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+use serde::Deserialize;
+
+// Utility AI constants
+const RESTLESSNESS_RATE: f32 = 0.08; // Per second spent standing around
+const SETTLE_RATE: f32 = 0.05; // Per second spent wandering
+const APPROACH_RANGE: f32 = 15.0; // The player has to be this close to be worth walking over to
+const APPROACH_STOP: f32 = 2.5; // Close enough to say hello without crowding them
+const COMMITMENT_BONUS: f32 = 0.25; // Keeps NPCs from flip-flopping between near-equal actions
+const TARGET_REACHED: f32 = 0.3;
+
+pub struct UtilityAiPlugin;
+
+impl Plugin for UtilityAiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            choose_utility_actions
+                .after(update_ai_lod)
+                .after(yield_to_player)
+                .before(update_npcs)
+                .before(PhysicsSet::SyncBackend)
+                .in_set(GameStateSet::Playing),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UtilityAction {
+    #[default]
+    Idle,
+    Wander,
+    ApproachPlayer,
+    GoHome,
+}
+
+impl UtilityAction {
+    const ALL: [UtilityAction; 4] = [
+        UtilityAction::Idle,
+        UtilityAction::Wander,
+        UtilityAction::ApproachPlayer,
+        UtilityAction::GoHome,
+    ];
+}
+
+// How much an archetype cares about each action, set per archetype in `npcs.spawn.ron`
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct UtilityWeights {
+    #[serde(default = "default_weight")]
+    pub idle: f32,
+    #[serde(default = "default_weight")]
+    pub wander: f32,
+    #[serde(default = "default_weight")]
+    pub approach_player: f32,
+    #[serde(default = "default_weight")]
+    pub go_home: f32,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+impl UtilityWeights {
+    fn weight(&self, action: UtilityAction) -> f32 {
+        match action {
+            UtilityAction::Idle => self.idle,
+            UtilityAction::Wander => self.wander,
+            UtilityAction::ApproachPlayer => self.approach_player,
+            UtilityAction::GoHome => self.go_home,
+        }
+    }
+}
+
+// Component on NPCs that pick what to do by scoring their options, instead of wandering on a timer
+#[derive(Component)]
+pub struct UtilityAi {
+    pub weights: UtilityWeights,
+    pub action: UtilityAction,
+    // Grows while standing around and wears off while wandering, from 0 to 1
+    pub restlessness: f32,
+}
+
+impl UtilityAi {
+    pub fn new(weights: UtilityWeights) -> Self {
+        Self {
+            weights,
+            action: UtilityAction::default(),
+            restlessness: 0.0,
+        }
+    }
+}
+
+// What the NPC knows about its surroundings when scoring
+struct UtilityContext {
+    player_distance: f32,
+    home_distance: f32,
+    wander_radius: f32,
+    relationship: Relationship,
+    familiar: bool,
+    night: bool,
+}
+
+// Each action's appeal from 0 to 1, before the archetype's weights
+fn score(action: UtilityAction, ai: &UtilityAi, context: &UtilityContext) -> f32 {
+    match action {
+        UtilityAction::Idle => 1.0 - ai.restlessness,
+        UtilityAction::Wander => {
+            if context.night {
+                0.0
+            } else {
+                ai.restlessness
+            }
+        }
+        UtilityAction::ApproachPlayer => {
+            if context.player_distance > APPROACH_RANGE || context.player_distance < APPROACH_STOP {
+                return 0.0;
+            }
+            let friendliness = match context.relationship {
+                Relationship::Hostile | Relationship::Unfriendly => return 0.0,
+                Relationship::Neutral => 0.4,
+                Relationship::Friendly => 0.7,
+                Relationship::Allied => 1.0,
+            };
+            let familiarity = if context.familiar { 1.0 } else { 0.6 };
+            let closeness = 1.0 - context.player_distance / APPROACH_RANGE;
+            friendliness * familiarity * closeness
+        }
+        UtilityAction::GoHome => {
+            let homesick = (context.home_distance / (context.wander_radius * 4.0)).min(1.0);
+            if context.night { 1.0 } else { homesick }
+        }
+    }
+}
+
+// Score every action each step and steer the NPC toward whichever wins
+#[allow(clippy::type_complexity)]
+fn choose_utility_actions(
+    mut rng: ResMut<NpcRng>,
+    time: Res<Time>,
+    clock: Res<GameClock>,
+    standings: Res<FactionStandings>,
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<Npc>)>,
+    mut npcs: Query<
+        (
+            &Transform,
+            &mut Npc,
+            &mut UtilityAi,
+            &AiLod,
+            &Faction,
+            &NpcMemory,
+        ),
+        (
+            Without<Following>,
+            Without<PatrolRoute>,
+            Without<Yielding>,
+            Without<UsingProp>,
+            Without<Errand>,
+        ),
+    >,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let delta_time = time.delta_secs();
+    let night = clock.time_of_day() == "night";
+    let rng = &mut rng.0;
+
+    for (transform, mut npc, mut ai, lod, faction, memory) in npcs.iter_mut() {
+        // Suspicious NPCs are busy trailing the player
+        if lod.level == AiLodLevel::Asleep || memory.is_suspicious() {
+            continue;
+        }
+
+        let rate = if ai.action == UtilityAction::Wander {
+            -SETTLE_RATE
+        } else {
+            RESTLESSNESS_RATE
+        };
+        ai.restlessness = (ai.restlessness + rate * delta_time).clamp(0.0, 1.0);
+
+        let context = UtilityContext {
+            player_distance: transform.translation.distance(player.translation),
+            home_distance: transform
+                .translation
+                .with_y(0.0)
+                .distance(npc.home_position.with_y(0.0)),
+            wander_radius: npc.wander_radius,
+            relationship: standings.player_relationship(*faction),
+            familiar: memory.times_talked > 0,
+            night,
+        };
+        let best = UtilityAction::ALL
+            .into_iter()
+            .map(|action| {
+                let commitment = if action == ai.action {
+                    COMMITMENT_BONUS
+                } else {
+                    0.0
+                };
+                let utility = score(action, &ai, &context) * ai.weights.weight(action);
+                (action, utility + commitment)
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(action, _)| action)
+            .unwrap_or_default();
+        let changed = best != ai.action;
+        ai.action = best;
+
+        let reached = (npc.target_position - transform.translation)
+            .with_y(0.0)
+            .length()
+            <= TARGET_REACHED;
+        match best {
+            UtilityAction::Idle => npc.target_position = transform.translation,
+            UtilityAction::Wander => {
+                if changed || reached {
+                    npc.pick_wander_target(rng);
+                }
+            }
+            UtilityAction::ApproachPlayer => {
+                let away = (transform.translation - player.translation)
+                    .with_y(0.0)
+                    .normalize_or_zero();
+                npc.target_position =
+                    player.translation.with_y(transform.translation.y) + away * APPROACH_STOP;
+            }
+            UtilityAction::GoHome => npc.target_position = npc.home_position,
+        }
+    }
+}
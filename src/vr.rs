@@ -0,0 +1,392 @@
+use crate::{
+    DialogueMode, DialogueOptionButton, DialogueUI, GameStateSet, LookInput, PlayerCamera,
+    handle_dialogue_hover, player_look, update_interaction_target,
+};
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+    },
+    ui::UiSystem,
+};
+use bevy_mod_openxr::action_binding::OxrSendActionBindings;
+use bevy_mod_xr::{
+    actions::ActionType,
+    session::{XrSessionCreated, XrTracker, XrTrackingRoot},
+};
+use bevy_rapier3d::control::KinematicCharacterController;
+use bevy_xr_utils::{
+    tracking_utils::{
+        TrackingUtilitiesPlugin, XrTrackedLeftGrip, XrTrackedRightGrip, XrTrackedView,
+        suggest_action_bindings,
+    },
+    xr_utils_actions::{
+        ActiveSet, XRUtilsAction, XRUtilsActionSet, XRUtilsActionState, XRUtilsActionSystemSet,
+        XRUtilsActionsPlugin, XRUtilsBinding,
+    },
+};
+
+// VR constants
+const CONTROLLER_PROFILES: [&str; 2] = [
+    "/interaction_profiles/oculus/touch_controller",
+    "/interaction_profiles/valve/index_controller",
+];
+const SNAP_TURN_ANGLE: f32 = 30.0; // Degrees per flick of the stick
+const SNAP_TURN_THRESHOLD: f32 = 0.7; // Stick deflection that turns
+const SNAP_TURN_RELEASE: f32 = 0.3; // Stick has to come back under this before turning again
+const HAND_SIZE: Vec3 = Vec3::new(0.08, 0.08, 0.14);
+const POINTER_LENGTH: f32 = 4.0;
+const POINTER_WIDTH: f32 = 0.004;
+const POINTER_COLOR: Color = Color::srgba(0.9, 0.9, 1.0, 0.6);
+const DIALOGUE_PANEL_PIXELS: UVec2 = UVec2::new(1280, 720);
+const DIALOGUE_PANEL_WIDTH: f32 = 1.6; // Metres across
+const DIALOGUE_PANEL_DISTANCE: f32 = 1.4; // How far in front of the headset it opens
+
+pub struct VrPlugin;
+
+impl Plugin for VrPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((TrackingUtilitiesPlugin, XRUtilsActionsPlugin))
+            .init_resource::<SnapTurn>()
+            .add_systems(OxrSendActionBindings, suggest_action_bindings)
+            .add_systems(
+                Startup,
+                (
+                    create_vr_actions.before(XRUtilsActionSystemSet::CreateEvents),
+                    setup_dialogue_panel,
+                ),
+            )
+            .add_systems(XrSessionCreated, spawn_hands)
+            .add_systems(Update, snap_turn.in_set(GameStateSet::Playing))
+            .add_systems(
+                Update,
+                look_with_headset
+                    .after(player_look)
+                    .before(update_interaction_target),
+            )
+            .add_systems(OnEnter(DialogueMode::Talking), open_dialogue_panel)
+            .add_systems(
+                Update,
+                point_at_dialogue_options
+                    .before(handle_dialogue_hover)
+                    .run_if(in_state(DialogueMode::Talking)),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    show_dialogue_ui_on_panel.before(UiSystem::Prepare),
+                    follow_headset.before(TransformSystem::TransformPropagate),
+                ),
+            );
+    }
+}
+
+// Markers for the actions read from the controllers
+#[derive(Component)]
+struct SelectAction;
+
+#[derive(Component)]
+struct TurnAction;
+
+// Resource tracking whether the stick has come back to center since the last turn
+#[derive(Resource)]
+struct SnapTurn {
+    armed: bool,
+}
+
+impl Default for SnapTurn {
+    fn default() -> Self {
+        Self { armed: true }
+    }
+}
+
+// Resource with the off-screen camera the dialogue panel is drawn by, and what shows it in the
+// world. A window's UI can't be seen from inside the headset.
+#[derive(Resource)]
+struct DialoguePanel {
+    camera: Entity,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+// Marker for the quad showing the dialogue panel in front of the player
+#[derive(Component)]
+struct DialoguePanelQuad;
+
+fn create_vr_actions(mut commands: Commands) {
+    let set = commands
+        .spawn((
+            XRUtilsActionSet {
+                name: "paperclips".into(),
+                pretty_name: "Paperclips".into(),
+                priority: u32::MIN,
+            },
+            ActiveSet,
+        ))
+        .id();
+    let actions = [
+        (
+            "select",
+            "Select",
+            ActionType::Bool,
+            "/user/hand/right/input/trigger/value",
+            commands.spawn(SelectAction).id(),
+        ),
+        (
+            "snap_turn",
+            "Snap Turn",
+            ActionType::Vector,
+            "/user/hand/right/input/thumbstick",
+            commands.spawn(TurnAction).id(),
+        ),
+    ];
+    for (name, localized_name, action_type, binding, action) in actions {
+        commands.entity(action).insert(XRUtilsAction {
+            action_name: name.into(),
+            localized_name: localized_name.into(),
+            action_type,
+        });
+        for profile in CONTROLLER_PROFILES {
+            let binding = commands
+                .spawn(XRUtilsBinding {
+                    profile: profile.into(),
+                    binding: binding.into(),
+                })
+                .id();
+            commands.entity(action).add_child(binding);
+        }
+        commands.entity(set).add_child(action);
+    }
+}
+
+// A block for each hand, and a pointer out of the right one to pick dialogue options with
+fn spawn_hands(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let hand_mesh = meshes.add(Cuboid::from_size(HAND_SIZE));
+    let hand_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.85, 0.7, 0.6),
+        perceptual_roughness: 0.8,
+        ..default()
+    });
+    commands.spawn((
+        Mesh3d(hand_mesh.clone()),
+        MeshMaterial3d(hand_material.clone()),
+        Transform::default(),
+        XrTrackedLeftGrip,
+        XrTracker,
+    ));
+    commands
+        .spawn((
+            Mesh3d(hand_mesh),
+            MeshMaterial3d(hand_material),
+            Transform::default(),
+            XrTrackedRightGrip,
+            XrTracker,
+        ))
+        .with_children(|hand| {
+            hand.spawn((
+                Mesh3d(meshes.add(Cuboid::new(POINTER_WIDTH, POINTER_WIDTH, POINTER_LENGTH))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: POINTER_COLOR,
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    ..default()
+                })),
+                Transform::from_xyz(0.0, 0.0, -POINTER_LENGTH / 2.0),
+            ));
+        });
+    // Where the headset is, relative to the tracking root
+    commands.spawn((Transform::default(), XrTrackedView, XrTracker));
+}
+
+// Aim the player camera wherever the headset faces, so talking, reading and grabbing go by where
+// the player is really looking. The tracking root turns with the body, so the headset's turn is
+// already relative to it.
+fn look_with_headset(
+    view: Query<&Transform, (With<XrTrackedView>, Without<PlayerCamera>)>,
+    mut camera: Query<&mut Transform, With<PlayerCamera>>,
+) {
+    let (Ok(view), Ok(mut camera)) = (view.get_single(), camera.get_single_mut()) else {
+        return;
+    };
+    camera.rotation = view.rotation;
+}
+
+// Put the headset's eyes where the player camera is, turned with the player's body. Walking is
+// left to the character motor, which goes wherever the body faces.
+fn follow_headset(
+    player: Query<&Transform, (With<KinematicCharacterController>, Without<XrTrackingRoot>)>,
+    camera: Query<&Transform, (With<PlayerCamera>, Without<XrTrackingRoot>)>,
+    view: Query<&Transform, (With<XrTrackedView>, Without<XrTrackingRoot>)>,
+    mut root: Query<&mut Transform, With<XrTrackingRoot>>,
+) {
+    let (Ok(player), Ok(camera), Ok(view), Ok(mut root)) = (
+        player.get_single(),
+        camera.get_single(),
+        view.get_single(),
+        root.get_single_mut(),
+    ) else {
+        return;
+    };
+    root.rotation = player.rotation;
+    root.translation =
+        player.translation + player.rotation * (camera.translation - view.translation);
+}
+
+// Flick the right stick to turn on the spot, once per flick
+fn snap_turn(
+    actions: Query<&XRUtilsActionState, With<TurnAction>>,
+    mut snap_turn: ResMut<SnapTurn>,
+    mut look: ResMut<LookInput>,
+) {
+    let Some(XRUtilsActionState::Vector(stick)) = actions.iter().next() else {
+        return;
+    };
+    let x = stick.current_state[0];
+    if x.abs() < SNAP_TURN_RELEASE {
+        snap_turn.armed = true;
+    } else if x.abs() > SNAP_TURN_THRESHOLD && snap_turn.armed {
+        look.x -= x.signum() * SNAP_TURN_ANGLE;
+        snap_turn.armed = false;
+    }
+}
+
+fn setup_dialogue_panel(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let size = Extent3d {
+        width: DIALOGUE_PANEL_PIXELS.x,
+        height: DIALOGUE_PANEL_PIXELS.y,
+        ..default()
+    };
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    let image = images.add(image);
+
+    let camera = commands
+        .spawn((
+            Camera2d,
+            Camera {
+                target: RenderTarget::Image(image.clone()),
+                clear_color: ClearColorConfig::Custom(Color::NONE),
+                order: -1,
+                ..default()
+            },
+        ))
+        .id();
+    let aspect = DIALOGUE_PANEL_PIXELS.y as f32 / DIALOGUE_PANEL_PIXELS.x as f32;
+    commands.insert_resource(DialoguePanel {
+        camera,
+        mesh: meshes.add(Rectangle::new(
+            DIALOGUE_PANEL_WIDTH,
+            DIALOGUE_PANEL_WIDTH * aspect,
+        )),
+        material: materials.add(StandardMaterial {
+            base_color_texture: Some(image),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            cull_mode: None,
+            ..default()
+        }),
+    });
+}
+
+// Draw the dialogue panel for the headset instead of the window
+fn show_dialogue_ui_on_panel(
+    mut commands: Commands,
+    panel: Res<DialoguePanel>,
+    roots: Query<Entity, Added<DialogueUI>>,
+) {
+    for entity in roots.iter() {
+        commands.entity(entity).insert(TargetCamera(panel.camera));
+    }
+}
+
+// Hang the panel level in front of wherever the player is looking when the conversation opens
+fn open_dialogue_panel(
+    mut commands: Commands,
+    panel: Res<DialoguePanel>,
+    camera: Query<&GlobalTransform, With<PlayerCamera>>,
+) {
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+    let eye = camera.translation();
+    let ahead = camera.forward().with_y(0.0).normalize_or(Vec3::NEG_Z);
+    let position = eye + ahead * DIALOGUE_PANEL_DISTANCE;
+    commands.spawn((
+        Mesh3d(panel.mesh.clone()),
+        MeshMaterial3d(panel.material.clone()),
+        // The rectangle faces +Z, so turn that back toward the player
+        Transform::from_translation(position).looking_to(ahead, Vec3::Y),
+        DialoguePanelQuad,
+        StateScoped(DialogueMode::Talking),
+    ));
+}
+
+// Hover and press dialogue options with the right hand's pointer and trigger, through the same
+// `Interaction` the mouse uses
+fn point_at_dialogue_options(
+    hands: Query<&GlobalTransform, With<XrTrackedRightGrip>>,
+    quads: Query<&GlobalTransform, With<DialoguePanelQuad>>,
+    select: Query<&XRUtilsActionState, With<SelectAction>>,
+    mut buttons: Query<
+        (&ComputedNode, &GlobalTransform, &mut Interaction),
+        With<DialogueOptionButton>,
+    >,
+) {
+    let (Ok(hand), Ok(quad)) = (hands.get_single(), quads.get_single()) else {
+        return;
+    };
+    let pressed = matches!(
+        select.iter().next(),
+        Some(XRUtilsActionState::Bool(state))
+            if state.current_state && state.changed_since_last_sync
+    );
+
+    // Where the pointer crosses the panel, in the panel's pixels from its top-left corner
+    let origin = hand.translation();
+    let direction = hand.forward();
+    let normal = quad.back();
+    let along = direction.dot(*normal);
+    let distance = (quad.translation() - origin).dot(*normal) / along;
+    let pixel =
+        (along.abs() > f32::EPSILON && (0.0..POINTER_LENGTH).contains(&distance)).then(|| {
+            let local = quad
+                .affine()
+                .inverse()
+                .transform_point3(origin + direction * distance);
+            let size = Vec2::new(
+                DIALOGUE_PANEL_WIDTH,
+                DIALOGUE_PANEL_WIDTH * DIALOGUE_PANEL_PIXELS.y as f32
+                    / DIALOGUE_PANEL_PIXELS.x as f32,
+            );
+            let uv = Vec2::new(local.x / size.x + 0.5, 0.5 - local.y / size.y);
+            uv * DIALOGUE_PANEL_PIXELS.as_vec2()
+        });
+
+    for (node, transform, mut interaction) in buttons.iter_mut() {
+        let rect = Rect::from_center_size(transform.translation().truncate(), node.size());
+        let target = match pixel {
+            Some(pixel) if rect.contains(pixel) && pressed => Interaction::Pressed,
+            Some(pixel) if rect.contains(pixel) => Interaction::Hovered,
+            _ => Interaction::None,
+        };
+        interaction.set_if_neq(target);
+    }
+}
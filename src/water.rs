@@ -0,0 +1,282 @@
+use crate::{GRAVITY, GameStateSet, particles::ParticleBurst, player_movement};
+use bevy::{audio::Volume, prelude::*, render::mesh::VertexAttributeValues};
+use bevy_rapier3d::{control::KinematicCharacterController, prelude::*};
+
+// Water constants
+pub const SWIM_SPEED_SCALE: f32 = 0.5; // Share of walking speed the player manages in water
+pub const SWIM_RISE_SPEED: f32 = 2.0;
+pub const SWIM_DRAG: f32 = 4.0; // How quickly water soaks up a dive or a jump
+pub const FLOAT_DEPTH: f32 = 0.1; // How far under the surface the player's middle floats, eyes just above
+const POOL_FLOOR: Vec3 = Vec3::new(-30.0, -3.0, 0.0); // Matches the basin in the level scene
+const WATER_HALF_EXTENTS: Vec3 = Vec3::new(4.0, 1.35, 5.0);
+const SURFACE_SUBDIVISIONS: u32 = 24;
+const WAVE_HEIGHT: f32 = 0.04;
+const WAVE_LENGTH: f32 = 2.5;
+const WAVE_SPEED: f32 = 1.2;
+const BUOYANCY: f32 = 1.6; // Upward push on a fully sunk body, in multiples of gravity
+const WATER_DRAG: f32 = 2.0;
+const SPLASH_CLIP: &str = "audio/water/splash.ogg";
+const SPLASH_MIN_SPEED: f32 = 1.0; // Slower than this and things slip in without a splash
+const HARD_SPLASH_SPEED: f32 = 10.0; // Hitting the water this fast or faster makes the biggest splash
+const SPLASH_COLOR: Color = Color::srgb(0.8, 0.9, 1.0);
+
+pub struct WaterPlugin;
+
+impl Plugin for WaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Splash>()
+            .add_systems(Startup, (spawn_water, load_splash_clip))
+            .add_systems(Update, (animate_water_surfaces, play_splashes))
+            .add_systems(
+                FixedUpdate,
+                (
+                    detect_swimming.before(player_movement),
+                    float_bodies.before(PhysicsSet::SyncBackend),
+                )
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Volume of water, centered on its transform
+#[derive(Component)]
+pub struct Water {
+    pub half_extents: Vec3,
+}
+
+impl Water {
+    fn surface(&self, transform: &Transform) -> f32 {
+        transform.translation.y + self.half_extents.y
+    }
+
+    fn contains(&self, transform: &Transform, point: Vec3) -> bool {
+        let offset = (point - transform.translation).abs();
+        offset.x <= self.half_extents.x
+            && offset.z <= self.half_extents.z
+            && offset.y <= self.half_extents.y
+    }
+}
+
+// Component on the player while they're in over their chest
+#[derive(Component)]
+pub struct Swimming {
+    // Height of the water's surface
+    pub surface: f32,
+}
+
+// Marker for a dynamic body bobbing in water
+#[derive(Component)]
+struct Floating;
+
+// Component on the translucent plane that ripples on top of the water
+#[derive(Component)]
+struct WaterSurface;
+
+// Event sent when something drops into the water
+#[derive(Event)]
+pub struct Splash {
+    pub position: Vec3,
+    // How hard it hit the water, from 0 to 1
+    pub strength: f32,
+}
+
+#[derive(Resource)]
+struct SplashClip(Handle<AudioSource>);
+
+fn load_splash_clip(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(SplashClip(asset_server.load(SPLASH_CLIP)));
+}
+
+fn spawn_water(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let surface_mesh = Plane3d::default()
+        .mesh()
+        .size(WATER_HALF_EXTENTS.x * 2.0, WATER_HALF_EXTENTS.z * 2.0)
+        .subdivisions(SURFACE_SUBDIVISIONS)
+        .build();
+    commands
+        .spawn((
+            Transform::from_translation(POOL_FLOOR + Vec3::Y * WATER_HALF_EXTENTS.y),
+            Visibility::default(),
+            Water {
+                half_extents: WATER_HALF_EXTENTS,
+            },
+        ))
+        .with_child((
+            Mesh3d(meshes.add(surface_mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgba(0.15, 0.4, 0.65, 0.6),
+                alpha_mode: AlphaMode::Blend,
+                perceptual_roughness: 0.1,
+                reflectance: 0.6,
+                // Seen from underneath too, when swimming
+                cull_mode: None,
+                double_sided: true,
+                ..default()
+            })),
+            Transform::from_xyz(0.0, WATER_HALF_EXTENTS.y, 0.0),
+            WaterSurface,
+        ));
+}
+
+// Roll two crossing waves over each surface's vertices, with normals to match
+fn animate_water_surfaces(
+    time: Res<Time>,
+    surfaces: Query<&Mesh3d, With<WaterSurface>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let phase = time.elapsed_secs() * WAVE_SPEED;
+    let k = std::f32::consts::TAU / WAVE_LENGTH;
+    for mesh in surfaces.iter() {
+        let Some(mesh) = meshes.get_mut(&mesh.0) else {
+            continue;
+        };
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        else {
+            continue;
+        };
+        let mut normals = Vec::with_capacity(positions.len());
+        for position in positions.iter_mut() {
+            let (x, z) = (position[0], position[2]);
+            let (a, b) = (k * x + phase, k * 0.7 * z + phase * 1.3);
+            position[1] = WAVE_HEIGHT * (a.sin() + b.sin());
+            let slope = Vec2::new(WAVE_HEIGHT * k * a.cos(), WAVE_HEIGHT * k * 0.7 * b.cos());
+            normals.push(Vec3::new(-slope.x, 1.0, -slope.y).normalize().to_array());
+        }
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    }
+}
+
+// Put the player in swimming once the water is over their middle, splashing on the way in
+fn detect_swimming(
+    mut commands: Commands,
+    time: Res<Time>,
+    player: Query<(Entity, &Transform, Option<&Swimming>), With<KinematicCharacterController>>,
+    waters: Query<(&Transform, &Water)>,
+    mut splashes: EventWriter<Splash>,
+    mut previous: Local<Option<Vec3>>,
+) {
+    let Ok((entity, transform, swimming)) = player.get_single() else {
+        return;
+    };
+    let position = transform.translation;
+    let falling = previous.map_or(0.0, |previous| previous.y - position.y) / time.delta_secs();
+    *previous = Some(position);
+    let surface = waters
+        .iter()
+        .filter(|(water_transform, water)| water.contains(water_transform, position))
+        .map(|(water_transform, water)| water.surface(water_transform))
+        .find(|surface| position.y < *surface);
+
+    match (surface, swimming) {
+        (Some(surface), None) => {
+            commands.entity(entity).insert(Swimming { surface });
+            if falling > SPLASH_MIN_SPEED {
+                splashes.send(Splash {
+                    position: position.with_y(surface),
+                    strength: (falling / HARD_SPLASH_SPEED).min(1.0),
+                });
+            }
+        }
+        (None, Some(_)) => {
+            commands.entity(entity).remove::<Swimming>();
+        }
+        _ => {}
+    }
+}
+
+// Push dynamic bodies up by how much of them is under water, and slow them down
+#[allow(clippy::type_complexity)]
+fn float_bodies(
+    mut commands: Commands,
+    bodies: Query<(
+        Entity,
+        &Transform,
+        &RigidBody,
+        &Collider,
+        &ReadMassProperties,
+        Option<&Velocity>,
+        Has<Floating>,
+    )>,
+    waters: Query<(&Transform, &Water)>,
+    mut splashes: EventWriter<Splash>,
+    time: Res<Time>,
+) {
+    let delta_time = time.delta_secs();
+    for (entity, transform, body, collider, mass, velocity, floating) in bodies.iter() {
+        if *body != RigidBody::Dynamic {
+            continue;
+        }
+        let position = transform.translation;
+        let half_height = collider.raw.compute_local_aabb().half_extents().y;
+        let water = waters
+            .iter()
+            .find(|(water_transform, water)| {
+                water.contains(
+                    water_transform,
+                    position.with_y(water_transform.translation.y),
+                )
+            })
+            .map(|(water_transform, water)| water.surface(water_transform));
+        // Share of the body below the surface
+        let submerged = water.map_or(0.0, |surface| {
+            ((surface - (position.y - half_height)) / (half_height * 2.0)).clamp(0.0, 1.0)
+        });
+
+        if submerged <= 0.0 {
+            if floating {
+                commands.entity(entity).remove::<Floating>();
+            }
+            continue;
+        }
+        let velocity = velocity.map_or(Vec3::ZERO, |velocity| velocity.linvel);
+        if !floating {
+            commands.entity(entity).insert(Floating);
+            if -velocity.y > SPLASH_MIN_SPEED
+                && let Some(surface) = water
+            {
+                splashes.send(Splash {
+                    position: position.with_y(surface),
+                    strength: (-velocity.y / HARD_SPLASH_SPEED).min(1.0),
+                });
+            }
+        }
+        let mass = mass.get().mass;
+        let lift = Vec3::Y * -GRAVITY * BUOYANCY * submerged;
+        let drag = -velocity * WATER_DRAG * submerged;
+        commands.entity(entity).insert(ExternalImpulse {
+            impulse: (lift + drag) * mass * delta_time,
+            torque_impulse: Vec3::ZERO,
+        });
+    }
+}
+
+// Spray and a splash sound wherever something hit the water
+fn play_splashes(
+    mut commands: Commands,
+    mut splashes: EventReader<Splash>,
+    mut bursts: EventWriter<ParticleBurst>,
+    clip: Res<SplashClip>,
+) {
+    for splash in splashes.read() {
+        bursts.send(ParticleBurst {
+            position: splash.position,
+            count: 8 + (24.0 * splash.strength) as usize,
+            color: SPLASH_COLOR,
+            speed: 2.0 + 4.0 * splash.strength,
+            lifetime: 0.8,
+            size: 0.12,
+        });
+        commands.spawn((
+            AudioPlayer(clip.0.clone()),
+            PlaybackSettings::DESPAWN
+                .with_volume(Volume::new(0.3 + 0.7 * splash.strength))
+                .with_spatial(true),
+            Transform::from_translation(splash.position),
+        ));
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{
+    GameStateSet, NPC_WANDER_RADIUS, Npc,
+    clock::{DAYS_PER_WEEK, GameClock},
+    economy::{Merchant, TradeGood},
+    factions::Faction,
+    merchant_stalls::{Shop, ShopConfig},
+    npc_reactions::NpcReaction,
+    ron_asset::RonAssetLoader,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+// World event constants
+const WORLD_SCHEDULE_PATH: &str = "world.schedule.ron";
+pub const CUBE_ANOMALY_EVENT: &str = "cube_anomaly";
+pub const CARAVAN_EVENT: &str = "merchant_caravan";
+const CARAVAN_POSITION: Vec3 = Vec3::new(30.0, 1.0, 10.0);
+
+pub struct WorldEventsPlugin;
+
+impl Plugin for WorldEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<WorldSchedule>()
+            .register_asset_loader(RonAssetLoader::<WorldSchedule>::new(&["schedule.ron"]))
+            .init_resource::<ActiveWorldEvents>()
+            .add_event::<WorldEventStarted>()
+            .add_event::<WorldEventEnded>()
+            .add_systems(Startup, load_world_schedule)
+            .add_systems(
+                Update,
+                (
+                    run_world_schedule,
+                    (arrive_caravan, depart_caravan, update_scheduled_presence),
+                )
+                    .chain()
+                    .in_set(GameStateSet::Playing),
+            );
+    }
+}
+
+// Calendar of world events loaded from data
+#[derive(Asset, TypePath, Deserialize)]
+pub struct WorldSchedule {
+    pub events: Vec<ScheduledEvent>,
+}
+
+#[derive(Deserialize)]
+pub struct ScheduledEvent {
+    pub name: String,
+    pub recurrence: Recurrence,
+    // Hour of the day the event starts
+    pub hour: f32,
+    // How many in-game hours the event lasts
+    #[serde(default = "default_event_duration")]
+    pub duration: f32,
+}
+
+#[derive(Deserialize)]
+pub enum Recurrence {
+    Daily,
+    Weekly { day: u32 },
+    Once { day: u32 },
+}
+
+fn default_event_duration() -> f32 {
+    1.0
+}
+
+impl ScheduledEvent {
+    fn occurs_on(&self, day: u32) -> bool {
+        match self.recurrence {
+            Recurrence::Daily => true,
+            Recurrence::Weekly { day: weekday } => day % DAYS_PER_WEEK == weekday,
+            Recurrence::Once { day: event_day } => day == event_day,
+        }
+    }
+
+    // Check today's occurrence and yesterday's, in case it runs past midnight
+    fn is_active(&self, clock: &GameClock) -> bool {
+        (0..=1).any(|days_ago| {
+            let Some(day) = clock.day.checked_sub(days_ago) else {
+                return false;
+            };
+            let hours_since_start = clock.hour + days_ago as f32 * 24.0 - self.hour;
+            self.occurs_on(day) && (0.0..self.duration).contains(&hours_since_start)
+        })
+    }
+}
+
+// Resource listing the world events currently in progress
+#[derive(Resource, Default)]
+pub struct ActiveWorldEvents {
+    active: HashSet<String>,
+}
+
+impl ActiveWorldEvents {
+    pub fn is_active(&self, name: &str) -> bool {
+        self.active.contains(name)
+    }
+}
+
+// Event sent when a scheduled world event begins
+#[derive(Event)]
+pub struct WorldEventStarted {
+    pub name: String,
+}
+
+// Event sent when a scheduled world event ends
+#[derive(Event)]
+pub struct WorldEventEnded {
+    pub name: String,
+}
+
+// Component for NPCs that are only around while a world event is active
+#[derive(Component)]
+pub struct ScheduledPresence {
+    pub event: String,
+}
+
+// Component for the merchant brought in by the caravan
+#[derive(Component)]
+struct CaravanMerchant;
+
+#[derive(Resource)]
+struct WorldScheduleHandle(Handle<WorldSchedule>);
+
+fn load_world_schedule(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(WorldScheduleHandle(asset_server.load(WORLD_SCHEDULE_PATH)));
+}
+
+fn run_world_schedule(
+    clock: Res<GameClock>,
+    handle: Res<WorldScheduleHandle>,
+    schedules: Res<Assets<WorldSchedule>>,
+    mut active_events: ResMut<ActiveWorldEvents>,
+    mut started: EventWriter<WorldEventStarted>,
+    mut ended: EventWriter<WorldEventEnded>,
+) {
+    let Some(schedule) = schedules.get(&handle.0) else {
+        return;
+    };
+
+    let now: HashSet<String> = schedule
+        .events
+        .iter()
+        .filter(|event| event.is_active(&clock))
+        .map(|event| event.name.clone())
+        .collect();
+
+    for name in now.difference(&active_events.active) {
+        println!("World event started: {name}");
+        started.send(WorldEventStarted { name: name.clone() });
+    }
+    for name in active_events.active.difference(&now) {
+        println!("World event ended: {name}");
+        ended.send(WorldEventEnded { name: name.clone() });
+    }
+    active_events.active = now;
+}
+
+fn arrive_caravan(
+    mut commands: Commands,
+    mut events: EventReader<WorldEventStarted>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for _ in events.read().filter(|event| event.name == CARAVAN_EVENT) {
+        commands.spawn((
+            Mesh3d(meshes.add(Cylinder::new(0.5, 2.0))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgb(0.9, 0.8, 0.3),
+                perceptual_roughness: 0.4,
+                ..default()
+            })),
+            Transform::from_translation(CARAVAN_POSITION),
+            Collider::cylinder(1.0, 0.5),
+            RigidBody::KinematicPositionBased,
+            Npc {
+                home_position: CARAVAN_POSITION,
+                target_position: CARAVAN_POSITION,
+                movement_timer: Timer::from_seconds(5.0, TimerMode::Once),
+                name: "Caravan Trader".to_string(),
+                dialogue_id: "merchant".to_string(),
+                reaction: NpcReaction::default(),
+                wander_radius: NPC_WANDER_RADIUS,
+            },
+            Faction::Merchants,
+            Merchant {
+                price_modifier: 1.3,
+            },
+            // Trades off the back of the cart all day, in the rarer goods
+            Shop::new(ShopConfig {
+                opens: 0.0,
+                closes: 24.0,
+                stock: [(TradeGood::CubeShards, 10), (TradeGood::DataChips, 15)]
+                    .into_iter()
+                    .collect(),
+            }),
+            CaravanMerchant,
+        ));
+    }
+}
+
+fn depart_caravan(
+    mut commands: Commands,
+    mut events: EventReader<WorldEventEnded>,
+    caravan_query: Query<Entity, With<CaravanMerchant>>,
+) {
+    for _ in events.read().filter(|event| event.name == CARAVAN_EVENT) {
+        for entity in caravan_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// Show or hide NPCs that only appear during their world event
+fn update_scheduled_presence(
+    mut commands: Commands,
+    active_events: Res<ActiveWorldEvents>,
+    mut query: Query<(Entity, &ScheduledPresence, &mut Visibility)>,
+) {
+    for (entity, presence, mut visibility) in query.iter_mut() {
+        let present = active_events.is_active(&presence.event);
+        let target = if present {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility == target {
+            continue;
+        }
+
+        *visibility = target;
+        if present {
+            commands.entity(entity).remove::<ColliderDisabled>();
+        } else {
+            commands.entity(entity).insert(ColliderDisabled);
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use crate::{
+    DialogueNode, DialogueOption, DialogueTree, dialogue_assets::DialogueAsset,
+    dialogue_variables::DialogueCondition,
+};
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use std::collections::HashMap;
+use std::fmt;
+
+// Option text used when a Yarn node ends the conversation
+const YARN_END_TEXT: &str = "Goodbye.";
+// Node Yarn starts at by convention
+const YARN_START_NODE: &str = "Start";
+
+// Loads `.yarn` files as dialogue trees
+#[derive(Default)]
+pub struct YarnLoader;
+
+impl AssetLoader for YarnLoader {
+    type Asset = DialogueAsset;
+    type Settings = ();
+    type Error = YarnError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<DialogueAsset, YarnError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let source = String::from_utf8(bytes).map_err(|_| YarnError::InvalidUtf8)?;
+        Ok(DialogueAsset {
+            tree: parse_yarn(&source)?,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["yarn"]
+    }
+}
+
+#[derive(Debug)]
+pub enum YarnError {
+    Io(std::io::Error),
+    InvalidUtf8,
+    MissingTitle { line: usize },
+    UnterminatedNode { title: String },
+    InvalidCondition { line: usize, condition: String },
+    NoNodes,
+}
+
+impl fmt::Display for YarnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            YarnError::Io(error) => write!(f, "could not read yarn file: {error}"),
+            YarnError::InvalidUtf8 => write!(f, "yarn file is not valid UTF-8"),
+            YarnError::MissingTitle { line } => {
+                write!(f, "node ending on line {line} has no title")
+            }
+            YarnError::UnterminatedNode { title } => {
+                write!(f, "node '{title}' is missing its closing ===")
+            }
+            YarnError::InvalidCondition { line, condition } => {
+                write!(f, "unsupported condition '{condition}' on line {line}")
+            }
+            YarnError::NoNodes => write!(f, "yarn file contains no nodes"),
+        }
+    }
+}
+
+impl std::error::Error for YarnError {}
+
+impl From<std::io::Error> for YarnError {
+    fn from(error: std::io::Error) -> Self {
+        YarnError::Io(error)
+    }
+}
+
+// A body line with its indentation and 1-based line number
+struct YarnLine<'a> {
+    number: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+// Parse Yarn Spinner source into a dialogue tree
+pub fn parse_yarn(source: &str) -> Result<DialogueTree, YarnError> {
+    let mut nodes = HashMap::new();
+    let mut first_title = None;
+
+    let mut lines = source.lines().enumerate();
+    loop {
+        // Header: `key: value` lines up to `---`
+        let mut title = None;
+        let mut header_end = None;
+        for (index, line) in lines.by_ref() {
+            let line = line.trim();
+            if line == "---" {
+                header_end = Some(index + 1);
+                break;
+            }
+            if let Some(value) = line.strip_prefix("title:") {
+                title = Some(value.trim().to_string());
+            }
+        }
+        let Some(header_end) = header_end else {
+            break;
+        };
+        let title = title.ok_or(YarnError::MissingTitle { line: header_end })?;
+
+        // Body: everything up to `===`
+        let mut body = Vec::new();
+        let mut terminated = false;
+        for (index, line) in lines.by_ref() {
+            if line.trim() == "===" {
+                terminated = true;
+                break;
+            }
+            let text = line.trim();
+            if text.is_empty() || text.starts_with("//") {
+                continue;
+            }
+            body.push(YarnLine {
+                number: index + 1,
+                indent: line.len() - line.trim_start().len(),
+                text,
+            });
+        }
+        if !terminated {
+            return Err(YarnError::UnterminatedNode { title });
+        }
+
+        first_title.get_or_insert_with(|| title.clone());
+        parse_node(&title, &body, &mut nodes)?;
+    }
+
+    let root_node = if nodes.contains_key(YARN_START_NODE) {
+        YARN_START_NODE.to_string()
+    } else {
+        first_title.ok_or(YarnError::NoNodes)?
+    };
+
+    Ok(DialogueTree {
+        nodes,
+        root_node,
+        ambient: Vec::new(),
+    })
+}
+
+// Convert a node body into one dialogue node, plus extra nodes for option bodies
+fn parse_node(
+    title: &str,
+    body: &[YarnLine],
+    nodes: &mut HashMap<String, DialogueNode>,
+) -> Result<(), YarnError> {
+    let base_indent = body.iter().map(|line| line.indent).min().unwrap_or(0);
+
+    let mut text = Vec::new();
+    let mut tags = Vec::new();
+    let mut options = Vec::new();
+    let mut jump = None;
+
+    let mut index = 0;
+    while index < body.len() {
+        let line = &body[index];
+        index += 1;
+
+        if let Some(option) = line.text.strip_prefix("->") {
+            // The option body is every following line indented deeper than the option
+            let start = index;
+            while index < body.len() && body[index].indent > line.indent {
+                index += 1;
+            }
+            options.push((line.number, option.trim(), &body[start..index]));
+        } else if line.indent == base_indent {
+            if let Some(target) = parse_jump(line.text) {
+                jump = Some(target);
+            } else if line.text.starts_with("<<") {
+                ignore_command(title, line);
+            } else {
+                text.push(strip_line(line.text));
+                tags.extend(line_tags(line.text));
+            }
+        }
+    }
+
+    // Lines that just jump on become linear nodes, anything else ends the conversation
+    let continuation = |jump: Option<&str>| match jump {
+        Some(target) => (Vec::new(), Some(target.to_string())),
+        None => (vec![DialogueOption::exit(YARN_END_TEXT)], None),
+    };
+
+    let mut node_options = Vec::new();
+    for (option_index, (number, option, option_body)) in options.into_iter().enumerate() {
+        let (option_text, condition) = match option.split_once("<<if") {
+            Some((option_text, condition)) => {
+                let condition = condition.trim().trim_end_matches(">>").trim();
+                let parsed = DialogueCondition::parse(condition).ok_or_else(|| {
+                    YarnError::InvalidCondition {
+                        line: number,
+                        condition: condition.to_string(),
+                    }
+                })?;
+                (strip_line(option_text), Some(parsed))
+            }
+            None => (strip_line(option), None),
+        };
+
+        let option_jump = option_body
+            .iter()
+            .find_map(|line| parse_jump(line.text))
+            .or(jump);
+        for line in option_body {
+            if line.text.starts_with("<<") && parse_jump(line.text).is_none() {
+                ignore_command(title, line);
+            }
+        }
+        let option_text_lines: Vec<_> = option_body
+            .iter()
+            .filter(|line| !line.text.starts_with("<<") && !line.text.starts_with("->"))
+            .map(|line| strip_line(line.text))
+            .collect();
+        let option_tags: Vec<_> = option_body
+            .iter()
+            .filter(|line| !line.text.starts_with("<<") && !line.text.starts_with("->"))
+            .flat_map(|line| line_tags(line.text))
+            .collect();
+
+        // Lines inside an option become their own node before continuing
+        let mut dialogue_option = if option_text_lines.is_empty() {
+            match option_jump {
+                Some(target) => DialogueOption::reply(option_text, target),
+                None => DialogueOption::exit(option_text),
+            }
+        } else {
+            let node_id = format!("{title}.{option_index}");
+            let (options, next) = continuation(option_jump);
+            nodes.insert(
+                node_id.clone(),
+                DialogueNode {
+                    text: option_text_lines.join("\n"),
+                    options,
+                    next,
+                    tags: option_tags,
+                },
+            );
+            DialogueOption::reply(option_text, node_id)
+        };
+        if let Some(condition) = condition {
+            dialogue_option = dialogue_option.with_condition(condition);
+        }
+        node_options.push(dialogue_option);
+    }
+
+    let mut next = None;
+    if node_options.is_empty() {
+        (node_options, next) = continuation(jump);
+    }
+
+    nodes.insert(
+        title.to_string(),
+        DialogueNode {
+            text: text.join("\n"),
+            options: node_options,
+            next,
+            tags,
+        },
+    );
+    Ok(())
+}
+
+// Trailing `#hashtags` become node tags, except Yarn's own `#key:value` metadata
+fn line_tags(line: &str) -> impl Iterator<Item = String> + '_ {
+    let hashtags = line.find(" #").map_or("", |index| &line[index..]);
+    hashtags
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .filter(|tag| !tag.is_empty() && !tag.contains(':'))
+        .map(str::to_string)
+}
+
+// Commands other than `<<jump>>`, like `<<set $met to true>>`, have nothing to map onto, so
+// they're dropped with a warning
+fn ignore_command(title: &str, line: &YarnLine) {
+    println!(
+        "Ignoring unsupported Yarn command `{}` in node `{title}` on line {}",
+        line.text, line.number
+    );
+}
+
+fn parse_jump(line: &str) -> Option<&str> {
+    line.strip_prefix("<<jump")?
+        .strip_suffix(">>")
+        .map(str::trim)
+}
+
+// Drop the speaker prefix and any trailing `#hashtags` from a line
+fn strip_line(line: &str) -> String {
+    let line = match line.find(" #") {
+        Some(index) => &line[..index],
+        None => line,
+    };
+    let line = match line.split_once(": ") {
+        Some((speaker, rest)) if !speaker.contains('"') && speaker.len() <= 32 => rest,
+        _ => line,
+    };
+    line.trim().to_string()
+}
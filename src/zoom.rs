@@ -0,0 +1,88 @@
+use crate::{
+    GameState, GameStateSet, PlayerCamera,
+    accessibility::AccessibilitySettings,
+    input_map::{ActionState, InputAction},
+};
+use bevy::prelude::*;
+
+// Zoom constants
+const ZOOM_FOV_SCALE: f32 = 0.4; // Fraction of the normal field of view while zoomed
+const ZOOM_SPEED: f32 = 12.0; // How quickly the field of view follows the button
+
+pub struct ZoomPlugin;
+
+impl Plugin for ZoomPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Zoom>()
+            .add_systems(Update, update_zoom.in_set(GameStateSet::Playing))
+            .add_systems(OnExit(GameState::Playing), reset_zoom);
+    }
+}
+
+// Resource with how far the camera is zoomed in
+#[derive(Resource)]
+pub struct Zoom {
+    // Field of view the camera had before any zooming
+    base_fov: f32,
+    // Current field of view as a fraction of the base
+    fov_scale: f32,
+}
+
+impl Default for Zoom {
+    fn default() -> Self {
+        Self {
+            base_fov: PerspectiveProjection::default().fov,
+            fov_scale: 1.0,
+        }
+    }
+}
+
+impl Zoom {
+    // Look speed follows the field of view so aim feels the same at any zoom
+    pub fn look_scale(&self) -> f32 {
+        self.fov_scale
+    }
+}
+
+fn update_zoom(
+    time: Res<Time>,
+    actions: Res<ActionState>,
+    accessibility: Res<AccessibilitySettings>,
+    mut zoom: ResMut<Zoom>,
+    mut cameras: Query<&mut Projection, With<PlayerCamera>>,
+) {
+    let target = if actions.pressed(InputAction::Zoom) {
+        ZOOM_FOV_SCALE
+    } else {
+        1.0
+    };
+    if zoom.fov_scale == target {
+        return;
+    }
+    // With motion reduced the view cuts straight to the new field of view instead of sweeping
+    let blend = if accessibility.reduce_motion {
+        1.0
+    } else {
+        (ZOOM_SPEED * time.delta_secs()).min(1.0)
+    };
+    zoom.fov_scale = zoom.fov_scale.lerp(target, blend);
+    // Settle exactly so the camera isn't touched every frame once there
+    if (zoom.fov_scale - target).abs() < 1e-3 {
+        zoom.fov_scale = target;
+    }
+    set_fov(&zoom, &mut cameras);
+}
+
+// Conversations always use the normal view
+fn reset_zoom(mut zoom: ResMut<Zoom>, mut cameras: Query<&mut Projection, With<PlayerCamera>>) {
+    zoom.fov_scale = 1.0;
+    set_fov(&zoom, &mut cameras);
+}
+
+fn set_fov(zoom: &Zoom, cameras: &mut Query<&mut Projection, With<PlayerCamera>>) {
+    for mut projection in cameras.iter_mut() {
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = zoom.base_fov * zoom.fov_scale;
+        }
+    }
+}